@@ -255,14 +255,14 @@ mod tests {
     impl Repository for TestRepo {
         async fn find_by_email(
             &self,
-            _email: &str,
+            _email: &user::Email,
         ) -> Result<Option<AuthRecord>> {
             Ok(self.record.clone())
         }
 
         async fn find_by_id(
             &self,
-            _user_id: &str,
+            _user_id: &user::Id,
         ) -> Result<Option<AuthRecord>> {
             Ok(self.record.clone())
         }
@@ -273,14 +273,14 @@ mod tests {
     }
 
     impl PasswordHasher for TestHasher {
-        fn hash(&self, _password: &str) -> Result<String> {
-            Ok("hash".to_string())
+        fn hash(&self, _password: &str) -> Result<PasswordHash> {
+            Ok(PasswordHash::new("hash"))
         }
 
         fn verify(
             &self,
             _password: &str,
-            _password_hash: &str,
+            _password_hash: &PasswordHash,
         ) -> Result<bool> {
             Ok(self.ok)
         }
@@ -291,10 +291,10 @@ mod tests {
         let repo = Arc::new(TestRepo {
             record: Some(
                 AuthRecord::builder()
-                    .id("user-1".to_string())
-                    .username("user".to_string())
-                    .email("user@example.com".to_string())
-                    .password_hash("hash".to_string())
+                    .id(user::Id::new_v4())
+                    .username(user::Username::try_new("user").expect("username"))
+                    .email(user::Email::try_new("user@example.com").expect("email"))
+                    .password_hash(PasswordHash::new("hash"))
                     .build(),
             ),
         });
@@ -304,7 +304,7 @@ mod tests {
         let user = provider
             .authenticate(
                 Credentials::builder()
-                    .email("user@example.com".to_string())
+                    .email(user::Email::try_new("user@example.com").expect("email"))
                     .password(SecretString::new("pw".into()))
                     .build(),
             )
@@ -319,10 +319,10 @@ mod tests {
         let repo = Arc::new(TestRepo {
             record: Some(
                 AuthRecord::builder()
-                    .id("user-1".to_string())
-                    .username("user".to_string())
-                    .email("user@example.com".to_string())
-                    .password_hash("hash".to_string())
+                    .id(user::Id::new_v4())
+                    .username(user::Username::try_new("user").expect("username"))
+                    .email(user::Email::try_new("user@example.com").expect("email"))
+                    .password_hash(PasswordHash::new("hash"))
                     .build(),
             ),
         });
@@ -332,7 +332,7 @@ mod tests {
         let user = provider
             .authenticate(
                 Credentials::builder()
-                    .email("user@example.com".to_string())
+                    .email(user::Email::try_new("user@example.com").expect("email"))
                     .password(SecretString::new("pw".into()))
                     .build(),
             )
@@ -0,0 +1,179 @@
+use std::time::SystemTime;
+
+use bon::Builder;
+use nutype::nutype;
+use strum_macros::{Display, EnumString};
+
+use domain::chat;
+
+use super::{ModerationReason, RoomRole};
+
+/// The author's case for restoring a removed message.
+#[nutype(
+    sanitize(trim),
+    validate(not_empty, len_char_max = 1000),
+    derive(Clone, Debug, PartialEq, Display)
+)]
+pub struct AppealStatement(String);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Display, EnumString)]
+pub enum AppealStatus {
+    #[strum(serialize = "pending")]
+    Pending,
+    #[strum(serialize = "upheld")]
+    Upheld,
+    #[strum(serialize = "overturned")]
+    Overturned,
+}
+
+impl AppealStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            AppealStatus::Pending => "Appeal pending",
+            AppealStatus::Upheld => "Removal upheld",
+            AppealStatus::Overturned => "Removal overturned, message restored",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Display, EnumString)]
+pub enum AppealDecision {
+    /// Keeps the message removed.
+    #[strum(serialize = "uphold")]
+    Uphold,
+    /// Restores the message.
+    #[strum(serialize = "overturn")]
+    Overturn,
+}
+
+impl AppealDecision {
+    pub fn status(self) -> AppealStatus {
+        match self {
+            AppealDecision::Uphold => AppealStatus::Upheld,
+            AppealDecision::Overturn => AppealStatus::Overturned,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct FileAppeal {
+    pub message_id: chat::MessageId,
+    pub appellant_id: chat::UserId,
+    pub statement: AppealStatement,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct ListAppeals {
+    /// Only appeals from rooms this member moderates are listed.
+    pub reviewer_id: chat::UserId,
+    #[builder(default = 50)]
+    pub limit: usize,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct ListRemovals {
+    pub author_id: chat::UserId,
+    #[builder(default = 50)]
+    pub limit: usize,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct DecideAppeal {
+    pub message_id: chat::MessageId,
+    pub reviewer_id: chat::UserId,
+    pub decision: AppealDecision,
+}
+
+/// An appeal with the removed message it is about.
+#[derive(Clone, Debug, PartialEq, Builder)]
+pub struct Appeal {
+    pub message_id: chat::MessageId,
+    pub room_id: chat::RoomId,
+    pub room_name: chat::RoomName,
+    pub appellant_id: chat::UserId,
+    pub appellant_name: domain::user::Username,
+    pub body: chat::MessageBody,
+    pub reason: ModerationReason,
+    pub statement: AppealStatement,
+    /// Who removed the message; they may not decide the appeal.
+    pub original_reviewer_id: Option<chat::UserId>,
+    pub status: AppealStatus,
+    pub filed_at: SystemTime,
+}
+
+/// One of the author's removed messages and where its appeal stands.
+#[derive(Clone, Debug, PartialEq, Builder)]
+pub struct Removal {
+    pub message_id: chat::MessageId,
+    pub room_name: chat::RoomName,
+    pub body: chat::MessageBody,
+    pub reason: ModerationReason,
+    pub removed_at: SystemTime,
+    pub appeal: Option<AppealStatus>,
+}
+
+/// Owners, admins and moderators of the appeal's room decide it, except
+/// the appellant and whoever removed the message.
+pub(super) fn may_decide(
+    appeal: &Appeal,
+    reviewer_id: &chat::UserId,
+    role: Option<RoomRole>,
+) -> bool {
+    matches!(
+        role,
+        Some(RoomRole::Owner | RoomRole::Admin | RoomRole::Moderator)
+    ) && appeal.appellant_id != *reviewer_id
+        && appeal.original_reviewer_id.as_ref() != Some(reviewer_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn original_reviewer_and_appellant_cannot_decide() {
+        let id = |value: u128| chat::UserId::from_uuid(uuid::Uuid::from_u128(value));
+        let appeal = Appeal::builder()
+            .message_id(chat::MessageId::from_uuid(uuid::Uuid::from_u128(9)))
+            .room_id(chat::RoomId::from_uuid(uuid::Uuid::from_u128(8)))
+            .room_name(chat::RoomName::Lobby)
+            .appellant_id(id(1))
+            .appellant_name(domain::user::Username::try_new("ada").expect("username"))
+            .body(chat::MessageBody::try_new("hello").expect("body"))
+            .reason(ModerationReason::try_new("report").expect("reason"))
+            .statement(AppealStatement::try_new("It was a joke").expect("statement"))
+            .original_reviewer_id(id(2))
+            .status(AppealStatus::Pending)
+            .filed_at(SystemTime::UNIX_EPOCH)
+            .build();
+
+        let moderator = Some(RoomRole::Moderator);
+        assert!(!may_decide(&appeal, &id(1), moderator));
+        assert!(!may_decide(&appeal, &id(2), moderator));
+        assert!(may_decide(&appeal, &id(3), moderator));
+    }
+
+    #[test]
+    fn only_room_staff_can_decide() {
+        let id = |value: u128| chat::UserId::from_uuid(uuid::Uuid::from_u128(value));
+        let appeal = Appeal::builder()
+            .message_id(chat::MessageId::from_uuid(uuid::Uuid::from_u128(9)))
+            .room_id(chat::RoomId::from_uuid(uuid::Uuid::from_u128(8)))
+            .room_name(chat::RoomName::Lobby)
+            .appellant_id(id(1))
+            .appellant_name(domain::user::Username::try_new("ada").expect("username"))
+            .body(chat::MessageBody::try_new("hello").expect("body"))
+            .reason(ModerationReason::try_new("report").expect("reason"))
+            .statement(AppealStatement::try_new("It was a joke").expect("statement"))
+            .original_reviewer_id(id(2))
+            .status(AppealStatus::Pending)
+            .filed_at(SystemTime::UNIX_EPOCH)
+            .build();
+
+        assert!(!may_decide(&appeal, &id(3), None));
+        assert!(!may_decide(&appeal, &id(3), Some(RoomRole::Member)));
+        for role in [RoomRole::Owner, RoomRole::Admin, RoomRole::Moderator] {
+            assert!(may_decide(&appeal, &id(3), Some(role)));
+        }
+    }
+}
@@ -0,0 +1,199 @@
+use async_trait::async_trait;
+use bon::Builder;
+
+use domain::chat;
+
+use super::{Error, Result};
+
+pub const MAX_ATTACHMENT_BYTES: usize = 10 * 1024 * 1024;
+pub const MAX_ATTACHMENTS_PER_MESSAGE: usize = 4;
+/// Longest thumbnail edge in pixels.
+pub const THUMBNAIL_SIZE: u32 = 320;
+
+/// Raw bytes keyed by `BlobKey`. Keys are never reused, so `put` may
+/// overwrite and `delete` of a missing key succeeds.
+#[async_trait]
+pub trait BlobStore: Send + Sync {
+    async fn put(
+        &self,
+        key: &chat::BlobKey,
+        bytes: &[u8],
+    ) -> Result<()>;
+    async fn get(
+        &self,
+        key: &chat::BlobKey,
+    ) -> Result<Option<Vec<u8>>>;
+    async fn delete(
+        &self,
+        key: &chat::BlobKey,
+    ) -> Result<()>;
+}
+
+/// Used when no store is configured; uploads fail, sweeps succeed.
+pub(super) struct DisabledBlobStore;
+
+#[async_trait]
+impl BlobStore for DisabledBlobStore {
+    async fn put(
+        &self,
+        _key: &chat::BlobKey,
+        _bytes: &[u8],
+    ) -> Result<()> {
+        Err(Error::Repo("attachments are disabled".to_string().into()))
+    }
+
+    async fn get(
+        &self,
+        _key: &chat::BlobKey,
+    ) -> Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+
+    async fn delete(
+        &self,
+        _key: &chat::BlobKey,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Shrinks an image to fit `THUMBNAIL_SIZE`. `None` when the image cannot
+/// be decoded within the implementation's limits.
+#[async_trait]
+pub trait Thumbnailer: Send + Sync {
+    async fn thumbnail(
+        &self,
+        content_type: chat::AttachmentType,
+        bytes: &[u8],
+    ) -> Option<Vec<u8>>;
+}
+
+pub(super) struct DisabledThumbnailer;
+
+#[async_trait]
+impl Thumbnailer for DisabledThumbnailer {
+    async fn thumbnail(
+        &self,
+        _content_type: chat::AttachmentType,
+        _bytes: &[u8],
+    ) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+/// Detects the format from magic bytes; the client's declared type is
+/// never trusted. Text must be UTF-8 without NUL bytes.
+pub fn sniff_attachment(bytes: &[u8]) -> Option<chat::AttachmentType> {
+    match bytes {
+        [0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a, ..] => Some(chat::AttachmentType::Png),
+        [0xff, 0xd8, 0xff, ..] => Some(chat::AttachmentType::Jpeg),
+        [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Some(chat::AttachmentType::Gif),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => {
+            Some(chat::AttachmentType::Webp)
+        }
+        [b'%', b'P', b'D', b'F', b'-', ..] => Some(chat::AttachmentType::Pdf),
+        _ if !bytes.contains(&0) && std::str::from_utf8(bytes).is_ok() => {
+            Some(chat::AttachmentType::Text)
+        }
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct AttachmentUpload {
+    pub name: chat::AttachmentName,
+    pub bytes: Vec<u8>,
+}
+
+/// Posts a message carrying files. Without a caption the file names
+/// become the body.
+#[derive(Clone, Debug, Builder)]
+pub struct PostAttachments {
+    pub room_id: chat::RoomId,
+    pub user_id: chat::UserId,
+    pub body: Option<chat::MessageBody>,
+    pub files: Vec<AttachmentUpload>,
+    pub client_id: Option<chat::ClientId>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AttachedMessage {
+    pub message: chat::Message,
+    pub attachments: Vec<chat::Attachment>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttachmentVariant {
+    Original,
+    Thumbnail,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct OpenAttachment {
+    pub attachment_id: chat::AttachmentId,
+    pub user_id: chat::UserId,
+    #[builder(default = AttachmentVariant::Original)]
+    pub variant: AttachmentVariant,
+}
+
+#[derive(Clone, Debug)]
+pub struct AttachmentDownload {
+    pub attachment: chat::Attachment,
+    /// PNG for thumbnails, otherwise `attachment.content_type`.
+    pub content_type: chat::AttachmentType,
+    pub bytes: Vec<u8>,
+}
+
+/// Attachment rows whose message was deleted or redacted.
+#[derive(Clone, Debug, Builder)]
+pub struct OrphanedAttachment {
+    pub id: chat::AttachmentId,
+    pub blob_key: chat::BlobKey,
+    pub thumbnail_key: Option<chat::BlobKey>,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct SweepAttachments {
+    #[builder(default = 100)]
+    pub limit: usize,
+}
+
+pub(super) fn blob_key(
+    attachment_id: &chat::AttachmentId,
+    suffix: &str,
+) -> chat::BlobKey {
+    chat::BlobKey::try_new(format!(
+        "attachments/{}/{}",
+        attachment_id.as_uuid().simple(),
+        suffix
+    ))
+    .expect("blob key")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniffs_by_content_not_name() {
+        assert_eq!(
+            sniff_attachment(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"),
+            Some(chat::AttachmentType::Png)
+        );
+        assert_eq!(
+            sniff_attachment(b"RIFF\x10\0\0\0WEBPVP8 "),
+            Some(chat::AttachmentType::Webp)
+        );
+        assert_eq!(sniff_attachment(b"%PDF-1.7"), Some(chat::AttachmentType::Pdf));
+        assert_eq!(sniff_attachment("notes ✓".as_bytes()), Some(chat::AttachmentType::Text));
+        assert_eq!(sniff_attachment(b"MZ\x90\0\x03\0"), None);
+        assert_eq!(sniff_attachment(b"\xff\xfe\0h"), None);
+    }
+
+    #[test]
+    fn names_keep_only_the_last_segment() {
+        let name = chat::AttachmentName::try_new("../../etc/pass\nwd").expect("name");
+        assert_eq!(name.to_string(), "passwd");
+        assert!(chat::AttachmentName::try_new("dir/").is_err());
+    }
+}
@@ -0,0 +1,324 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use bon::Builder;
+
+use domain::chat;
+
+use super::{CommandArgs, CommandName};
+
+/// Bot commands start with `!` so they never collide with slash commands.
+pub const BOT_COMMAND_PREFIX: char = '!';
+
+/// What made a bot look at a message.
+#[derive(Clone, Debug, PartialEq)]
+pub enum BotTrigger {
+    /// `@username` appears in the message.
+    Mention,
+    /// The message starts with `!name` and the bot answers `name`.
+    Command { name: CommandName, args: CommandArgs },
+    /// One of the bot's keywords appears as a word.
+    Keyword(&'static str),
+}
+
+impl BotTrigger {
+    /// Commands win over mentions, mentions over keywords.
+    pub fn detect(
+        bot: &dyn Bot,
+        body: &chat::MessageBody,
+    ) -> Option<Self> {
+        let text = body.to_string();
+        if let Some(rest) = text.strip_prefix(BOT_COMMAND_PREFIX) {
+            let (name, args) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            if let (Ok(name), Ok(args)) = (CommandName::try_new(name), CommandArgs::try_new(args))
+                && bot.commands().contains(&name.to_string().as_str())
+            {
+                return Some(BotTrigger::Command { name, args });
+            }
+        }
+
+        let words = text
+            .split_whitespace()
+            .map(|word| {
+                word.trim_end_matches(|c: char| !c.is_alphanumeric())
+                    .to_lowercase()
+            })
+            .collect::<Vec<_>>();
+        let mention = format!("@{}", bot.username());
+        if words.contains(&mention) {
+            return Some(BotTrigger::Mention);
+        }
+        bot.keywords()
+            .iter()
+            .find(|keyword| {
+                words
+                    .iter()
+                    .any(|word| word.trim_start_matches(|c: char| !c.is_alphanumeric()) == **keyword)
+            })
+            .map(|keyword| BotTrigger::Keyword(keyword))
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BotTrigger::Mention => "mention",
+            BotTrigger::Command { .. } => "command",
+            BotTrigger::Keyword(_) => "keyword",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BotEvent {
+    pub message: chat::Message,
+    pub trigger: BotTrigger,
+}
+
+/// Replies allowed per room within `window`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BotRateLimit {
+    pub max_replies: u32,
+    pub window: Duration,
+}
+
+impl Default for BotRateLimit {
+    fn default() -> Self {
+        Self {
+            max_replies: 5,
+            window: Duration::from_secs(60),
+        }
+    }
+}
+
+/// A bot account that reacts to room messages. The service creates the
+/// account on first use, keeps it in the room, enforces `rate_limit` and
+/// audits every reply under the bot's own id.
+#[async_trait]
+pub trait Bot: Send + Sync {
+    /// Account username, lowercase; members mention the bot as `@username`.
+    fn username(&self) -> &'static str;
+    /// Command names answered after `!`.
+    fn commands(&self) -> &'static [&'static str] {
+        &[]
+    }
+    /// Lowercase words the bot listens for.
+    fn keywords(&self) -> &'static [&'static str] {
+        &[]
+    }
+    fn rate_limit(&self) -> BotRateLimit {
+        BotRateLimit::default()
+    }
+    /// `None` keeps the bot quiet.
+    async fn react(
+        &self,
+        event: &BotEvent,
+    ) -> Option<chat::MessageBody>;
+}
+
+/// A reply a bot posted, with the name to render it under.
+#[derive(Clone, Debug, Builder)]
+pub struct BotPost {
+    pub bot: domain::user::Username,
+    pub message: chat::Message,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct BotWindow {
+    started_at: SystemTime,
+    replies: u32,
+}
+
+impl BotWindow {
+    /// Counts a reply if the limit allows it, starting a new window once the
+    /// old one has passed.
+    fn admit(
+        window: Option<BotWindow>,
+        limit: BotRateLimit,
+        now: SystemTime,
+    ) -> Option<BotWindow> {
+        match window {
+            Some(window)
+                if now
+                    .duration_since(window.started_at)
+                    .is_ok_and(|elapsed| elapsed < limit.window) =>
+            {
+                (window.replies < limit.max_replies).then_some(BotWindow {
+                    replies: window.replies + 1,
+                    ..window
+                })
+            }
+            _ => (limit.max_replies > 0).then_some(BotWindow {
+                started_at: now,
+                replies: 1,
+            }),
+        }
+    }
+}
+
+/// Registered bots plus their resolved accounts and rate windows. Both are
+/// kept in memory, so limits are per process.
+#[derive(Default)]
+pub struct BotRegistry {
+    bots: Vec<Arc<dyn Bot>>,
+    accounts: Mutex<HashMap<&'static str, chat::UserId>>,
+    windows: Mutex<HashMap<(&'static str, chat::RoomId), BotWindow>>,
+}
+
+impl BotRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        registry.register(Arc::new(DemoBot));
+        registry
+    }
+
+    /// Registering a username twice replaces the earlier bot.
+    pub fn register(
+        &mut self,
+        bot: Arc<dyn Bot>,
+    ) -> &mut Self {
+        self.bots
+            .retain(|existing| existing.username() != bot.username());
+        self.bots.push(bot);
+        self
+    }
+
+    pub fn bots(&self) -> impl Iterator<Item = &Arc<dyn Bot>> {
+        self.bots.iter()
+    }
+
+    pub(super) fn account(
+        &self,
+        username: &'static str,
+    ) -> Option<chat::UserId> {
+        self.accounts
+            .lock()
+            .ok()
+            .and_then(|accounts| accounts.get(username).copied())
+    }
+
+    pub(super) fn remember_account(
+        &self,
+        username: &'static str,
+        user_id: chat::UserId,
+    ) {
+        if let Ok(mut accounts) = self.accounts.lock() {
+            accounts.insert(username, user_id);
+        }
+    }
+
+    pub(super) fn is_bot_account(
+        &self,
+        user_id: &chat::UserId,
+    ) -> bool {
+        self.accounts
+            .lock()
+            .is_ok_and(|accounts| accounts.values().any(|id| id == user_id))
+    }
+
+    /// Takes one reply from the bot's allowance in the room.
+    pub(super) fn admit(
+        &self,
+        bot: &dyn Bot,
+        room_id: chat::RoomId,
+        now: SystemTime,
+    ) -> bool {
+        let Ok(mut windows) = self.windows.lock() else {
+            return false;
+        };
+        let key = (bot.username(), room_id);
+        match BotWindow::admit(windows.get(&key).copied(), bot.rate_limit(), now) {
+            Some(window) => {
+                windows.insert(key, window);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Answers `!ping`, `!echo` and `!help`, greets mentions and says hello
+/// back.
+struct DemoBot;
+
+impl DemoBot {
+    const HELP: &'static str =
+        "I answer !ping, !echo <text> and !help. Mention @demobot to say hi.";
+}
+
+#[async_trait]
+impl Bot for DemoBot {
+    fn username(&self) -> &'static str {
+        "demobot"
+    }
+
+    fn commands(&self) -> &'static [&'static str] {
+        &["ping", "echo", "help"]
+    }
+
+    fn keywords(&self) -> &'static [&'static str] {
+        &["hello"]
+    }
+
+    async fn react(
+        &self,
+        event: &BotEvent,
+    ) -> Option<chat::MessageBody> {
+        let reply = match &event.trigger {
+            BotTrigger::Command { name, args } => match name.to_string().as_str() {
+                "ping" => "pong".to_string(),
+                "echo" if !args.to_string().is_empty() => args.to_string(),
+                _ => Self::HELP.to_string(),
+            },
+            BotTrigger::Mention => format!("Hi! {}", Self::HELP),
+            BotTrigger::Keyword(_) => "Hello! Mention @demobot if you need me.".to_string(),
+        };
+        chat::MessageBody::try_new(reply).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(value: &str) -> Option<BotTrigger> {
+        BotTrigger::detect(&DemoBot, &chat::MessageBody::try_new(value).expect("body"))
+    }
+
+    #[test]
+    fn detects_commands_mentions_and_keywords() {
+        assert_eq!(
+            detect("!echo  hi there"),
+            Some(BotTrigger::Command {
+                name: CommandName::try_new("echo").expect("name"),
+                args: CommandArgs::try_new("hi there").expect("args"),
+            })
+        );
+        assert_eq!(detect("hey @DemoBot, you up?"), Some(BotTrigger::Mention));
+        assert_eq!(detect("Hello everyone!"), Some(BotTrigger::Keyword("hello")));
+        assert_eq!(detect("!deploy now"), None);
+        assert_eq!(detect("othello is a game"), None);
+    }
+
+    #[test]
+    fn window_caps_replies_until_it_expires() {
+        let limit = BotRateLimit {
+            max_replies: 2,
+            window: Duration::from_secs(60),
+        };
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+
+        let first = BotWindow::admit(None, limit, start);
+        let second = BotWindow::admit(first, limit, start + Duration::from_secs(1));
+        let third = BotWindow::admit(second, limit, start + Duration::from_secs(2));
+        let later = BotWindow::admit(second, limit, start + Duration::from_secs(61));
+
+        assert!(first.is_some() && second.is_some());
+        assert!(third.is_none());
+        assert_eq!(later.map(|window| window.replies), Some(1));
+    }
+}
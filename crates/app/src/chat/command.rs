@@ -0,0 +1,540 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use nutype::nutype;
+
+use domain::chat;
+
+use super::{
+    AuditAction, AuditKey, AuditValue, JoinRoom, PostMessage, Result, RoomRole, Service,
+    SetTrustLevel, TrustLevel,
+};
+
+pub const COMMAND_PREFIX: char = '/';
+
+/// Lowercase command name without the leading slash.
+#[nutype(
+    sanitize(trim, lowercase),
+    validate(
+        not_empty,
+        len_char_max = 32,
+        predicate = |name| name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    ),
+    derive(Clone, Debug, PartialEq, Display)
+)]
+pub struct CommandName(String);
+
+/// Everything after the command name; may be empty.
+#[nutype(
+    sanitize(trim),
+    validate(len_char_max = 1000),
+    derive(Clone, Debug, PartialEq, Display)
+)]
+pub struct CommandArgs(String);
+
+impl CommandArgs {
+    /// Splits off the first whitespace-separated word.
+    pub fn split_first(&self) -> Option<(String, String)> {
+        let value = self.to_string();
+        if value.is_empty() {
+            return None;
+        }
+        let (head, tail) = value.split_once(char::is_whitespace).unwrap_or((&value, ""));
+        Some((head.to_string(), tail.trim().to_string()))
+    }
+}
+
+/// Text shown only to the caller.
+#[nutype(
+    sanitize(trim),
+    derive(Clone, Debug, PartialEq, Display)
+)]
+pub struct CommandReply(String);
+
+impl From<&str> for CommandReply {
+    fn from(value: &str) -> Self {
+        CommandReply::new(value)
+    }
+}
+
+impl From<String> for CommandReply {
+    fn from(value: String) -> Self {
+        CommandReply::new(value)
+    }
+}
+
+/// A chat input after parsing. `//text` escapes the prefix and posts
+/// `/text` as a plain message.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CommandInput {
+    Plain(chat::MessageBody),
+    Command { name: CommandName, args: CommandArgs },
+}
+
+impl CommandInput {
+    pub fn parse(body: chat::MessageBody) -> Self {
+        let text = body.to_string();
+        let Some(rest) = text.strip_prefix(COMMAND_PREFIX) else {
+            return CommandInput::Plain(body);
+        };
+        if rest.starts_with(COMMAND_PREFIX) {
+            return chat::MessageBody::try_new(rest)
+                .map(CommandInput::Plain)
+                .unwrap_or(CommandInput::Plain(body));
+        }
+
+        let (name, args) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        match (CommandName::try_new(name), CommandArgs::try_new(args)) {
+            (Ok(name), Ok(args)) => CommandInput::Command { name, args },
+            _ => CommandInput::Plain(body),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CommandPermission {
+    Member,
+    /// Room owners, admins and moderators.
+    Moderator,
+}
+
+impl CommandPermission {
+    pub fn allows(
+        self,
+        role: RoomRole,
+    ) -> bool {
+        match self {
+            CommandPermission::Member => true,
+            CommandPermission::Moderator => {
+                matches!(role, RoomRole::Owner | RoomRole::Admin | RoomRole::Moderator)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CommandContext {
+    pub room_id: chat::RoomId,
+    pub user_id: chat::UserId,
+    pub role: RoomRole,
+    pub args: CommandArgs,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CommandOutcome {
+    /// A message was posted to the room, as with plain input.
+    Posted(chat::Message),
+    /// Only the caller sees the reply.
+    Reply(CommandReply),
+    /// The room topic changed; everyone viewing the room should see the new one.
+    TopicChanged {
+        room_id: chat::RoomId,
+        topic: Option<chat::RoomTopic>,
+        reply: CommandReply,
+    },
+}
+
+/// A slash command. Handlers run after the registry has checked membership
+/// and `permission`, and act through the public `Service` API.
+#[async_trait]
+pub trait CommandHandler: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// One-line usage shown by `/help`.
+    fn usage(&self) -> &'static str;
+    fn permission(&self) -> CommandPermission {
+        CommandPermission::Member
+    }
+    async fn run(
+        &self,
+        service: &Service,
+        context: CommandContext,
+    ) -> Result<CommandOutcome>;
+}
+
+/// Commands available in chat input. Registering a name twice replaces the
+/// earlier handler, so modules can override built-ins.
+#[derive(Clone, Default)]
+pub struct CommandRegistry {
+    handlers: Vec<Arc<dyn CommandHandler>>,
+}
+
+impl CommandRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        registry
+            .register(Arc::new(MeCommand))
+            .register(Arc::new(TopicCommand))
+            .register(Arc::new(InviteCommand))
+            .register(Arc::new(MuteCommand))
+            .register(Arc::new(TrustCommand {
+                level: TrustLevel::Quarantined,
+            }))
+            .register(Arc::new(TrustCommand {
+                level: TrustLevel::Standard,
+            }))
+            .register(Arc::new(HelpCommand));
+        registry
+    }
+
+    pub fn register(
+        &mut self,
+        handler: Arc<dyn CommandHandler>,
+    ) -> &mut Self {
+        self.handlers
+            .retain(|existing| existing.name() != handler.name());
+        self.handlers.push(handler);
+        self
+    }
+
+    pub fn find(
+        &self,
+        name: &CommandName,
+    ) -> Option<Arc<dyn CommandHandler>> {
+        self.handlers
+            .iter()
+            .find(|handler| handler.name() == name.to_string())
+            .cloned()
+    }
+
+    pub fn handlers(&self) -> impl Iterator<Item = &Arc<dyn CommandHandler>> {
+        self.handlers.iter()
+    }
+}
+
+/// Default and maximum `/mute` length.
+pub const DEFAULT_MUTE: Duration = Duration::from_secs(10 * 60);
+pub const MAX_MUTE: Duration = Duration::from_secs(24 * 60 * 60);
+
+struct MeCommand;
+
+#[async_trait]
+impl CommandHandler for MeCommand {
+    fn name(&self) -> &'static str {
+        "me"
+    }
+
+    fn usage(&self) -> &'static str {
+        "/me <action> — post an action, e.g. /me waves"
+    }
+
+    async fn run(
+        &self,
+        service: &Service,
+        context: CommandContext,
+    ) -> Result<CommandOutcome> {
+        if context.args.to_string().is_empty() {
+            return Ok(CommandOutcome::Reply(self.usage().into()));
+        }
+        let Ok(body) = chat::MessageBody::try_new(format!("* {}", context.args)) else {
+            return Ok(CommandOutcome::Reply(self.usage().into()));
+        };
+        service
+            .post_message(
+                PostMessage::builder()
+                    .room_id(context.room_id)
+                    .user_id(context.user_id)
+                    .body(body)
+                    .build(),
+            )
+            .await
+            .map(CommandOutcome::Posted)
+    }
+}
+
+struct TopicCommand;
+
+#[async_trait]
+impl CommandHandler for TopicCommand {
+    fn name(&self) -> &'static str {
+        "topic"
+    }
+
+    fn usage(&self) -> &'static str {
+        "/topic [text] — set the room topic, or clear it with no text"
+    }
+
+    fn permission(&self) -> CommandPermission {
+        CommandPermission::Moderator
+    }
+
+    async fn run(
+        &self,
+        service: &Service,
+        context: CommandContext,
+    ) -> Result<CommandOutcome> {
+        let topic = match context.args.to_string().as_str() {
+            "" => None,
+            value => match chat::RoomTopic::try_new(value) {
+                Ok(topic) => Some(topic),
+                Err(error) => {
+                    return Ok(CommandOutcome::Reply(format!("Invalid topic: {error}").into()));
+                }
+            },
+        };
+        service
+            .set_topic(&context.room_id, &context.user_id, topic.clone())
+            .await?;
+
+        let reply = match &topic {
+            Some(topic) => format!("Topic set to “{topic}”."),
+            None => "Topic cleared.".to_string(),
+        };
+        Ok(CommandOutcome::TopicChanged {
+            room_id: context.room_id,
+            topic,
+            reply: reply.into(),
+        })
+    }
+}
+
+struct InviteCommand;
+
+#[async_trait]
+impl CommandHandler for InviteCommand {
+    fn name(&self) -> &'static str {
+        "invite"
+    }
+
+    fn usage(&self) -> &'static str {
+        "/invite <username> — add someone to this room"
+    }
+
+    fn permission(&self) -> CommandPermission {
+        CommandPermission::Moderator
+    }
+
+    async fn run(
+        &self,
+        service: &Service,
+        context: CommandContext,
+    ) -> Result<CommandOutcome> {
+        let Some((username, _)) = context.args.split_first() else {
+            return Ok(CommandOutcome::Reply(self.usage().into()));
+        };
+        let Some(user_id) = service.find_user_by_name(&username).await? else {
+            return Ok(CommandOutcome::Reply(format!("No user named {username}.").into()));
+        };
+        if service.check_membership(&context.room_id, &user_id).await.is_ok() {
+            return Ok(CommandOutcome::Reply(
+                format!("{username} is already in this room.").into(),
+            ));
+        }
+
+        service
+            .join_room(
+                JoinRoom::builder()
+                    .room_id(context.room_id)
+                    .user_id(user_id)
+                    .build(),
+            )
+            .await?;
+        service
+            .audit
+            .record(service.audit_entry(
+                context.room_id,
+                context.user_id,
+                AuditAction::MemberInvite,
+                vec![(
+                    AuditKey::TargetUserId,
+                    AuditValue::new(user_id.as_uuid().to_string()),
+                )],
+            ))
+            .await?;
+
+        Ok(CommandOutcome::Reply(format!("Invited {username}.").into()))
+    }
+}
+
+struct MuteCommand;
+
+#[async_trait]
+impl CommandHandler for MuteCommand {
+    fn name(&self) -> &'static str {
+        "mute"
+    }
+
+    fn usage(&self) -> &'static str {
+        "/mute <username> [minutes] — stop someone posting here for a while (default 10)"
+    }
+
+    fn permission(&self) -> CommandPermission {
+        CommandPermission::Moderator
+    }
+
+    async fn run(
+        &self,
+        service: &Service,
+        context: CommandContext,
+    ) -> Result<CommandOutcome> {
+        let Some((username, minutes)) = context.args.split_first() else {
+            return Ok(CommandOutcome::Reply(self.usage().into()));
+        };
+        let duration = match minutes.as_str() {
+            "" => DEFAULT_MUTE,
+            value => match value.parse::<u64>() {
+                Ok(minutes) if minutes > 0 => {
+                    Duration::from_secs(minutes.saturating_mul(60)).min(MAX_MUTE)
+                }
+                _ => return Ok(CommandOutcome::Reply(self.usage().into())),
+            },
+        };
+        let Some(user_id) = service.find_user_by_name(&username).await? else {
+            return Ok(CommandOutcome::Reply(format!("No user named {username}.").into()));
+        };
+
+        match service
+            .mute_member(&context.room_id, &context.user_id, &user_id, duration)
+            .await?
+        {
+            true => Ok(CommandOutcome::Reply(
+                format!("Muted {username} for {} minutes.", duration.as_secs() / 60).into(),
+            )),
+            false => Ok(CommandOutcome::Reply(
+                format!("{username} can't be muted here.").into(),
+            )),
+        }
+    }
+}
+
+/// `/quarantine` and `/unquarantine`, which set an account's trust level.
+struct TrustCommand {
+    level: TrustLevel,
+}
+
+#[async_trait]
+impl CommandHandler for TrustCommand {
+    fn name(&self) -> &'static str {
+        match self.level {
+            TrustLevel::Quarantined => "quarantine",
+            TrustLevel::Standard => "unquarantine",
+        }
+    }
+
+    fn usage(&self) -> &'static str {
+        match self.level {
+            TrustLevel::Quarantined => {
+                "/quarantine <username> — hide someone's new messages from everyone but them and moderators, and queue them for review"
+            }
+            TrustLevel::Standard => "/unquarantine <username> — let someone's messages reach the room again",
+        }
+    }
+
+    fn permission(&self) -> CommandPermission {
+        CommandPermission::Moderator
+    }
+
+    async fn run(
+        &self,
+        service: &Service,
+        context: CommandContext,
+    ) -> Result<CommandOutcome> {
+        let Some((username, _)) = context.args.split_first() else {
+            return Ok(CommandOutcome::Reply(self.usage().into()));
+        };
+        let Some(user_id) = service.find_user_by_name(&username).await? else {
+            return Ok(CommandOutcome::Reply(format!("No user named {username}.").into()));
+        };
+
+        let changed = service
+            .set_trust_level(
+                SetTrustLevel::builder()
+                    .room_id(context.room_id)
+                    .actor_id(context.user_id)
+                    .target_id(user_id)
+                    .level(self.level)
+                    .build(),
+            )
+            .await?;
+        let reply = match (changed, self.level) {
+            (true, TrustLevel::Quarantined) => format!("Quarantined {username}."),
+            (true, TrustLevel::Standard) => format!("Released {username} from quarantine."),
+            (false, _) => format!("{username} can't be quarantined from here."),
+        };
+        Ok(CommandOutcome::Reply(reply.into()))
+    }
+}
+
+struct HelpCommand;
+
+#[async_trait]
+impl CommandHandler for HelpCommand {
+    fn name(&self) -> &'static str {
+        "help"
+    }
+
+    fn usage(&self) -> &'static str {
+        "/help — list the commands you can use"
+    }
+
+    async fn run(
+        &self,
+        service: &Service,
+        context: CommandContext,
+    ) -> Result<CommandOutcome> {
+        let lines = service
+            .commands
+            .handlers()
+            .filter(|handler| handler.permission().allows(context.role))
+            .map(|handler| handler.usage())
+            .collect::<Vec<_>>()
+            .join("\n");
+        Ok(CommandOutcome::Reply(lines.into()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: &str) -> CommandInput {
+        CommandInput::parse(chat::MessageBody::try_new(value).expect("body"))
+    }
+
+    #[test]
+    fn parses_commands_and_escapes() {
+        assert_eq!(
+            parse("/Topic  Release day "),
+            CommandInput::Command {
+                name: CommandName::try_new("topic").expect("name"),
+                args: CommandArgs::try_new("Release day").expect("args"),
+            }
+        );
+        assert_eq!(
+            parse("//shrug"),
+            CommandInput::Plain(chat::MessageBody::try_new("/shrug").expect("body"))
+        );
+        assert_eq!(
+            parse("/usr/bin is a path"),
+            CommandInput::Plain(chat::MessageBody::try_new("/usr/bin is a path").expect("body"))
+        );
+    }
+
+    #[test]
+    fn registering_a_name_twice_replaces_the_handler() {
+        let mut registry = CommandRegistry::with_builtins();
+        let before = registry.handlers().count();
+        registry.register(Arc::new(HelpCommand));
+
+        assert_eq!(registry.handlers().count(), before);
+        assert!(registry
+            .find(&CommandName::try_new("ME").expect("name"))
+            .is_some());
+    }
+
+    #[test]
+    fn quarantine_commands_need_a_moderator() {
+        let registry = CommandRegistry::with_builtins();
+        for name in ["quarantine", "unquarantine"] {
+            let handler = registry
+                .find(&CommandName::try_new(name).expect("name"))
+                .expect("registered");
+            assert!(!handler.permission().allows(RoomRole::Member));
+            assert!(handler.permission().allows(RoomRole::Moderator));
+        }
+    }
+}
@@ -9,6 +9,24 @@ pub enum Error {
     RoomNotFound,
     MessageNotFound,
     NotMember,
+    Forbidden,
+    InvalidSchedule,
+    ScheduleNotFound,
+    PinLimitReached,
+    InvalidPoll,
+    PollNotFound,
+    PollClosed,
+    AlreadyVoted,
+    Muted,
+    BotNameTaken,
+    InvalidWebhook,
+    WebhookNotFound,
+    InvalidAttachment,
+    AttachmentNotFound,
+    InvalidReport,
+    ModerationConflict,
+    InvalidAppeal,
+    Flooded,
 }
 
 impl From<domain::chat::Error> for Error {
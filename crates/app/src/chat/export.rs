@@ -0,0 +1,258 @@
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use bon::Builder;
+use strum_macros::{Display, EnumString};
+
+use domain::chat;
+
+use super::{Repository, Result};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Display, EnumString)]
+pub enum ExportFormat {
+    #[strum(serialize = "json")]
+    Json,
+    #[strum(serialize = "csv")]
+    Csv,
+    #[strum(serialize = "ndjson")]
+    Ndjson,
+}
+
+impl ExportFormat {
+    pub fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "application/json",
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Ndjson => "ndjson",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct ExportRoom {
+    pub room_id: chat::RoomId,
+    pub user_id: chat::UserId,
+    pub format: ExportFormat,
+    #[builder(default = 500)]
+    pub page_size: usize,
+}
+
+/// One exported message. Every status is included so transcripts show what
+/// moderation removed or held back.
+#[derive(Clone, Debug, Builder)]
+pub struct ExportRecord {
+    pub message_id: chat::MessageId,
+    pub author_id: chat::UserId,
+    pub author: Option<domain::user::Username>,
+    pub body: chat::MessageBody,
+    pub status: chat::MessageStatus,
+    pub created_at: SystemTime,
+}
+
+/// Keyset position for paging through a room in `(created_at, id)` order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Builder)]
+pub struct ExportCursor {
+    pub created_at: SystemTime,
+    pub message_id: chat::MessageId,
+}
+
+impl From<&ExportRecord> for ExportCursor {
+    fn from(record: &ExportRecord) -> Self {
+        Self {
+            created_at: record.created_at,
+            message_id: record.message_id,
+        }
+    }
+}
+
+/// Pull-based export: each `next_chunk` fetches at most one page from the
+/// repository and returns it already encoded, so callers can stream the
+/// result without holding the room in memory.
+pub struct RoomExport {
+    repo: Arc<dyn Repository>,
+    room_id: chat::RoomId,
+    format: ExportFormat,
+    page_size: usize,
+    cursor: Option<ExportCursor>,
+    written: usize,
+    state: ExportState,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ExportState {
+    Header,
+    Rows,
+    Footer,
+    Done,
+}
+
+impl RoomExport {
+    pub(super) fn new(
+        repo: Arc<dyn Repository>,
+        room_id: chat::RoomId,
+        format: ExportFormat,
+        page_size: usize,
+    ) -> Self {
+        Self {
+            repo,
+            room_id,
+            format,
+            page_size: page_size.max(1),
+            cursor: None,
+            written: 0,
+            state: ExportState::Header,
+        }
+    }
+
+    pub fn format(&self) -> ExportFormat {
+        self.format
+    }
+
+    pub fn room_id(&self) -> chat::RoomId {
+        self.room_id
+    }
+
+    pub async fn next_chunk(&mut self) -> Result<Option<String>> {
+        loop {
+            match self.state {
+                ExportState::Header => {
+                    self.state = ExportState::Rows;
+                    if let Some(header) = header(self.format) {
+                        return Ok(Some(header.to_string()));
+                    }
+                }
+                ExportState::Rows => {
+                    let page = self
+                        .repo
+                        .export_messages(&self.room_id, self.cursor.as_ref(), self.page_size)
+                        .await?;
+                    if page.len() < self.page_size {
+                        self.state = ExportState::Footer;
+                    }
+                    let Some(last) = page.last() else {
+                        continue;
+                    };
+                    self.cursor = Some(ExportCursor::from(last));
+
+                    let mut chunk = String::new();
+                    for record in &page {
+                        encode_record(self.format, record, self.written == 0, &mut chunk);
+                        self.written += 1;
+                    }
+                    return Ok(Some(chunk));
+                }
+                ExportState::Footer => {
+                    self.state = ExportState::Done;
+                    if let Some(footer) = footer(self.format) {
+                        return Ok(Some(footer.to_string()));
+                    }
+                }
+                ExportState::Done => return Ok(None),
+            }
+        }
+    }
+}
+
+const CSV_HEADER: &str = "message_id,author_id,author,status,created_at_ms,body\n";
+
+fn header(format: ExportFormat) -> Option<&'static str> {
+    match format {
+        ExportFormat::Json => Some("["),
+        ExportFormat::Csv => Some(CSV_HEADER),
+        ExportFormat::Ndjson => None,
+    }
+}
+
+fn footer(format: ExportFormat) -> Option<&'static str> {
+    match format {
+        ExportFormat::Json => Some("]\n"),
+        ExportFormat::Csv | ExportFormat::Ndjson => None,
+    }
+}
+
+fn encode_record(
+    format: ExportFormat,
+    record: &ExportRecord,
+    first: bool,
+    out: &mut String,
+) {
+    match format {
+        ExportFormat::Json => {
+            if !first {
+                out.push(',');
+            }
+            out.push_str(&record_json(record).to_string());
+        }
+        ExportFormat::Ndjson => {
+            out.push_str(&record_json(record).to_string());
+            out.push('\n');
+        }
+        ExportFormat::Csv => {
+            let fields = [
+                record.message_id.as_uuid().to_string(),
+                record.author_id.as_uuid().to_string(),
+                record
+                    .author
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_default(),
+                record.status.to_string(),
+                timestamp_ms(record.created_at).to_string(),
+                record.body.to_string(),
+            ];
+            let line = fields
+                .iter()
+                .map(|field| csv_field(field))
+                .collect::<Vec<_>>()
+                .join(",");
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+}
+
+fn record_json(record: &ExportRecord) -> serde_json::Value {
+    serde_json::json!({
+        "message_id": record.message_id.as_uuid().to_string(),
+        "author_id": record.author_id.as_uuid().to_string(),
+        "author": record.author.as_ref().map(ToString::to_string),
+        "status": record.status.to_string(),
+        "created_at_ms": timestamp_ms(record.created_at),
+        "body": record.body.to_string(),
+    })
+}
+
+pub(super) fn timestamp_ms(value: SystemTime) -> u64 {
+    value
+        .duration_since(UNIX_EPOCH)
+        .map(|value| u64::try_from(value.as_millis()).unwrap_or(u64::MAX))
+        .unwrap_or_default()
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_fields_quote_separators_and_quotes() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, SystemTime};
+
+use bon::Builder;
+use strum_macros::{Display, EnumString};
+
+use domain::chat;
+
+/// How far back posts are compared and strikes are counted.
+pub const FLOOD_WINDOW: Duration = Duration::from_secs(10 * 60);
+/// How long the third strike inside `FLOOD_WINDOW` mutes the poster.
+pub const FLOOD_MUTE: Duration = Duration::from_secs(10 * 60);
+/// Most recent posts compared against a new one.
+pub const FLOOD_RECENT_POSTS: usize = 50;
+
+/// Posting into this many distinct rooms inside `BURST_WINDOW`, the new
+/// post included, counts as a burst.
+const BURST_ROOMS: usize = 4;
+const BURST_WINDOW: Duration = Duration::from_secs(30);
+/// Trigram similarity at or above which two bodies are near-duplicates.
+const NEAR_DUPLICATE_SIMILARITY: f64 = 0.85;
+/// Shorter normalized bodies ("ok", "thanks!") are never duplicates.
+const MIN_COMPARED_CHARS: usize = 12;
+
+/// One of the poster's own recent messages.
+#[derive(Clone, Debug, PartialEq, Builder)]
+pub struct RecentPost {
+    pub room_id: chat::RoomId,
+    pub body: chat::MessageBody,
+    pub created_at: SystemTime,
+}
+
+/// Why a post looks like flooding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Display, EnumString)]
+pub enum FloodSignal {
+    #[strum(serialize = "duplicate")]
+    Duplicate,
+    #[strum(serialize = "near_duplicate")]
+    NearDuplicate,
+    #[strum(serialize = "room_burst")]
+    RoomBurst,
+}
+
+/// What happens to a flagged post, escalating with each strike.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Display, EnumString)]
+pub enum FloodAction {
+    #[strum(serialize = "reject")]
+    Reject,
+    /// Stored as pending and queued for review.
+    #[strum(serialize = "hold")]
+    Hold,
+    /// Rejected, and the poster is muted in the room for `FLOOD_MUTE`.
+    #[strum(serialize = "mute")]
+    Mute,
+}
+
+impl FloodAction {
+    /// `strikes` includes the one just recorded.
+    pub fn for_strikes(strikes: usize) -> Self {
+        match strikes {
+            0 | 1 => FloodAction::Reject,
+            2 => FloodAction::Hold,
+            _ => FloodAction::Mute,
+        }
+    }
+}
+
+/// The strongest signal a new post raises against the poster's recent
+/// posts, if any.
+pub(super) fn detect(
+    room_id: &chat::RoomId,
+    body: &chat::MessageBody,
+    recent: &[RecentPost],
+    now: SystemTime,
+) -> Option<FloodSignal> {
+    let recent: Vec<&RecentPost> = recent
+        .iter()
+        .filter(|post| within(post.created_at, now, FLOOD_WINDOW))
+        .collect();
+
+    let normalized = normalize(&body.to_string());
+    if normalized.chars().count() >= MIN_COMPARED_CHARS {
+        let others: Vec<String> = recent
+            .iter()
+            .map(|post| normalize(&post.body.to_string()))
+            .collect();
+        if others.contains(&normalized) {
+            return Some(FloodSignal::Duplicate);
+        }
+        if others
+            .iter()
+            .any(|other| similarity(&normalized, other) >= NEAR_DUPLICATE_SIMILARITY)
+        {
+            return Some(FloodSignal::NearDuplicate);
+        }
+    }
+
+    let mut rooms: HashSet<&chat::RoomId> = recent
+        .iter()
+        .filter(|post| within(post.created_at, now, BURST_WINDOW))
+        .map(|post| &post.room_id)
+        .collect();
+    rooms.insert(room_id);
+    (rooms.len() >= BURST_ROOMS).then_some(FloodSignal::RoomBurst)
+}
+
+fn within(
+    at: SystemTime,
+    now: SystemTime,
+    window: Duration,
+) -> bool {
+    now.duration_since(at).map_or(true, |age| age <= window)
+}
+
+/// Lowercase words with punctuation and repeated whitespace dropped, so
+/// "Buy NOW!!!" and "buy now" compare equal.
+fn normalize(body: &str) -> String {
+    body.to_lowercase()
+        .split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Jaccard similarity of the two strings' character trigrams.
+fn similarity(
+    left: &str,
+    right: &str,
+) -> f64 {
+    let left = trigrams(left);
+    let right = trigrams(right);
+    let union = left.union(&right).count();
+    if union == 0 {
+        return 0.0;
+    }
+    left.intersection(&right).count() as f64 / union as f64
+}
+
+fn trigrams(text: &str) -> HashSet<[char; 3]> {
+    let mut window = VecDeque::with_capacity(3);
+    let mut grams = HashSet::new();
+    for ch in text.chars() {
+        if window.len() == 3 {
+            window.pop_front();
+        }
+        window.push_back(ch);
+        if window.len() == 3 {
+            grams.insert([window[0], window[1], window[2]]);
+        }
+    }
+    grams
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn room(value: u128) -> chat::RoomId {
+        chat::RoomId::from_uuid(uuid::Uuid::from_u128(value))
+    }
+
+    fn post(
+        room_id: u128,
+        body: &str,
+        seconds_ago: u64,
+    ) -> RecentPost {
+        RecentPost::builder()
+            .room_id(room(room_id))
+            .body(chat::MessageBody::try_new(body).expect("body"))
+            .created_at(now() - Duration::from_secs(seconds_ago))
+            .build()
+    }
+
+    fn now() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000)
+    }
+
+    fn body(text: &str) -> chat::MessageBody {
+        chat::MessageBody::try_new(text).expect("body")
+    }
+
+    #[test]
+    fn flags_repeats_across_rooms_but_not_short_or_stale_ones() {
+        let recent = vec![
+            post(1, "Buy cheap followers now!!!", 60),
+            post(1, "ok", 10),
+            post(2, "Totally different announcement text", 20 * 60),
+        ];
+
+        assert_eq!(
+            detect(&room(2), &body("buy CHEAP followers now"), &recent, now()),
+            Some(FloodSignal::Duplicate)
+        );
+        assert_eq!(
+            detect(&room(2), &body("Buy cheap followers now x2"), &recent, now()),
+            Some(FloodSignal::NearDuplicate)
+        );
+        assert_eq!(detect(&room(2), &body("ok"), &recent, now()), None);
+        assert_eq!(
+            detect(&room(2), &body("Totally different announcement text"), &recent, now()),
+            None
+        );
+    }
+
+    #[test]
+    fn flags_bursts_across_many_rooms() {
+        let recent = vec![
+            post(1, "hello there", 5),
+            post(2, "good morning", 10),
+            post(3, "anyone around?", 15),
+        ];
+
+        assert_eq!(
+            detect(&room(4), &body("hi all"), &recent, now()),
+            Some(FloodSignal::RoomBurst)
+        );
+        assert_eq!(detect(&room(3), &body("hi all"), &recent, now()), None);
+    }
+
+    #[test]
+    fn escalates_with_strikes() {
+        assert_eq!(FloodAction::for_strikes(1), FloodAction::Reject);
+        assert_eq!(FloodAction::for_strikes(2), FloodAction::Hold);
+        assert_eq!(FloodAction::for_strikes(5), FloodAction::Mute);
+    }
+}
@@ -0,0 +1,148 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bon::Builder;
+use nutype::nutype;
+
+use domain::chat;
+
+#[derive(Clone, Debug, Builder)]
+pub struct ImportTranscript {
+    pub room_id: chat::RoomId,
+    pub actor_id: chat::UserId,
+    pub transcript: ImportSource,
+}
+
+/// Raw NDJSON transcript, one message object per line. The shape matches
+/// `ExportFormat::Ndjson`, so exports can be re-imported.
+#[nutype(derive(Clone, Debug, PartialEq))]
+pub struct ImportSource(String);
+
+#[nutype(
+    sanitize(trim),
+    derive(Clone, Debug, PartialEq, Display)
+)]
+pub struct ImportErrorText(String);
+
+impl From<&str> for ImportErrorText {
+    fn from(value: &str) -> Self {
+        ImportErrorText::new(value)
+    }
+}
+
+impl From<String> for ImportErrorText {
+    fn from(value: String) -> Self {
+        ImportErrorText::new(value)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Builder)]
+pub struct ImportLineError {
+    /// 1-based line number in the transcript.
+    pub line: usize,
+    pub reason: ImportErrorText,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub pending: usize,
+    pub placeholders: usize,
+    pub errors: Vec<ImportLineError>,
+}
+
+/// A transcript line after validation. Optional fields fall back to fresh
+/// ids, the import time and the moderation policy.
+#[derive(Clone, Debug, Builder)]
+pub(super) struct ImportLine {
+    pub message_id: Option<chat::MessageId>,
+    pub author_id: Option<chat::UserId>,
+    pub author: Option<domain::user::Username>,
+    pub body: chat::MessageBody,
+    pub removed: bool,
+    pub created_at: Option<SystemTime>,
+}
+
+impl ImportLine {
+    pub(super) fn parse(line: &str) -> Result<Self, ImportErrorText> {
+        let value = serde_json::from_str::<serde_json::Value>(line)
+            .map_err(|error| ImportErrorText::from(format!("invalid json: {error}")))?;
+        let object = value
+            .as_object()
+            .ok_or_else(|| ImportErrorText::from("expected a json object"))?;
+
+        let body = object
+            .get("body")
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| ImportErrorText::from("missing body"))?;
+        let body = chat::MessageBody::try_new(body)
+            .map_err(|error| ImportErrorText::from(format!("invalid body: {error}")))?;
+
+        let author = match object.get("author").and_then(serde_json::Value::as_str) {
+            Some(value) => Some(
+                domain::user::Username::try_new(value)
+                    .map_err(|error| ImportErrorText::from(format!("invalid author: {error}")))?,
+            ),
+            None => None,
+        };
+        let author_id = uuid_field(object, "author_id").map(chat::UserId::from_uuid);
+        if author.is_none() && author_id.is_none() {
+            return Err(ImportErrorText::from("missing author and author_id"));
+        }
+
+        Ok(ImportLine::builder()
+            .maybe_message_id(uuid_field(object, "message_id").map(chat::MessageId::from_uuid))
+            .maybe_author_id(author_id)
+            .maybe_author(author)
+            .body(body)
+            .removed(
+                object.get("status").and_then(serde_json::Value::as_str)
+                    == Some(chat::MessageStatus::Removed.to_string().as_str()),
+            )
+            .maybe_created_at(
+                object
+                    .get("created_at_ms")
+                    .and_then(serde_json::Value::as_u64)
+                    .map(|value| UNIX_EPOCH + Duration::from_millis(value)),
+            )
+            .build())
+    }
+}
+
+/// Ids that don't parse are dropped rather than rejected; the importer
+/// assigns a fresh one instead.
+fn uuid_field(
+    object: &serde_json::Map<String, serde_json::Value>,
+    key: &str,
+) -> Option<uuid::Uuid> {
+    object
+        .get(key)
+        .and_then(serde_json::Value::as_str)
+        .and_then(|value| value.parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_exported_lines() {
+        let line = ImportLine::parse(
+            r#"{"message_id":"6b0c1d9e-7f4c-4a59-9d0c-1f7d2f7b9a11","author":"ada","status":"removed","created_at_ms":1700000000000,"body":"hello"}"#,
+        )
+        .expect("line");
+
+        assert!(line.message_id.is_some());
+        assert!(line.removed);
+        assert_eq!(
+            line.created_at,
+            Some(UNIX_EPOCH + Duration::from_millis(1_700_000_000_000))
+        );
+    }
+
+    #[test]
+    fn rejects_lines_without_an_author() {
+        let error = ImportLine::parse(r#"{"body":"hello"}"#).expect_err("error");
+
+        assert_eq!(error.to_string(), "missing author and author_id");
+    }
+}
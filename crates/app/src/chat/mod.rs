@@ -1,7 +1,23 @@
+mod appeal;
+mod attachment;
+mod bot;
+mod command;
 mod error;
+mod export;
+mod flood;
+mod import;
+mod moderation;
+mod pin;
+mod poll;
+mod preview;
+mod report;
+mod retention;
+mod schedule;
+mod trust;
+mod webhook;
 
 use std::sync::Arc;
-use std::time::UNIX_EPOCH;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use async_trait::async_trait;
 use bon::{bon, Builder};
@@ -9,7 +25,69 @@ use nutype::nutype;
 use strum_macros::{Display, EnumString};
 
 use domain::chat;
+pub use attachment::{
+    AttachedMessage, AttachmentDownload, AttachmentUpload, AttachmentVariant, BlobStore,
+    MAX_ATTACHMENT_BYTES, MAX_ATTACHMENTS_PER_MESSAGE, OpenAttachment, OrphanedAttachment,
+    PostAttachments, SweepAttachments, THUMBNAIL_SIZE, Thumbnailer, sniff_attachment,
+};
+use attachment::{DisabledBlobStore, DisabledThumbnailer, blob_key};
+pub use bot::{
+    BOT_COMMAND_PREFIX, Bot, BotEvent, BotPost, BotRateLimit, BotRegistry, BotTrigger,
+};
+pub use command::{
+    COMMAND_PREFIX, CommandArgs, CommandArgsError, CommandContext, CommandHandler, CommandInput,
+    CommandName, CommandNameError, CommandOutcome, CommandPermission, CommandRegistry,
+    CommandReply, DEFAULT_MUTE, MAX_MUTE,
+};
 pub use error::{Error, InvalidIdText, RepoErrorText, Result};
+pub use export::{ExportCursor, ExportFormat, ExportRecord, ExportRoom, RoomExport};
+pub use flood::{
+    FLOOD_MUTE, FLOOD_RECENT_POSTS, FLOOD_WINDOW, FloodAction, FloodSignal, RecentPost,
+};
+pub use import::{
+    ImportErrorText, ImportLineError, ImportReport, ImportSource, ImportTranscript,
+};
+use import::ImportLine;
+pub use appeal::{
+    Appeal, AppealDecision, AppealStatement, AppealStatementError, AppealStatus, DecideAppeal,
+    FileAppeal, ListAppeals, ListRemovals, Removal,
+};
+use appeal::may_decide;
+pub use trust::{Audience, SetTrustLevel, TrustLevel};
+pub use moderation::{
+    ClaimModeration, ListModerationQueue, MODERATION_CLAIM_LEASE, MODERATION_SLA,
+    ModerationClaim, ModerationOutcome, ReleaseModeration, SlaStatus,
+};
+pub use pin::{ListPins, MAX_PINS_PER_ROOM, PinMessage, PinnedMessage, RoomPins, UnpinMessage};
+pub use poll::{
+    CastVote, ClosePoll, CreatePoll, ListPolls, MAX_POLL_OPTIONS, MIN_POLL_OPTIONS,
+    PollResults, PollTally,
+};
+use poll::{valid_ballot, valid_options};
+pub use preview::{
+    CachedPreview, FAILED_PREVIEW_TTL, LINK_PREVIEW_TTL, LinkPreviewFetcher, first_url,
+};
+use preview::DisabledLinkPreviewFetcher;
+pub use report::{
+    DEFAULT_REPORT_HIDE_THRESHOLD, MessageReport, ReportCategory, ReportMessage, ReportOutcome,
+    ReportTally,
+};
+use report::reaches_threshold;
+pub use retention::{
+    PreviewPurge, PurgeMode, PurgeReport, RetentionCount, RetentionCountError,
+    RetentionDays, RetentionDaysError, RetentionPolicy, RoomPurge, RoomRetention,
+    RunPurge, SetRetention,
+};
+pub use schedule::{
+    CancelSchedule, ListSchedules, MAX_SCHEDULE_AHEAD, PostDueSchedules, ScheduleMessage,
+};
+pub use webhook::{
+    CreateWebhook, DEFAULT_INCOMING_BOT, DeleteWebhook, DeliverWebhooks, DeliveryReport,
+    FindIncomingWebhook, ListDeliveries, ListWebhooks, MAX_WEBHOOK_ATTEMPTS,
+    MAX_WEBHOOKS_PER_ROOM, PostIncoming, RegenerateIncomingWebhook, RevokeIncomingWebhook,
+    WebhookAttempt, WebhookRequest, WebhookSender, webhook_backoff,
+};
+use webhook::{DisabledWebhookSender, apply_attempt, webhook_payload};
 
 #[derive(Clone, Debug, Builder)]
 pub struct PostMessage {
@@ -27,6 +105,49 @@ pub struct ListMessages {
     pub limit: usize,
 }
 
+#[derive(Clone, Debug, Builder)]
+pub struct MarkRead {
+    pub room_id: chat::RoomId,
+    pub user_id: chat::UserId,
+    pub message_id: chat::MessageId,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct SeenBy {
+    pub room_id: chat::RoomId,
+    pub viewer_id: chat::UserId,
+    pub message_id: chat::MessageId,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct RoomUnread {
+    pub room_id: chat::RoomId,
+    pub room_name: chat::RoomName,
+    pub unread: u64,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct SearchMessages {
+    pub user_id: chat::UserId,
+    pub query: SearchQuery,
+    #[builder(default = 20)]
+    pub limit: usize,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct SearchHit {
+    pub message: chat::Message,
+    pub room_name: chat::RoomName,
+    pub rank: f32,
+    pub highlight: SearchHighlight,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct Typing {
+    pub room_id: chat::RoomId,
+    pub user_id: chat::UserId,
+}
+
 #[derive(Clone, Debug, Builder)]
 pub struct CreateRoom {
     pub name: chat::RoomName,
@@ -47,6 +168,9 @@ pub struct ModerateMessage {
     pub reviewer_id: chat::UserId,
     pub decision: ModerationDecision,
     pub reason: Option<ModerationReason>,
+    /// The queue revision the reviewer saw; a decision on any other
+    /// revision is rejected.
+    pub revision: Option<u64>,
 }
 
 #[derive(Clone, Debug, Builder)]
@@ -59,6 +183,30 @@ pub struct ModerationItem {
     pub queue_status: ModerationQueueStatus,
     pub reason: ModerationReason,
     pub created_at: TimestampText,
+    /// Member reports since the last review, oldest first.
+    #[builder(default)]
+    pub reports: Vec<MessageReport>,
+    /// When the item entered (or re-entered) the queue.
+    pub queued_at: SystemTime,
+    /// Bumped on every decision and reopen.
+    pub revision: u64,
+    pub claim: Option<ModerationClaim>,
+}
+
+impl ModerationItem {
+    pub fn age(
+        &self,
+        now: SystemTime,
+    ) -> std::time::Duration {
+        now.duration_since(self.queued_at).unwrap_or_default()
+    }
+
+    pub fn sla(
+        &self,
+        now: SystemTime,
+    ) -> SlaStatus {
+        SlaStatus::for_age(self.age(now))
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -67,6 +215,21 @@ pub enum ModerationDecision {
     Remove,
 }
 
+/// Outcome of a typing ping. `Started` is the only change subscribers need
+/// to hear about; `Refreshed` just extends the TTL.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TypingChange {
+    Started,
+    Refreshed,
+    Throttled,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TypingExpired {
+    pub room_id: chat::RoomId,
+    pub user_id: chat::UserId,
+}
+
 #[derive(Clone, Debug, Builder)]
 pub struct AuditEntry {
     pub room_id: chat::RoomId,
@@ -81,6 +244,10 @@ pub enum RoomRole {
     Member,
     #[strum(serialize = "owner")]
     Owner,
+    #[strum(serialize = "admin")]
+    Admin,
+    #[strum(serialize = "moderator")]
+    Moderator,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Display, EnumString)]
@@ -107,6 +274,46 @@ pub struct ModerationReason(String);
 )]
 pub struct TimestampText(String);
 
+#[nutype(
+    sanitize(trim),
+    validate(not_empty, len_char_max = 200),
+    derive(Clone, Debug, PartialEq, Display)
+)]
+pub struct SearchQuery(String);
+
+/// Message body excerpt with matches wrapped in `MATCH_START`/`MATCH_END`.
+#[nutype(derive(Clone, Debug, PartialEq, Display))]
+pub struct SearchHighlight(String);
+
+impl SearchHighlight {
+    pub const MATCH_START: char = '\u{2}';
+    pub const MATCH_END: char = '\u{3}';
+
+    /// Splits the excerpt into `(text, is_match)` segments.
+    pub fn segments(&self) -> Vec<(String, bool)> {
+        let value = self.to_string();
+        let mut segments = Vec::new();
+        let mut current = String::new();
+        let mut in_match = false;
+        for ch in value.chars() {
+            let toggles = (ch == Self::MATCH_START && !in_match)
+                || (ch == Self::MATCH_END && in_match);
+            if toggles {
+                if !current.is_empty() {
+                    segments.push((std::mem::take(&mut current), in_match));
+                }
+                in_match = !in_match;
+            } else {
+                current.push(ch);
+            }
+        }
+        if !current.is_empty() {
+            segments.push((current, in_match));
+        }
+        segments
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Display, EnumString)]
 pub enum AuditAction {
     #[strum(serialize = "chat.room.create")]
@@ -117,6 +324,52 @@ pub enum AuditAction {
     MessagePost,
     #[strum(serialize = "chat.message.moderate")]
     MessageModerate,
+    #[strum(serialize = "chat.message.report")]
+    MessageReport,
+    #[strum(serialize = "chat.message.flood")]
+    MessageFlood,
+    #[strum(serialize = "chat.message.appeal")]
+    MessageAppeal,
+    #[strum(serialize = "chat.message.appeal_decide")]
+    MessageAppealDecide,
+    #[strum(serialize = "chat.room.export")]
+    RoomExport,
+    #[strum(serialize = "chat.room.import")]
+    RoomImport,
+    #[strum(serialize = "chat.room.retention")]
+    RoomRetention,
+    #[strum(serialize = "chat.room.purge")]
+    RoomPurge,
+    #[strum(serialize = "chat.message.schedule")]
+    MessageSchedule,
+    #[strum(serialize = "chat.message.schedule_cancel")]
+    MessageScheduleCancel,
+    #[strum(serialize = "chat.message.pin")]
+    MessagePin,
+    #[strum(serialize = "chat.message.unpin")]
+    MessageUnpin,
+    #[strum(serialize = "chat.poll.create")]
+    PollCreate,
+    #[strum(serialize = "chat.poll.close")]
+    PollClose,
+    #[strum(serialize = "chat.room.topic")]
+    RoomTopic,
+    #[strum(serialize = "chat.member.invite")]
+    MemberInvite,
+    #[strum(serialize = "chat.member.mute")]
+    MemberMute,
+    #[strum(serialize = "chat.member.trust")]
+    MemberTrust,
+    #[strum(serialize = "chat.bot.reply")]
+    BotReply,
+    #[strum(serialize = "chat.webhook.create")]
+    WebhookCreate,
+    #[strum(serialize = "chat.webhook.delete")]
+    WebhookDelete,
+    #[strum(serialize = "chat.webhook.incoming_regenerate")]
+    IncomingWebhookRegenerate,
+    #[strum(serialize = "chat.webhook.incoming_revoke")]
+    IncomingWebhookRevoke,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Display, EnumString)]
@@ -135,6 +388,56 @@ pub enum AuditKey {
     TimestampMs,
     #[strum(serialize = "role")]
     Role,
+    #[strum(serialize = "format")]
+    Format,
+    #[strum(serialize = "imported")]
+    Imported,
+    #[strum(serialize = "failed")]
+    Failed,
+    #[strum(serialize = "mode")]
+    Mode,
+    #[strum(serialize = "max_age_days")]
+    MaxAgeDays,
+    #[strum(serialize = "max_messages")]
+    MaxMessages,
+    #[strum(serialize = "expired")]
+    Expired,
+    #[strum(serialize = "purged")]
+    Purged,
+    #[strum(serialize = "schedule_id")]
+    ScheduleId,
+    #[strum(serialize = "send_at_ms")]
+    SendAtMs,
+    #[strum(serialize = "kind")]
+    Kind,
+    #[strum(serialize = "topic")]
+    Topic,
+    #[strum(serialize = "target_user_id")]
+    TargetUserId,
+    #[strum(serialize = "muted_until_ms")]
+    MutedUntilMs,
+    #[strum(serialize = "trust_level")]
+    TrustLevel,
+    #[strum(serialize = "bot")]
+    Bot,
+    #[strum(serialize = "trigger")]
+    Trigger,
+    #[strum(serialize = "trigger_message_id")]
+    TriggerMessageId,
+    #[strum(serialize = "webhook_id")]
+    WebhookId,
+    #[strum(serialize = "url")]
+    Url,
+    #[strum(serialize = "events")]
+    Events,
+    #[strum(serialize = "category")]
+    Category,
+    #[strum(serialize = "reports")]
+    Reports,
+    #[strum(serialize = "signal")]
+    Signal,
+    #[strum(serialize = "strikes")]
+    Strikes,
 }
 
 #[nutype(
@@ -181,11 +484,322 @@ pub trait Repository: Send + Sync {
         room_id: &chat::RoomId,
         user_id: &chat::UserId,
     ) -> Result<bool>;
+    /// Every member of the room, whatever their role.
+    async fn room_members(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<Vec<chat::UserId>>;
     async fn update_message_status(
         &self,
         message_id: &chat::MessageId,
         status: chat::MessageStatus,
     ) -> Result<()>;
+    /// Moves the member's read marker to `message_id` if it is newer than the
+    /// current one. Returns `false` when the marker did not advance.
+    async fn mark_read(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+        message_id: &chat::MessageId,
+    ) -> Result<bool>;
+    async fn list_unread(
+        &self,
+        user_id: &chat::UserId,
+    ) -> Result<Vec<RoomUnread>>;
+    async fn list_readers(
+        &self,
+        room_id: &chat::RoomId,
+        message_id: &chat::MessageId,
+    ) -> Result<Vec<chat::UserId>>;
+    /// Full-text search over visible messages in rooms `user_id` belongs to,
+    /// best match first.
+    async fn search_messages(
+        &self,
+        user_id: &chat::UserId,
+        query: &SearchQuery,
+        limit: usize,
+    ) -> Result<Vec<SearchHit>>;
+    async fn member_role(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+    ) -> Result<Option<RoomRole>>;
+    /// One page of every message in the room, oldest first, strictly after
+    /// `after`.
+    async fn export_messages(
+        &self,
+        room_id: &chat::RoomId,
+        after: Option<&ExportCursor>,
+        limit: usize,
+    ) -> Result<Vec<ExportRecord>>;
+    async fn save_retention(
+        &self,
+        retention: &RoomRetention,
+    ) -> Result<()>;
+    async fn clear_retention(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<()>;
+    async fn find_retention(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<Option<RoomRetention>>;
+    async fn list_retention(&self) -> Result<Vec<RoomRetention>>;
+    async fn count_expired(
+        &self,
+        retention: &RoomRetention,
+        now: std::time::SystemTime,
+    ) -> Result<u64>;
+    /// Deletes or redacts up to `limit` of the oldest expired messages along
+    /// with their moderation rows; returns how many were affected.
+    async fn purge_expired(
+        &self,
+        retention: &RoomRetention,
+        now: std::time::SystemTime,
+        limit: usize,
+    ) -> Result<u64>;
+    async fn insert_schedule(
+        &self,
+        schedule: &chat::ScheduledMessage,
+    ) -> Result<()>;
+    async fn find_schedule(
+        &self,
+        schedule_id: &chat::ScheduleId,
+    ) -> Result<Option<chat::ScheduledMessage>>;
+    /// Pending schedules for one author in one room, soonest first.
+    async fn list_schedules(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+    ) -> Result<Vec<chat::ScheduledMessage>>;
+    /// Cancels a schedule that is still pending; false otherwise.
+    async fn cancel_schedule(
+        &self,
+        schedule_id: &chat::ScheduleId,
+    ) -> Result<bool>;
+    /// Moves up to `limit` pending schedules due at `now` to `Sending` and
+    /// returns them, so concurrent workers never claim the same row.
+    async fn claim_due_schedules(
+        &self,
+        now: std::time::SystemTime,
+        limit: usize,
+    ) -> Result<Vec<chat::ScheduledMessage>>;
+    async fn finish_schedule(
+        &self,
+        schedule_id: &chat::ScheduleId,
+        status: chat::ScheduleStatus,
+        message_id: Option<&chat::MessageId>,
+    ) -> Result<()>;
+    /// Pins a message; false when it was already pinned.
+    async fn insert_pin(
+        &self,
+        room_id: &chat::RoomId,
+        message_id: &chat::MessageId,
+        pinned_by: &chat::UserId,
+        pinned_at: std::time::SystemTime,
+    ) -> Result<bool>;
+    /// Unpins a message; false when it wasn't pinned.
+    async fn delete_pin(
+        &self,
+        message_id: &chat::MessageId,
+    ) -> Result<bool>;
+    /// Pins of visible messages in a room, most recently pinned first.
+    async fn list_pins(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<Vec<PinnedMessage>>;
+    /// Counts the same pins `list_pins` returns.
+    async fn count_pins(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<usize>;
+    async fn insert_poll(
+        &self,
+        poll: &chat::Poll,
+    ) -> Result<()>;
+    async fn find_poll(
+        &self,
+        message_id: &chat::MessageId,
+    ) -> Result<Option<chat::Poll>>;
+    /// Polls in `room_id` attached to any of `message_ids`, with tallies.
+    async fn poll_results(
+        &self,
+        room_id: &chat::RoomId,
+        message_ids: &[chat::MessageId],
+    ) -> Result<Vec<PollResults>>;
+    /// Records one ballot; false when the user already voted.
+    async fn cast_vote(
+        &self,
+        message_id: &chat::MessageId,
+        user_id: &chat::UserId,
+        option_ids: &[chat::PollOptionId],
+        voted_at: std::time::SystemTime,
+    ) -> Result<bool>;
+    /// Freezes a poll; false when it was already closed.
+    async fn close_poll(
+        &self,
+        message_id: &chat::MessageId,
+        closed_at: std::time::SystemTime,
+    ) -> Result<bool>;
+    /// Freezes every open poll whose close time is at or before `now` and
+    /// returns them.
+    async fn close_due_polls(
+        &self,
+        now: std::time::SystemTime,
+    ) -> Result<Vec<chat::Poll>>;
+    async fn set_room_topic(
+        &self,
+        room_id: &chat::RoomId,
+        topic: Option<&chat::RoomTopic>,
+    ) -> Result<()>;
+    async fn find_room_topic(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<Option<chat::RoomTopic>>;
+    /// Mutes a member until `until`, replacing any earlier mute. `muted_by`
+    /// is `None` for automatic mutes.
+    async fn save_mute(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+        until: std::time::SystemTime,
+        muted_by: Option<&chat::UserId>,
+    ) -> Result<()>;
+    async fn muted_until(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+    ) -> Result<Option<std::time::SystemTime>>;
+    /// `TrustLevel::Standard` for accounts never set.
+    async fn trust_level(
+        &self,
+        user_id: &chat::UserId,
+    ) -> Result<TrustLevel>;
+    async fn save_trust_level(
+        &self,
+        user_id: &chat::UserId,
+        level: TrustLevel,
+        set_by: &chat::UserId,
+        set_at: SystemTime,
+    ) -> Result<()>;
+    /// Owners, admins and moderators of the room.
+    async fn room_moderators(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<Vec<chat::UserId>>;
+    /// Rooms where the user is an owner, admin or moderator.
+    async fn moderated_rooms(
+        &self,
+        user_id: &chat::UserId,
+    ) -> Result<Vec<chat::RoomId>>;
+    /// The user's posts in any room since `since`, newest first.
+    async fn recent_posts(
+        &self,
+        user_id: &chat::UserId,
+        since: SystemTime,
+        limit: usize,
+    ) -> Result<Vec<RecentPost>>;
+    /// Records a flood strike and returns the user's strikes since `since`,
+    /// this one included.
+    async fn record_flood_strike(
+        &self,
+        user_id: &chat::UserId,
+        room_id: &chat::RoomId,
+        signal: FloodSignal,
+        at: SystemTime,
+        since: SystemTime,
+    ) -> Result<usize>;
+    async fn insert_webhook(
+        &self,
+        webhook: &chat::Webhook,
+    ) -> Result<()>;
+    async fn find_webhook(
+        &self,
+        webhook_id: &chat::WebhookId,
+    ) -> Result<Option<chat::Webhook>>;
+    async fn list_webhooks(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<Vec<chat::Webhook>>;
+    async fn delete_webhook(
+        &self,
+        webhook_id: &chat::WebhookId,
+    ) -> Result<bool>;
+    /// Queues `payload` for every webhook in the room subscribed to
+    /// `event`; returns how many deliveries were queued.
+    async fn enqueue_webhook_deliveries(
+        &self,
+        room_id: &chat::RoomId,
+        event: chat::WebhookEvent,
+        payload: &chat::WebhookPayload,
+        now: std::time::SystemTime,
+    ) -> Result<usize>;
+    /// Marks due pending deliveries (and ones stuck sending) as sending
+    /// and returns them with their webhook.
+    async fn claim_due_deliveries(
+        &self,
+        now: std::time::SystemTime,
+        limit: usize,
+    ) -> Result<Vec<(chat::Webhook, chat::WebhookDelivery)>>;
+    async fn save_delivery_attempt(
+        &self,
+        delivery: &chat::WebhookDelivery,
+    ) -> Result<()>;
+    async fn list_deliveries(
+        &self,
+        webhook_id: &chat::WebhookId,
+        limit: usize,
+    ) -> Result<Vec<chat::WebhookDelivery>>;
+    /// Inserts or replaces the room's incoming webhook.
+    async fn save_incoming_webhook(
+        &self,
+        webhook: &chat::IncomingWebhook,
+    ) -> Result<()>;
+    async fn find_incoming_webhook(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<Option<chat::IncomingWebhook>>;
+    async fn find_incoming_webhook_by_token(
+        &self,
+        token: &chat::IncomingWebhookToken,
+    ) -> Result<Option<chat::IncomingWebhook>>;
+    async fn delete_incoming_webhook(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<bool>;
+    async fn find_link_previews(
+        &self,
+        urls: &[chat::PreviewUrl],
+    ) -> Result<Vec<CachedPreview>>;
+    /// Inserts or refreshes the cache entry for `preview.preview.url`.
+    async fn save_link_preview(
+        &self,
+        preview: &CachedPreview,
+    ) -> Result<()>;
+    async fn insert_attachment(
+        &self,
+        attachment: &chat::Attachment,
+    ) -> Result<()>;
+    /// Only attachments whose message still exists.
+    async fn find_attachment(
+        &self,
+        attachment_id: &chat::AttachmentId,
+    ) -> Result<Option<chat::Attachment>>;
+    async fn list_attachments(
+        &self,
+        message_ids: &[chat::MessageId],
+    ) -> Result<Vec<chat::Attachment>>;
+    /// Attachments whose message was deleted or redacted by retention.
+    /// Moderator removals keep their files, since an appeal may restore
+    /// the message.
+    async fn list_orphaned_attachments(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<OrphanedAttachment>>;
+    async fn delete_attachment(
+        &self,
+        attachment_id: &chat::AttachmentId,
+    ) -> Result<()>;
 }
 
 #[async_trait]
@@ -195,17 +809,85 @@ pub trait ModerationQueue: Send + Sync {
         message_id: &chat::MessageId,
         reason: &ModerationReason,
     ) -> Result<()>;
+    /// Pending items from `rooms`, oldest queued first; only claims still
+    /// held at `now` are attached.
     async fn list_pending(
         &self,
+        rooms: &[chat::RoomId],
+        room_id: Option<&chat::RoomId>,
+        reason: Option<&ModerationReason>,
+        now: SystemTime,
         limit: usize,
     ) -> Result<Vec<ModerationItem>>;
+    /// Takes or renews the lease until `until`. False when the item is no
+    /// longer pending or another reviewer holds an unexpired lease.
+    async fn claim(
+        &self,
+        message_id: &chat::MessageId,
+        reviewer_id: &chat::UserId,
+        now: SystemTime,
+        until: SystemTime,
+    ) -> Result<bool>;
+    async fn release(
+        &self,
+        message_id: &chat::MessageId,
+        reviewer_id: &chat::UserId,
+    ) -> Result<()>;
+    /// Records the decision only if the item is still pending, matches
+    /// `revision` when given and is not leased to another reviewer; false
+    /// otherwise.
     async fn complete(
         &self,
         message_id: &chat::MessageId,
         reviewer_id: &chat::UserId,
         decision: ModerationDecision,
         reason: Option<ModerationReason>,
-    ) -> Result<()>;
+        revision: Option<u64>,
+        now: SystemTime,
+    ) -> Result<bool>;
+    /// Records a report once per member and queues the message under the
+    /// `report` reason, reopening a reviewed entry.
+    async fn report(
+        &self,
+        message_id: &chat::MessageId,
+        reporter_id: &chat::UserId,
+        category: ReportCategory,
+        reported_at: SystemTime,
+    ) -> Result<ReportTally>;
+    /// Files the author's appeal against a removal. False when the message
+    /// is not theirs, is not removed, or already has an appeal.
+    async fn file_appeal(
+        &self,
+        message_id: &chat::MessageId,
+        appellant_id: &chat::UserId,
+        statement: &AppealStatement,
+        filed_at: SystemTime,
+    ) -> Result<bool>;
+    async fn find_appeal(
+        &self,
+        message_id: &chat::MessageId,
+    ) -> Result<Option<Appeal>>;
+    /// Pending appeals from `rooms`, oldest first.
+    async fn list_appeals(
+        &self,
+        rooms: &[chat::RoomId],
+        limit: usize,
+    ) -> Result<Vec<Appeal>>;
+    /// The author's removed messages, newest first.
+    async fn list_removals(
+        &self,
+        author_id: &chat::UserId,
+        limit: usize,
+    ) -> Result<Vec<Removal>>;
+    /// Records the outcome if the appeal is still pending; overturning also
+    /// marks the queue entry approved. False when someone decided first.
+    async fn decide_appeal(
+        &self,
+        message_id: &chat::MessageId,
+        reviewer_id: &chat::UserId,
+        decision: AppealDecision,
+        decided_at: SystemTime,
+    ) -> Result<bool>;
 }
 
 #[async_trait]
@@ -225,6 +907,57 @@ pub trait AuditLog: Send + Sync {
     ) -> Result<()>;
 }
 
+/// Ephemeral typing state keyed by room and user. Implementations keep it
+/// in memory only; nothing here is ever persisted.
+pub trait TypingStore: Send + Sync {
+    fn start(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+        now: std::time::SystemTime,
+    ) -> TypingChange;
+    fn stop(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+    ) -> bool;
+    fn expire(
+        &self,
+        now: std::time::SystemTime,
+    ) -> Vec<TypingExpired>;
+}
+
+/// Resolves accounts that post without signing in: transcript authors
+/// during imports and bots.
+#[async_trait]
+pub trait AuthorDirectory: Send + Sync {
+    async fn user_exists(
+        &self,
+        user_id: &chat::UserId,
+    ) -> Result<bool>;
+    async fn find_by_username(
+        &self,
+        username: &domain::user::Username,
+    ) -> Result<Option<chat::UserId>>;
+    /// Creates an account without credentials so imported messages keep an
+    /// author that cannot sign in.
+    async fn create_placeholder(
+        &self,
+        username: &domain::user::Username,
+    ) -> Result<chat::UserId>;
+    /// Finds or creates the bot account. Fails with `BotNameTaken` when a
+    /// human already has the username.
+    async fn ensure_bot(
+        &self,
+        username: &domain::user::Username,
+    ) -> Result<chat::UserId>;
+    /// Any account kind, including ones that cannot sign in.
+    async fn find_username(
+        &self,
+        user_id: &chat::UserId,
+    ) -> Result<Option<domain::user::Username>>;
+}
+
 pub trait Clock: Send + Sync {
     fn now(&self) -> std::time::SystemTime;
 }
@@ -232,6 +965,12 @@ pub trait Clock: Send + Sync {
 pub trait IdGenerator: Send + Sync {
     fn new_room_id(&self) -> chat::RoomId;
     fn new_message_id(&self) -> chat::MessageId;
+    fn new_schedule_id(&self) -> chat::ScheduleId;
+    fn new_poll_option_id(&self) -> chat::PollOptionId;
+    fn new_webhook_id(&self) -> chat::WebhookId;
+    /// Unguessable: the token is the only credential of an incoming webhook.
+    fn new_incoming_webhook_token(&self) -> chat::IncomingWebhookToken;
+    fn new_attachment_id(&self) -> chat::AttachmentId;
 }
 
 #[derive(Clone)]
@@ -240,29 +979,20 @@ pub struct Service {
     moderation: Arc<dyn ModerationQueue>,
     rate_limiter: Arc<dyn RateLimiter>,
     audit: Arc<dyn AuditLog>,
+    typing: Arc<dyn TypingStore>,
+    authors: Arc<dyn AuthorDirectory>,
     clock: Arc<dyn Clock>,
     ids: Arc<dyn IdGenerator>,
+    commands: Arc<CommandRegistry>,
+    bots: Arc<BotRegistry>,
+    webhooks: Arc<dyn WebhookSender>,
+    previews: Arc<dyn LinkPreviewFetcher>,
+    blobs: Arc<dyn BlobStore>,
+    thumbnails: Arc<dyn Thumbnailer>,
+    report_threshold: usize,
 }
 
 impl Service {
-    pub fn new(
-        repo: Arc<dyn Repository>,
-        moderation: Arc<dyn ModerationQueue>,
-        rate_limiter: Arc<dyn RateLimiter>,
-        audit: Arc<dyn AuditLog>,
-        clock: Arc<dyn Clock>,
-        ids: Arc<dyn IdGenerator>,
-    ) -> Self {
-        Self {
-            repo,
-            moderation,
-            rate_limiter,
-            audit,
-            clock,
-            ids,
-        }
-    }
-
     pub async fn create_room(
         &self,
         command: CreateRoom,
@@ -317,7 +1047,15 @@ impl Service {
             ))
             .await?;
 
-        Ok(())
+        self.emit(
+            command.room_id,
+            chat::WebhookEvent::MemberJoined,
+            serde_json::json!({
+                "user_id": command.user_id.as_uuid().to_string(),
+                "role": command.role.to_string(),
+            }),
+        )
+        .await
     }
 
     pub async fn list_messages(
@@ -336,16 +1074,80 @@ impl Service {
             return Err(Error::NotMember);
         }
 
-        self.repo
+        let messages = self
+            .repo
             .list_messages(&command.room_id, command.limit)
-            .await
+            .await?;
+        if !messages
+            .iter()
+            .any(|message| message.status == chat::MessageStatus::Quarantined)
+        {
+            return Ok(messages);
+        }
+        let moderator = self
+            .is_room_moderator(&command.room_id, &command.user_id)
+            .await?;
+        Ok(messages
+            .into_iter()
+            .filter(|message| {
+                message.status != chat::MessageStatus::Quarantined
+                    || moderator
+                    || message.user_id == command.user_id
+            })
+            .collect())
     }
 
+    /// Pending items from the rooms the reviewer moderates; `Forbidden`
+    /// when they moderate none.
     pub async fn list_moderation_queue(
         &self,
-        limit: usize,
+        command: ListModerationQueue,
     ) -> Result<Vec<ModerationItem>> {
-        self.moderation.list_pending(limit).await
+        let rooms = self.repo.moderated_rooms(&command.reviewer_id).await?;
+        if rooms.is_empty() {
+            return Err(Error::Forbidden);
+        }
+        self.moderation
+            .list_pending(
+                &rooms,
+                command.room_id.as_ref(),
+                command.reason.as_ref(),
+                self.clock.now(),
+                command.limit,
+            )
+            .await
+    }
+
+    /// Leases a pending item to the reviewer for `MODERATION_CLAIM_LEASE`;
+    /// returns when the lease ends. The reviewer must moderate the room.
+    pub async fn claim_moderation(
+        &self,
+        command: ClaimModeration,
+    ) -> Result<SystemTime> {
+        self.moderated_message(&command.message_id, &command.reviewer_id)
+            .await?;
+        let now = self.clock.now();
+        let until = now + MODERATION_CLAIM_LEASE;
+        if !self
+            .moderation
+            .claim(&command.message_id, &command.reviewer_id, now, until)
+            .await?
+        {
+            return Err(Error::ModerationConflict);
+        }
+        Ok(until)
+    }
+
+    /// Drops the reviewer's own lease; other leases are left alone.
+    pub async fn release_moderation(
+        &self,
+        command: ReleaseModeration,
+    ) -> Result<()> {
+        self.moderated_message(&command.message_id, &command.reviewer_id)
+            .await?;
+        self.moderation
+            .release(&command.message_id, &command.reviewer_id)
+            .await
     }
 
     pub async fn find_room_by_name(
@@ -359,6 +1161,16 @@ impl Service {
         &self,
         command: PostMessage,
     ) -> Result<chat::Message> {
+        self.check_post(&command).await?;
+        let held = self.screen_flood(&command).await?;
+        self.store_message(command, held).await
+    }
+
+    /// Room, membership, mute and rate limit checks every post goes through.
+    async fn check_post(
+        &self,
+        command: &PostMessage,
+    ) -> Result<()> {
         let Some(_) = self.repo.find_room(&command.room_id).await? else {
             return Err(Error::RoomNotFound);
         };
@@ -371,15 +1183,92 @@ impl Service {
             return Err(Error::NotMember);
         }
 
+        if self.is_muted(&command.room_id, &command.user_id).await? {
+            return Err(Error::Muted);
+        }
+
         self.rate_limiter
             .check(&command.room_id, &command.user_id)
             .await?;
+        Ok(())
+    }
 
-        let requires_moderation = should_moderate(&command.body);
-        let status = if requires_moderation {
-            chat::MessageStatus::Pending
+    /// Compares the post with the author's recent ones in every room. Each
+    /// flagged post is a strike; strikes inside `FLOOD_WINDOW` escalate from
+    /// rejecting the post, to holding it for review, to muting the author.
+    /// Returns whether to hold the post.
+    async fn screen_flood(
+        &self,
+        command: &PostMessage,
+    ) -> Result<bool> {
+        let now = self.clock.now();
+        let since = now.checked_sub(FLOOD_WINDOW).unwrap_or(UNIX_EPOCH);
+        let recent = self
+            .repo
+            .recent_posts(&command.user_id, since, FLOOD_RECENT_POSTS)
+            .await?;
+        let Some(signal) = flood::detect(&command.room_id, &command.body, &recent, now) else {
+            return Ok(false);
+        };
+
+        let strikes = self
+            .repo
+            .record_flood_strike(&command.user_id, &command.room_id, signal, now, since)
+            .await?;
+        let action = FloodAction::for_strikes(strikes);
+        let mut details = vec![
+            (AuditKey::Signal, AuditValue::new(signal.to_string())),
+            (AuditKey::Strikes, AuditValue::new(strikes.to_string())),
+            (AuditKey::Decision, AuditValue::new(action.to_string())),
+        ];
+        if action == FloodAction::Mute {
+            let until = now + FLOOD_MUTE;
+            self.repo
+                .save_mute(&command.room_id, &command.user_id, until, None)
+                .await?;
+            details.push((
+                AuditKey::MutedUntilMs,
+                AuditValue::new(
+                    until
+                        .duration_since(UNIX_EPOCH)
+                        .map(|value| value.as_millis().to_string())
+                        .unwrap_or_default(),
+                ),
+            ));
+        }
+        self.audit
+            .record(self.audit_entry(
+                command.room_id,
+                command.user_id,
+                AuditAction::MessageFlood,
+                details,
+            ))
+            .await?;
+
+        match action {
+            FloodAction::Reject => Err(Error::Flooded),
+            FloodAction::Hold => Ok(true),
+            FloodAction::Mute => Err(Error::Muted),
+        }
+    }
+
+    /// Inserts a checked post, queues it for moderation when needed and
+    /// audits it under the author. `held` posts wait for review.
+    async fn store_message(
+        &self,
+        command: PostMessage,
+        held: bool,
+    ) -> Result<chat::Message> {
+        let quarantined =
+            self.repo.trust_level(&command.user_id).await? == TrustLevel::Quarantined;
+        let (status, queue_reason) = if quarantined {
+            (chat::MessageStatus::Quarantined, Some("quarantine"))
+        } else if held {
+            (chat::MessageStatus::Pending, Some("flood"))
+        } else if should_moderate(&command.body) {
+            (chat::MessageStatus::Pending, Some("auto"))
         } else {
-            chat::MessageStatus::Visible
+            (chat::MessageStatus::Visible, None)
         };
 
         let message = chat::Message {
@@ -393,12 +1282,13 @@ impl Service {
         };
 
         self.repo.insert_message(&message).await?;
+        self.typing.stop(&message.room_id, &message.user_id);
 
-        if requires_moderation {
+        if let Some(reason) = queue_reason {
             self.moderation
                 .enqueue(
                     &message.id,
-                    &ModerationReason::try_new("auto")
+                    &ModerationReason::try_new(reason)
                         .expect("moderation reason"),
                 )
                 .await?;
@@ -426,150 +1316,3318 @@ impl Service {
             ))
             .await?;
 
+        if status == chat::MessageStatus::Visible {
+            self.emit(
+                message.room_id,
+                chat::WebhookEvent::MessagePosted,
+                message_json(&message),
+            )
+            .await?;
+        }
+
         Ok(message)
     }
 
-    pub async fn moderate_message(
+    async fn is_muted(
         &self,
-        command: ModerateMessage,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+    ) -> Result<bool> {
+        Ok(self
+            .repo
+            .muted_until(room_id, user_id)
+            .await?
+            .is_some_and(|until| until > self.clock.now()))
+    }
+
+    pub async fn check_membership(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
     ) -> Result<()> {
+        if self.repo.is_member(room_id, user_id).await? {
+            Ok(())
+        } else {
+            Err(Error::NotMember)
+        }
+    }
+
+    pub async fn mark_read(
+        &self,
+        command: MarkRead,
+    ) -> Result<bool> {
+        self.check_membership(&command.room_id, &command.user_id)
+            .await?;
         let Some(message) =
             self.repo.find_message(&command.message_id).await?
         else {
             return Err(Error::MessageNotFound);
         };
+        if message.room_id != command.room_id {
+            return Err(Error::MessageNotFound);
+        }
 
-        let status = match command.decision {
-            ModerationDecision::Approve => chat::MessageStatus::Visible,
-            ModerationDecision::Remove => chat::MessageStatus::Removed,
-        };
+        self.repo
+            .mark_read(&command.room_id, &command.user_id, &command.message_id)
+            .await
+    }
+
+    pub async fn list_unread(
+        &self,
+        user_id: &chat::UserId,
+    ) -> Result<Vec<RoomUnread>> {
+        self.repo.list_unread(user_id).await
+    }
+
+    /// Who should receive the room's read receipts and unread badges.
+    pub async fn room_members(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<Vec<chat::UserId>> {
+        self.repo.room_members(room_id).await
+    }
 
+    pub async fn seen_by(
+        &self,
+        command: SeenBy,
+    ) -> Result<Vec<chat::UserId>> {
+        self.check_membership(&command.room_id, &command.viewer_id)
+            .await?;
         self.repo
-            .update_message_status(&command.message_id, status)
+            .list_readers(&command.room_id, &command.message_id)
+            .await
+    }
+
+    pub async fn search_messages(
+        &self,
+        command: SearchMessages,
+    ) -> Result<Vec<SearchHit>> {
+        self.repo
+            .search_messages(&command.user_id, &command.query, command.limit)
+            .await
+    }
+
+    /// Starts a transcript export for room owners and admins. The audit entry
+    /// is written up front, before any rows are streamed.
+    pub async fn export_room(
+        &self,
+        command: ExportRoom,
+    ) -> Result<RoomExport> {
+        self.require_room_admin(&command.room_id, &command.user_id)
             .await?;
 
-        self.moderation
-            .complete(
-                &command.message_id,
-                &command.reviewer_id,
-                command.decision,
-                command.reason.clone(),
-            )
+        self.audit
+            .record(self.audit_entry(
+                command.room_id,
+                command.user_id,
+                AuditAction::RoomExport,
+                vec![(
+                    AuditKey::Format,
+                    AuditValue::new(command.format.to_string()),
+                )],
+            ))
+            .await?;
+
+        Ok(RoomExport::new(
+            self.repo.clone(),
+            command.room_id,
+            command.format,
+            command.page_size,
+        ))
+    }
+
+    /// Imports an NDJSON transcript into a room. Rate limits are skipped but
+    /// every line still goes through the moderation policy; bad lines are
+    /// reported and skipped rather than aborting the import.
+    pub async fn import_transcript(
+        &self,
+        command: ImportTranscript,
+    ) -> Result<ImportReport> {
+        self.require_room_admin(&command.room_id, &command.actor_id)
             .await?;
 
+        let mut report = ImportReport::default();
+        let source = command.transcript.into_inner();
+        for (index, line) in source.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let outcome = match ImportLine::parse(line) {
+                Ok(parsed) => self.import_line(&command.room_id, parsed, &mut report).await,
+                Err(reason) => Err(reason),
+            };
+            if let Err(reason) = outcome {
+                report.errors.push(
+                    ImportLineError::builder()
+                        .line(index + 1)
+                        .reason(reason)
+                        .build(),
+                );
+            }
+        }
+
         self.audit
             .record(self.audit_entry(
-                message.room_id,
-                command.reviewer_id,
-                AuditAction::MessageModerate,
+                command.room_id,
+                command.actor_id,
+                AuditAction::RoomImport,
                 vec![
                     (
-                        AuditKey::MessageId,
-                        AuditValue::new(
-                            message.id.as_uuid().to_string(),
-                        )
-                        ,
-                    ),
-                    (
-                        AuditKey::Decision,
-                        AuditValue::new(format!(
-                            "{:?}",
-                            command.decision
-                        ))
-                        ,
+                        AuditKey::Imported,
+                        AuditValue::new(report.imported.to_string()),
                     ),
                     (
-                        AuditKey::Reason,
-                        command
-                            .reason
-                            .clone()
-                            .map(|reason| {
-                                AuditValue::new(reason.to_string())
-                                    
-                            })
-                            .unwrap_or_else(|| {
-                                AuditValue::new("")
-                                    
-                            }),
+                        AuditKey::Failed,
+                        AuditValue::new(report.errors.len().to_string()),
                     ),
                 ],
             ))
             .await?;
 
-        Ok(())
+        Ok(report)
     }
-}
 
-fn should_moderate(
-    body: &chat::MessageBody,
-) -> bool {
-    let value = body.to_string();
-    value.len() > 300 || LinkPrefix::is_present(&value)
-}
+    pub async fn set_retention(
+        &self,
+        command: SetRetention,
+    ) -> Result<()> {
+        self.require_room_admin(&command.room_id, &command.user_id)
+            .await?;
 
-#[derive(Clone, Copy, Debug)]
-enum LinkPrefix {
-    Http,
-    Https,
-}
+        let metadata = match &command.policy {
+            Some(policy) => {
+                self.repo
+                    .save_retention(
+                        &RoomRetention::builder()
+                            .room_id(command.room_id)
+                            .policy(policy.clone())
+                            .set_by(command.user_id)
+                            .build(),
+                    )
+                    .await?;
+                vec![
+                    (AuditKey::Mode, AuditValue::new(policy.mode.to_string())),
+                    (
+                        AuditKey::MaxAgeDays,
+                        AuditValue::new(
+                            policy
+                                .max_age
+                                .map(|value| value.to_string())
+                                .unwrap_or_default(),
+                        ),
+                    ),
+                    (
+                        AuditKey::MaxMessages,
+                        AuditValue::new(
+                            policy
+                                .max_messages
+                                .map(|value| value.to_string())
+                                .unwrap_or_default(),
+                        ),
+                    ),
+                ]
+            }
+            None => {
+                self.repo.clear_retention(&command.room_id).await?;
+                vec![(AuditKey::Mode, AuditValue::new("forever"))]
+            }
+        };
 
-impl LinkPrefix {
-    fn as_str(self) -> &'static str {
-        match self {
-            LinkPrefix::Http => "http://",
-            LinkPrefix::Https => "https://",
+        self.audit
+            .record(self.audit_entry(
+                command.room_id,
+                command.user_id,
+                AuditAction::RoomRetention,
+                metadata,
+            ))
+            .await
+    }
+
+    pub async fn find_retention(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<Option<RoomRetention>> {
+        self.repo.find_retention(room_id).await
+    }
+
+    /// Applies retention policies in batches. Dry runs only count what would
+    /// be removed; real runs record one audit entry per room they touched.
+    pub async fn purge_expired(
+        &self,
+        command: RunPurge,
+    ) -> Result<PurgeReport> {
+        let now = self.clock.now();
+        let policies = match &command.room_id {
+            Some(room_id) => self
+                .repo
+                .find_retention(room_id)
+                .await?
+                .into_iter()
+                .collect(),
+            None => self.repo.list_retention().await?,
+        };
+        let batch_size = command.batch_size.max(1);
+
+        let mut report = PurgeReport {
+            dry_run: command.dry_run,
+            rooms: Vec::with_capacity(policies.len()),
+        };
+        for retention in policies {
+            let expired = self.repo.count_expired(&retention, now).await?;
+            let mut purged = 0;
+            if !command.dry_run && expired > 0 {
+                loop {
+                    let batch = self
+                        .repo
+                        .purge_expired(&retention, now, batch_size)
+                        .await?;
+                    purged += batch;
+                    if batch < batch_size as u64 {
+                        break;
+                    }
+                }
+
+                self.audit
+                    .record(self.audit_entry(
+                        retention.room_id,
+                        retention.set_by,
+                        AuditAction::RoomPurge,
+                        vec![
+                            (
+                                AuditKey::Mode,
+                                AuditValue::new(retention.policy.mode.to_string()),
+                            ),
+                            (AuditKey::Expired, AuditValue::new(expired.to_string())),
+                            (AuditKey::Purged, AuditValue::new(purged.to_string())),
+                        ],
+                    ))
+                    .await?;
+            }
+
+            report.rooms.push(
+                RoomPurge::builder()
+                    .room_id(retention.room_id)
+                    .mode(retention.policy.mode)
+                    .expired(expired)
+                    .purged(purged)
+                    .build(),
+            );
         }
+
+        Ok(report)
     }
 
-    fn is_present(value: &str) -> bool {
-        [Self::Http, Self::Https]
-            .iter()
-            .any(|prefix| value.contains(prefix.as_str()))
+    pub async fn preview_purge(
+        &self,
+        command: PreviewPurge,
+    ) -> Result<PurgeReport> {
+        self.require_room_admin(&command.room_id, &command.user_id)
+            .await?;
+        self.purge_expired(
+            RunPurge::builder()
+                .room_id(command.room_id)
+                .dry_run(true)
+                .build(),
+        )
+        .await
     }
-}
 
-impl Service {
-    fn audit_entry(
+    pub async fn schedule_message(
         &self,
-        room_id: chat::RoomId,
-        actor_id: chat::UserId,
-        action: AuditAction,
-        mut metadata: Vec<(AuditKey, AuditValue)>,
-    ) -> AuditEntry {
-        let timestamp = self
-            .clock
-            .now()
-            .duration_since(UNIX_EPOCH)
-            .map(|value| value.as_millis().to_string())
-            .unwrap_or_else(|_| "0".to_string());
+        command: ScheduleMessage,
+    ) -> Result<chat::ScheduledMessage> {
+        self.check_membership(&command.room_id, &command.user_id)
+            .await?;
 
-        metadata.push((
-            AuditKey::TimestampMs,
-            AuditValue::new(timestamp),
-        ));
+        let now = self.clock.now();
+        if command.send_at <= now || command.send_at > now + MAX_SCHEDULE_AHEAD {
+            return Err(Error::InvalidSchedule);
+        }
 
-        AuditEntry::builder()
-            .room_id(room_id)
-            .actor_id(actor_id)
-            .action(action)
-            .metadata(metadata)
-            .build()
+        let schedule = chat::ScheduledMessage::builder()
+            .id(self.ids.new_schedule_id())
+            .room_id(command.room_id)
+            .user_id(command.user_id)
+            .body(command.body)
+            .send_at(command.send_at)
+            .status(chat::ScheduleStatus::Pending)
+            .build();
+        self.repo.insert_schedule(&schedule).await?;
+
+        self.audit
+            .record(self.audit_entry(
+                schedule.room_id,
+                schedule.user_id,
+                AuditAction::MessageSchedule,
+                vec![
+                    (
+                        AuditKey::ScheduleId,
+                        AuditValue::new(schedule.id.as_uuid().to_string()),
+                    ),
+                    (
+                        AuditKey::SendAtMs,
+                        AuditValue::new(
+                            schedule
+                                .send_at
+                                .duration_since(UNIX_EPOCH)
+                                .map(|value| value.as_millis().to_string())
+                                .unwrap_or_default(),
+                        ),
+                    ),
+                ],
+            ))
+            .await?;
+
+        Ok(schedule)
     }
-}
 
-#[bon]
-impl Service {
-    #[builder]
-    pub fn builder(
-        #[builder(setters(name = with_repo))] repo: Arc<dyn Repository>,
-        #[builder(setters(name = with_moderation_queue))]
-        moderation: Arc<dyn ModerationQueue>,
-        #[builder(setters(name = with_rate_limiter))]
-        rate_limiter: Arc<dyn RateLimiter>,
-        #[builder(setters(name = with_audit_log))] audit: Arc<dyn AuditLog>,
-        #[builder(setters(name = with_clock))] clock: Arc<dyn Clock>,
-        #[builder(setters(name = with_id_generator))] ids: Arc<dyn IdGenerator>,
-    ) -> Self {
-        Self::new(repo, moderation, rate_limiter, audit, clock, ids)
+    /// Only the author can cancel, and only while the schedule is pending.
+    pub async fn cancel_schedule(
+        &self,
+        command: CancelSchedule,
+    ) -> Result<chat::ScheduledMessage> {
+        let Some(schedule) = self.repo.find_schedule(&command.schedule_id).await?
+        else {
+            return Err(Error::ScheduleNotFound);
+        };
+        if schedule.user_id != command.user_id {
+            return Err(Error::ScheduleNotFound);
+        }
+        if !self.repo.cancel_schedule(&schedule.id).await? {
+            return Err(Error::InvalidSchedule);
+        }
+
+        self.audit
+            .record(self.audit_entry(
+                schedule.room_id,
+                schedule.user_id,
+                AuditAction::MessageScheduleCancel,
+                vec![(
+                    AuditKey::ScheduleId,
+                    AuditValue::new(schedule.id.as_uuid().to_string()),
+                )],
+            ))
+            .await?;
+
+        Ok(schedule)
+    }
+
+    pub async fn list_schedules(
+        &self,
+        command: ListSchedules,
+    ) -> Result<Vec<chat::ScheduledMessage>> {
+        self.check_membership(&command.room_id, &command.user_id)
+            .await?;
+        self.repo
+            .list_schedules(&command.room_id, &command.user_id)
+            .await
+    }
+
+    /// Posts every schedule due by the injected clock through
+    /// `post_message`, so rate limits and moderation apply as usual.
+    /// Rate-limited schedules go back to pending for the next run; other
+    /// failures are final.
+    pub async fn post_due_schedules(
+        &self,
+        command: PostDueSchedules,
+    ) -> Result<Vec<(chat::ScheduledMessage, chat::Message)>> {
+        let due = self
+            .repo
+            .claim_due_schedules(self.clock.now(), command.limit)
+            .await?;
+
+        let mut posted = Vec::with_capacity(due.len());
+        for schedule in due {
+            let client_id = chat::ClientId::try_new(format!(
+                "schedule:{}",
+                schedule.id.as_uuid()
+            ))
+            .ok();
+            let result = self
+                .post_message(
+                    PostMessage::builder()
+                        .room_id(schedule.room_id)
+                        .user_id(schedule.user_id)
+                        .body(schedule.body.clone())
+                        .maybe_client_id(client_id)
+                        .build(),
+                )
+                .await;
+
+            match result {
+                Ok(message) => {
+                    self.repo
+                        .finish_schedule(
+                            &schedule.id,
+                            chat::ScheduleStatus::Sent,
+                            Some(&message.id),
+                        )
+                        .await?;
+                    posted.push((schedule, message));
+                }
+                Err(Error::RateLimited) => {
+                    self.repo
+                        .finish_schedule(&schedule.id, chat::ScheduleStatus::Pending, None)
+                        .await?;
+                }
+                Err(error) => {
+                    tracing::warn!(
+                        schedule_id = %schedule.id.as_uuid(),
+                        %error,
+                        "scheduled message failed"
+                    );
+                    self.repo
+                        .finish_schedule(&schedule.id, chat::ScheduleStatus::Failed, None)
+                        .await?;
+                }
+            }
+        }
+
+        Ok(posted)
+    }
+
+    /// Owners, admins and moderators may pin and unpin messages in their room.
+    pub async fn can_moderate_room(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+    ) -> Result<bool> {
+        Ok(matches!(
+            self.repo.member_role(room_id, user_id).await?,
+            Some(RoomRole::Owner | RoomRole::Admin | RoomRole::Moderator)
+        ))
+    }
+
+    /// Only visible messages can be pinned, at most `MAX_PINS_PER_ROOM` per
+    /// room. Returns the room's pins after the change.
+    pub async fn pin_message(
+        &self,
+        command: PinMessage,
+    ) -> Result<RoomPins> {
+        let message = self
+            .repo
+            .find_message(&command.message_id)
+            .await?
+            .filter(|message| message.status == chat::MessageStatus::Visible)
+            .ok_or(Error::MessageNotFound)?;
+        self.require_room_moderator(&message.room_id, &command.user_id)
+            .await?;
+        if self.repo.count_pins(&message.room_id).await? >= MAX_PINS_PER_ROOM {
+            return Err(Error::PinLimitReached);
+        }
+
+        if self
+            .repo
+            .insert_pin(
+                &message.room_id,
+                &message.id,
+                &command.user_id,
+                self.clock.now(),
+            )
+            .await?
+        {
+            self.audit
+                .record(self.audit_entry(
+                    message.room_id,
+                    command.user_id,
+                    AuditAction::MessagePin,
+                    vec![(
+                        AuditKey::MessageId,
+                        AuditValue::new(message.id.as_uuid().to_string()),
+                    )],
+                ))
+                .await?;
+        }
+
+        self.room_pins(message.room_id).await
+    }
+
+    pub async fn unpin_message(
+        &self,
+        command: UnpinMessage,
+    ) -> Result<RoomPins> {
+        let message = self
+            .repo
+            .find_message(&command.message_id)
+            .await?
+            .ok_or(Error::MessageNotFound)?;
+        self.require_room_moderator(&message.room_id, &command.user_id)
+            .await?;
+
+        if self.repo.delete_pin(&message.id).await? {
+            self.audit
+                .record(self.audit_entry(
+                    message.room_id,
+                    command.user_id,
+                    AuditAction::MessageUnpin,
+                    vec![(
+                        AuditKey::MessageId,
+                        AuditValue::new(message.id.as_uuid().to_string()),
+                    )],
+                ))
+                .await?;
+        }
+
+        self.room_pins(message.room_id).await
+    }
+
+    async fn room_pins(
+        &self,
+        room_id: chat::RoomId,
+    ) -> Result<RoomPins> {
+        Ok(RoomPins::builder()
+            .room_id(room_id)
+            .pins(self.repo.list_pins(&room_id).await?)
+            .build())
+    }
+
+    pub async fn list_pins(
+        &self,
+        command: ListPins,
+    ) -> Result<Vec<PinnedMessage>> {
+        self.check_membership(&command.room_id, &command.user_id)
+            .await?;
+        self.repo.list_pins(&command.room_id).await
+    }
+
+    pub async fn create_poll(
+        &self,
+        command: CreatePoll,
+    ) -> Result<(chat::Message, PollResults)> {
+        if !valid_options(&command.options)
+            || command
+                .closes_at
+                .is_some_and(|closes_at| closes_at <= self.clock.now())
+        {
+            return Err(Error::InvalidPoll);
+        }
+
+        let message = self
+            .post_message(
+                PostMessage::builder()
+                    .room_id(command.room_id)
+                    .user_id(command.user_id)
+                    .body(command.question)
+                    .build(),
+            )
+            .await?;
+        let poll = chat::Poll::builder()
+            .message_id(message.id)
+            .room_id(message.room_id)
+            .kind(command.kind)
+            .options(
+                command
+                    .options
+                    .into_iter()
+                    .map(|label| {
+                        chat::PollOption::builder()
+                            .id(self.ids.new_poll_option_id())
+                            .label(label)
+                            .build()
+                    })
+                    .collect(),
+            )
+            .maybe_closes_at(command.closes_at)
+            .build();
+        self.repo.insert_poll(&poll).await?;
+
+        self.audit
+            .record(self.audit_entry(
+                message.room_id,
+                message.user_id,
+                AuditAction::PollCreate,
+                vec![
+                    (
+                        AuditKey::MessageId,
+                        AuditValue::new(message.id.as_uuid().to_string()),
+                    ),
+                    (AuditKey::Kind, AuditValue::new(poll.kind.to_string())),
+                ],
+            ))
+            .await?;
+
+        let results = PollResults::builder()
+            .tallies(
+                poll.options
+                    .iter()
+                    .map(|option| PollTally::builder().option(option.clone()).votes(0).build())
+                    .collect(),
+            )
+            .poll(poll)
+            .author_id(message.user_id)
+            .voters(0)
+            .build();
+        Ok((message, results))
+    }
+
+    pub async fn cast_vote(
+        &self,
+        command: CastVote,
+    ) -> Result<PollResults> {
+        let poll = self.visible_poll(&command.message_id).await?;
+        self.check_membership(&poll.room_id, &command.user_id)
+            .await?;
+        if poll.is_closed(self.clock.now()) {
+            return Err(Error::PollClosed);
+        }
+        let ballot = valid_ballot(&poll, &command.option_ids).ok_or(Error::InvalidPoll)?;
+
+        if !self
+            .repo
+            .cast_vote(&poll.message_id, &command.user_id, &ballot, self.clock.now())
+            .await?
+        {
+            return Err(Error::AlreadyVoted);
+        }
+
+        self.current_results(&poll).await
+    }
+
+    /// The poll author or a room moderator can close a poll early; results
+    /// are frozen from then on.
+    pub async fn close_poll(
+        &self,
+        command: ClosePoll,
+    ) -> Result<PollResults> {
+        let poll = self.visible_poll(&command.message_id).await?;
+        let author = self
+            .repo
+            .find_message(&poll.message_id)
+            .await?
+            .map(|message| message.user_id);
+        if author != Some(command.user_id) {
+            self.require_room_moderator(&poll.room_id, &command.user_id)
+                .await?;
+        }
+        if poll.closed_at.is_some() {
+            return Err(Error::PollClosed);
+        }
+
+        if self
+            .repo
+            .close_poll(&poll.message_id, self.clock.now())
+            .await?
+        {
+            self.audit
+                .record(self.audit_entry(
+                    poll.room_id,
+                    command.user_id,
+                    AuditAction::PollClose,
+                    vec![(
+                        AuditKey::MessageId,
+                        AuditValue::new(poll.message_id.as_uuid().to_string()),
+                    )],
+                ))
+                .await?;
+        }
+
+        let poll = self.visible_poll(&command.message_id).await?;
+        let results = self.current_results(&poll).await?;
+        self.emit_poll_closed(&results).await?;
+        Ok(results)
+    }
+
+    pub async fn list_polls(
+        &self,
+        command: ListPolls,
+    ) -> Result<Vec<PollResults>> {
+        self.check_membership(&command.room_id, &command.user_id)
+            .await?;
+        self.repo
+            .poll_results(&command.room_id, &command.message_ids)
+            .await
+    }
+
+    /// Freezes polls whose close time has passed by the injected clock and
+    /// returns their final results.
+    pub async fn close_due_polls(&self) -> Result<Vec<PollResults>> {
+        let closed = self.repo.close_due_polls(self.clock.now()).await?;
+        let mut results = Vec::with_capacity(closed.len());
+        for poll in closed {
+            let closed = self.current_results(&poll).await?;
+            self.emit_poll_closed(&closed).await?;
+            results.push(closed);
+        }
+        Ok(results)
+    }
+
+    /// Entry point for chat input: plain bodies are posted, `/name args`
+    /// runs the registered command after membership and permission checks.
+    /// Unknown or forbidden commands answer with a reply instead of failing.
+    pub async fn handle_input(
+        &self,
+        command: PostMessage,
+    ) -> Result<CommandOutcome> {
+        let (name, args) = match CommandInput::parse(command.body.clone()) {
+            CommandInput::Plain(body) => {
+                return self
+                    .post_message(PostMessage { body, ..command })
+                    .await
+                    .map(CommandOutcome::Posted);
+            }
+            CommandInput::Command { name, args } => (name, args),
+        };
+
+        if self.repo.find_room(&command.room_id).await?.is_none() {
+            return Err(Error::RoomNotFound);
+        }
+        let role = self
+            .repo
+            .member_role(&command.room_id, &command.user_id)
+            .await?
+            .ok_or(Error::NotMember)?;
+        let Some(handler) = self.commands.find(&name) else {
+            return Ok(CommandOutcome::Reply(CommandReply::from(format!(
+                "Unknown command {COMMAND_PREFIX}{name}. Type {COMMAND_PREFIX}help for the list."
+            ))));
+        };
+        if !handler.permission().allows(role) {
+            return Ok(CommandOutcome::Reply(CommandReply::from(format!(
+                "Only room moderators can use {COMMAND_PREFIX}{name}."
+            ))));
+        }
+
+        handler
+            .run(
+                self,
+                CommandContext {
+                    room_id: command.room_id,
+                    user_id: command.user_id,
+                    role,
+                    args,
+                },
+            )
+            .await
+    }
+
+    pub async fn room_topic(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+    ) -> Result<Option<chat::RoomTopic>> {
+        self.check_membership(room_id, user_id).await?;
+        self.repo.find_room_topic(room_id).await
+    }
+
+    /// Moderators only; `None` clears the topic.
+    pub async fn set_topic(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+        topic: Option<chat::RoomTopic>,
+    ) -> Result<()> {
+        self.require_room_moderator(room_id, user_id).await?;
+        self.repo.set_room_topic(room_id, topic.as_ref()).await?;
+        self.audit
+            .record(self.audit_entry(
+                *room_id,
+                *user_id,
+                AuditAction::RoomTopic,
+                vec![(
+                    AuditKey::Topic,
+                    AuditValue::new(
+                        topic.as_ref().map(ToString::to_string).unwrap_or_default(),
+                    ),
+                )],
+            ))
+            .await?;
+        self.emit(
+            *room_id,
+            chat::WebhookEvent::RoomTopic,
+            serde_json::json!({
+                "topic": topic.map(|topic| topic.to_string()),
+                "set_by": user_id.as_uuid().to_string(),
+            }),
+        )
+        .await
+    }
+
+    /// Resolves a username to a chat user, if the account exists.
+    pub async fn find_user_by_name(
+        &self,
+        username: &str,
+    ) -> Result<Option<chat::UserId>> {
+        match domain::user::Username::try_new(username) {
+            Ok(username) => self.authors.find_by_username(&username).await,
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Stops `target_id` posting in the room for `duration`. Returns false
+    /// when the target isn't a plain member (moderators can't be muted).
+    pub async fn mute_member(
+        &self,
+        room_id: &chat::RoomId,
+        actor_id: &chat::UserId,
+        target_id: &chat::UserId,
+        duration: std::time::Duration,
+    ) -> Result<bool> {
+        self.require_room_moderator(room_id, actor_id).await?;
+        if self.repo.member_role(room_id, target_id).await? != Some(RoomRole::Member) {
+            return Ok(false);
+        }
+
+        let until = self.clock.now() + duration.min(MAX_MUTE);
+        self.repo
+            .save_mute(room_id, target_id, until, Some(actor_id))
+            .await?;
+        self.audit
+            .record(self.audit_entry(
+                *room_id,
+                *actor_id,
+                AuditAction::MemberMute,
+                vec![
+                    (
+                        AuditKey::TargetUserId,
+                        AuditValue::new(target_id.as_uuid().to_string()),
+                    ),
+                    (
+                        AuditKey::MutedUntilMs,
+                        AuditValue::new(
+                            until
+                                .duration_since(UNIX_EPOCH)
+                                .map(|value| value.as_millis().to_string())
+                                .unwrap_or_default(),
+                        ),
+                    ),
+                ],
+            ))
+            .await?;
+
+        Ok(true)
+    }
+
+    /// Sets an account's trust level from a room the actor moderates.
+    /// Returns false when the target isn't a plain member there, so
+    /// moderators can't quarantine each other.
+    pub async fn set_trust_level(
+        &self,
+        command: SetTrustLevel,
+    ) -> Result<bool> {
+        self.require_room_moderator(&command.room_id, &command.actor_id)
+            .await?;
+        if self
+            .repo
+            .member_role(&command.room_id, &command.target_id)
+            .await?
+            != Some(RoomRole::Member)
+        {
+            return Ok(false);
+        }
+
+        self.repo
+            .save_trust_level(
+                &command.target_id,
+                command.level,
+                &command.actor_id,
+                self.clock.now(),
+            )
+            .await?;
+        self.audit
+            .record(self.audit_entry(
+                command.room_id,
+                command.actor_id,
+                AuditAction::MemberTrust,
+                vec![
+                    (
+                        AuditKey::TargetUserId,
+                        AuditValue::new(command.target_id.as_uuid().to_string()),
+                    ),
+                    (
+                        AuditKey::TrustLevel,
+                        AuditValue::new(command.level.to_string()),
+                    ),
+                ],
+            ))
+            .await?;
+
+        Ok(true)
+    }
+
+    /// Who a new message goes out to: everyone, unless it is quarantined.
+    pub async fn audience(
+        &self,
+        message: &chat::Message,
+    ) -> Result<Audience> {
+        if message.status != chat::MessageStatus::Quarantined {
+            return Ok(Audience::Everyone);
+        }
+        let mut users = self.repo.room_moderators(&message.room_id).await?;
+        if !users.contains(&message.user_id) {
+            users.push(message.user_id);
+        }
+        Ok(Audience::Only(users))
+    }
+
+    /// Lets every registered bot react to a posted message. Messages from
+    /// bots and ones held for moderation are ignored, so bots never answer
+    /// each other. Replies skip the member rate limiter and count against
+    /// the bot's own limit instead; muted bots stay quiet.
+    pub async fn run_bots(
+        &self,
+        message: &chat::Message,
+    ) -> Result<Vec<BotPost>> {
+        if message.status != chat::MessageStatus::Visible {
+            return Ok(Vec::new());
+        }
+
+        let mut accounts = Vec::new();
+        for bot in self.bots.bots() {
+            let Ok(username) = domain::user::Username::try_new(bot.username()) else {
+                tracing::warn!(bot = bot.username(), "invalid bot username");
+                continue;
+            };
+            let user_id = match self.bots.account(bot.username()) {
+                Some(user_id) => user_id,
+                None => {
+                    let user_id = self.authors.ensure_bot(&username).await?;
+                    self.bots.remember_account(bot.username(), user_id);
+                    user_id
+                }
+            };
+            accounts.push((bot, username, user_id));
+        }
+        if self.bots.is_bot_account(&message.user_id) {
+            return Ok(Vec::new());
+        }
+
+        let mut posts = Vec::new();
+        for (bot, username, user_id) in accounts {
+            let Some(trigger) = BotTrigger::detect(bot.as_ref(), &message.body) else {
+                continue;
+            };
+            if !self.repo.is_member(&message.room_id, &user_id).await? {
+                self.join_room(
+                    JoinRoom::builder()
+                        .room_id(message.room_id)
+                        .user_id(user_id)
+                        .build(),
+                )
+                .await?;
+            }
+            if self.is_muted(&message.room_id, &user_id).await? {
+                continue;
+            }
+            if !self.bots.admit(bot.as_ref(), message.room_id, self.clock.now()) {
+                tracing::debug!(bot = bot.username(), "bot rate limited");
+                continue;
+            }
+
+            let event = BotEvent {
+                message: message.clone(),
+                trigger,
+            };
+            let Some(body) = bot.react(&event).await else {
+                continue;
+            };
+            let reply = self
+                .store_message(
+                    PostMessage::builder()
+                        .room_id(message.room_id)
+                        .user_id(user_id)
+                        .body(body)
+                        .build(),
+                    false,
+                )
+                .await?;
+            self.audit
+                .record(self.audit_entry(
+                    reply.room_id,
+                    user_id,
+                    AuditAction::BotReply,
+                    vec![
+                        (AuditKey::Bot, AuditValue::new(username.to_string())),
+                        (
+                            AuditKey::MessageId,
+                            AuditValue::new(reply.id.as_uuid().to_string()),
+                        ),
+                        (
+                            AuditKey::Trigger,
+                            AuditValue::new(event.trigger.as_str()),
+                        ),
+                        (
+                            AuditKey::TriggerMessageId,
+                            AuditValue::new(message.id.as_uuid().to_string()),
+                        ),
+                        (
+                            AuditKey::TargetUserId,
+                            AuditValue::new(message.user_id.as_uuid().to_string()),
+                        ),
+                    ],
+                ))
+                .await?;
+
+            posts.push(BotPost::builder().bot(username).message(reply).build());
+        }
+
+        Ok(posts)
+    }
+
+    /// Subscribes a URL to room events. Owners and admins only.
+    pub async fn create_webhook(
+        &self,
+        command: CreateWebhook,
+    ) -> Result<chat::Webhook> {
+        self.require_room_admin(&command.room_id, &command.user_id)
+            .await?;
+        let mut events = command.events;
+        events.sort_by_key(ToString::to_string);
+        events.dedup();
+        if events.is_empty() {
+            return Err(Error::InvalidWebhook);
+        }
+        if self.repo.list_webhooks(&command.room_id).await?.len() >= MAX_WEBHOOKS_PER_ROOM {
+            return Err(Error::InvalidWebhook);
+        }
+        if !self.webhooks.accepts(&command.url).await {
+            return Err(Error::InvalidWebhook);
+        }
+
+        let webhook = chat::Webhook::builder()
+            .id(self.ids.new_webhook_id())
+            .room_id(command.room_id)
+            .url(command.url)
+            .events(events)
+            .secret(command.secret)
+            .created_by(command.user_id)
+            .created_at(self.clock.now())
+            .build();
+        self.repo.insert_webhook(&webhook).await?;
+        self.audit
+            .record(self.audit_entry(
+                webhook.room_id,
+                command.user_id,
+                AuditAction::WebhookCreate,
+                vec![
+                    (
+                        AuditKey::WebhookId,
+                        AuditValue::new(webhook.id.as_uuid().to_string()),
+                    ),
+                    (AuditKey::Url, AuditValue::new(webhook.url.to_string())),
+                    (
+                        AuditKey::Events,
+                        AuditValue::new(
+                            webhook
+                                .events
+                                .iter()
+                                .map(ToString::to_string)
+                                .collect::<Vec<_>>()
+                                .join(","),
+                        ),
+                    ),
+                ],
+            ))
+            .await?;
+
+        Ok(webhook)
+    }
+
+    /// Removes the subscription and its delivery log.
+    pub async fn delete_webhook(
+        &self,
+        command: DeleteWebhook,
+    ) -> Result<()> {
+        let webhook = self.admin_webhook(&command.webhook_id, &command.user_id).await?;
+        self.repo.delete_webhook(&webhook.id).await?;
+        self.audit
+            .record(self.audit_entry(
+                webhook.room_id,
+                command.user_id,
+                AuditAction::WebhookDelete,
+                vec![(
+                    AuditKey::WebhookId,
+                    AuditValue::new(webhook.id.as_uuid().to_string()),
+                )],
+            ))
+            .await
+    }
+
+    pub async fn list_webhooks(
+        &self,
+        command: ListWebhooks,
+    ) -> Result<Vec<chat::Webhook>> {
+        self.require_room_admin(&command.room_id, &command.user_id)
+            .await?;
+        self.repo.list_webhooks(&command.room_id).await
+    }
+
+    /// Newest deliveries first.
+    pub async fn list_webhook_deliveries(
+        &self,
+        command: ListDeliveries,
+    ) -> Result<Vec<chat::WebhookDelivery>> {
+        let webhook = self.admin_webhook(&command.webhook_id, &command.user_id).await?;
+        self.repo.list_deliveries(&webhook.id, command.limit).await
+    }
+
+    /// Sends due deliveries one by one and records each attempt; failures
+    /// are retried with `webhook_backoff` until `MAX_WEBHOOK_ATTEMPTS`.
+    pub async fn deliver_webhooks(
+        &self,
+        command: DeliverWebhooks,
+    ) -> Result<DeliveryReport> {
+        let due = self
+            .repo
+            .claim_due_deliveries(self.clock.now(), command.limit)
+            .await?;
+        let mut report = DeliveryReport::default();
+        for (webhook, delivery) in due {
+            let attempt = self
+                .webhooks
+                .send(
+                    &WebhookRequest::builder()
+                        .delivery_id(delivery.id)
+                        .event(delivery.event)
+                        .url(webhook.url)
+                        .secret(webhook.secret)
+                        .payload(delivery.payload.clone())
+                        .attempt(delivery.attempts + 1)
+                        .build(),
+                )
+                .await;
+            let delivery = apply_attempt(delivery, attempt, self.clock.now());
+            self.repo.save_delivery_attempt(&delivery).await?;
+            match delivery.status {
+                chat::WebhookDeliveryStatus::Delivered => report.delivered += 1,
+                chat::WebhookDeliveryStatus::Failed => report.failed += 1,
+                _ => report.retrying += 1,
+            }
+        }
+        Ok(report)
+    }
+
+    pub async fn find_incoming_webhook(
+        &self,
+        command: FindIncomingWebhook,
+    ) -> Result<Option<chat::IncomingWebhook>> {
+        self.require_room_admin(&command.room_id, &command.user_id)
+            .await?;
+        self.repo.find_incoming_webhook(&command.room_id).await
+    }
+
+    /// Issues a fresh token for the room's incoming webhook, creating it on
+    /// first use. Owners and admins only.
+    pub async fn regenerate_incoming_webhook(
+        &self,
+        command: RegenerateIncomingWebhook,
+    ) -> Result<chat::IncomingWebhook> {
+        self.require_room_admin(&command.room_id, &command.user_id)
+            .await?;
+        let existing = self.repo.find_incoming_webhook(&command.room_id).await?;
+        let bot_id = match (command.bot_name, existing) {
+            (Some(name), _) => self.authors.ensure_bot(&name).await?,
+            (None, Some(existing)) => existing.bot_id,
+            (None, None) => {
+                let name = domain::user::Username::try_new(DEFAULT_INCOMING_BOT)
+                    .expect("incoming bot name");
+                self.authors.ensure_bot(&name).await?
+            }
+        };
+        if !self.repo.is_member(&command.room_id, &bot_id).await? {
+            self.join_room(
+                JoinRoom::builder()
+                    .room_id(command.room_id)
+                    .user_id(bot_id)
+                    .build(),
+            )
+            .await?;
+        }
+
+        let webhook = chat::IncomingWebhook::builder()
+            .room_id(command.room_id)
+            .token(self.ids.new_incoming_webhook_token())
+            .bot_id(bot_id)
+            .created_by(command.user_id)
+            .created_at(self.clock.now())
+            .build();
+        self.repo.save_incoming_webhook(&webhook).await?;
+        self.audit
+            .record(self.audit_entry(
+                webhook.room_id,
+                command.user_id,
+                AuditAction::IncomingWebhookRegenerate,
+                vec![(
+                    AuditKey::TargetUserId,
+                    AuditValue::new(webhook.bot_id.as_uuid().to_string()),
+                )],
+            ))
+            .await?;
+
+        Ok(webhook)
+    }
+
+    /// Disables the room's incoming webhook URL.
+    pub async fn revoke_incoming_webhook(
+        &self,
+        command: RevokeIncomingWebhook,
+    ) -> Result<()> {
+        self.require_room_admin(&command.room_id, &command.user_id)
+            .await?;
+        if !self.repo.delete_incoming_webhook(&command.room_id).await? {
+            return Err(Error::WebhookNotFound);
+        }
+        self.audit
+            .record(self.audit_entry(
+                command.room_id,
+                command.user_id,
+                AuditAction::IncomingWebhookRevoke,
+                Vec::new(),
+            ))
+            .await
+    }
+
+    /// Posts into the token's room as its bot. The bot goes through the
+    /// same mute, rate limit and moderation checks as a member, but skips
+    /// flood screening: integrations legitimately repeat themselves.
+    pub async fn post_incoming(
+        &self,
+        command: PostIncoming,
+    ) -> Result<BotPost> {
+        let Some(webhook) = self
+            .repo
+            .find_incoming_webhook_by_token(&command.token)
+            .await?
+        else {
+            return Err(Error::WebhookNotFound);
+        };
+        let Some(username) = self.authors.find_username(&webhook.bot_id).await? else {
+            return Err(Error::WebhookNotFound);
+        };
+        if !self.repo.is_member(&webhook.room_id, &webhook.bot_id).await? {
+            self.join_room(
+                JoinRoom::builder()
+                    .room_id(webhook.room_id)
+                    .user_id(webhook.bot_id)
+                    .build(),
+            )
+            .await?;
+        }
+
+        let post = PostMessage::builder()
+            .room_id(webhook.room_id)
+            .user_id(webhook.bot_id)
+            .body(command.body)
+            .build();
+        self.check_post(&post).await?;
+        let message = self.store_message(post, false).await?;
+
+        Ok(BotPost::builder().bot(username).message(message).build())
+    }
+
+    /// Card for the first link in a visible message, from the cache or a
+    /// fresh fetch. Failed fetches are cached as empty previews so a dead
+    /// link is not fetched on every post.
+    pub async fn unfurl_message(
+        &self,
+        message: &chat::Message,
+    ) -> Result<Option<chat::LinkPreview>> {
+        if message.status != chat::MessageStatus::Visible {
+            return Ok(None);
+        }
+        let Some(url) = first_url(&message.body) else {
+            return Ok(None);
+        };
+
+        let now = self.clock.now();
+        let cached = self
+            .repo
+            .find_link_previews(std::slice::from_ref(&url))
+            .await?
+            .into_iter()
+            .next();
+        let preview = match cached {
+            Some(cached) if cached.is_fresh(now) => cached.preview,
+            _ => {
+                let fetched = self.previews.fetch(&url).await;
+                let preview = chat::LinkPreview {
+                    url: url.clone(),
+                    ..fetched.unwrap_or_else(|| chat::LinkPreview::empty(url))
+                };
+                self.repo
+                    .save_link_preview(
+                        &CachedPreview::builder()
+                            .preview(preview.clone())
+                            .fetched_at(now)
+                            .build(),
+                    )
+                    .await?;
+                preview
+            }
+        };
+
+        Ok((!preview.is_empty()).then_some(preview))
+    }
+
+    /// Cached cards for already listed messages; never fetches.
+    pub async fn link_previews(
+        &self,
+        messages: &[chat::Message],
+    ) -> Result<Vec<(chat::MessageId, chat::LinkPreview)>> {
+        let links = messages
+            .iter()
+            .filter(|message| message.status == chat::MessageStatus::Visible)
+            .filter_map(|message| first_url(&message.body).map(|url| (message.id, url)))
+            .collect::<Vec<_>>();
+        if links.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let urls = links.iter().map(|(_, url)| url.clone()).collect::<Vec<_>>();
+        let cached = self.repo.find_link_previews(&urls).await?;
+        Ok(links
+            .into_iter()
+            .filter_map(|(message_id, url)| {
+                cached
+                    .iter()
+                    .find(|cached| cached.preview.url == url && !cached.preview.is_empty())
+                    .map(|cached| (message_id, cached.preview.clone()))
+            })
+            .collect())
+    }
+
+    /// Posts a message with files. Every file is checked and stored before
+    /// the message is posted, and the blobs are dropped again if posting or
+    /// recording the attachments fails, so no message ever points at a
+    /// missing file and no file outlives a failed upload.
+    pub async fn post_attachments(
+        &self,
+        command: PostAttachments,
+    ) -> Result<AttachedMessage> {
+        if command.files.is_empty() || command.files.len() > MAX_ATTACHMENTS_PER_MESSAGE {
+            return Err(Error::InvalidAttachment);
+        }
+        let mut checked = Vec::with_capacity(command.files.len());
+        for file in command.files {
+            if file.bytes.is_empty() || file.bytes.len() > MAX_ATTACHMENT_BYTES {
+                return Err(Error::InvalidAttachment);
+            }
+            let content_type = sniff_attachment(&file.bytes).ok_or(Error::InvalidAttachment)?;
+            checked.push((file, content_type));
+        }
+        let body = match command.body {
+            Some(body) => body,
+            None => chat::MessageBody::try_new(
+                checked
+                    .iter()
+                    .map(|(file, _)| file.name.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            )
+            .map_err(|error| Error::Domain(error.into()))?,
+        };
+
+        let mut stored = Vec::with_capacity(checked.len());
+        let mut keys = Vec::new();
+        for (file, content_type) in checked {
+            let id = self.ids.new_attachment_id();
+            let key = blob_key(&id, "original");
+            if let Err(error) = self.blobs.put(&key, &file.bytes).await {
+                self.drop_blobs(&keys).await;
+                return Err(error);
+            }
+            keys.push(key.clone());
+            let thumbnail = if content_type.is_image() {
+                self.thumbnails.thumbnail(content_type, &file.bytes).await
+            } else {
+                None
+            };
+            let thumbnail_key = match thumbnail {
+                Some(thumbnail) => {
+                    let thumbnail_key = blob_key(&id, "thumbnail");
+                    if let Err(error) = self.blobs.put(&thumbnail_key, &thumbnail).await {
+                        self.drop_blobs(&keys).await;
+                        return Err(error);
+                    }
+                    keys.push(thumbnail_key.clone());
+                    Some(thumbnail_key)
+                }
+                None => None,
+            };
+            stored.push((id, file.name, content_type, file.bytes.len(), key, thumbnail_key));
+        }
+
+        let message = match self
+            .post_message(
+                PostMessage::builder()
+                    .room_id(command.room_id)
+                    .user_id(command.user_id)
+                    .body(body)
+                    .maybe_client_id(command.client_id)
+                    .build(),
+            )
+            .await
+        {
+            Ok(message) => message,
+            Err(error) => {
+                self.drop_blobs(&keys).await;
+                return Err(error);
+            }
+        };
+
+        let mut attachments: Vec<chat::Attachment> = Vec::with_capacity(stored.len());
+        for (id, name, content_type, size, blob_key, thumbnail_key) in stored {
+            let attachment = chat::Attachment::builder()
+                .id(id)
+                .message_id(message.id)
+                .room_id(message.room_id)
+                .uploaded_by(message.user_id)
+                .name(name)
+                .content_type(content_type)
+                .size_bytes(size as u64)
+                .blob_key(blob_key)
+                .maybe_thumbnail_key(thumbnail_key)
+                .created_at(message.created_at)
+                .build();
+            if let Err(error) = self.repo.insert_attachment(&attachment).await {
+                for inserted in &attachments {
+                    if let Err(error) = self.repo.delete_attachment(&inserted.id).await {
+                        tracing::warn!(
+                            ?error,
+                            attachment_id = %inserted.id.as_uuid(),
+                            "attachment row cleanup failed"
+                        );
+                    }
+                }
+                self.drop_blobs(&keys).await;
+                return Err(error);
+            }
+            attachments.push(attachment);
+        }
+
+        Ok(AttachedMessage {
+            message,
+            attachments,
+        })
+    }
+
+    /// Members may download files of visible messages; uploaders and the
+    /// room's moderators also see them while the message waits for
+    /// moderation.
+    pub async fn open_attachment(
+        &self,
+        command: OpenAttachment,
+    ) -> Result<AttachmentDownload> {
+        let attachment = self
+            .repo
+            .find_attachment(&command.attachment_id)
+            .await?
+            .ok_or(Error::AttachmentNotFound)?;
+        if !self
+            .repo
+            .is_member(&attachment.room_id, &command.user_id)
+            .await?
+        {
+            return Err(Error::NotMember);
+        }
+        let message = self
+            .repo
+            .find_message(&attachment.message_id)
+            .await?
+            .ok_or(Error::AttachmentNotFound)?;
+        let readable = match message.status {
+            chat::MessageStatus::Visible => true,
+            chat::MessageStatus::Pending | chat::MessageStatus::Quarantined => {
+                message.user_id == command.user_id
+                    || self
+                        .is_room_moderator(&message.room_id, &command.user_id)
+                        .await?
+            }
+            chat::MessageStatus::Removed => false,
+        };
+        if !readable {
+            return Err(Error::AttachmentNotFound);
+        }
+
+        let (key, content_type) = match (command.variant, &attachment.thumbnail_key) {
+            (AttachmentVariant::Thumbnail, Some(key)) => (key.clone(), chat::AttachmentType::Png),
+            (AttachmentVariant::Thumbnail, None) => return Err(Error::AttachmentNotFound),
+            (AttachmentVariant::Original, _) => {
+                (attachment.blob_key.clone(), attachment.content_type)
+            }
+        };
+        let bytes = self
+            .blobs
+            .get(&key)
+            .await?
+            .ok_or(Error::AttachmentNotFound)?;
+
+        Ok(AttachmentDownload {
+            attachment,
+            content_type,
+            bytes,
+        })
+    }
+
+    /// Attachments of already listed messages.
+    pub async fn list_attachments(
+        &self,
+        messages: &[chat::Message],
+    ) -> Result<Vec<chat::Attachment>> {
+        let message_ids = messages
+            .iter()
+            .filter(|message| message.status != chat::MessageStatus::Removed)
+            .map(|message| message.id)
+            .collect::<Vec<_>>();
+        if message_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.repo.list_attachments(&message_ids).await
+    }
+
+    /// Deletes the files of purged and redacted messages; returns how many
+    /// attachments were cleaned up.
+    pub async fn sweep_attachments(
+        &self,
+        command: SweepAttachments,
+    ) -> Result<usize> {
+        let orphaned = self
+            .repo
+            .list_orphaned_attachments(command.limit)
+            .await?;
+        for attachment in &orphaned {
+            self.blobs.delete(&attachment.blob_key).await?;
+            if let Some(key) = &attachment.thumbnail_key {
+                self.blobs.delete(key).await?;
+            }
+            self.repo.delete_attachment(&attachment.id).await?;
+        }
+        Ok(orphaned.len())
+    }
+
+    async fn drop_blobs(
+        &self,
+        keys: &[chat::BlobKey],
+    ) {
+        for key in keys {
+            if let Err(error) = self.blobs.delete(key).await {
+                tracing::warn!(?error, key = %key, "attachment blob cleanup failed");
+            }
+        }
+    }
+
+    /// Username for any account, bots and import placeholders included.
+    pub async fn author_name(
+        &self,
+        user_id: &chat::UserId,
+    ) -> Result<Option<domain::user::Username>> {
+        self.authors.find_username(user_id).await
+    }
+
+    pub async fn start_typing(
+        &self,
+        command: Typing,
+    ) -> Result<TypingChange> {
+        let is_member = self
+            .repo
+            .is_member(&command.room_id, &command.user_id)
+            .await?;
+        if !is_member {
+            return Err(Error::NotMember);
+        }
+
+        match self.typing.start(
+            &command.room_id,
+            &command.user_id,
+            self.clock.now(),
+        ) {
+            TypingChange::Throttled => Err(Error::RateLimited),
+            change => Ok(change),
+        }
+    }
+
+    pub fn stop_typing(
+        &self,
+        command: Typing,
+    ) -> bool {
+        self.typing.stop(&command.room_id, &command.user_id)
+    }
+
+    pub fn expire_typing(&self) -> Vec<TypingExpired> {
+        self.typing.expire(self.clock.now())
+    }
+
+    /// Only the room's owners, admins and moderators may decide. Returns
+    /// the message with its new status and who was shown it before.
+    pub async fn moderate_message(
+        &self,
+        command: ModerateMessage,
+    ) -> Result<ModerationOutcome> {
+        let mut message = self
+            .moderated_message(&command.message_id, &command.reviewer_id)
+            .await?;
+        let audience = self.audience(&message).await?;
+
+        let status = match command.decision {
+            ModerationDecision::Approve => chat::MessageStatus::Visible,
+            ModerationDecision::Remove => chat::MessageStatus::Removed,
+        };
+
+        // The queue row decides who wins; the message only changes after.
+        if !self
+            .moderation
+            .complete(
+                &command.message_id,
+                &command.reviewer_id,
+                command.decision,
+                command.reason.clone(),
+                command.revision,
+                self.clock.now(),
+            )
+            .await?
+        {
+            return Err(Error::ModerationConflict);
+        }
+
+        self.repo
+            .update_message_status(&command.message_id, status)
+            .await?;
+        message.status = status;
+
+        self.audit
+            .record(self.audit_entry(
+                message.room_id,
+                command.reviewer_id,
+                AuditAction::MessageModerate,
+                vec![
+                    (
+                        AuditKey::MessageId,
+                        AuditValue::new(
+                            message.id.as_uuid().to_string(),
+                        )
+                        ,
+                    ),
+                    (
+                        AuditKey::Decision,
+                        AuditValue::new(format!(
+                            "{:?}",
+                            command.decision
+                        ))
+                        ,
+                    ),
+                    (
+                        AuditKey::Reason,
+                        command
+                            .reason
+                            .clone()
+                            .map(|reason| {
+                                AuditValue::new(reason.to_string())
+                                    
+                            })
+                            .unwrap_or_else(|| {
+                                AuditValue::new("")
+                                    
+                            }),
+                    ),
+                ],
+            ))
+            .await?;
+
+        self.emit(
+            message.room_id,
+            chat::WebhookEvent::MessageModerated,
+            serde_json::json!({
+                "message_id": message.id.as_uuid().to_string(),
+                "decision": format!("{:?}", command.decision).to_lowercase(),
+                "reviewer_id": command.reviewer_id.as_uuid().to_string(),
+            }),
+        )
+        .await?;
+
+        Ok(ModerationOutcome { message, audience })
+    }
+}
+
+impl Service {
+    /// Files a member's report against someone else's message. Reaching the
+    /// threshold hides a visible message (status pending) until a moderator
+    /// decides; repeat reports by the same member change nothing.
+    pub async fn report_message(
+        &self,
+        command: ReportMessage,
+    ) -> Result<ReportOutcome> {
+        let mut message = self
+            .repo
+            .find_message(&command.message_id)
+            .await?
+            .filter(|message| message.status != chat::MessageStatus::Removed)
+            .ok_or(Error::MessageNotFound)?;
+        if !self
+            .repo
+            .is_member(&message.room_id, &command.reporter_id)
+            .await?
+        {
+            return Err(Error::NotMember);
+        }
+        if message.user_id == command.reporter_id {
+            return Err(Error::InvalidReport);
+        }
+
+        let tally = self
+            .moderation
+            .report(
+                &message.id,
+                &command.reporter_id,
+                command.category,
+                self.clock.now(),
+            )
+            .await?;
+        let hidden = message.status == chat::MessageStatus::Visible
+            && reaches_threshold(tally, self.report_threshold);
+        if hidden {
+            self.repo
+                .update_message_status(&message.id, chat::MessageStatus::Pending)
+                .await?;
+            message.status = chat::MessageStatus::Pending;
+        }
+
+        if tally.counted {
+            self.audit
+                .record(self.audit_entry(
+                    message.room_id,
+                    command.reporter_id,
+                    AuditAction::MessageReport,
+                    vec![
+                        (
+                            AuditKey::MessageId,
+                            AuditValue::new(message.id.as_uuid().to_string()),
+                        ),
+                        (
+                            AuditKey::Category,
+                            AuditValue::new(command.category.to_string()),
+                        ),
+                        (
+                            AuditKey::Reports,
+                            AuditValue::new(tally.open_reports.to_string()),
+                        ),
+                        (
+                            AuditKey::Status,
+                            AuditValue::new(format!("{:?}", message.status)),
+                        ),
+                    ],
+                ))
+                .await?;
+        }
+
+        Ok(ReportOutcome {
+            message,
+            tally,
+            hidden,
+        })
+    }
+}
+
+impl Service {
+    /// Files the author's one appeal against a removal of their message.
+    pub async fn file_appeal(
+        &self,
+        command: FileAppeal,
+    ) -> Result<()> {
+        let message = self
+            .repo
+            .find_message(&command.message_id)
+            .await?
+            .filter(|message| {
+                message.status == chat::MessageStatus::Removed
+                    && message.user_id == command.appellant_id
+            })
+            .ok_or(Error::InvalidAppeal)?;
+
+        if !self
+            .moderation
+            .file_appeal(
+                &message.id,
+                &command.appellant_id,
+                &command.statement,
+                self.clock.now(),
+            )
+            .await?
+        {
+            return Err(Error::InvalidAppeal);
+        }
+
+        self.audit
+            .record(self.audit_entry(
+                message.room_id,
+                command.appellant_id,
+                AuditAction::MessageAppeal,
+                vec![(
+                    AuditKey::MessageId,
+                    AuditValue::new(message.id.as_uuid().to_string()),
+                )],
+            ))
+            .await
+    }
+
+    /// Pending appeals from the rooms the reviewer moderates.
+    pub async fn list_appeals(
+        &self,
+        command: ListAppeals,
+    ) -> Result<Vec<Appeal>> {
+        let rooms = self.repo.moderated_rooms(&command.reviewer_id).await?;
+        if rooms.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.moderation.list_appeals(&rooms, command.limit).await
+    }
+
+    pub async fn list_removals(
+        &self,
+        command: ListRemovals,
+    ) -> Result<Vec<Removal>> {
+        self.moderation
+            .list_removals(&command.author_id, command.limit)
+            .await
+    }
+
+    /// Decides a pending appeal. Only the room's owners, admins and
+    /// moderators may, and never the member who removed the message or the
+    /// appellant; overturning restores the message. Returns the appeal with
+    /// its outcome.
+    pub async fn decide_appeal(
+        &self,
+        command: DecideAppeal,
+    ) -> Result<Appeal> {
+        let mut appeal = self
+            .moderation
+            .find_appeal(&command.message_id)
+            .await?
+            .ok_or(Error::MessageNotFound)?;
+        let role = self
+            .repo
+            .member_role(&appeal.room_id, &command.reviewer_id)
+            .await?
+            .ok_or(Error::NotMember)?;
+        if !may_decide(&appeal, &command.reviewer_id, Some(role)) {
+            return Err(Error::Forbidden);
+        }
+
+        if !self
+            .moderation
+            .decide_appeal(
+                &appeal.message_id,
+                &command.reviewer_id,
+                command.decision,
+                self.clock.now(),
+            )
+            .await?
+        {
+            return Err(Error::ModerationConflict);
+        }
+        if command.decision == AppealDecision::Overturn {
+            self.repo
+                .update_message_status(&appeal.message_id, chat::MessageStatus::Visible)
+                .await?;
+        }
+        appeal.status = command.decision.status();
+
+        self.audit
+            .record(self.audit_entry(
+                appeal.room_id,
+                command.reviewer_id,
+                AuditAction::MessageAppealDecide,
+                vec![
+                    (
+                        AuditKey::MessageId,
+                        AuditValue::new(appeal.message_id.as_uuid().to_string()),
+                    ),
+                    (
+                        AuditKey::TargetUserId,
+                        AuditValue::new(appeal.appellant_id.as_uuid().to_string()),
+                    ),
+                    (
+                        AuditKey::Decision,
+                        AuditValue::new(appeal.status.to_string()),
+                    ),
+                ],
+            ))
+            .await?;
+
+        Ok(appeal)
+    }
+}
+
+fn message_json(message: &chat::Message) -> serde_json::Value {
+    serde_json::json!({
+        "message_id": message.id.as_uuid().to_string(),
+        "user_id": message.user_id.as_uuid().to_string(),
+        "body": message.body.to_string(),
+        "created_at_ms": export::timestamp_ms(message.created_at),
+    })
+}
+
+fn should_moderate(
+    body: &chat::MessageBody,
+) -> bool {
+    let value = body.to_string();
+    value.len() > 300 || LinkPrefix::is_present(&value)
+}
+
+#[derive(Clone, Copy, Debug)]
+enum LinkPrefix {
+    Http,
+    Https,
+}
+
+impl LinkPrefix {
+    fn as_str(self) -> &'static str {
+        match self {
+            LinkPrefix::Http => "http://",
+            LinkPrefix::Https => "https://",
+        }
+    }
+
+    fn is_present(value: &str) -> bool {
+        [Self::Http, Self::Https]
+            .iter()
+            .any(|prefix| value.contains(prefix.as_str()))
+    }
+}
+
+impl Service {
+    async fn require_room_admin(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+    ) -> Result<()> {
+        if self.repo.find_room(room_id).await?.is_none() {
+            return Err(Error::RoomNotFound);
+        }
+        match self.repo.member_role(room_id, user_id).await? {
+            Some(RoomRole::Owner | RoomRole::Admin) => Ok(()),
+            Some(RoomRole::Member | RoomRole::Moderator) => Err(Error::Forbidden),
+            None => Err(Error::NotMember),
+        }
+    }
+
+    async fn is_room_moderator(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+    ) -> Result<bool> {
+        Ok(matches!(
+            self.repo.member_role(room_id, user_id).await?,
+            Some(RoomRole::Owner | RoomRole::Admin | RoomRole::Moderator)
+        ))
+    }
+
+    async fn require_room_moderator(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+    ) -> Result<()> {
+        match self.repo.member_role(room_id, user_id).await? {
+            Some(RoomRole::Owner | RoomRole::Admin | RoomRole::Moderator) => Ok(()),
+            Some(RoomRole::Member) => Err(Error::Forbidden),
+            None => Err(Error::NotMember),
+        }
+    }
+
+    /// The message, once `user_id` is known to moderate its room.
+    async fn moderated_message(
+        &self,
+        message_id: &chat::MessageId,
+        user_id: &chat::UserId,
+    ) -> Result<chat::Message> {
+        let message = self
+            .repo
+            .find_message(message_id)
+            .await?
+            .ok_or(Error::MessageNotFound)?;
+        self.require_room_moderator(&message.room_id, user_id)
+            .await?;
+        Ok(message)
+    }
+
+    async fn visible_poll(
+        &self,
+        message_id: &chat::MessageId,
+    ) -> Result<chat::Poll> {
+        let visible = self
+            .repo
+            .find_message(message_id)
+            .await?
+            .is_some_and(|message| message.status == chat::MessageStatus::Visible);
+        if !visible {
+            return Err(Error::PollNotFound);
+        }
+        self.repo
+            .find_poll(message_id)
+            .await?
+            .ok_or(Error::PollNotFound)
+    }
+
+    async fn current_results(
+        &self,
+        poll: &chat::Poll,
+    ) -> Result<PollResults> {
+        self.repo
+            .poll_results(&poll.room_id, &[poll.message_id])
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(Error::PollNotFound)
+    }
+
+    async fn import_line(
+        &self,
+        room_id: &chat::RoomId,
+        line: ImportLine,
+        report: &mut ImportReport,
+    ) -> core::result::Result<(), ImportErrorText> {
+        let repo_error = |error: Error| ImportErrorText::from(error.to_string());
+
+        let message_id = match line.message_id {
+            Some(id) if self.repo.find_message(&id).await.map_err(repo_error)?.is_some() => {
+                return Err(ImportErrorText::from(format!(
+                    "message {} already exists",
+                    id.as_uuid()
+                )));
+            }
+            Some(id) => id,
+            None => self.ids.new_message_id(),
+        };
+
+        let user_id = self.resolve_author(&line, report).await?;
+        self.repo
+            .add_membership(room_id, &user_id, RoomRole::Member)
+            .await
+            .map_err(repo_error)?;
+
+        let now = self.clock.now();
+        let requires_moderation = !line.removed && should_moderate(&line.body);
+        let status = if line.removed {
+            chat::MessageStatus::Removed
+        } else if requires_moderation {
+            chat::MessageStatus::Pending
+        } else {
+            chat::MessageStatus::Visible
+        };
+        let message = chat::Message {
+            id: message_id,
+            room_id: *room_id,
+            user_id,
+            body: line.body,
+            status,
+            client_id: None,
+            created_at: line.created_at.filter(|value| *value <= now).unwrap_or(now),
+        };
+        self.repo.insert_message(&message).await.map_err(repo_error)?;
+
+        if requires_moderation {
+            self.moderation
+                .enqueue(
+                    &message.id,
+                    &ModerationReason::try_new("import")
+                        .expect("moderation reason"),
+                )
+                .await
+                .map_err(repo_error)?;
+            report.pending += 1;
+        }
+        report.imported += 1;
+        Ok(())
+    }
+
+    async fn resolve_author(
+        &self,
+        line: &ImportLine,
+        report: &mut ImportReport,
+    ) -> core::result::Result<chat::UserId, ImportErrorText> {
+        let repo_error = |error: Error| ImportErrorText::from(error.to_string());
+
+        if let Some(user_id) = line.author_id
+            && self.authors.user_exists(&user_id).await.map_err(repo_error)?
+        {
+            return Ok(user_id);
+        }
+        let Some(username) = &line.author else {
+            return Err(ImportErrorText::from(
+                "author_id does not match an existing user",
+            ));
+        };
+        if let Some(user_id) = self
+            .authors
+            .find_by_username(username)
+            .await
+            .map_err(repo_error)?
+        {
+            return Ok(user_id);
+        }
+        let user_id = self
+            .authors
+            .create_placeholder(username)
+            .await
+            .map_err(repo_error)?;
+        report.placeholders += 1;
+        Ok(user_id)
+    }
+
+    async fn admin_webhook(
+        &self,
+        webhook_id: &chat::WebhookId,
+        user_id: &chat::UserId,
+    ) -> Result<chat::Webhook> {
+        let webhook = self
+            .repo
+            .find_webhook(webhook_id)
+            .await?
+            .ok_or(Error::WebhookNotFound)?;
+        self.require_room_admin(&webhook.room_id, user_id).await?;
+        Ok(webhook)
+    }
+
+    /// Queues an event for the room's subscribed webhooks.
+    async fn emit(
+        &self,
+        room_id: chat::RoomId,
+        event: chat::WebhookEvent,
+        data: serde_json::Value,
+    ) -> Result<()> {
+        let now = self.clock.now();
+        self.repo
+            .enqueue_webhook_deliveries(
+                &room_id,
+                event,
+                &webhook_payload(event, &room_id, now, data),
+                now,
+            )
+            .await
+            .map(|_| ())
+    }
+
+    async fn emit_poll_closed(
+        &self,
+        results: &PollResults,
+    ) -> Result<()> {
+        self.emit(
+            results.poll.room_id,
+            chat::WebhookEvent::PollClosed,
+            serde_json::json!({
+                "message_id": results.poll.message_id.as_uuid().to_string(),
+                "voters": results.voters,
+                "tallies": results
+                    .tallies
+                    .iter()
+                    .map(|tally| serde_json::json!({
+                        "option_id": tally.option.id.as_uuid().to_string(),
+                        "label": tally.option.label.to_string(),
+                        "votes": tally.votes,
+                    }))
+                    .collect::<Vec<_>>(),
+            }),
+        )
+        .await
+    }
+
+    fn audit_entry(
+        &self,
+        room_id: chat::RoomId,
+        actor_id: chat::UserId,
+        action: AuditAction,
+        mut metadata: Vec<(AuditKey, AuditValue)>,
+    ) -> AuditEntry {
+        let timestamp = self
+            .clock
+            .now()
+            .duration_since(UNIX_EPOCH)
+            .map(|value| value.as_millis().to_string())
+            .unwrap_or_else(|_| "0".to_string());
+
+        metadata.push((
+            AuditKey::TimestampMs,
+            AuditValue::new(timestamp),
+        ));
+
+        AuditEntry::builder()
+            .room_id(room_id)
+            .actor_id(actor_id)
+            .action(action)
+            .metadata(metadata)
+            .build()
+    }
+}
+
+#[bon]
+impl Service {
+    #[builder]
+    pub fn builder(
+        #[builder(setters(name = with_repo))] repo: Arc<dyn Repository>,
+        #[builder(setters(name = with_moderation_queue))]
+        moderation: Arc<dyn ModerationQueue>,
+        #[builder(setters(name = with_rate_limiter))]
+        rate_limiter: Arc<dyn RateLimiter>,
+        #[builder(setters(name = with_audit_log))] audit: Arc<dyn AuditLog>,
+        #[builder(setters(name = with_typing_store))]
+        typing: Arc<dyn TypingStore>,
+        #[builder(setters(name = with_author_directory))]
+        authors: Arc<dyn AuthorDirectory>,
+        #[builder(setters(name = with_clock))] clock: Arc<dyn Clock>,
+        #[builder(setters(name = with_id_generator))] ids: Arc<dyn IdGenerator>,
+        /// Slash commands; the built-ins unless set.
+        #[builder(
+            setters(name = with_command_registry),
+            default = Arc::new(CommandRegistry::with_builtins())
+        )]
+        commands: Arc<CommandRegistry>,
+        /// Bots that react to posted messages; the demo bot unless set.
+        #[builder(
+            setters(name = with_bot_registry),
+            default = Arc::new(BotRegistry::with_builtins())
+        )]
+        bots: Arc<BotRegistry>,
+        /// Delivers outgoing webhooks; attempts fail (and retry) unless set.
+        #[builder(
+            setters(name = with_webhook_sender),
+            default = Arc::new(DisabledWebhookSender)
+        )]
+        webhooks: Arc<dyn WebhookSender>,
+        /// Reads link cards for posted URLs; no cards unless set.
+        #[builder(
+            setters(name = with_link_preview_fetcher),
+            default = Arc::new(DisabledLinkPreviewFetcher)
+        )]
+        previews: Arc<dyn LinkPreviewFetcher>,
+        /// Stores attachment bytes; uploads fail unless set.
+        #[builder(setters(name = with_blob_store), default = Arc::new(DisabledBlobStore))]
+        blobs: Arc<dyn BlobStore>,
+        /// Makes image thumbnails; none unless set.
+        #[builder(setters(name = with_thumbnailer), default = Arc::new(DisabledThumbnailer))]
+        thumbnails: Arc<dyn Thumbnailer>,
+        /// Open reports that hide a message until it is reviewed.
+        #[builder(
+            setters(name = with_report_threshold),
+            default = DEFAULT_REPORT_HIDE_THRESHOLD
+        )]
+        report_threshold: usize,
+    ) -> Self {
+        Self {
+            repo,
+            moderation,
+            rate_limiter,
+            audit,
+            typing,
+            authors,
+            clock,
+            ids,
+            commands,
+            bots,
+            webhooks,
+            previews,
+            blobs,
+            thumbnails,
+            report_threshold,
+        }
+    }
+}
+
+#[cfg(test)]
+mod testing;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlight_segments_split_on_markers() {
+        let highlight = SearchHighlight::new(format!(
+            "say {}hello{} to the {}room{}",
+            SearchHighlight::MATCH_START,
+            SearchHighlight::MATCH_END,
+            SearchHighlight::MATCH_START,
+            SearchHighlight::MATCH_END,
+        ));
+
+        assert_eq!(
+            highlight.segments(),
+            vec![
+                ("say ".to_string(), false),
+                ("hello".to_string(), true),
+                (" to the ".to_string(), false),
+                ("room".to_string(), true),
+            ]
+        );
+    }
+
+    mod read_state {
+        use super::super::testing::{Harness, epoch, room_id, user_id};
+        use super::*;
+
+        const ALICE: u128 = 1;
+        const BOB: u128 = 2;
+        const CAROL: u128 = 3;
+
+        fn harness() -> Harness {
+            let harness = Harness::new(epoch(1_000));
+            harness.room(
+                10,
+                chat::RoomName::Lobby,
+                &[
+                    (ALICE, RoomRole::Member),
+                    (BOB, RoomRole::Member),
+                    (CAROL, RoomRole::Member),
+                ],
+            );
+            harness.room(20, chat::RoomName::Support, &[(BOB, RoomRole::Member)]);
+            harness
+        }
+
+        fn post(
+            harness: &Harness,
+            message_id: u128,
+            room: u128,
+            author: u128,
+            at: u64,
+        ) -> chat::MessageId {
+            harness.post(message_id, room, author, chat::MessageStatus::Visible, at)
+        }
+
+        fn mark(
+            room: u128,
+            user: u128,
+            message_id: chat::MessageId,
+        ) -> MarkRead {
+            MarkRead::builder()
+                .room_id(room_id(room))
+                .user_id(user_id(user))
+                .message_id(message_id)
+                .build()
+        }
+
+        #[tokio::test]
+        async fn mark_read_only_moves_forward() {
+            let harness = harness();
+            let older = post(&harness, 100, 10, ALICE, 100);
+            let newer = post(&harness, 101, 10, ALICE, 200);
+
+            assert!(
+                harness
+                    .service
+                    .mark_read(mark(10, BOB, newer))
+                    .await
+                    .unwrap()
+            );
+            assert!(
+                !harness
+                    .service
+                    .mark_read(mark(10, BOB, older))
+                    .await
+                    .unwrap()
+            );
+            assert!(
+                !harness
+                    .service
+                    .mark_read(mark(10, BOB, newer))
+                    .await
+                    .unwrap()
+            );
+        }
+
+        #[tokio::test]
+        async fn mark_read_rejects_outsiders_and_other_rooms_messages() {
+            let harness = harness();
+            let lobby = post(&harness, 100, 10, ALICE, 100);
+            let support = post(&harness, 200, 20, BOB, 100);
+
+            assert!(matches!(
+                harness.service.mark_read(mark(20, ALICE, support)).await,
+                Err(Error::NotMember)
+            ));
+            assert!(matches!(
+                harness.service.mark_read(mark(10, BOB, support)).await,
+                Err(Error::MessageNotFound)
+            ));
+            assert!(
+                harness
+                    .service
+                    .mark_read(mark(10, BOB, lobby))
+                    .await
+                    .unwrap()
+            );
+        }
+
+        #[tokio::test]
+        async fn list_unread_counts_visible_messages_from_others_after_the_marker() {
+            let harness = harness();
+            let first = post(&harness, 100, 10, ALICE, 100);
+            post(&harness, 101, 10, ALICE, 200);
+            harness.post(102, 10, ALICE, chat::MessageStatus::Pending, 300);
+            post(&harness, 103, 10, BOB, 400);
+            post(&harness, 200, 20, CAROL, 100);
+
+            let unread = |rooms: Vec<RoomUnread>| {
+                rooms
+                    .into_iter()
+                    .map(|room| (room.room_name, room.unread))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(
+                unread(harness.service.list_unread(&user_id(BOB)).await.unwrap()),
+                vec![(chat::RoomName::Lobby, 2), (chat::RoomName::Support, 1)]
+            );
+
+            harness
+                .service
+                .mark_read(mark(10, BOB, first))
+                .await
+                .unwrap();
+            assert_eq!(
+                unread(harness.service.list_unread(&user_id(BOB)).await.unwrap()),
+                vec![(chat::RoomName::Lobby, 1), (chat::RoomName::Support, 1)]
+            );
+        }
+
+        #[tokio::test]
+        async fn seen_by_lists_members_who_read_at_or_past_the_message() {
+            let harness = harness();
+            let first = post(&harness, 100, 10, ALICE, 100);
+            let second = post(&harness, 101, 10, ALICE, 200);
+            let seen_by = |room: u128, viewer: u128, message_id: chat::MessageId| {
+                SeenBy::builder()
+                    .room_id(room_id(room))
+                    .viewer_id(user_id(viewer))
+                    .message_id(message_id)
+                    .build()
+            };
+
+            for (reader, message_id) in [(CAROL, second), (BOB, first), (ALICE, second)] {
+                harness
+                    .service
+                    .mark_read(mark(10, reader, message_id))
+                    .await
+                    .unwrap();
+            }
+
+            assert_eq!(
+                harness
+                    .service
+                    .seen_by(seen_by(10, ALICE, first))
+                    .await
+                    .unwrap(),
+                vec![user_id(CAROL), user_id(BOB)]
+            );
+            assert_eq!(
+                harness
+                    .service
+                    .seen_by(seen_by(10, BOB, second))
+                    .await
+                    .unwrap(),
+                vec![user_id(CAROL)]
+            );
+            assert!(matches!(
+                harness.service.seen_by(seen_by(20, ALICE, first)).await,
+                Err(Error::NotMember)
+            ));
+        }
+
+        #[tokio::test]
+        async fn room_members_include_every_role() {
+            let harness = harness();
+            harness.room(
+                30,
+                chat::RoomName::Demo,
+                &[(CAROL, RoomRole::Owner), (BOB, RoomRole::Member)],
+            );
+
+            assert_eq!(
+                harness.service.room_members(&room_id(30)).await.unwrap(),
+                vec![user_id(BOB), user_id(CAROL)]
+            );
+        }
+    }
+
+    mod schedules {
+        use super::super::testing::{Harness, body, epoch, room_id, user_id};
+        use super::*;
+
+        #[tokio::test]
+        async fn schedule_fires_once_it_comes_due() {
+            let harness = Harness::new(epoch(1_000));
+            harness.room(10, chat::RoomName::Lobby, &[(1, RoomRole::Member)]);
+            let schedule = harness
+                .service
+                .schedule_message(
+                    ScheduleMessage::builder()
+                        .room_id(room_id(10))
+                        .user_id(user_id(1))
+                        .body(body("standup in five"))
+                        .send_at(epoch(1_060))
+                        .build(),
+                )
+                .await
+                .unwrap();
+            let post_due = || {
+                harness
+                    .service
+                    .post_due_schedules(PostDueSchedules::builder().build())
+            };
+
+            harness.clock.set(epoch(1_059));
+            assert!(post_due().await.unwrap().is_empty());
+
+            harness.clock.set(epoch(1_060));
+            let posted = post_due().await.unwrap();
+            assert_eq!(posted.len(), 1);
+            let (_, message) = &posted[0];
+            assert_eq!(message.room_id, room_id(10));
+            assert_eq!(message.body, body("standup in five"));
+            assert_eq!(message.created_at, epoch(1_060));
+
+            let sent = harness
+                .repo
+                .find_schedule(&schedule.id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(sent.status, chat::ScheduleStatus::Sent);
+            assert_eq!(sent.message_id, Some(message.id));
+            let messages = harness.repo.list_messages(&room_id(10), 10).await.unwrap();
+            assert_eq!(messages, vec![message.clone()]);
+
+            harness.clock.set(epoch(1_120));
+            assert!(post_due().await.unwrap().is_empty());
+        }
+    }
+
+    mod retention {
+        use super::super::testing::{Harness, epoch, room_id, user_id};
+        use super::*;
+
+        const DAY: u64 = 86_400;
+        const NOW: u64 = 100 * DAY;
+
+        async fn harness(policy: RetentionPolicy) -> Harness {
+            let harness = Harness::new(epoch(NOW));
+            harness.room(10, chat::RoomName::Lobby, &[]);
+            let retention = RoomRetention::builder()
+                .room_id(room_id(10))
+                .policy(policy)
+                .set_by(user_id(1))
+                .build();
+            harness.repo.save_retention(&retention).await.unwrap();
+            harness
+        }
+
+        fn post(
+            harness: &Harness,
+            message_id: u128,
+            at: u64,
+        ) -> chat::MessageId {
+            harness.post(message_id, 10, 2, chat::MessageStatus::Visible, at)
+        }
+
+        fn remaining(harness: &Harness) -> Vec<chat::MessageId> {
+            harness
+                .repo
+                .messages()
+                .into_iter()
+                .map(|message| message.id)
+                .collect()
+        }
+
+        fn run(batch_size: usize) -> RunPurge {
+            RunPurge::builder().batch_size(batch_size).build()
+        }
+
+        #[tokio::test]
+        async fn purge_keeps_messages_exactly_at_the_age_cutoff() {
+            let harness = harness(
+                RetentionPolicy::builder()
+                    .max_age(RetentionDays::try_new(1).unwrap())
+                    .build(),
+            )
+            .await;
+            post(&harness, 100, NOW - 3 * DAY);
+            post(&harness, 101, NOW - DAY - 1);
+            let at_cutoff = post(&harness, 102, NOW - DAY);
+            let fresh = post(&harness, 103, NOW - 1);
+
+            let report = harness.service.purge_expired(run(500)).await.unwrap();
+
+            assert_eq!((report.expired(), report.rooms[0].purged), (2, 2));
+            assert_eq!(remaining(&harness), vec![at_cutoff, fresh]);
+            assert_eq!(harness.audit.actions(), vec![AuditAction::RoomPurge]);
+        }
+
+        #[tokio::test]
+        async fn purge_by_count_skips_rows_already_redacted() {
+            let harness = harness(
+                RetentionPolicy::builder()
+                    .max_messages(RetentionCount::try_new(2).unwrap())
+                    .mode(PurgeMode::Redact)
+                    .build(),
+            )
+            .await;
+            for (message_id, at) in [(100, 10), (101, 20), (102, 30), (103, 40)] {
+                post(&harness, message_id, at);
+            }
+
+            let first = harness.service.purge_expired(run(500)).await.unwrap();
+            let second = harness.service.purge_expired(run(500)).await.unwrap();
+
+            assert_eq!(first.rooms[0].purged, 2);
+            assert_eq!(second.expired(), 0);
+            let statuses: Vec<chat::MessageStatus> = harness
+                .repo
+                .messages()
+                .into_iter()
+                .map(|message| message.status)
+                .collect();
+            assert_eq!(
+                statuses,
+                vec![
+                    chat::MessageStatus::Removed,
+                    chat::MessageStatus::Removed,
+                    chat::MessageStatus::Visible,
+                    chat::MessageStatus::Visible,
+                ]
+            );
+        }
+
+        #[tokio::test]
+        async fn purge_works_through_batches_until_one_comes_back_short() {
+            let harness = harness(
+                RetentionPolicy::builder()
+                    .max_age(RetentionDays::try_new(1).unwrap())
+                    .build(),
+            )
+            .await;
+            for message_id in 100..105 {
+                post(&harness, message_id, message_id as u64);
+            }
+
+            let report = harness.service.purge_expired(run(2)).await.unwrap();
+
+            assert_eq!(report.rooms[0].purged, 5);
+            assert_eq!(harness.repo.purge_batches(), vec![2, 2, 1]);
+            assert!(remaining(&harness).is_empty());
+        }
+
+        #[tokio::test]
+        async fn dry_run_reports_without_deleting() {
+            let harness = harness(
+                RetentionPolicy::builder()
+                    .max_age(RetentionDays::try_new(1).unwrap())
+                    .build(),
+            )
+            .await;
+            let old = post(&harness, 100, 10);
+
+            let report = harness
+                .service
+                .purge_expired(RunPurge::builder().dry_run(true).build())
+                .await
+                .unwrap();
+
+            assert!(report.dry_run);
+            assert_eq!((report.expired(), report.rooms[0].purged), (1, 0));
+            assert_eq!(remaining(&harness), vec![old]);
+            assert!(harness.repo.purge_batches().is_empty());
+            assert!(harness.audit.actions().is_empty());
+        }
+    }
+
+    mod pins {
+        use super::super::testing::{Harness, epoch, room_id, user_id};
+        use super::*;
+
+        const MODERATOR: u128 = 1;
+        const MEMBER: u128 = 2;
+
+        fn harness() -> Harness {
+            let harness = Harness::new(epoch(1_000));
+            harness.room(
+                10,
+                chat::RoomName::Lobby,
+                &[(MODERATOR, RoomRole::Moderator), (MEMBER, RoomRole::Member)],
+            );
+            harness
+        }
+
+        fn post(
+            harness: &Harness,
+            message_id: u128,
+            status: chat::MessageStatus,
+        ) -> chat::MessageId {
+            harness.post(message_id, 10, MEMBER, status, message_id as u64)
+        }
+
+        fn pin(
+            message_id: chat::MessageId,
+            user: u128,
+        ) -> PinMessage {
+            PinMessage::builder()
+                .message_id(message_id)
+                .user_id(user_id(user))
+                .build()
+        }
+
+        #[tokio::test]
+        async fn pinning_stops_at_the_room_cap() {
+            let harness = harness();
+            for message_id in 0..MAX_PINS_PER_ROOM as u128 {
+                let message_id =
+                    post(&harness, 100 + message_id, chat::MessageStatus::Visible);
+                harness
+                    .service
+                    .pin_message(pin(message_id, MODERATOR))
+                    .await
+                    .unwrap();
+            }
+            let extra = post(&harness, 200, chat::MessageStatus::Visible);
+
+            assert!(matches!(
+                harness.service.pin_message(pin(extra, MODERATOR)).await,
+                Err(Error::PinLimitReached)
+            ));
+            let pins = harness.repo.list_pins(&room_id(10)).await.unwrap();
+            assert_eq!(pins.len(), MAX_PINS_PER_ROOM);
+            assert!(pins.iter().all(|pinned| pinned.message.id != extra));
+        }
+
+        #[tokio::test]
+        async fn pinning_rejects_pending_and_removed_messages() {
+            let harness = harness();
+            let pending = post(&harness, 100, chat::MessageStatus::Pending);
+            let removed = post(&harness, 101, chat::MessageStatus::Removed);
+
+            for message_id in [pending, removed] {
+                assert!(matches!(
+                    harness
+                        .service
+                        .pin_message(pin(message_id, MODERATOR))
+                        .await,
+                    Err(Error::MessageNotFound)
+                ));
+            }
+            assert_eq!(harness.repo.count_pins(&room_id(10)).await.unwrap(), 0);
+            assert!(harness.audit.actions().is_empty());
+        }
+
+        #[tokio::test]
+        async fn pinning_requires_a_room_moderator() {
+            let harness = harness();
+            let message_id = post(&harness, 100, chat::MessageStatus::Visible);
+
+            assert!(matches!(
+                harness.service.pin_message(pin(message_id, MEMBER)).await,
+                Err(Error::Forbidden)
+            ));
+        }
+    }
+
+    mod webhooks {
+        use super::super::testing::{Harness, epoch, room_id, user_id};
+        use super::*;
+
+        /// Accepts only the one public receiver, like the HTTP sender would
+        /// for an address it resolved.
+        struct PublicOnly;
+
+        #[async_trait]
+        impl WebhookSender for PublicOnly {
+            async fn accepts(
+                &self,
+                url: &chat::WebhookUrl,
+            ) -> bool {
+                url.as_ref().starts_with("https://hooks.example.com/")
+            }
+
+            async fn send(
+                &self,
+                _request: &WebhookRequest,
+            ) -> WebhookAttempt {
+                WebhookAttempt::Delivered { status: 204 }
+            }
+        }
+
+        fn create(url: &str) -> CreateWebhook {
+            CreateWebhook::builder()
+                .room_id(room_id(10))
+                .user_id(user_id(1))
+                .url(chat::WebhookUrl::try_new(url).unwrap())
+                .events(vec![chat::WebhookEvent::MessagePosted])
+                .secret(chat::WebhookSecret::try_new("a-long-enough-secret").unwrap())
+                .build()
+        }
+
+        #[tokio::test]
+        async fn create_refuses_urls_the_sender_will_not_deliver_to() {
+            let harness = Harness::with_webhook_sender(epoch(1_000), Arc::new(PublicOnly));
+            harness.room(10, chat::RoomName::Lobby, &[(1, RoomRole::Owner)]);
+
+            for url in [
+                "http://127.0.0.1:8080/hook",
+                "http://169.254.169.254/latest",
+            ] {
+                assert!(matches!(
+                    harness.service.create_webhook(create(url)).await,
+                    Err(Error::InvalidWebhook)
+                ));
+            }
+            assert!(
+                harness
+                    .repo
+                    .list_webhooks(&room_id(10))
+                    .await
+                    .unwrap()
+                    .is_empty()
+            );
+
+            let webhook = harness
+                .service
+                .create_webhook(create("https://hooks.example.com/chat"))
+                .await
+                .unwrap();
+            assert_eq!(
+                harness.repo.list_webhooks(&room_id(10)).await.unwrap(),
+                vec![webhook]
+            );
+        }
+    }
+
+    mod attachments {
+        use super::super::testing::{Harness, epoch, room_id, user_id};
+        use super::*;
+
+        const AUTHOR: u128 = 1;
+        const MODERATOR: u128 = 2;
+        const ADMIN: u128 = 3;
+        const READER: u128 = 4;
+
+        fn harness() -> Harness {
+            let harness = Harness::new(epoch(1_000));
+            harness.room(
+                10,
+                chat::RoomName::Lobby,
+                &[
+                    (AUTHOR, RoomRole::Member),
+                    (MODERATOR, RoomRole::Moderator),
+                    (ADMIN, RoomRole::Admin),
+                    (READER, RoomRole::Member),
+                ],
+            );
+            harness
+        }
+
+        fn open(attachment: &chat::Attachment) -> OpenAttachment {
+            OpenAttachment::builder()
+                .attachment_id(attachment.id)
+                .user_id(user_id(READER))
+                .build()
+        }
+
+        async fn sweep(harness: &Harness) -> usize {
+            harness
+                .service
+                .sweep_attachments(SweepAttachments::builder().build())
+                .await
+                .unwrap()
+        }
+
+        #[tokio::test]
+        async fn overturned_removal_still_serves_its_attachment() {
+            let harness = harness();
+            let message_id =
+                harness.post(100, 10, AUTHOR, chat::MessageStatus::Visible, 900);
+            let attachment = harness.attach(500, message_id);
+
+            harness.remove(message_id, MODERATOR).await;
+            assert!(matches!(
+                harness.service.open_attachment(open(&attachment)).await,
+                Err(Error::AttachmentNotFound)
+            ));
+            assert_eq!(sweep(&harness).await, 0);
+
+            harness.appeal(message_id, AUTHOR).await;
+            harness
+                .service
+                .decide_appeal(
+                    DecideAppeal::builder()
+                        .message_id(message_id)
+                        .reviewer_id(user_id(ADMIN))
+                        .decision(AppealDecision::Overturn)
+                        .build(),
+                )
+                .await
+                .unwrap();
+
+            let download = harness
+                .service
+                .open_attachment(open(&attachment))
+                .await
+                .unwrap();
+            assert_eq!(download.attachment, attachment);
+            assert_eq!(download.bytes, b"\x89PNG".to_vec());
+        }
+
+        #[tokio::test]
+        async fn moderators_open_attachments_of_held_messages() {
+            let harness = harness();
+            for (message_id, status) in [
+                (100, chat::MessageStatus::Pending),
+                (101, chat::MessageStatus::Quarantined),
+            ] {
+                let message_id = harness.post(message_id, 10, AUTHOR, status, 900);
+                let attachment =
+                    harness.attach(400 + message_id.as_uuid().as_u128(), message_id);
+                let open_as = |user: u128| {
+                    OpenAttachment::builder()
+                        .attachment_id(attachment.id)
+                        .user_id(user_id(user))
+                        .build()
+                };
+
+                for user in [AUTHOR, MODERATOR, ADMIN] {
+                    assert!(harness.service.open_attachment(open_as(user)).await.is_ok());
+                }
+                assert!(matches!(
+                    harness.service.open_attachment(open_as(READER)).await,
+                    Err(Error::AttachmentNotFound)
+                ));
+            }
+        }
+
+        #[tokio::test]
+        async fn failed_attachment_insert_drops_the_stored_files() {
+            let harness = harness();
+            harness.repo.reject_attachments();
+            let file = AttachmentUpload::builder()
+                .name(chat::AttachmentName::try_new("chart.png").expect("name"))
+                .bytes(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec())
+                .build();
+
+            let posted = harness
+                .service
+                .post_attachments(
+                    PostAttachments::builder()
+                        .room_id(room_id(10))
+                        .user_id(user_id(AUTHOR))
+                        .files(vec![file.clone(), file])
+                        .build(),
+                )
+                .await;
+
+            assert!(matches!(posted, Err(Error::Repo(_))));
+            assert_eq!(harness.blobs.count(), 0);
+        }
+
+        #[tokio::test]
+        async fn sweep_deletes_files_of_deleted_messages() {
+            let harness = harness();
+            let message_id =
+                harness.post(100, 10, AUTHOR, chat::MessageStatus::Visible, 900);
+            let attachment = harness.attach(500, message_id);
+            harness.repo.delete_message(&message_id);
+
+            assert_eq!(sweep(&harness).await, 1);
+            assert_eq!(harness.blobs.get(&attachment.blob_key).await.unwrap(), None);
+            assert!(
+                harness
+                    .repo
+                    .list_attachments(&[message_id])
+                    .await
+                    .unwrap()
+                    .is_empty()
+            );
+        }
+    }
+
+    mod moderation {
+        use super::super::testing::{Harness, epoch, user_id};
+        use super::*;
+
+        const AUTHOR: u128 = 1;
+        const MEMBER: u128 = 2;
+        const OUTSIDER: u128 = 3;
+        const MODERATOR: u128 = 4;
+
+        fn remove(
+            message_id: chat::MessageId,
+            reviewer: u128,
+        ) -> ModerateMessage {
+            ModerateMessage::builder()
+                .message_id(message_id)
+                .reviewer_id(user_id(reviewer))
+                .decision(ModerationDecision::Remove)
+                .build()
+        }
+
+        #[tokio::test]
+        async fn only_the_rooms_staff_claim_or_decide_queued_messages() {
+            let harness = Harness::new(epoch(1_000));
+            harness.room(
+                10,
+                chat::RoomName::Lobby,
+                &[(AUTHOR, RoomRole::Member), (MEMBER, RoomRole::Member)],
+            );
+            harness.room(20, chat::RoomName::Support, &[(OUTSIDER, RoomRole::Owner)]);
+            let message_id =
+                harness.post(100, 10, AUTHOR, chat::MessageStatus::Pending, 900);
+
+            assert!(matches!(
+                harness
+                    .service
+                    .claim_moderation(
+                        ClaimModeration::builder()
+                            .message_id(message_id)
+                            .reviewer_id(user_id(MEMBER))
+                            .build(),
+                    )
+                    .await,
+                Err(Error::Forbidden)
+            ));
+            assert!(matches!(
+                harness
+                    .service
+                    .release_moderation(
+                        ReleaseModeration::builder()
+                            .message_id(message_id)
+                            .reviewer_id(user_id(MEMBER))
+                            .build(),
+                    )
+                    .await,
+                Err(Error::Forbidden)
+            ));
+            assert!(matches!(
+                harness
+                    .service
+                    .moderate_message(remove(message_id, MEMBER))
+                    .await,
+                Err(Error::Forbidden)
+            ));
+            assert!(matches!(
+                harness
+                    .service
+                    .moderate_message(remove(message_id, OUTSIDER))
+                    .await,
+                Err(Error::NotMember)
+            ));
+
+            let message = harness
+                .repo
+                .find_message(&message_id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(message.status, chat::MessageStatus::Pending);
+            assert!(harness.audit.actions().is_empty());
+        }
+
+        #[tokio::test]
+        async fn removing_a_quarantined_message_keeps_its_audience() {
+            let harness = Harness::new(epoch(1_000));
+            harness.room(
+                10,
+                chat::RoomName::Lobby,
+                &[
+                    (AUTHOR, RoomRole::Member),
+                    (MEMBER, RoomRole::Member),
+                    (MODERATOR, RoomRole::Moderator),
+                ],
+            );
+            let message_id =
+                harness.post(100, 10, AUTHOR, chat::MessageStatus::Quarantined, 900);
+
+            let outcome = harness
+                .service
+                .moderate_message(remove(message_id, MODERATOR))
+                .await
+                .unwrap();
+
+            assert_eq!(outcome.message.status, chat::MessageStatus::Removed);
+            assert_eq!(
+                outcome.audience,
+                Audience::Only(vec![user_id(MODERATOR), user_id(AUTHOR)])
+            );
+        }
+
+        #[tokio::test]
+        async fn the_queue_lists_only_rooms_the_viewer_moderates() {
+            let harness = Harness::new(epoch(1_000));
+            harness.room(
+                10,
+                chat::RoomName::Lobby,
+                &[(AUTHOR, RoomRole::Member), (MEMBER, RoomRole::Member)],
+            );
+            harness.room(
+                20,
+                chat::RoomName::Support,
+                &[(AUTHOR, RoomRole::Member), (OUTSIDER, RoomRole::Owner)],
+            );
+            harness.post(100, 10, AUTHOR, chat::MessageStatus::Pending, 900);
+            let support = harness.post(200, 20, AUTHOR, chat::MessageStatus::Pending, 910);
+            let queue = |reviewer: u128| {
+                ListModerationQueue::builder()
+                    .reviewer_id(user_id(reviewer))
+                    .build()
+            };
+
+            assert!(matches!(
+                harness.service.list_moderation_queue(queue(MEMBER)).await,
+                Err(Error::Forbidden)
+            ));
+            let items = harness
+                .service
+                .list_moderation_queue(queue(OUTSIDER))
+                .await
+                .unwrap();
+            assert_eq!(
+                items.iter().map(|item| item.message_id).collect::<Vec<_>>(),
+                vec![support]
+            );
+        }
+    }
+
+    mod appeals {
+        use super::super::testing::{Harness, epoch, user_id};
+        use super::*;
+
+        const AUTHOR: u128 = 1;
+        const MODERATOR: u128 = 2;
+        const OWNER: u128 = 3;
+        const MEMBER: u128 = 4;
+        const OUTSIDER: u128 = 5;
+
+        /// A removed message with a pending appeal.
+        async fn appealed() -> (Harness, chat::MessageId) {
+            let harness = Harness::new(epoch(1_000));
+            harness.room(
+                10,
+                chat::RoomName::Lobby,
+                &[
+                    (AUTHOR, RoomRole::Member),
+                    (MODERATOR, RoomRole::Moderator),
+                    (OWNER, RoomRole::Owner),
+                    (MEMBER, RoomRole::Member),
+                ],
+            );
+            harness.room(20, chat::RoomName::Support, &[(OUTSIDER, RoomRole::Owner)]);
+            let message_id =
+                harness.post(100, 10, AUTHOR, chat::MessageStatus::Visible, 900);
+            harness.remove(message_id, MODERATOR).await;
+            harness.appeal(message_id, AUTHOR).await;
+            (harness, message_id)
+        }
+
+        fn decide(
+            message_id: chat::MessageId,
+            reviewer: u128,
+        ) -> DecideAppeal {
+            DecideAppeal::builder()
+                .message_id(message_id)
+                .reviewer_id(user_id(reviewer))
+                .decision(AppealDecision::Overturn)
+                .build()
+        }
+
+        #[tokio::test]
+        async fn only_the_rooms_staff_decide_appeals() {
+            let (harness, message_id) = appealed().await;
+
+            assert!(matches!(
+                harness
+                    .service
+                    .decide_appeal(decide(message_id, OUTSIDER))
+                    .await,
+                Err(Error::NotMember)
+            ));
+            for reviewer in [MEMBER, AUTHOR, MODERATOR] {
+                assert!(matches!(
+                    harness
+                        .service
+                        .decide_appeal(decide(message_id, reviewer))
+                        .await,
+                    Err(Error::Forbidden)
+                ));
+            }
+
+            let appeal = harness
+                .service
+                .decide_appeal(decide(message_id, OWNER))
+                .await
+                .unwrap();
+            assert_eq!(appeal.status, AppealStatus::Overturned);
+            let message = harness
+                .repo
+                .find_message(&message_id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(message.status, chat::MessageStatus::Visible);
+        }
+
+        #[tokio::test]
+        async fn appeals_are_listed_to_the_rooms_staff_only() {
+            let (harness, message_id) = appealed().await;
+            let listed = |reviewer: u128| {
+                ListAppeals::builder()
+                    .reviewer_id(user_id(reviewer))
+                    .build()
+            };
+
+            let appeals = harness.service.list_appeals(listed(OWNER)).await.unwrap();
+            assert_eq!(
+                appeals
+                    .iter()
+                    .map(|appeal| appeal.message_id)
+                    .collect::<Vec<_>>(),
+                vec![message_id]
+            );
+            for reviewer in [MEMBER, OUTSIDER] {
+                assert!(
+                    harness
+                        .service
+                        .list_appeals(listed(reviewer))
+                        .await
+                        .unwrap()
+                        .is_empty()
+                );
+            }
+        }
+    }
+
+    mod flood {
+        use super::super::testing::{Harness, body, epoch, room_id, user_id};
+        use super::*;
+
+        const AUTHOR: u128 = 1;
+
+        #[tokio::test]
+        async fn the_third_strike_mutes_without_a_moderator() {
+            let harness = Harness::new(epoch(1_000));
+            harness.room(10, chat::RoomName::Lobby, &[(AUTHOR, RoomRole::Member)]);
+            harness.post(100_000, 10, AUTHOR, chat::MessageStatus::Visible, 990);
+            for at in [960, 980] {
+                harness
+                    .repo
+                    .record_flood_strike(
+                        &user_id(AUTHOR),
+                        &room_id(10),
+                        FloodSignal::Duplicate,
+                        epoch(at),
+                        epoch(0),
+                    )
+                    .await
+                    .unwrap();
+            }
+
+            let repeated = harness
+                .service
+                .post_message(
+                    PostMessage::builder()
+                        .room_id(room_id(10))
+                        .user_id(user_id(AUTHOR))
+                        .body(body("message 100000"))
+                        .build(),
+                )
+                .await;
+
+            assert!(matches!(repeated, Err(Error::Muted)));
+            let mutes = harness.repo.mutes();
+            assert_eq!(mutes.len(), 1);
+            assert_eq!(mutes[0].user_id, user_id(AUTHOR));
+            assert_eq!(mutes[0].until, epoch(1_000) + FLOOD_MUTE);
+            assert_eq!(mutes[0].muted_by, None);
+        }
     }
 }
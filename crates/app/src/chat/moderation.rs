@@ -0,0 +1,94 @@
+use std::time::{Duration, SystemTime};
+
+use bon::Builder;
+
+use domain::chat;
+
+use super::{Audience, ModerationReason};
+
+/// How long a claim keeps other reviewers off an item; claiming again
+/// renews it.
+pub const MODERATION_CLAIM_LEASE: Duration = Duration::from_secs(10 * 60);
+/// Target time from queueing to decision.
+pub const MODERATION_SLA: Duration = Duration::from_secs(60 * 60);
+/// Items older than this are flagged before they breach the SLA.
+const DUE_SOON_AFTER: Duration = Duration::from_secs(45 * 60);
+
+#[derive(Clone, Debug, Builder)]
+pub struct ListModerationQueue {
+    /// Only items from rooms this member moderates are listed.
+    pub reviewer_id: chat::UserId,
+    pub room_id: Option<chat::RoomId>,
+    pub reason: Option<ModerationReason>,
+    #[builder(default = 50)]
+    pub limit: usize,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct ClaimModeration {
+    pub message_id: chat::MessageId,
+    pub reviewer_id: chat::UserId,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct ReleaseModeration {
+    pub message_id: chat::MessageId,
+    pub reviewer_id: chat::UserId,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ModerationOutcome {
+    /// The message with its new status.
+    pub message: chat::Message,
+    /// Who was shown the message before the decision.
+    pub audience: Audience,
+}
+
+/// An unexpired lease on a queue item.
+#[derive(Clone, Debug, PartialEq, Builder)]
+pub struct ModerationClaim {
+    pub reviewer_id: chat::UserId,
+    pub reviewer_name: domain::user::Username,
+    pub expires_at: SystemTime,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SlaStatus {
+    OnTrack,
+    DueSoon,
+    Overdue,
+}
+
+impl SlaStatus {
+    pub fn for_age(age: Duration) -> Self {
+        if age >= MODERATION_SLA {
+            SlaStatus::Overdue
+        } else if age >= DUE_SOON_AFTER {
+            SlaStatus::DueSoon
+        } else {
+            SlaStatus::OnTrack
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SlaStatus::OnTrack => "On track",
+            SlaStatus::DueSoon => "Due soon",
+            SlaStatus::Overdue => "Overdue",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sla_flags_old_items() {
+        let minutes = |value: u64| Duration::from_secs(value * 60);
+        assert_eq!(SlaStatus::for_age(minutes(5)), SlaStatus::OnTrack);
+        assert_eq!(SlaStatus::for_age(minutes(45)), SlaStatus::DueSoon);
+        assert_eq!(SlaStatus::for_age(minutes(59)), SlaStatus::DueSoon);
+        assert_eq!(SlaStatus::for_age(minutes(60)), SlaStatus::Overdue);
+    }
+}
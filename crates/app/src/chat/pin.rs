@@ -0,0 +1,40 @@
+use std::time::SystemTime;
+
+use bon::Builder;
+
+use domain::chat;
+
+/// Pins kept per room; pinning beyond this fails with `PinLimitReached`.
+pub const MAX_PINS_PER_ROOM: usize = 5;
+
+#[derive(Clone, Debug, Builder)]
+pub struct PinMessage {
+    pub message_id: chat::MessageId,
+    pub user_id: chat::UserId,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct UnpinMessage {
+    pub message_id: chat::MessageId,
+    pub user_id: chat::UserId,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct ListPins {
+    pub room_id: chat::RoomId,
+    pub user_id: chat::UserId,
+}
+
+#[derive(Clone, Debug, PartialEq, Builder)]
+pub struct PinnedMessage {
+    pub message: chat::Message,
+    pub pinned_by: chat::UserId,
+    pub pinned_at: SystemTime,
+}
+
+/// A room's pins after a pin or unpin.
+#[derive(Clone, Debug, PartialEq, Builder)]
+pub struct RoomPins {
+    pub room_id: chat::RoomId,
+    pub pins: Vec<PinnedMessage>,
+}
@@ -0,0 +1,144 @@
+use std::time::SystemTime;
+
+use bon::Builder;
+
+use domain::chat;
+
+pub const MIN_POLL_OPTIONS: usize = 2;
+pub const MAX_POLL_OPTIONS: usize = 10;
+
+/// Posts `question` as a normal message (rate limits and moderation apply)
+/// and attaches the poll to it.
+#[derive(Clone, Debug, Builder)]
+pub struct CreatePoll {
+    pub room_id: chat::RoomId,
+    pub user_id: chat::UserId,
+    pub question: chat::MessageBody,
+    pub options: Vec<chat::PollOptionLabel>,
+    #[builder(default = chat::PollKind::Single)]
+    pub kind: chat::PollKind,
+    pub closes_at: Option<SystemTime>,
+}
+
+/// One ballot per member. Single-choice polls take exactly one option.
+#[derive(Clone, Debug, Builder)]
+pub struct CastVote {
+    pub message_id: chat::MessageId,
+    pub user_id: chat::UserId,
+    pub option_ids: Vec<chat::PollOptionId>,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct ClosePoll {
+    pub message_id: chat::MessageId,
+    pub user_id: chat::UserId,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct ListPolls {
+    pub room_id: chat::RoomId,
+    pub user_id: chat::UserId,
+    pub message_ids: Vec<chat::MessageId>,
+}
+
+#[derive(Clone, Debug, PartialEq, Builder)]
+pub struct PollTally {
+    pub option: chat::PollOption,
+    pub votes: u64,
+}
+
+/// A poll with its current counts, options in poll order.
+#[derive(Clone, Debug, PartialEq, Builder)]
+pub struct PollResults {
+    pub poll: chat::Poll,
+    /// Author of the question message; they may close the poll early.
+    pub author_id: chat::UserId,
+    pub tallies: Vec<PollTally>,
+    /// Members who cast a ballot; differs from the vote sum for
+    /// multiple-choice polls.
+    pub voters: u64,
+}
+
+/// Option labels must be distinct (ignoring case) and within the option
+/// count limits.
+pub(super) fn valid_options(options: &[chat::PollOptionLabel]) -> bool {
+    if !(MIN_POLL_OPTIONS..=MAX_POLL_OPTIONS).contains(&options.len()) {
+        return false;
+    }
+    let mut seen = std::collections::HashSet::with_capacity(options.len());
+    options
+        .iter()
+        .all(|option| seen.insert(option.to_string().to_lowercase()))
+}
+
+/// Deduplicates the ballot and checks it against the poll's options and
+/// kind.
+pub(super) fn valid_ballot(
+    poll: &chat::Poll,
+    option_ids: &[chat::PollOptionId],
+) -> Option<Vec<chat::PollOptionId>> {
+    let mut ballot = Vec::with_capacity(option_ids.len());
+    for option_id in option_ids {
+        if !poll.options.iter().any(|option| option.id == *option_id) {
+            return None;
+        }
+        if !ballot.contains(option_id) {
+            ballot.push(*option_id);
+        }
+    }
+    match (poll.kind, ballot.len()) {
+        (_, 0) => None,
+        (chat::PollKind::Single, 1) | (chat::PollKind::Multiple, _) => Some(ballot),
+        (chat::PollKind::Single, _) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn label(value: &str) -> chat::PollOptionLabel {
+        chat::PollOptionLabel::try_new(value).expect("label")
+    }
+
+    fn poll(kind: chat::PollKind) -> chat::Poll {
+        chat::Poll::builder()
+            .message_id(chat::MessageId::new_v4())
+            .room_id(chat::RoomId::new_v4())
+            .kind(kind)
+            .options(vec![
+                chat::PollOption::builder()
+                    .id(chat::PollOptionId::new_v4())
+                    .label(label("yes"))
+                    .build(),
+                chat::PollOption::builder()
+                    .id(chat::PollOptionId::new_v4())
+                    .label(label("no"))
+                    .build(),
+            ])
+            .build()
+    }
+
+    #[test]
+    fn options_must_be_distinct_and_counted() {
+        assert!(valid_options(&[label("yes"), label("no")]));
+        assert!(!valid_options(&[label("yes")]));
+        assert!(!valid_options(&[label("Yes"), label("yes")]));
+    }
+
+    #[test]
+    fn single_choice_ballots_take_one_option() {
+        let single = poll(chat::PollKind::Single);
+        let both = [single.options[0].id, single.options[1].id];
+        assert!(valid_ballot(&single, &both).is_none());
+        assert_eq!(
+            valid_ballot(&single, &[both[0], both[0]]),
+            Some(vec![both[0]])
+        );
+
+        let multiple = poll(chat::PollKind::Multiple);
+        let both = [multiple.options[0].id, multiple.options[1].id];
+        assert_eq!(valid_ballot(&multiple, &both), Some(both.to_vec()));
+        assert!(valid_ballot(&multiple, &[chat::PollOptionId::new_v4()]).is_none());
+    }
+}
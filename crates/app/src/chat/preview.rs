@@ -0,0 +1,106 @@
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+use bon::Builder;
+
+use domain::chat;
+
+/// How long a fetched card is reused before the link is fetched again.
+pub const LINK_PREVIEW_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Failed fetches are cached too, but retried sooner.
+pub const FAILED_PREVIEW_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Fetches the page behind a link and reads its card. Implementations
+/// must refuse private network addresses; `None` means no card.
+#[async_trait]
+pub trait LinkPreviewFetcher: Send + Sync {
+    async fn fetch(
+        &self,
+        url: &chat::PreviewUrl,
+    ) -> Option<chat::LinkPreview>;
+}
+
+/// Used when no fetcher is configured; no link gets a card.
+pub(super) struct DisabledLinkPreviewFetcher;
+
+#[async_trait]
+impl LinkPreviewFetcher for DisabledLinkPreviewFetcher {
+    async fn fetch(
+        &self,
+        _url: &chat::PreviewUrl,
+    ) -> Option<chat::LinkPreview> {
+        None
+    }
+}
+
+/// A cache entry, keyed by `preview.url`.
+#[derive(Clone, Debug, PartialEq, Builder)]
+pub struct CachedPreview {
+    pub preview: chat::LinkPreview,
+    pub fetched_at: SystemTime,
+}
+
+impl CachedPreview {
+    pub fn is_fresh(
+        &self,
+        now: SystemTime,
+    ) -> bool {
+        let ttl = if self.preview.is_empty() {
+            FAILED_PREVIEW_TTL
+        } else {
+            LINK_PREVIEW_TTL
+        };
+        now.duration_since(self.fetched_at)
+            .is_ok_and(|age| age < ttl)
+    }
+}
+
+/// The first http(s) link in a message, without trailing punctuation.
+pub fn first_url(body: &chat::MessageBody) -> Option<chat::PreviewUrl> {
+    body.to_string()
+        .split_whitespace()
+        .map(|word| word.trim_start_matches(['(', '<', '"', '\'']))
+        .filter(|word| word.starts_with("https://") || word.starts_with("http://"))
+        .map(|word| word.trim_end_matches(['.', ',', ';', ':', '!', '?', ')', '>', '"', '\'']))
+        .find_map(|word| chat::PreviewUrl::try_new(word).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(body: &str) -> Option<String> {
+        first_url(&chat::MessageBody::try_new(body).expect("body")).map(|url| url.to_string())
+    }
+
+    #[test]
+    fn finds_the_first_link() {
+        assert_eq!(
+            url("see (https://example.com/a?b=1), then http://other.test"),
+            Some("https://example.com/a?b=1".to_string())
+        );
+        assert_eq!(url("ftp://example.com and example.com"), None);
+    }
+
+    #[test]
+    fn failed_fetches_expire_sooner() {
+        let fetched_at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let url = chat::PreviewUrl::try_new("https://example.com").expect("url");
+        let failed = CachedPreview::builder()
+            .preview(chat::LinkPreview::empty(url.clone()))
+            .fetched_at(fetched_at)
+            .build();
+        let card = CachedPreview::builder()
+            .preview(chat::LinkPreview {
+                title: chat::PreviewTitle::try_new("Example").ok(),
+                ..chat::LinkPreview::empty(url)
+            })
+            .fetched_at(fetched_at)
+            .build();
+        let later = fetched_at + FAILED_PREVIEW_TTL;
+
+        assert!(!failed.is_fresh(later));
+        assert!(card.is_fresh(later));
+        assert!(!card.is_fresh(fetched_at + LINK_PREVIEW_TTL));
+    }
+}
@@ -0,0 +1,113 @@
+use std::time::SystemTime;
+
+use bon::Builder;
+use strum_macros::{Display, EnumString};
+
+use domain::chat;
+
+/// Reports since the last review that hide a message until a moderator
+/// decides, unless the service is built with another threshold.
+pub const DEFAULT_REPORT_HIDE_THRESHOLD: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Display, EnumString)]
+pub enum ReportCategory {
+    #[strum(serialize = "spam")]
+    Spam,
+    #[strum(serialize = "harassment")]
+    Harassment,
+    #[strum(serialize = "hate")]
+    Hate,
+    #[strum(serialize = "violence")]
+    Violence,
+    #[strum(serialize = "sexual")]
+    Sexual,
+    #[strum(serialize = "other")]
+    Other,
+}
+
+impl ReportCategory {
+    pub const ALL: [ReportCategory; 6] = [
+        ReportCategory::Spam,
+        ReportCategory::Harassment,
+        ReportCategory::Hate,
+        ReportCategory::Violence,
+        ReportCategory::Sexual,
+        ReportCategory::Other,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ReportCategory::Spam => "Spam",
+            ReportCategory::Harassment => "Harassment",
+            ReportCategory::Hate => "Hate speech",
+            ReportCategory::Violence => "Violence or threats",
+            ReportCategory::Sexual => "Sexual content",
+            ReportCategory::Other => "Something else",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct ReportMessage {
+    pub message_id: chat::MessageId,
+    pub reporter_id: chat::UserId,
+    pub category: ReportCategory,
+}
+
+/// One member's report as shown to moderators.
+#[derive(Clone, Debug, PartialEq, Builder)]
+pub struct MessageReport {
+    pub reporter_id: chat::UserId,
+    pub reporter_name: domain::user::Username,
+    pub category: ReportCategory,
+    pub reported_at: SystemTime,
+}
+
+/// State of a message's reports after one was filed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReportTally {
+    /// Distinct reporters since the message was last reviewed.
+    pub open_reports: usize,
+    /// False when this member had already reported the message.
+    pub counted: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReportOutcome {
+    pub message: chat::Message,
+    pub tally: ReportTally,
+    /// Set when this report crossed the threshold and hid the message.
+    pub hidden: bool,
+}
+
+pub(super) fn reaches_threshold(
+    tally: ReportTally,
+    threshold: usize,
+) -> bool {
+    tally.counted && tally.open_reports >= threshold
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn categories_round_trip() {
+        for category in ReportCategory::ALL {
+            assert_eq!(category.to_string().parse::<ReportCategory>(), Ok(category));
+        }
+        assert!("rude".parse::<ReportCategory>().is_err());
+    }
+
+    #[test]
+    fn only_new_reports_hide() {
+        let tally = |open_reports, counted| ReportTally {
+            open_reports,
+            counted,
+        };
+        assert!(!reaches_threshold(tally(2, true), 3));
+        assert!(reaches_threshold(tally(3, true), 3));
+        assert!(!reaches_threshold(tally(3, false), 3));
+        assert!(reaches_threshold(tally(5, true), 3));
+    }
+}
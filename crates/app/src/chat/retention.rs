@@ -0,0 +1,96 @@
+use bon::Builder;
+use nutype::nutype;
+use strum_macros::{Display, EnumString};
+
+use domain::chat;
+
+#[nutype(
+    validate(greater = 0),
+    derive(Clone, Copy, Debug, PartialEq, Eq, Display)
+)]
+pub struct RetentionDays(u32);
+
+#[nutype(
+    validate(greater = 0),
+    derive(Clone, Copy, Debug, PartialEq, Eq, Display)
+)]
+pub struct RetentionCount(u32);
+
+/// What happens to expired messages. `Redact` keeps the row (and thread
+/// position) but blanks the body and marks it removed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Display, EnumString)]
+pub enum PurgeMode {
+    #[strum(serialize = "delete")]
+    Delete,
+    #[strum(serialize = "redact")]
+    Redact,
+}
+
+/// Per-room retention. A message expires when it is older than `max_age`
+/// or falls outside the newest `max_messages`; rooms without a policy keep
+/// everything.
+#[derive(Clone, Debug, PartialEq, Builder)]
+pub struct RetentionPolicy {
+    pub max_age: Option<RetentionDays>,
+    pub max_messages: Option<RetentionCount>,
+    #[builder(default = PurgeMode::Delete)]
+    pub mode: PurgeMode,
+}
+
+#[derive(Clone, Debug, PartialEq, Builder)]
+pub struct RoomRetention {
+    pub room_id: chat::RoomId,
+    pub policy: RetentionPolicy,
+    /// Admin who last changed the policy; purge runs are audited as them.
+    pub set_by: chat::UserId,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct SetRetention {
+    pub room_id: chat::RoomId,
+    pub user_id: chat::UserId,
+    /// `None` keeps messages forever.
+    pub policy: Option<RetentionPolicy>,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct RunPurge {
+    /// Limit the run to one room; all rooms with a policy otherwise.
+    pub room_id: Option<chat::RoomId>,
+    #[builder(default = false)]
+    pub dry_run: bool,
+    #[builder(default = 500)]
+    pub batch_size: usize,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct PreviewPurge {
+    pub room_id: chat::RoomId,
+    pub user_id: chat::UserId,
+}
+
+#[derive(Clone, Debug, PartialEq, Builder)]
+pub struct RoomPurge {
+    pub room_id: chat::RoomId,
+    pub mode: PurgeMode,
+    /// Messages past the policy when the run started.
+    pub expired: u64,
+    /// Messages actually deleted or redacted; always 0 for dry runs.
+    pub purged: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PurgeReport {
+    pub dry_run: bool,
+    pub rooms: Vec<RoomPurge>,
+}
+
+impl PurgeReport {
+    pub fn expired(&self) -> u64 {
+        self.rooms.iter().map(|room| room.expired).sum()
+    }
+
+    pub fn purged(&self) -> u64 {
+        self.rooms.iter().map(|room| room.purged).sum()
+    }
+}
@@ -0,0 +1,34 @@
+use std::time::{Duration, SystemTime};
+
+use bon::Builder;
+
+use domain::chat;
+
+/// How far ahead a message may be scheduled.
+pub const MAX_SCHEDULE_AHEAD: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+#[derive(Clone, Debug, Builder)]
+pub struct ScheduleMessage {
+    pub room_id: chat::RoomId,
+    pub user_id: chat::UserId,
+    pub body: chat::MessageBody,
+    pub send_at: SystemTime,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct CancelSchedule {
+    pub schedule_id: chat::ScheduleId,
+    pub user_id: chat::UserId,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct ListSchedules {
+    pub room_id: chat::RoomId,
+    pub user_id: chat::UserId,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct PostDueSchedules {
+    #[builder(default = 100)]
+    pub limit: usize,
+}
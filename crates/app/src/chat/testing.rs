@@ -0,0 +1,1572 @@
+//! In-memory fakes for `Service` tests. They mirror the semantics the
+//! Postgres repository documents on each trait method, and fail loudly on
+//! methods no test relies on yet.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use async_trait::async_trait;
+
+use domain::chat;
+
+use super::*;
+
+/// What the Postgres repository writes over a redacted body.
+const REDACTED_BODY: &str = "[redacted]";
+
+fn unsupported<T>(method: &str) -> Result<T> {
+    Err(Error::Repo(
+        format!("{method} is not supported by the test fake").into(),
+    ))
+}
+
+pub(super) fn room_id(value: u128) -> chat::RoomId {
+    chat::RoomId::from_uuid(uuid::Uuid::from_u128(value))
+}
+
+pub(super) fn user_id(value: u128) -> chat::UserId {
+    chat::UserId::from_uuid(uuid::Uuid::from_u128(value))
+}
+
+pub(super) fn body(text: &str) -> chat::MessageBody {
+    chat::MessageBody::try_new(text).expect("body")
+}
+
+pub(super) fn epoch(seconds: u64) -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_secs(seconds)
+}
+
+struct Member {
+    role: RoomRole,
+    last_read: Option<chat::MessageId>,
+    /// Orders readers like `last_read_at` does.
+    read_sequence: u64,
+}
+
+#[derive(Clone)]
+pub(super) struct Mute {
+    pub(super) room_id: chat::RoomId,
+    pub(super) user_id: chat::UserId,
+    pub(super) until: SystemTime,
+    pub(super) muted_by: Option<chat::UserId>,
+}
+
+struct Pin {
+    room_id: chat::RoomId,
+    message_id: chat::MessageId,
+    pinned_by: chat::UserId,
+    pinned_at: SystemTime,
+}
+
+#[derive(Default)]
+struct RepoState {
+    rooms: Vec<chat::Room>,
+    messages: Vec<chat::Message>,
+    members: HashMap<(chat::RoomId, chat::UserId), Member>,
+    read_sequence: u64,
+    schedules: Vec<chat::ScheduledMessage>,
+    pins: Vec<Pin>,
+    webhooks: Vec<chat::Webhook>,
+    attachments: Vec<chat::Attachment>,
+    retention: Vec<RoomRetention>,
+    redacted: Vec<chat::MessageId>,
+    mutes: Vec<Mute>,
+    /// Makes `insert_attachment` fail, like a lost connection would.
+    reject_attachments: bool,
+    flood_strikes: Vec<(chat::UserId, SystemTime)>,
+    /// Rows touched by each `purge_expired` call, in call order.
+    purge_batches: Vec<usize>,
+}
+
+impl RepoState {
+    fn message(
+        &self,
+        message_id: &chat::MessageId,
+    ) -> Option<&chat::Message> {
+        self.messages
+            .iter()
+            .find(|message| message.id == *message_id)
+    }
+
+    /// Pins whose message is still visible, like the repository's join.
+    fn visible_pins(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Vec<PinnedMessage> {
+        self.pins
+            .iter()
+            .filter(|pin| pin.room_id == *room_id)
+            .filter_map(|pin| {
+                let message = self
+                    .message(&pin.message_id)
+                    .filter(|message| message.status == chat::MessageStatus::Visible)?;
+                Some(
+                    PinnedMessage::builder()
+                        .message(message.clone())
+                        .pinned_by(pin.pinned_by)
+                        .pinned_at(pin.pinned_at)
+                        .build(),
+                )
+            })
+            .collect()
+    }
+
+    /// Unredacted messages past the policy, oldest first: older than
+    /// `max_age` days, or beyond the newest `max_messages`.
+    fn expired(
+        &self,
+        retention: &RoomRetention,
+        now: SystemTime,
+    ) -> Vec<chat::MessageId> {
+        let mut ranked: Vec<&chat::Message> = self
+            .messages
+            .iter()
+            .filter(|message| {
+                message.room_id == retention.room_id && !self.redacted.contains(&message.id)
+            })
+            .collect();
+        ranked.sort_by_key(|message| {
+            std::cmp::Reverse((message.created_at, *message.id.as_uuid()))
+        });
+        let cutoff = retention
+            .policy
+            .max_age
+            .map(|days| now - Duration::from_secs(u64::from(days.into_inner()) * 86_400));
+        let keep = retention
+            .policy
+            .max_messages
+            .map(|count| count.into_inner() as usize);
+
+        let mut expired: Vec<&chat::Message> = ranked
+            .into_iter()
+            .enumerate()
+            .filter(|(index, message)| {
+                cutoff.is_some_and(|cutoff| message.created_at < cutoff)
+                    || keep.is_some_and(|keep| index + 1 > keep)
+            })
+            .map(|(_, message)| message)
+            .collect();
+        expired.sort_by_key(|message| (message.created_at, *message.id.as_uuid()));
+        expired.into_iter().map(|message| message.id).collect()
+    }
+}
+
+#[derive(Default)]
+pub(super) struct MemoryRepo {
+    state: Mutex<RepoState>,
+}
+
+impl MemoryRepo {
+    fn state(&self) -> std::sync::MutexGuard<'_, RepoState> {
+        self.state.lock().expect("repo lock")
+    }
+
+    pub(super) fn messages(&self) -> Vec<chat::Message> {
+        self.state().messages.clone()
+    }
+
+    pub(super) fn purge_batches(&self) -> Vec<usize> {
+        self.state().purge_batches.clone()
+    }
+
+    pub(super) fn mutes(&self) -> Vec<Mute> {
+        self.state().mutes.clone()
+    }
+
+    pub(super) fn reject_attachments(&self) {
+        self.state().reject_attachments = true;
+    }
+
+    pub(super) fn delete_message(
+        &self,
+        message_id: &chat::MessageId,
+    ) {
+        self.state()
+            .messages
+            .retain(|message| message.id != *message_id);
+    }
+}
+
+#[async_trait]
+impl Repository for MemoryRepo {
+    async fn create_room(
+        &self,
+        _room: &chat::Room,
+    ) -> Result<()> {
+        unsupported("create_room")
+    }
+
+    async fn find_room(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<Option<chat::Room>> {
+        Ok(self
+            .state()
+            .rooms
+            .iter()
+            .find(|room| room.id == *room_id)
+            .cloned())
+    }
+
+    async fn find_room_by_name(
+        &self,
+        _name: &chat::RoomName,
+    ) -> Result<Option<chat::Room>> {
+        unsupported("find_room_by_name")
+    }
+
+    async fn list_messages(
+        &self,
+        room_id: &chat::RoomId,
+        limit: usize,
+    ) -> Result<Vec<chat::Message>> {
+        let state = self.state();
+        let mut messages: Vec<chat::Message> = state
+            .messages
+            .iter()
+            .filter(|message| message.room_id == *room_id)
+            .cloned()
+            .collect();
+        messages.sort_by_key(|message| message.created_at);
+        let skip = messages.len().saturating_sub(limit);
+        Ok(messages.into_iter().skip(skip).collect())
+    }
+
+    async fn find_message(
+        &self,
+        message_id: &chat::MessageId,
+    ) -> Result<Option<chat::Message>> {
+        Ok(self.state().message(message_id).cloned())
+    }
+
+    async fn insert_message(
+        &self,
+        message: &chat::Message,
+    ) -> Result<()> {
+        self.state().messages.push(message.clone());
+        Ok(())
+    }
+
+    async fn add_membership(
+        &self,
+        _room_id: &chat::RoomId,
+        _user_id: &chat::UserId,
+        _role: RoomRole,
+    ) -> Result<()> {
+        unsupported("add_membership")
+    }
+
+    async fn is_member(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+    ) -> Result<bool> {
+        Ok(self.state().members.contains_key(&(*room_id, *user_id)))
+    }
+
+    async fn room_members(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<Vec<chat::UserId>> {
+        let mut members: Vec<chat::UserId> = self
+            .state()
+            .members
+            .keys()
+            .filter(|(room, _)| room == room_id)
+            .map(|(_, user)| *user)
+            .collect();
+        members.sort_by_key(|user| *user.as_uuid());
+        Ok(members)
+    }
+
+    async fn update_message_status(
+        &self,
+        message_id: &chat::MessageId,
+        status: chat::MessageStatus,
+    ) -> Result<()> {
+        if let Some(message) = self
+            .state()
+            .messages
+            .iter_mut()
+            .find(|message| message.id == *message_id)
+        {
+            message.status = status;
+        }
+        Ok(())
+    }
+
+    async fn mark_read(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+        message_id: &chat::MessageId,
+    ) -> Result<bool> {
+        let mut state = self.state();
+        let Some(target) = state
+            .message(message_id)
+            .filter(|message| message.room_id == *room_id)
+            .map(|message| message.created_at)
+        else {
+            return Ok(false);
+        };
+        let previous = state
+            .members
+            .get(&(*room_id, *user_id))
+            .and_then(|member| member.last_read)
+            .and_then(|last_read| state.message(&last_read))
+            .map(|message| message.created_at);
+        if previous.is_some_and(|previous| target <= previous) {
+            return Ok(false);
+        }
+        state.read_sequence += 1;
+        let sequence = state.read_sequence;
+        let Some(member) = state.members.get_mut(&(*room_id, *user_id)) else {
+            return Ok(false);
+        };
+        member.last_read = Some(*message_id);
+        member.read_sequence = sequence;
+        Ok(true)
+    }
+
+    async fn list_unread(
+        &self,
+        user_id: &chat::UserId,
+    ) -> Result<Vec<RoomUnread>> {
+        let state = self.state();
+        let mut rooms: Vec<RoomUnread> = state
+            .members
+            .iter()
+            .filter(|((_, member_id), _)| member_id == user_id)
+            .filter_map(|((room_id, _), member)| {
+                let room = state.rooms.iter().find(|room| room.id == *room_id)?;
+                let read_at = member
+                    .last_read
+                    .and_then(|last_read| state.message(&last_read))
+                    .map(|message| message.created_at);
+                let unread = state
+                    .messages
+                    .iter()
+                    .filter(|message| message.room_id == *room_id)
+                    .filter(|message| message.status == chat::MessageStatus::Visible)
+                    .filter(|message| message.user_id != *user_id)
+                    .filter(|message| {
+                        read_at.is_none_or(|read_at| message.created_at > read_at)
+                    })
+                    .count() as u64;
+                Some(
+                    RoomUnread::builder()
+                        .room_id(room.id)
+                        .room_name(room.name)
+                        .unread(unread)
+                        .build(),
+                )
+            })
+            .collect();
+        rooms.sort_by_key(|room| room.room_name.to_string());
+        Ok(rooms)
+    }
+
+    async fn list_readers(
+        &self,
+        room_id: &chat::RoomId,
+        message_id: &chat::MessageId,
+    ) -> Result<Vec<chat::UserId>> {
+        let state = self.state();
+        let Some(target) = state
+            .message(message_id)
+            .filter(|message| message.room_id == *room_id)
+        else {
+            return Ok(Vec::new());
+        };
+        let mut readers: Vec<(u64, chat::UserId)> = state
+            .members
+            .iter()
+            .filter(|((room, user), _)| room == room_id && *user != target.user_id)
+            .filter(|(_, member)| {
+                member
+                    .last_read
+                    .and_then(|last_read| state.message(&last_read))
+                    .is_some_and(|last_read| last_read.created_at >= target.created_at)
+            })
+            .map(|((_, user), member)| (member.read_sequence, *user))
+            .collect();
+        readers.sort_by_key(|(sequence, _)| *sequence);
+        Ok(readers.into_iter().map(|(_, user)| user).collect())
+    }
+
+    async fn search_messages(
+        &self,
+        _user_id: &chat::UserId,
+        _query: &SearchQuery,
+        _limit: usize,
+    ) -> Result<Vec<SearchHit>> {
+        unsupported("search_messages")
+    }
+
+    async fn member_role(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+    ) -> Result<Option<RoomRole>> {
+        Ok(self
+            .state()
+            .members
+            .get(&(*room_id, *user_id))
+            .map(|member| member.role))
+    }
+
+    async fn export_messages(
+        &self,
+        _room_id: &chat::RoomId,
+        _after: Option<&ExportCursor>,
+        _limit: usize,
+    ) -> Result<Vec<ExportRecord>> {
+        unsupported("export_messages")
+    }
+
+    async fn save_retention(
+        &self,
+        retention: &RoomRetention,
+    ) -> Result<()> {
+        let mut state = self.state();
+        state
+            .retention
+            .retain(|saved| saved.room_id != retention.room_id);
+        state.retention.push(retention.clone());
+        Ok(())
+    }
+
+    async fn clear_retention(
+        &self,
+        _room_id: &chat::RoomId,
+    ) -> Result<()> {
+        unsupported("clear_retention")
+    }
+
+    async fn find_retention(
+        &self,
+        _room_id: &chat::RoomId,
+    ) -> Result<Option<RoomRetention>> {
+        unsupported("find_retention")
+    }
+
+    async fn list_retention(&self) -> Result<Vec<RoomRetention>> {
+        Ok(self.state().retention.clone())
+    }
+
+    async fn count_expired(
+        &self,
+        retention: &RoomRetention,
+        now: SystemTime,
+    ) -> Result<u64> {
+        Ok(self.state().expired(retention, now).len() as u64)
+    }
+
+    async fn purge_expired(
+        &self,
+        retention: &RoomRetention,
+        now: SystemTime,
+        limit: usize,
+    ) -> Result<u64> {
+        let mut state = self.state();
+        let batch: Vec<chat::MessageId> = state
+            .expired(retention, now)
+            .into_iter()
+            .take(limit)
+            .collect();
+        match retention.policy.mode {
+            PurgeMode::Delete => state
+                .messages
+                .retain(|message| !batch.contains(&message.id)),
+            PurgeMode::Redact => {
+                for message in state
+                    .messages
+                    .iter_mut()
+                    .filter(|message| batch.contains(&message.id))
+                {
+                    message.body = body(REDACTED_BODY);
+                    message.status = chat::MessageStatus::Removed;
+                }
+                state.redacted.extend(batch.iter().copied());
+            }
+        }
+        state.purge_batches.push(batch.len());
+        Ok(batch.len() as u64)
+    }
+
+    async fn insert_schedule(
+        &self,
+        schedule: &chat::ScheduledMessage,
+    ) -> Result<()> {
+        self.state().schedules.push(schedule.clone());
+        Ok(())
+    }
+
+    async fn find_schedule(
+        &self,
+        schedule_id: &chat::ScheduleId,
+    ) -> Result<Option<chat::ScheduledMessage>> {
+        Ok(self
+            .state()
+            .schedules
+            .iter()
+            .find(|schedule| schedule.id == *schedule_id)
+            .cloned())
+    }
+
+    async fn list_schedules(
+        &self,
+        _room_id: &chat::RoomId,
+        _user_id: &chat::UserId,
+    ) -> Result<Vec<chat::ScheduledMessage>> {
+        unsupported("list_schedules")
+    }
+
+    async fn cancel_schedule(
+        &self,
+        _schedule_id: &chat::ScheduleId,
+    ) -> Result<bool> {
+        unsupported("cancel_schedule")
+    }
+
+    async fn claim_due_schedules(
+        &self,
+        now: SystemTime,
+        limit: usize,
+    ) -> Result<Vec<chat::ScheduledMessage>> {
+        let mut state = self.state();
+        let mut due: Vec<&mut chat::ScheduledMessage> = state
+            .schedules
+            .iter_mut()
+            .filter(|schedule| {
+                schedule.status == chat::ScheduleStatus::Pending && schedule.send_at <= now
+            })
+            .collect();
+        due.sort_by_key(|schedule| schedule.send_at);
+        Ok(due
+            .into_iter()
+            .take(limit)
+            .map(|schedule| {
+                schedule.status = chat::ScheduleStatus::Sending;
+                schedule.clone()
+            })
+            .collect())
+    }
+
+    async fn finish_schedule(
+        &self,
+        schedule_id: &chat::ScheduleId,
+        status: chat::ScheduleStatus,
+        message_id: Option<&chat::MessageId>,
+    ) -> Result<()> {
+        if let Some(schedule) = self
+            .state()
+            .schedules
+            .iter_mut()
+            .find(|schedule| schedule.id == *schedule_id)
+        {
+            schedule.status = status;
+            schedule.message_id = message_id.copied();
+        }
+        Ok(())
+    }
+
+    async fn insert_pin(
+        &self,
+        room_id: &chat::RoomId,
+        message_id: &chat::MessageId,
+        pinned_by: &chat::UserId,
+        pinned_at: SystemTime,
+    ) -> Result<bool> {
+        let mut state = self.state();
+        if state.pins.iter().any(|pin| pin.message_id == *message_id) {
+            return Ok(false);
+        }
+        state.pins.push(Pin {
+            room_id: *room_id,
+            message_id: *message_id,
+            pinned_by: *pinned_by,
+            pinned_at,
+        });
+        Ok(true)
+    }
+
+    async fn delete_pin(
+        &self,
+        _message_id: &chat::MessageId,
+    ) -> Result<bool> {
+        unsupported("delete_pin")
+    }
+
+    async fn list_pins(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<Vec<PinnedMessage>> {
+        let mut pins = self.state().visible_pins(room_id);
+        pins.sort_by_key(|pin| std::cmp::Reverse(pin.pinned_at));
+        Ok(pins)
+    }
+
+    async fn count_pins(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<usize> {
+        Ok(self.state().visible_pins(room_id).len())
+    }
+
+    async fn insert_poll(
+        &self,
+        _poll: &chat::Poll,
+    ) -> Result<()> {
+        unsupported("insert_poll")
+    }
+
+    async fn find_poll(
+        &self,
+        _message_id: &chat::MessageId,
+    ) -> Result<Option<chat::Poll>> {
+        unsupported("find_poll")
+    }
+
+    async fn poll_results(
+        &self,
+        _room_id: &chat::RoomId,
+        _message_ids: &[chat::MessageId],
+    ) -> Result<Vec<PollResults>> {
+        unsupported("poll_results")
+    }
+
+    async fn cast_vote(
+        &self,
+        _message_id: &chat::MessageId,
+        _user_id: &chat::UserId,
+        _option_ids: &[chat::PollOptionId],
+        _voted_at: std::time::SystemTime,
+    ) -> Result<bool> {
+        unsupported("cast_vote")
+    }
+
+    async fn close_poll(
+        &self,
+        _message_id: &chat::MessageId,
+        _closed_at: std::time::SystemTime,
+    ) -> Result<bool> {
+        unsupported("close_poll")
+    }
+
+    async fn close_due_polls(
+        &self,
+        _now: std::time::SystemTime,
+    ) -> Result<Vec<chat::Poll>> {
+        unsupported("close_due_polls")
+    }
+
+    async fn set_room_topic(
+        &self,
+        _room_id: &chat::RoomId,
+        _topic: Option<&chat::RoomTopic>,
+    ) -> Result<()> {
+        unsupported("set_room_topic")
+    }
+
+    async fn find_room_topic(
+        &self,
+        _room_id: &chat::RoomId,
+    ) -> Result<Option<chat::RoomTopic>> {
+        unsupported("find_room_topic")
+    }
+
+    async fn save_mute(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+        until: std::time::SystemTime,
+        muted_by: Option<&chat::UserId>,
+    ) -> Result<()> {
+        let mut state = self.state();
+        state
+            .mutes
+            .retain(|mute| mute.room_id != *room_id || mute.user_id != *user_id);
+        state.mutes.push(Mute {
+            room_id: *room_id,
+            user_id: *user_id,
+            until,
+            muted_by: muted_by.copied(),
+        });
+        Ok(())
+    }
+
+    async fn muted_until(
+        &self,
+        _room_id: &chat::RoomId,
+        _user_id: &chat::UserId,
+    ) -> Result<Option<std::time::SystemTime>> {
+        Ok(None)
+    }
+
+    async fn trust_level(
+        &self,
+        _user_id: &chat::UserId,
+    ) -> Result<TrustLevel> {
+        Ok(TrustLevel::default())
+    }
+
+    async fn save_trust_level(
+        &self,
+        _user_id: &chat::UserId,
+        _level: TrustLevel,
+        _set_by: &chat::UserId,
+        _set_at: SystemTime,
+    ) -> Result<()> {
+        unsupported("save_trust_level")
+    }
+
+    async fn room_moderators(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<Vec<chat::UserId>> {
+        let mut moderators: Vec<chat::UserId> = self
+            .state()
+            .members
+            .iter()
+            .filter(|((member_room, _), member)| {
+                member_room == room_id && member.role != RoomRole::Member
+            })
+            .map(|((_, user_id), _)| *user_id)
+            .collect();
+        moderators.sort_by_key(|user_id| *user_id.as_uuid());
+        Ok(moderators)
+    }
+
+    async fn moderated_rooms(
+        &self,
+        user_id: &chat::UserId,
+    ) -> Result<Vec<chat::RoomId>> {
+        Ok(self
+            .state()
+            .members
+            .iter()
+            .filter(|((_, member_id), member)| {
+                member_id == user_id && member.role != RoomRole::Member
+            })
+            .map(|((room_id, _), _)| *room_id)
+            .collect())
+    }
+
+    async fn recent_posts(
+        &self,
+        user_id: &chat::UserId,
+        since: SystemTime,
+        limit: usize,
+    ) -> Result<Vec<RecentPost>> {
+        let state = self.state();
+        let mut posts: Vec<RecentPost> = state
+            .messages
+            .iter()
+            .filter(|message| message.user_id == *user_id && message.created_at >= since)
+            .map(|message| {
+                RecentPost::builder()
+                    .room_id(message.room_id)
+                    .body(message.body.clone())
+                    .created_at(message.created_at)
+                    .build()
+            })
+            .collect();
+        posts.sort_by_key(|post| std::cmp::Reverse(post.created_at));
+        posts.truncate(limit);
+        Ok(posts)
+    }
+
+    async fn record_flood_strike(
+        &self,
+        user_id: &chat::UserId,
+        _room_id: &chat::RoomId,
+        _signal: FloodSignal,
+        at: SystemTime,
+        since: SystemTime,
+    ) -> Result<usize> {
+        let mut state = self.state();
+        state.flood_strikes.push((*user_id, at));
+        Ok(state
+            .flood_strikes
+            .iter()
+            .filter(|(struck, struck_at)| struck == user_id && *struck_at >= since)
+            .count())
+    }
+
+    async fn insert_webhook(
+        &self,
+        webhook: &chat::Webhook,
+    ) -> Result<()> {
+        self.state().webhooks.push(webhook.clone());
+        Ok(())
+    }
+
+    async fn find_webhook(
+        &self,
+        _webhook_id: &chat::WebhookId,
+    ) -> Result<Option<chat::Webhook>> {
+        unsupported("find_webhook")
+    }
+
+    async fn list_webhooks(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<Vec<chat::Webhook>> {
+        Ok(self
+            .state()
+            .webhooks
+            .iter()
+            .filter(|webhook| webhook.room_id == *room_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn delete_webhook(
+        &self,
+        _webhook_id: &chat::WebhookId,
+    ) -> Result<bool> {
+        unsupported("delete_webhook")
+    }
+
+    async fn enqueue_webhook_deliveries(
+        &self,
+        _room_id: &chat::RoomId,
+        _event: chat::WebhookEvent,
+        _payload: &chat::WebhookPayload,
+        _now: SystemTime,
+    ) -> Result<usize> {
+        Ok(0)
+    }
+
+    async fn claim_due_deliveries(
+        &self,
+        _now: std::time::SystemTime,
+        _limit: usize,
+    ) -> Result<Vec<(chat::Webhook, chat::WebhookDelivery)>> {
+        unsupported("claim_due_deliveries")
+    }
+
+    async fn save_delivery_attempt(
+        &self,
+        _delivery: &chat::WebhookDelivery,
+    ) -> Result<()> {
+        unsupported("save_delivery_attempt")
+    }
+
+    async fn list_deliveries(
+        &self,
+        _webhook_id: &chat::WebhookId,
+        _limit: usize,
+    ) -> Result<Vec<chat::WebhookDelivery>> {
+        unsupported("list_deliveries")
+    }
+
+    async fn save_incoming_webhook(
+        &self,
+        _webhook: &chat::IncomingWebhook,
+    ) -> Result<()> {
+        unsupported("save_incoming_webhook")
+    }
+
+    async fn find_incoming_webhook(
+        &self,
+        _room_id: &chat::RoomId,
+    ) -> Result<Option<chat::IncomingWebhook>> {
+        unsupported("find_incoming_webhook")
+    }
+
+    async fn find_incoming_webhook_by_token(
+        &self,
+        _token: &chat::IncomingWebhookToken,
+    ) -> Result<Option<chat::IncomingWebhook>> {
+        unsupported("find_incoming_webhook_by_token")
+    }
+
+    async fn delete_incoming_webhook(
+        &self,
+        _room_id: &chat::RoomId,
+    ) -> Result<bool> {
+        unsupported("delete_incoming_webhook")
+    }
+
+    async fn find_link_previews(
+        &self,
+        _urls: &[chat::PreviewUrl],
+    ) -> Result<Vec<CachedPreview>> {
+        unsupported("find_link_previews")
+    }
+
+    async fn save_link_preview(
+        &self,
+        _preview: &CachedPreview,
+    ) -> Result<()> {
+        unsupported("save_link_preview")
+    }
+
+    async fn insert_attachment(
+        &self,
+        attachment: &chat::Attachment,
+    ) -> Result<()> {
+        let mut state = self.state();
+        if state.reject_attachments {
+            return Err(Error::Repo("attachment insert rejected".to_string().into()));
+        }
+        state.attachments.push(attachment.clone());
+        Ok(())
+    }
+
+    async fn find_attachment(
+        &self,
+        attachment_id: &chat::AttachmentId,
+    ) -> Result<Option<chat::Attachment>> {
+        let state = self.state();
+        Ok(state
+            .attachments
+            .iter()
+            .find(|attachment| attachment.id == *attachment_id)
+            .filter(|attachment| state.message(&attachment.message_id).is_some())
+            .cloned())
+    }
+
+    async fn list_attachments(
+        &self,
+        message_ids: &[chat::MessageId],
+    ) -> Result<Vec<chat::Attachment>> {
+        Ok(self
+            .state()
+            .attachments
+            .iter()
+            .filter(|attachment| message_ids.contains(&attachment.message_id))
+            .cloned()
+            .collect())
+    }
+
+    async fn list_orphaned_attachments(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<OrphanedAttachment>> {
+        let state = self.state();
+        Ok(state
+            .attachments
+            .iter()
+            .filter(|attachment| {
+                state.message(&attachment.message_id).is_none()
+                    || state.redacted.contains(&attachment.message_id)
+            })
+            .take(limit)
+            .map(|attachment| {
+                OrphanedAttachment::builder()
+                    .id(attachment.id)
+                    .blob_key(attachment.blob_key.clone())
+                    .maybe_thumbnail_key(attachment.thumbnail_key.clone())
+                    .build()
+            })
+            .collect())
+    }
+
+    async fn delete_attachment(
+        &self,
+        attachment_id: &chat::AttachmentId,
+    ) -> Result<()> {
+        self.state()
+            .attachments
+            .retain(|attachment| attachment.id != *attachment_id);
+        Ok(())
+    }
+}
+
+/// Removals and appeals, following the repository's rules for who may
+/// appeal and decide. Messages are read from the shared `MemoryRepo`.
+pub(super) struct MemoryModeration {
+    repo: Arc<MemoryRepo>,
+    state: Mutex<ModerationState>,
+}
+
+#[derive(Default)]
+struct ModerationState {
+    /// Removed messages and who removed them.
+    removals: HashMap<chat::MessageId, chat::UserId>,
+    appeals: HashMap<chat::MessageId, Appeal>,
+}
+
+impl MemoryModeration {
+    fn state(&self) -> std::sync::MutexGuard<'_, ModerationState> {
+        self.state.lock().expect("moderation lock")
+    }
+}
+
+#[async_trait]
+impl ModerationQueue for MemoryModeration {
+    async fn enqueue(
+        &self,
+        _message_id: &chat::MessageId,
+        _reason: &ModerationReason,
+    ) -> Result<()> {
+        unsupported("enqueue")
+    }
+
+    /// Every `Pending` message stands in for a queue entry.
+    async fn list_pending(
+        &self,
+        rooms: &[chat::RoomId],
+        room_id: Option<&chat::RoomId>,
+        _reason: Option<&ModerationReason>,
+        _now: SystemTime,
+        limit: usize,
+    ) -> Result<Vec<ModerationItem>> {
+        let repo = self.repo.state();
+        Ok(repo
+            .messages
+            .iter()
+            .filter(|message| {
+                message.status == chat::MessageStatus::Pending
+                    && rooms.contains(&message.room_id)
+                    && room_id.is_none_or(|room_id| *room_id == message.room_id)
+            })
+            .take(limit)
+            .map(|message| {
+                let room = repo
+                    .rooms
+                    .iter()
+                    .find(|room| room.id == message.room_id)
+                    .expect("room");
+                ModerationItem::builder()
+                    .message_id(message.id)
+                    .room_id(message.room_id)
+                    .room_name(room.name)
+                    .user_id(message.user_id)
+                    .body(message.body.clone())
+                    .queue_status(ModerationQueueStatus::Pending)
+                    .reason(ModerationReason::try_new("auto").expect("reason"))
+                    .created_at(TimestampText::try_new("").expect("timestamp"))
+                    .queued_at(message.created_at)
+                    .revision(0)
+                    .build()
+            })
+            .collect())
+    }
+
+    async fn claim(
+        &self,
+        _message_id: &chat::MessageId,
+        _reviewer_id: &chat::UserId,
+        _now: SystemTime,
+        _until: SystemTime,
+    ) -> Result<bool> {
+        unsupported("claim")
+    }
+
+    async fn release(
+        &self,
+        _message_id: &chat::MessageId,
+        _reviewer_id: &chat::UserId,
+    ) -> Result<()> {
+        unsupported("release")
+    }
+
+    async fn complete(
+        &self,
+        message_id: &chat::MessageId,
+        reviewer_id: &chat::UserId,
+        decision: ModerationDecision,
+        _reason: Option<ModerationReason>,
+        _revision: Option<u64>,
+        _now: SystemTime,
+    ) -> Result<bool> {
+        let mut state = self.state();
+        match decision {
+            ModerationDecision::Approve => state.removals.remove(message_id),
+            ModerationDecision::Remove => state.removals.insert(*message_id, *reviewer_id),
+        };
+        Ok(true)
+    }
+
+    async fn report(
+        &self,
+        _message_id: &chat::MessageId,
+        _reporter_id: &chat::UserId,
+        _category: ReportCategory,
+        _reported_at: SystemTime,
+    ) -> Result<ReportTally> {
+        unsupported("report")
+    }
+
+    async fn file_appeal(
+        &self,
+        message_id: &chat::MessageId,
+        appellant_id: &chat::UserId,
+        statement: &AppealStatement,
+        filed_at: SystemTime,
+    ) -> Result<bool> {
+        let mut state = self.state();
+        let Some(reviewer_id) = state.removals.get(message_id).copied() else {
+            return Ok(false);
+        };
+        let repo = self.repo.state();
+        let Some(message) = repo
+            .message(message_id)
+            .filter(|message| message.user_id == *appellant_id)
+        else {
+            return Ok(false);
+        };
+        let room = repo
+            .rooms
+            .iter()
+            .find(|room| room.id == message.room_id)
+            .expect("room");
+        if state.appeals.contains_key(message_id) {
+            return Ok(false);
+        }
+        let appeal = Appeal::builder()
+            .message_id(message.id)
+            .room_id(message.room_id)
+            .room_name(room.name)
+            .appellant_id(*appellant_id)
+            .appellant_name(domain::user::Username::try_new("author").expect("username"))
+            .body(message.body.clone())
+            .reason(ModerationReason::try_new("removed").expect("reason"))
+            .statement(statement.clone())
+            .original_reviewer_id(reviewer_id)
+            .status(AppealStatus::Pending)
+            .filed_at(filed_at)
+            .build();
+        state.appeals.insert(*message_id, appeal);
+        Ok(true)
+    }
+
+    async fn find_appeal(
+        &self,
+        message_id: &chat::MessageId,
+    ) -> Result<Option<Appeal>> {
+        Ok(self.state().appeals.get(message_id).cloned())
+    }
+
+    async fn list_appeals(
+        &self,
+        rooms: &[chat::RoomId],
+        limit: usize,
+    ) -> Result<Vec<Appeal>> {
+        let mut appeals: Vec<Appeal> = self
+            .state()
+            .appeals
+            .values()
+            .filter(|appeal| {
+                appeal.status == AppealStatus::Pending && rooms.contains(&appeal.room_id)
+            })
+            .cloned()
+            .collect();
+        appeals.sort_by_key(|appeal| appeal.filed_at);
+        appeals.truncate(limit);
+        Ok(appeals)
+    }
+
+    async fn list_removals(
+        &self,
+        _author_id: &chat::UserId,
+        _limit: usize,
+    ) -> Result<Vec<Removal>> {
+        unsupported("list_removals")
+    }
+
+    async fn decide_appeal(
+        &self,
+        message_id: &chat::MessageId,
+        reviewer_id: &chat::UserId,
+        decision: AppealDecision,
+        _decided_at: SystemTime,
+    ) -> Result<bool> {
+        let mut state = self.state();
+        let Some(appeal) = state.appeals.get_mut(message_id).filter(|appeal| {
+            appeal.status == AppealStatus::Pending
+                && appeal.appellant_id != *reviewer_id
+                && appeal.original_reviewer_id.as_ref() != Some(reviewer_id)
+        }) else {
+            return Ok(false);
+        };
+        appeal.status = decision.status();
+        if decision == AppealDecision::Overturn {
+            state.removals.remove(message_id);
+        }
+        Ok(true)
+    }
+}
+
+#[derive(Default)]
+pub(super) struct MemoryBlobStore {
+    blobs: Mutex<HashMap<chat::BlobKey, Vec<u8>>>,
+}
+
+impl MemoryBlobStore {
+    fn blobs(&self) -> std::sync::MutexGuard<'_, HashMap<chat::BlobKey, Vec<u8>>> {
+        self.blobs.lock().expect("blob lock")
+    }
+
+    pub(super) fn count(&self) -> usize {
+        self.blobs().len()
+    }
+}
+
+#[async_trait]
+impl BlobStore for MemoryBlobStore {
+    async fn put(
+        &self,
+        key: &chat::BlobKey,
+        bytes: &[u8],
+    ) -> Result<()> {
+        self.blobs().insert(key.clone(), bytes.to_vec());
+        Ok(())
+    }
+
+    async fn get(
+        &self,
+        key: &chat::BlobKey,
+    ) -> Result<Option<Vec<u8>>> {
+        Ok(self.blobs().get(key).cloned())
+    }
+
+    async fn delete(
+        &self,
+        key: &chat::BlobKey,
+    ) -> Result<()> {
+        self.blobs().remove(key);
+        Ok(())
+    }
+}
+
+struct AllowAll;
+
+#[async_trait]
+impl RateLimiter for AllowAll {
+    async fn check(
+        &self,
+        _room_id: &chat::RoomId,
+        _user_id: &chat::UserId,
+    ) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub(super) struct MemoryAudit {
+    entries: Mutex<Vec<AuditEntry>>,
+}
+
+impl MemoryAudit {
+    pub(super) fn actions(&self) -> Vec<AuditAction> {
+        self.entries
+            .lock()
+            .expect("audit lock")
+            .iter()
+            .map(|entry| entry.action)
+            .collect()
+    }
+}
+
+#[async_trait]
+impl AuditLog for MemoryAudit {
+    async fn record(
+        &self,
+        entry: AuditEntry,
+    ) -> Result<()> {
+        self.entries.lock().expect("audit lock").push(entry);
+        Ok(())
+    }
+}
+
+struct NoTyping;
+
+impl TypingStore for NoTyping {
+    fn start(
+        &self,
+        _room_id: &chat::RoomId,
+        _user_id: &chat::UserId,
+        _now: SystemTime,
+    ) -> TypingChange {
+        TypingChange::Started
+    }
+
+    fn stop(
+        &self,
+        _room_id: &chat::RoomId,
+        _user_id: &chat::UserId,
+    ) -> bool {
+        false
+    }
+
+    fn expire(
+        &self,
+        _now: SystemTime,
+    ) -> Vec<TypingExpired> {
+        Vec::new()
+    }
+}
+
+struct NoAuthors;
+
+#[async_trait]
+impl AuthorDirectory for NoAuthors {
+    async fn user_exists(
+        &self,
+        _user_id: &chat::UserId,
+    ) -> Result<bool> {
+        unsupported("user_exists")
+    }
+
+    async fn find_by_username(
+        &self,
+        _username: &domain::user::Username,
+    ) -> Result<Option<chat::UserId>> {
+        unsupported("find_by_username")
+    }
+
+    async fn create_placeholder(
+        &self,
+        _username: &domain::user::Username,
+    ) -> Result<chat::UserId> {
+        unsupported("create_placeholder")
+    }
+
+    async fn ensure_bot(
+        &self,
+        _username: &domain::user::Username,
+    ) -> Result<chat::UserId> {
+        unsupported("ensure_bot")
+    }
+
+    async fn find_username(
+        &self,
+        _user_id: &chat::UserId,
+    ) -> Result<Option<domain::user::Username>> {
+        unsupported("find_username")
+    }
+}
+
+/// A clock the test moves by hand.
+pub(super) struct FakeClock {
+    now: Mutex<SystemTime>,
+}
+
+impl FakeClock {
+    pub(super) fn set(
+        &self,
+        now: SystemTime,
+    ) {
+        *self.now.lock().expect("clock lock") = now;
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> SystemTime {
+        *self.now.lock().expect("clock lock")
+    }
+}
+
+/// Hands out ids from a counter starting well above the ones tests seed.
+struct SequentialIds {
+    next: Mutex<u128>,
+}
+
+impl SequentialIds {
+    fn next(&self) -> uuid::Uuid {
+        let mut next = self.next.lock().expect("ids lock");
+        *next += 1;
+        uuid::Uuid::from_u128(*next)
+    }
+}
+
+impl IdGenerator for SequentialIds {
+    fn new_room_id(&self) -> chat::RoomId {
+        chat::RoomId::from_uuid(self.next())
+    }
+
+    fn new_message_id(&self) -> chat::MessageId {
+        chat::MessageId::from_uuid(self.next())
+    }
+
+    fn new_schedule_id(&self) -> chat::ScheduleId {
+        chat::ScheduleId::from_uuid(self.next())
+    }
+
+    fn new_poll_option_id(&self) -> chat::PollOptionId {
+        chat::PollOptionId::from_uuid(self.next())
+    }
+
+    fn new_webhook_id(&self) -> chat::WebhookId {
+        chat::WebhookId::from_uuid(self.next())
+    }
+
+    fn new_incoming_webhook_token(&self) -> chat::IncomingWebhookToken {
+        chat::IncomingWebhookToken::try_new(self.next().simple().to_string())
+            .expect("incoming webhook token")
+    }
+
+    fn new_attachment_id(&self) -> chat::AttachmentId {
+        chat::AttachmentId::from_uuid(self.next())
+    }
+}
+
+/// A service over the in-memory fakes, with handles to inspect them.
+pub(super) struct Harness {
+    pub(super) service: Service,
+    pub(super) repo: Arc<MemoryRepo>,
+    pub(super) audit: Arc<MemoryAudit>,
+    pub(super) blobs: Arc<MemoryBlobStore>,
+    pub(super) clock: Arc<FakeClock>,
+}
+
+impl Harness {
+    pub(super) fn new(now: SystemTime) -> Self {
+        Self::with_webhook_sender(now, Arc::new(DisabledWebhookSender))
+    }
+
+    pub(super) fn with_webhook_sender(
+        now: SystemTime,
+        webhooks: Arc<dyn WebhookSender>,
+    ) -> Self {
+        let repo = Arc::new(MemoryRepo::default());
+        let moderation = Arc::new(MemoryModeration {
+            repo: repo.clone(),
+            state: Mutex::new(ModerationState::default()),
+        });
+        let blobs = Arc::new(MemoryBlobStore::default());
+        let audit = Arc::new(MemoryAudit::default());
+        let clock = Arc::new(FakeClock {
+            now: Mutex::new(now),
+        });
+        let service = Service::builder()
+            .with_repo(repo.clone())
+            .with_moderation_queue(moderation)
+            .with_rate_limiter(Arc::new(AllowAll))
+            .with_audit_log(audit.clone())
+            .with_typing_store(Arc::new(NoTyping))
+            .with_author_directory(Arc::new(NoAuthors))
+            .with_clock(clock.clone())
+            .with_id_generator(Arc::new(SequentialIds {
+                next: Mutex::new(1 << 64),
+            }))
+            .with_webhook_sender(webhooks)
+            .with_blob_store(blobs.clone())
+            .build();
+        Self {
+            service,
+            repo,
+            audit,
+            blobs,
+            clock,
+        }
+    }
+
+    /// Adds a room with the given members.
+    pub(super) fn room(
+        &self,
+        room: u128,
+        name: chat::RoomName,
+        members: &[(u128, RoomRole)],
+    ) {
+        let mut state = self.repo.state();
+        state.rooms.push(chat::Room {
+            id: room_id(room),
+            name,
+            created_by: user_id(0),
+        });
+        for (user, role) in members {
+            state.members.insert(
+                (room_id(room), user_id(*user)),
+                Member {
+                    role: *role,
+                    last_read: None,
+                    read_sequence: 0,
+                },
+            );
+        }
+    }
+
+    /// Stores a message directly, bypassing the service's checks.
+    pub(super) fn post(
+        &self,
+        message_id: u128,
+        room: u128,
+        author: u128,
+        status: chat::MessageStatus,
+        at: u64,
+    ) -> chat::MessageId {
+        let id = chat::MessageId::from_uuid(uuid::Uuid::from_u128(message_id));
+        self.repo.state().messages.push(chat::Message {
+            id,
+            room_id: room_id(room),
+            user_id: user_id(author),
+            body: body(&format!("message {message_id}")),
+            status,
+            client_id: None,
+            created_at: epoch(at),
+        });
+        id
+    }
+
+    /// Stores a PNG attachment on `message_id`, row and blob both.
+    pub(super) fn attach(
+        &self,
+        attachment_id: u128,
+        message_id: chat::MessageId,
+    ) -> chat::Attachment {
+        let mut state = self.repo.state();
+        let message = state.message(&message_id).expect("message").clone();
+        let id = chat::AttachmentId::from_uuid(uuid::Uuid::from_u128(attachment_id));
+        let attachment = chat::Attachment::builder()
+            .id(id)
+            .message_id(message.id)
+            .room_id(message.room_id)
+            .uploaded_by(message.user_id)
+            .name(chat::AttachmentName::try_new("photo.png").expect("name"))
+            .content_type(chat::AttachmentType::Png)
+            .size_bytes(4)
+            .blob_key(blob_key(&id, "original"))
+            .created_at(message.created_at)
+            .build();
+        state.attachments.push(attachment.clone());
+        self.blobs
+            .blobs()
+            .insert(attachment.blob_key.clone(), b"\x89PNG".to_vec());
+        attachment
+    }
+
+    /// Removes the message as `reviewer` through the moderation flow.
+    pub(super) async fn remove(
+        &self,
+        message_id: chat::MessageId,
+        reviewer: u128,
+    ) {
+        self.service
+            .moderate_message(
+                ModerateMessage::builder()
+                    .message_id(message_id)
+                    .reviewer_id(user_id(reviewer))
+                    .decision(ModerationDecision::Remove)
+                    .build(),
+            )
+            .await
+            .expect("remove");
+    }
+
+    /// Files an appeal against the message's removal as `appellant`.
+    pub(super) async fn appeal(
+        &self,
+        message_id: chat::MessageId,
+        appellant: u128,
+    ) {
+        self.service
+            .file_appeal(
+                FileAppeal::builder()
+                    .message_id(message_id)
+                    .appellant_id(user_id(appellant))
+                    .statement(
+                        AppealStatement::try_new("It was on topic.").expect("statement"),
+                    )
+                    .build(),
+            )
+            .await
+            .expect("appeal");
+    }
+}
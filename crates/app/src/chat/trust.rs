@@ -0,0 +1,33 @@
+use bon::Builder;
+use strum_macros::{Display, EnumString};
+
+use domain::chat;
+
+/// How far the service trusts an account, across every room.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Display, EnumString)]
+pub enum TrustLevel {
+    #[default]
+    #[strum(serialize = "standard")]
+    Standard,
+    /// Shadow-muted: posts are stored as quarantined, queued for review and
+    /// shown only to the author and room moderators.
+    #[strum(serialize = "quarantined")]
+    Quarantined,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct SetTrustLevel {
+    /// The room the moderator acts from; they must moderate it.
+    pub room_id: chat::RoomId,
+    pub actor_id: chat::UserId,
+    pub target_id: chat::UserId,
+    pub level: TrustLevel,
+}
+
+/// Who may receive a new message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Audience {
+    Everyone,
+    /// The author and the room's moderators.
+    Only(Vec<chat::UserId>),
+}
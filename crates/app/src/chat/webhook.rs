@@ -0,0 +1,247 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use bon::Builder;
+
+use domain::chat;
+
+/// Attempts per delivery before it is marked failed.
+pub const MAX_WEBHOOK_ATTEMPTS: u32 = 6;
+pub const MAX_WEBHOOKS_PER_ROOM: usize = 10;
+
+const BACKOFF_BASE: Duration = Duration::from_secs(10);
+const BACKOFF_MAX: Duration = Duration::from_secs(60 * 60);
+
+/// Wait before retrying after `attempts` failed attempts: 10s, 20s, 40s, …
+/// capped at an hour.
+pub fn webhook_backoff(attempts: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempts.saturating_sub(1));
+    BACKOFF_BASE.saturating_mul(factor).min(BACKOFF_MAX)
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct CreateWebhook {
+    pub room_id: chat::RoomId,
+    pub user_id: chat::UserId,
+    pub url: chat::WebhookUrl,
+    pub events: Vec<chat::WebhookEvent>,
+    pub secret: chat::WebhookSecret,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct DeleteWebhook {
+    pub webhook_id: chat::WebhookId,
+    pub user_id: chat::UserId,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct ListWebhooks {
+    pub room_id: chat::RoomId,
+    pub user_id: chat::UserId,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct ListDeliveries {
+    pub webhook_id: chat::WebhookId,
+    pub user_id: chat::UserId,
+    #[builder(default = 50)]
+    pub limit: usize,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct DeliverWebhooks {
+    #[builder(default = 50)]
+    pub limit: usize,
+}
+
+/// Account incoming webhook posts are attributed to unless the admin picks
+/// another name.
+pub const DEFAULT_INCOMING_BOT: &str = "webhook";
+
+/// Creates the room's incoming webhook, or replaces its token so the old
+/// URL stops working. `bot_name` renames the posting account; `None` keeps
+/// the current one.
+#[derive(Clone, Debug, Builder)]
+pub struct RegenerateIncomingWebhook {
+    pub room_id: chat::RoomId,
+    pub user_id: chat::UserId,
+    pub bot_name: Option<domain::user::Username>,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct RevokeIncomingWebhook {
+    pub room_id: chat::RoomId,
+    pub user_id: chat::UserId,
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct FindIncomingWebhook {
+    pub room_id: chat::RoomId,
+    pub user_id: chat::UserId,
+}
+
+/// A post from an external system, authenticated by the URL token alone.
+#[derive(Clone, Debug, Builder)]
+pub struct PostIncoming {
+    pub token: chat::IncomingWebhookToken,
+    pub body: chat::MessageBody,
+}
+
+/// Everything a sender needs for one attempt. The sender signs `payload`
+/// with `secret`.
+#[derive(Clone, Debug, Builder)]
+pub struct WebhookRequest {
+    pub delivery_id: chat::WebhookDeliveryId,
+    pub event: chat::WebhookEvent,
+    pub url: chat::WebhookUrl,
+    pub secret: chat::WebhookSecret,
+    pub payload: chat::WebhookPayload,
+    pub attempt: u32,
+}
+
+/// Result of one HTTP attempt.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WebhookAttempt {
+    /// The subscriber answered 2xx.
+    Delivered { status: u16 },
+    /// The subscriber answered with another status.
+    Rejected { status: u16 },
+    /// No answer: connection error, timeout and the like.
+    Failed { error: chat::WebhookDeliveryError },
+}
+
+#[async_trait]
+pub trait WebhookSender: Send + Sync {
+    /// Whether the sender would deliver to `url` at all. Checked when a
+    /// webhook is created; `send` checks again on every attempt.
+    async fn accepts(
+        &self,
+        url: &chat::WebhookUrl,
+    ) -> bool;
+    async fn send(
+        &self,
+        request: &WebhookRequest,
+    ) -> WebhookAttempt;
+}
+
+/// Used when no sender is configured; every attempt fails and is retried.
+pub(super) struct DisabledWebhookSender;
+
+#[async_trait]
+impl WebhookSender for DisabledWebhookSender {
+    async fn accepts(
+        &self,
+        _url: &chat::WebhookUrl,
+    ) -> bool {
+        true
+    }
+
+    async fn send(
+        &self,
+        _request: &WebhookRequest,
+    ) -> WebhookAttempt {
+        WebhookAttempt::Failed {
+            error: chat::WebhookDeliveryError::try_new("webhook delivery is disabled")
+                .expect("delivery error"),
+        }
+    }
+}
+
+/// The JSON body every subscriber receives; `data` depends on the event.
+pub(super) fn webhook_payload(
+    event: chat::WebhookEvent,
+    room_id: &chat::RoomId,
+    occurred_at: std::time::SystemTime,
+    data: serde_json::Value,
+) -> chat::WebhookPayload {
+    chat::WebhookPayload::new(
+        serde_json::json!({
+            "event": event.to_string(),
+            "room_id": room_id.as_uuid().to_string(),
+            "occurred_at_ms": super::export::timestamp_ms(occurred_at),
+            "data": data,
+        })
+        .to_string(),
+    )
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DeliveryReport {
+    pub delivered: usize,
+    pub retrying: usize,
+    pub failed: usize,
+}
+
+/// Applies an attempt to a delivery: delivered, scheduled for a retry
+/// after `webhook_backoff`, or failed once attempts run out.
+pub(super) fn apply_attempt(
+    delivery: chat::WebhookDelivery,
+    attempt: WebhookAttempt,
+    now: std::time::SystemTime,
+) -> chat::WebhookDelivery {
+    let attempts = delivery.attempts + 1;
+    let (response_status, last_error) = match &attempt {
+        WebhookAttempt::Delivered { status } => (Some(*status), None),
+        WebhookAttempt::Rejected { status } => (
+            Some(*status),
+            chat::WebhookDeliveryError::try_new(format!("subscriber answered {status}")).ok(),
+        ),
+        WebhookAttempt::Failed { error } => (None, Some(error.clone())),
+    };
+    let (status, next_attempt_at) = match attempt {
+        WebhookAttempt::Delivered { .. } => (chat::WebhookDeliveryStatus::Delivered, now),
+        _ if attempts >= MAX_WEBHOOK_ATTEMPTS => (chat::WebhookDeliveryStatus::Failed, now),
+        _ => (
+            chat::WebhookDeliveryStatus::Pending,
+            now + webhook_backoff(attempts),
+        ),
+    };
+
+    chat::WebhookDelivery {
+        status,
+        attempts,
+        next_attempt_at,
+        response_status,
+        last_error,
+        ..delivery
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_and_caps() {
+        assert_eq!(webhook_backoff(1), Duration::from_secs(10));
+        assert_eq!(webhook_backoff(3), Duration::from_secs(40));
+        assert_eq!(webhook_backoff(40), BACKOFF_MAX);
+    }
+
+    #[test]
+    fn attempts_retry_then_fail() {
+        let now = std::time::SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let delivery = chat::WebhookDelivery::builder()
+            .id(chat::WebhookDeliveryId::new_v4())
+            .webhook_id(chat::WebhookId::new_v4())
+            .event(chat::WebhookEvent::MessagePosted)
+            .payload(chat::WebhookPayload::new("{}"))
+            .status(chat::WebhookDeliveryStatus::Sending)
+            .attempts(MAX_WEBHOOK_ATTEMPTS - 2)
+            .next_attempt_at(now)
+            .created_at(now)
+            .build();
+
+        let retried = apply_attempt(delivery, WebhookAttempt::Rejected { status: 503 }, now);
+        assert_eq!(retried.status, chat::WebhookDeliveryStatus::Pending);
+        assert_eq!(retried.response_status, Some(503));
+        assert!(retried.next_attempt_at > now);
+
+        let failed = apply_attempt(retried.clone(), WebhookAttempt::Rejected { status: 503 }, now);
+        assert_eq!(failed.status, chat::WebhookDeliveryStatus::Failed);
+
+        let delivered = apply_attempt(retried, WebhookAttempt::Delivered { status: 204 }, now);
+        assert_eq!(delivered.status, chat::WebhookDeliveryStatus::Delivered);
+        assert_eq!(delivered.last_error, None);
+    }
+}
@@ -0,0 +1,98 @@
+use bon::Builder;
+use nutype::nutype;
+
+use super::message::MessageId;
+use super::room::{RoomId, UserId};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AttachmentId(uuid::Uuid);
+
+impl AttachmentId {
+    pub fn new_v4() -> Self {
+        Self(uuid::Uuid::new_v4())
+    }
+
+    pub fn from_uuid(value: uuid::Uuid) -> Self {
+        Self(value)
+    }
+
+    pub fn as_uuid(&self) -> &uuid::Uuid {
+        &self.0
+    }
+}
+
+/// File name as uploaded, reduced to its last path segment.
+#[nutype(
+    sanitize(with = |name: String| {
+        name.rsplit(['/', '\\'])
+            .next()
+            .unwrap_or_default()
+            .chars()
+            .filter(|c| !c.is_control())
+            .collect::<String>()
+            .trim()
+            .to_string()
+    }),
+    validate(not_empty, len_char_max = 200),
+    derive(Debug, Clone, PartialEq, Display, AsRef)
+)]
+pub struct AttachmentName(String);
+
+/// Where a blob lives in the store: lowercase segments of letters, digits,
+/// `-` and `_` joined by `/`.
+#[nutype(
+    validate(
+        not_empty,
+        len_char_max = 200,
+        predicate = |key| key.split('/').all(|segment| {
+            !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+        })
+    ),
+    derive(Debug, Clone, PartialEq, Eq, Hash, Display, AsRef)
+)]
+pub struct BlobKey(String);
+
+/// Allowed attachment formats, as detected from the file's bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum_macros::Display, strum_macros::EnumString)]
+pub enum AttachmentType {
+    #[strum(serialize = "image/png")]
+    Png,
+    #[strum(serialize = "image/jpeg")]
+    Jpeg,
+    #[strum(serialize = "image/gif")]
+    Gif,
+    #[strum(serialize = "image/webp")]
+    Webp,
+    #[strum(serialize = "application/pdf")]
+    Pdf,
+    #[strum(serialize = "text/plain")]
+    Text,
+}
+
+impl AttachmentType {
+    pub fn is_image(self) -> bool {
+        matches!(
+            self,
+            AttachmentType::Png | AttachmentType::Jpeg | AttachmentType::Gif | AttachmentType::Webp
+        )
+    }
+}
+
+/// A file posted with a message. Bytes live in a blob store under
+/// `blob_key`; images also get a PNG thumbnail.
+#[derive(Debug, Clone, PartialEq, Builder)]
+pub struct Attachment {
+    pub id: AttachmentId,
+    pub message_id: MessageId,
+    pub room_id: RoomId,
+    pub uploaded_by: UserId,
+    pub name: AttachmentName,
+    pub content_type: AttachmentType,
+    pub size_bytes: u64,
+    pub blob_key: BlobKey,
+    pub thumbnail_key: Option<BlobKey>,
+    pub created_at: std::time::SystemTime,
+}
@@ -35,6 +35,10 @@ pub enum MessageStatus {
     Pending,
     #[strum(serialize = "removed")]
     Removed,
+    /// Held from a quarantined author: shown only to them and the room's
+    /// moderators until reviewed.
+    #[strum(serialize = "quarantined")]
+    Quarantined,
 }
 
 #[derive(Debug, Clone, PartialEq, Builder)]
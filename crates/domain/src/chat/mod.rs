@@ -1,7 +1,26 @@
-moddef::moddef!(mod { error, message, room });
+moddef::moddef!(mod { attachment, error, message, poll, preview, room, schedule, webhook });
 
+pub use attachment::{
+    Attachment, AttachmentId, AttachmentName, AttachmentNameError, AttachmentType, BlobKey,
+    BlobKeyError,
+};
 pub use error::{Error, Result};
 pub use message::{
     ClientId, Message, MessageBody, MessageBodyError, MessageId, MessageStatus,
 };
-pub use room::{Room, RoomId, RoomName, RoomNameError, UserId};
+pub use poll::{
+    Poll, PollKind, PollOption, PollOptionId, PollOptionLabel, PollOptionLabelError,
+};
+pub use preview::{
+    LinkPreview, PreviewDescription, PreviewDescriptionError, PreviewTitle, PreviewTitleError,
+    PreviewUrl, PreviewUrlError,
+};
+pub use room::{
+    Room, RoomId, RoomName, RoomNameError, RoomTopic, RoomTopicError, UserId,
+};
+pub use schedule::{ScheduleId, ScheduleStatus, ScheduledMessage};
+pub use webhook::{
+    IncomingWebhook, IncomingWebhookToken, IncomingWebhookTokenError, Webhook, WebhookDelivery, WebhookDeliveryError, WebhookDeliveryErrorError,
+    WebhookDeliveryId, WebhookDeliveryStatus, WebhookEvent, WebhookId, WebhookPayload,
+    WebhookSecret, WebhookSecretError, WebhookUrl, WebhookUrlError,
+};
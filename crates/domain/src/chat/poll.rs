@@ -0,0 +1,65 @@
+use bon::Builder;
+use nutype::nutype;
+
+use super::message::MessageId;
+use super::room::RoomId;
+
+#[nutype(
+    sanitize(trim),
+    validate(not_empty, len_char_max = 100),
+    derive(Debug, Clone, PartialEq, Display)
+)]
+pub struct PollOptionLabel(String);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PollOptionId(uuid::Uuid);
+
+impl PollOptionId {
+    pub fn new_v4() -> Self {
+        Self(uuid::Uuid::new_v4())
+    }
+
+    pub fn from_uuid(value: uuid::Uuid) -> Self {
+        Self(value)
+    }
+
+    pub fn as_uuid(&self) -> &uuid::Uuid {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum_macros::Display, strum_macros::EnumString)]
+pub enum PollKind {
+    #[strum(serialize = "single")]
+    Single,
+    #[strum(serialize = "multiple")]
+    Multiple,
+}
+
+#[derive(Debug, Clone, PartialEq, Builder)]
+pub struct PollOption {
+    pub id: PollOptionId,
+    pub label: PollOptionLabel,
+}
+
+/// A poll attached to the message holding its question. Options keep their
+/// creation order.
+#[derive(Debug, Clone, PartialEq, Builder)]
+pub struct Poll {
+    pub message_id: MessageId,
+    pub room_id: RoomId,
+    pub kind: PollKind,
+    pub options: Vec<PollOption>,
+    pub closes_at: Option<std::time::SystemTime>,
+    pub closed_at: Option<std::time::SystemTime>,
+}
+
+impl Poll {
+    /// Closed explicitly, or past its close time.
+    pub fn is_closed(
+        &self,
+        now: std::time::SystemTime,
+    ) -> bool {
+        self.closed_at.is_some() || self.closes_at.is_some_and(|closes_at| closes_at <= now)
+    }
+}
@@ -0,0 +1,52 @@
+use bon::Builder;
+use nutype::nutype;
+
+#[nutype(
+    sanitize(trim),
+    validate(
+        not_empty,
+        len_char_max = 2000,
+        predicate = |url| url.starts_with("https://") || url.starts_with("http://")
+    ),
+    derive(Debug, Clone, PartialEq, Eq, Hash, Display, AsRef)
+)]
+pub struct PreviewUrl(String);
+
+#[nutype(
+    sanitize(trim),
+    validate(not_empty, len_char_max = 300),
+    derive(Debug, Clone, PartialEq, Display)
+)]
+pub struct PreviewTitle(String);
+
+#[nutype(
+    sanitize(trim),
+    validate(not_empty, len_char_max = 1000),
+    derive(Debug, Clone, PartialEq, Display)
+)]
+pub struct PreviewDescription(String);
+
+/// Card shown under a message that links to `url`. A preview with neither
+/// title nor description records a failed fetch and is never shown.
+#[derive(Debug, Clone, PartialEq, Builder)]
+pub struct LinkPreview {
+    pub url: PreviewUrl,
+    pub title: Option<PreviewTitle>,
+    pub description: Option<PreviewDescription>,
+    pub image_url: Option<PreviewUrl>,
+}
+
+impl LinkPreview {
+    pub fn empty(url: PreviewUrl) -> Self {
+        Self {
+            url,
+            title: None,
+            description: None,
+            image_url: None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none() && self.description.is_none()
+    }
+}
@@ -53,6 +53,13 @@ impl RoomName {
     }
 }
 
+#[nutype(
+    sanitize(trim),
+    validate(not_empty, len_char_max = 200),
+    derive(Debug, Clone, PartialEq, Display)
+)]
+pub struct RoomTopic(String);
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct RoomId(uuid::Uuid);
 
@@ -0,0 +1,48 @@
+use bon::Builder;
+
+use super::message::{MessageBody, MessageId};
+use super::room::{RoomId, UserId};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScheduleId(uuid::Uuid);
+
+impl ScheduleId {
+    pub fn new_v4() -> Self {
+        Self(uuid::Uuid::new_v4())
+    }
+
+    pub fn from_uuid(value: uuid::Uuid) -> Self {
+        Self(value)
+    }
+
+    pub fn as_uuid(&self) -> &uuid::Uuid {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum_macros::Display, strum_macros::EnumString)]
+pub enum ScheduleStatus {
+    #[strum(serialize = "pending")]
+    Pending,
+    #[strum(serialize = "sending")]
+    Sending,
+    #[strum(serialize = "sent")]
+    Sent,
+    #[strum(serialize = "cancelled")]
+    Cancelled,
+    #[strum(serialize = "failed")]
+    Failed,
+}
+
+/// A message composed now and posted at `send_at`. Once sent, `message_id`
+/// points at the posted message.
+#[derive(Debug, Clone, PartialEq, Builder)]
+pub struct ScheduledMessage {
+    pub id: ScheduleId,
+    pub room_id: RoomId,
+    pub user_id: UserId,
+    pub body: MessageBody,
+    pub send_at: std::time::SystemTime,
+    pub status: ScheduleStatus,
+    pub message_id: Option<MessageId>,
+}
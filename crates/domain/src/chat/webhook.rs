@@ -0,0 +1,166 @@
+use bon::Builder;
+use nutype::nutype;
+
+use super::room::{RoomId, UserId};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WebhookId(uuid::Uuid);
+
+impl WebhookId {
+    pub fn new_v4() -> Self {
+        Self(uuid::Uuid::new_v4())
+    }
+
+    pub fn from_uuid(value: uuid::Uuid) -> Self {
+        Self(value)
+    }
+
+    pub fn as_uuid(&self) -> &uuid::Uuid {
+        &self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WebhookDeliveryId(uuid::Uuid);
+
+impl WebhookDeliveryId {
+    pub fn new_v4() -> Self {
+        Self(uuid::Uuid::new_v4())
+    }
+
+    pub fn from_uuid(value: uuid::Uuid) -> Self {
+        Self(value)
+    }
+
+    pub fn as_uuid(&self) -> &uuid::Uuid {
+        &self.0
+    }
+}
+
+#[nutype(
+    sanitize(trim),
+    validate(
+        not_empty,
+        len_char_max = 2000,
+        predicate = |url| url.starts_with("https://") || url.starts_with("http://")
+    ),
+    derive(Debug, Clone, PartialEq, Display, AsRef)
+)]
+pub struct WebhookUrl(String);
+
+/// Shared secret for payload signatures. Never shown in `Debug` output.
+#[nutype(
+    validate(len_char_min = 16, len_char_max = 200),
+    derive(Clone, PartialEq, AsRef)
+)]
+pub struct WebhookSecret(String);
+
+impl std::fmt::Debug for WebhookSecret {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        f.write_str("WebhookSecret(..)")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum_macros::Display, strum_macros::EnumString)]
+pub enum WebhookEvent {
+    #[strum(serialize = "message.posted")]
+    MessagePosted,
+    #[strum(serialize = "message.moderated")]
+    MessageModerated,
+    #[strum(serialize = "member.joined")]
+    MemberJoined,
+    #[strum(serialize = "room.topic")]
+    RoomTopic,
+    #[strum(serialize = "poll.closed")]
+    PollClosed,
+}
+
+/// A room subscription: every event in `events` is POSTed to `url`, signed
+/// with `secret`.
+#[derive(Debug, Clone, PartialEq, Builder)]
+pub struct Webhook {
+    pub id: WebhookId,
+    pub room_id: RoomId,
+    pub url: WebhookUrl,
+    pub events: Vec<WebhookEvent>,
+    pub secret: WebhookSecret,
+    pub created_by: UserId,
+    pub created_at: std::time::SystemTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum_macros::Display, strum_macros::EnumString)]
+pub enum WebhookDeliveryStatus {
+    #[strum(serialize = "pending")]
+    Pending,
+    #[strum(serialize = "sending")]
+    Sending,
+    #[strum(serialize = "delivered")]
+    Delivered,
+    #[strum(serialize = "failed")]
+    Failed,
+}
+
+/// JSON body sent to the subscriber.
+#[nutype(derive(Debug, Clone, PartialEq, Display, AsRef))]
+pub struct WebhookPayload(String);
+
+/// Why the last attempt failed: a transport error or a non-2xx answer.
+#[nutype(
+    sanitize(trim),
+    validate(len_char_max = 500),
+    derive(Debug, Clone, PartialEq, Display)
+)]
+pub struct WebhookDeliveryError(String);
+
+/// One event queued for one webhook, with the outcome of its latest
+/// attempt.
+#[derive(Debug, Clone, PartialEq, Builder)]
+pub struct WebhookDelivery {
+    pub id: WebhookDeliveryId,
+    pub webhook_id: WebhookId,
+    pub event: WebhookEvent,
+    pub payload: WebhookPayload,
+    pub status: WebhookDeliveryStatus,
+    #[builder(default = 0)]
+    pub attempts: u32,
+    pub next_attempt_at: std::time::SystemTime,
+    pub response_status: Option<u16>,
+    pub last_error: Option<WebhookDeliveryError>,
+    pub created_at: std::time::SystemTime,
+}
+
+/// Secret path segment of a room's incoming webhook URL. Never shown in
+/// `Debug` output.
+#[nutype(
+    sanitize(trim),
+    validate(
+        len_char_min = 32,
+        len_char_max = 128,
+        predicate = |token| token.chars().all(|c| c.is_ascii_alphanumeric())
+    ),
+    derive(Clone, PartialEq, Display, AsRef)
+)]
+pub struct IncomingWebhookToken(String);
+
+impl std::fmt::Debug for IncomingWebhookToken {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        f.write_str("IncomingWebhookToken(..)")
+    }
+}
+
+/// Lets external systems post into a room through a secret URL. Posts are
+/// attributed to the `bot_id` account.
+#[derive(Debug, Clone, PartialEq, Builder)]
+pub struct IncomingWebhook {
+    pub room_id: RoomId,
+    pub token: IncomingWebhookToken,
+    pub bot_id: UserId,
+    pub created_by: UserId,
+    pub created_at: std::time::SystemTime,
+}
@@ -4,8 +4,18 @@ pub use error::{Error, Result};
 pub use new_user::NewUser;
 use nutype::nutype;
 pub use repo::Repository;
+use strum_macros::{Display, EnumString};
 pub use user::User;
 
+/// Bots are accounts without credentials; only humans can sign in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString)]
+pub enum Kind {
+    #[strum(serialize = "human")]
+    Human,
+    #[strum(serialize = "bot")]
+    Bot,
+}
+
 #[nutype(
     sanitize(trim, lowercase),
     validate(not_empty, len_char_max = 20),
@@ -1,5 +1,6 @@
 use axum::{
     body::Body,
+    extract::Extension,
     http::Request,
     middleware::Next,
     response::{IntoResponse, Redirect, Response},
@@ -113,6 +114,7 @@ impl From<domain::user::Id> for UserId {
 pub type Session = AuthSession<Backend>;
 
 pub async fn set_user_context_middleware(
+    Extension(state): Extension<crate::State>,
     auth_session: Session,
     req: Request<Body>,
     next: Next,
@@ -121,6 +123,18 @@ pub async fn set_user_context_middleware(
         request::set_user_id(user.id.to_string());
     }
 
+    // axum-login drops the user when the session auth hash no longer
+    // matches (e.g. after a password change), so any stream still linked
+    // to a different user in this session is stale.
+    if let Some(session_id) = request::current_context()
+        .and_then(|context| context.session_id)
+    {
+        state.sse.close_stale_streams(
+            &session_id,
+            auth_session.user.as_ref().map(|user| &user.id),
+        );
+    }
+
     next.run(req).await
 }
 
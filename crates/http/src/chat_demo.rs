@@ -1,6 +1,12 @@
 pub struct ChatContext {
     pub room: domain::chat::Room,
     pub messages: Vec<crate::views::partials::ChatMessage>,
+    pub rooms: Vec<crate::views::partials::ChatRoomUnread>,
+    pub seen_by: Vec<crate::types::Text>,
+    pub schedules: Vec<crate::views::partials::ChatScheduleItem>,
+    pub pins: Vec<crate::views::partials::ChatPinnedItem>,
+    pub can_pin: bool,
+    pub topic: Option<domain::chat::RoomTopic>,
 }
 
 pub async fn load_chat_context(
@@ -18,11 +24,48 @@ pub async fn load_chat_context(
                 .build(),
         )
         .await?;
-    let message_views = to_message_views(state, &messages).await;
+    let polls = state
+        .chat
+        .list_polls(
+            app::chat::ListPolls::builder()
+                .room_id(room.id)
+                .user_id(chat_user_id)
+                .message_ids(messages.iter().map(|message| message.id).collect())
+                .build(),
+        )
+        .await?;
+    let previews = state.chat.link_previews(&messages).await?;
+    let attachments = state.chat.list_attachments(&messages).await?;
+    let message_views =
+        to_message_views(state, &messages, &polls, &previews, &attachments).await;
+    let rooms = room_unread_views(state, chat_user_id).await?;
+    let seen_by = match messages.first() {
+        Some(latest) => seen_by_names(state, room.id, chat_user_id, latest.id).await?,
+        None => Vec::new(),
+    };
+    let schedules = schedule_views(state, room.id, chat_user_id).await?;
+    let pins = state
+        .chat
+        .list_pins(
+            app::chat::ListPins::builder()
+                .room_id(room.id)
+                .user_id(chat_user_id)
+                .build(),
+        )
+        .await?;
+    let pins = pin_views(state, &pins).await;
+    let can_pin = state.chat.can_moderate_room(&room.id, &chat_user_id).await?;
+    let topic = state.chat.room_topic(&room.id, &chat_user_id).await?;
 
     Ok(ChatContext {
         room,
         messages: message_views,
+        rooms,
+        seen_by,
+        schedules,
+        pins,
+        can_pin,
+        topic,
     })
 }
 
@@ -59,6 +102,9 @@ async fn ensure_room(
 async fn to_message_views(
     state: &crate::State,
     messages: &[domain::chat::Message],
+    polls: &[app::chat::PollResults],
+    previews: &[(domain::chat::MessageId, domain::chat::LinkPreview)],
+    attachments: &[domain::chat::Attachment],
 ) -> Vec<crate::views::partials::ChatMessage> {
     let mut names = std::collections::HashMap::new();
     for message in messages {
@@ -66,8 +112,8 @@ async fn to_message_views(
         if names.contains_key(&user_id) {
             continue;
         }
-        if let Ok(Some(user)) = state.auth.get_user(&user_id).await {
-            names.insert(user_id, user.username);
+        if let Ok(Some(username)) = state.chat.author_name(&message.user_id).await {
+            names.insert(user_id, username);
         }
     }
 
@@ -95,11 +141,846 @@ async fn to_message_views(
                 .timestamp(crate::types::Text::from(format_message_time(message.created_at)))
                 .body(crate::types::Text::from(message.body.to_string()))
                 .status(crate::types::Text::from(format!("{:?}", message.status)))
+                .maybe_poll(
+                    polls
+                        .iter()
+                        .find(|poll| poll.poll.message_id == message.id)
+                        .map(poll_view),
+                )
+                .maybe_preview(
+                    previews
+                        .iter()
+                        .find(|(message_id, _)| *message_id == message.id)
+                        .map(|(_, preview)| preview_view(preview)),
+                )
+                .attachments(
+                    attachments
+                        .iter()
+                        .filter(|attachment| attachment.message_id == message.id)
+                        .map(attachment_view)
+                        .collect(),
+                )
                 .build()
         })
         .collect()
 }
 
+pub(crate) fn poll_view(
+    results: &app::chat::PollResults,
+) -> crate::views::partials::ChatPoll {
+    let total_votes = results.tallies.iter().map(|tally| tally.votes).sum();
+    crate::views::partials::ChatPoll::builder()
+        .message_id(crate::types::Text::from(
+            results.poll.message_id.as_uuid().to_string(),
+        ))
+        .author_id(crate::types::Text::from(results.author_id.as_uuid().to_string()))
+        .options(
+            results
+                .tallies
+                .iter()
+                .map(|tally| {
+                    crate::views::partials::ChatPollOption::builder()
+                        .option_id(crate::types::Text::from(
+                            tally.option.id.as_uuid().to_string(),
+                        ))
+                        .label(crate::types::Text::from(tally.option.label.to_string()))
+                        .votes(tally.votes)
+                        .total_votes(total_votes)
+                        .build()
+                })
+                .collect(),
+        )
+        .voters(results.voters)
+        .multiple(results.poll.kind == chat::PollKind::Multiple)
+        .closed(results.poll.is_closed(std::time::SystemTime::now()))
+        .maybe_closes_at(
+            results
+                .poll
+                .closes_at
+                .map(|closes_at| crate::types::Text::from(format_message_time(closes_at))),
+        )
+        .build()
+}
+
+pub(crate) fn preview_view(
+    preview: &domain::chat::LinkPreview,
+) -> crate::views::partials::ChatLinkPreview {
+    crate::views::partials::ChatLinkPreview::builder()
+        .url(crate::types::Text::from(preview.url.to_string()))
+        .maybe_title(
+            preview
+                .title
+                .as_ref()
+                .map(|title| crate::types::Text::from(title.to_string())),
+        )
+        .maybe_description(
+            preview
+                .description
+                .as_ref()
+                .map(|description| crate::types::Text::from(description.to_string())),
+        )
+        .maybe_image_url(
+            preview
+                .image_url
+                .as_ref()
+                .map(|image_url| crate::types::Text::from(image_url.to_string())),
+        )
+        .build()
+}
+
+pub(crate) fn attachment_view(
+    attachment: &domain::chat::Attachment,
+) -> crate::views::partials::ChatAttachment {
+    let url = |route: crate::paths::Route| {
+        crate::types::Text::from(
+            route
+                .as_str()
+                .replace("{attachment_id}", &attachment.id.as_uuid().to_string()),
+        )
+    };
+    crate::views::partials::ChatAttachment::builder()
+        .url(url(crate::paths::Route::ChatAttachment))
+        .name(crate::types::Text::from(attachment.name.to_string()))
+        .size(crate::types::Text::from(format_size(attachment.size_bytes)))
+        .maybe_thumbnail_url(
+            attachment
+                .thumbnail_key
+                .as_ref()
+                .map(|_| url(crate::paths::Route::ChatAttachmentThumbnail)),
+        )
+        .build()
+}
+
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        0..1024 => format!("{} B", bytes),
+        1024..1_048_576 => format!("{:.0} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+/// Fetches the card for a freshly posted link in the background and
+/// appends it to every copy of the message its audience has rendered.
+pub(crate) fn spawn_unfurl(
+    state: &crate::State,
+    message: domain::chat::Message,
+) {
+    if app::chat::first_url(&message.body).is_none() {
+        return;
+    }
+    let state = state.clone();
+    tokio::spawn(async move {
+        let preview = match state.chat.unfurl_message(&message).await {
+            Ok(Some(preview)) => preview,
+            Ok(None) => return,
+            Err(error) => {
+                tracing::warn!(?error, "chat link preview failed");
+                return;
+            }
+        };
+        let html = preview_view(&preview).render().into_string();
+        let event = datastar::prelude::PatchElements::new(html)
+            .selector(crate::views::partials::ChatLinkPreview::message_selector(
+                &crate::types::Text::from(message.id.as_uuid().to_string()),
+            ))
+            .mode(datastar::prelude::ElementPatchMode::Append)
+            .into_datastar_event();
+        send_to_audience(
+            &state,
+            &audience(&state, &message).await,
+            crate::sse::Event::from_event(event),
+        );
+    });
+}
+
+/// Updates the status label on every copy of the message its audience has
+/// rendered.
+pub(crate) fn push_message_status(
+    state: &crate::State,
+    audience: &app::chat::Audience,
+    message_id: &domain::chat::MessageId,
+    status: domain::chat::MessageStatus,
+) {
+    let status = crate::views::partials::ChatMessageStatus::builder()
+        .message_id(crate::types::Text::from(message_id.as_uuid().to_string()))
+        .status(crate::types::Text::from(format!("{:?}", status)))
+        .build();
+    let event = datastar::prelude::PatchElements::new(status.render().into_string())
+        .selector(status.selector())
+        .mode(datastar::prelude::ElementPatchMode::Outer)
+        .into_datastar_event();
+    send_to_audience(state, audience, crate::sse::Event::from_event(event));
+}
+
+/// Replaces the poll with the latest tallies for everyone viewing its room.
+pub(crate) fn push_poll(
+    state: &crate::State,
+    results: &app::chat::PollResults,
+) {
+    let html = poll_view(results).render().into_string();
+    let event = datastar::prelude::PatchElements::new(html)
+        .selector(crate::views::partials::ChatPoll::selector(
+            &crate::types::Text::from(results.poll.message_id.as_uuid().to_string()),
+        ))
+        .mode(datastar::prelude::ElementPatchMode::Outer)
+        .into_datastar_event();
+    send_to_viewers(
+        state,
+        &results.poll.room_id,
+        crate::sse::Event::from_event(event),
+    );
+}
+
+pub(crate) async fn start_typing(
+    state: &crate::State,
+    room_id: domain::chat::RoomId,
+    user: &crate::auth::User,
+) -> Result<(), crate::error::Error> {
+    let user_id = chat::UserId::from_uuid(*user.id.to_domain()?.as_uuid());
+    let change = state
+        .chat
+        .start_typing(
+            app::chat::Typing::builder()
+                .room_id(room_id)
+                .user_id(user_id)
+                .build(),
+        )
+        .await?;
+    if change == app::chat::TypingChange::Started {
+        let indicator = crate::views::partials::ChatTyping::builder()
+            .room_id(crate::types::Text::from(room_id.as_uuid().to_string()))
+            .user_id(crate::types::Text::from(user_id.as_uuid().to_string()))
+            .username(crate::types::Text::from(user.username.to_string()))
+            .build()
+            .render()
+            .into_string();
+        let event = datastar::prelude::PatchElements::new(indicator)
+            .selector(crate::views::partials::ChatTyping::list_selector(
+                &crate::types::Text::from(room_id.as_uuid().to_string()),
+            ))
+            .mode(datastar::prelude::ElementPatchMode::Append)
+            .into_datastar_event();
+        send_to_viewers(state, &room_id, crate::sse::Event::from_event(event));
+    }
+    Ok(())
+}
+
+/// Removes the typing indicator for `user_id`; the service already cleared
+/// the TTL entry when the message was posted or the entry expired.
+pub fn broadcast_typing_stopped(
+    state: &crate::State,
+    room_id: &chat::RoomId,
+    user_id: &chat::UserId,
+) {
+    let selector = crate::views::partials::ChatTyping::selector(
+        &crate::types::Text::from(room_id.as_uuid().to_string()),
+        &crate::types::Text::from(user_id.as_uuid().to_string()),
+    );
+    let event = datastar::prelude::PatchElements::new_remove(selector)
+        .into_datastar_event();
+    send_to_viewers(state, room_id, crate::sse::Event::from_event(event));
+}
+
+/// Sends `event` to everyone with the room open. Ephemeral room state
+/// (typing, presence) goes only to them, never to other rooms' viewers.
+fn send_to_viewers(
+    state: &crate::State,
+    room_id: &chat::RoomId,
+    event: crate::sse::Event,
+) {
+    for member in state.presence.online(room_id) {
+        let owner = crate::auth::UserId::from(domain::user::Id::from_uuid(
+            *member.user_id.as_uuid(),
+        ));
+        let _ = state.sse.send_to_user(&owner, event.clone());
+    }
+}
+
+pub async fn expire_typing_continuously(
+    state: crate::State,
+    interval: std::time::Duration,
+) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        for expired in state.chat.expire_typing() {
+            broadcast_typing_stopped(&state, &expired.room_id, &expired.user_id);
+        }
+    }
+}
+
+/// Runs retention purges on a fixed interval. With `dry_run` set the job
+/// only logs what each room would lose.
+pub async fn purge_expired_continuously(
+    state: crate::State,
+    interval: std::time::Duration,
+    dry_run: bool,
+) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        match state
+            .chat
+            .purge_expired(app::chat::RunPurge::builder().dry_run(dry_run).build())
+            .await
+        {
+            Ok(report) => tracing::info!(
+                dry_run = report.dry_run,
+                rooms = report.rooms.len(),
+                expired = report.expired(),
+                purged = report.purged(),
+                "chat retention purge finished"
+            ),
+            Err(error) => tracing::warn!(?error, "chat retention purge failed"),
+        }
+    }
+}
+
+/// Sends `event` to everyone, or only to the users a quarantined message
+/// is shown to.
+pub(crate) fn send_to_audience(
+    state: &crate::State,
+    audience: &app::chat::Audience,
+    event: crate::sse::Event,
+) {
+    match audience {
+        app::chat::Audience::Everyone => {
+            let _ = state.sse.broadcast(event);
+        }
+        app::chat::Audience::Only(users) => {
+            for user in users {
+                let owner =
+                    crate::auth::UserId::from(domain::user::Id::from_uuid(*user.as_uuid()));
+                let _ = state.sse.send_to_user(&owner, event.clone());
+            }
+        }
+    }
+}
+
+/// Who receives a new message. If the lookup fails, only the author
+/// gets it, so a quarantined message never leaks.
+pub(crate) async fn audience(
+    state: &crate::State,
+    message: &domain::chat::Message,
+) -> app::chat::Audience {
+    match state.chat.audience(message).await {
+        Ok(audience) => audience,
+        Err(error) => {
+            tracing::warn!(?error, "chat audience lookup failed");
+            app::chat::Audience::Only(vec![message.user_id])
+        }
+    }
+}
+
+/// Posts due scheduled messages on a fixed interval. Each one goes through
+/// `post_message`, so it is broadcast like any other new message.
+pub async fn post_scheduled_continuously(
+    state: crate::State,
+    interval: std::time::Duration,
+) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        let posted = match state
+            .chat
+            .post_due_schedules(app::chat::PostDueSchedules::builder().build())
+            .await
+        {
+            Ok(posted) => posted,
+            Err(error) => {
+                tracing::warn!(?error, "chat scheduled post failed");
+                continue;
+            }
+        };
+        for (schedule, message) in posted {
+            publish_scheduled(&state, &schedule, &message).await;
+        }
+    }
+}
+
+async fn publish_scheduled(
+    state: &crate::State,
+    schedule: &domain::chat::ScheduledMessage,
+    message: &domain::chat::Message,
+) {
+    let user_id = domain::user::Id::from_uuid(*message.user_id.as_uuid());
+    if let Ok(Some(user)) = state.auth.get_user(&user_id).await {
+        publish_read_state(state, message).await;
+        crate::handlers::broadcast_message(
+            state,
+            &audience(state, message).await,
+            &crate::handlers::render_message(message, &user.username),
+            crate::types::Text::from(message.body.to_string()),
+            crate::handlers::ChatSender::Schedule,
+            crate::types::UserIdText::new(user.id.as_uuid().to_string()),
+        );
+    }
+    spawn_unfurl(state, message.clone());
+    if let Err(error) = push_schedules(state, schedule.room_id, schedule.user_id).await {
+        tracing::warn!(?error, "chat schedule push failed");
+    }
+}
+
+/// Runs the room's bots on a freshly posted message in the background, so
+/// the poster's response doesn't wait on them, and broadcasts each reply.
+pub(crate) fn spawn_bot_replies(
+    state: &crate::State,
+    message: domain::chat::Message,
+) {
+    let state = state.clone();
+    tokio::spawn(async move {
+        let posts = match state.chat.run_bots(&message).await {
+            Ok(posts) => posts,
+            Err(error) => {
+                tracing::warn!(?error, "chat bots failed");
+                return;
+            }
+        };
+        for post in posts {
+            publish_bot_post(&state, &post).await;
+        }
+    });
+}
+
+/// Broadcasts a message posted under a bot account, from a bot reply or an
+/// incoming webhook.
+pub(crate) async fn publish_bot_post(
+    state: &crate::State,
+    post: &app::chat::BotPost,
+) {
+    publish_read_state(state, &post.message).await;
+    crate::handlers::broadcast_message(
+        state,
+        &audience(state, &post.message).await,
+        &crate::handlers::render_message(&post.message, &post.bot),
+        crate::types::Text::from(post.message.body.to_string()),
+        crate::handlers::ChatSender::Bot,
+        crate::types::UserIdText::new(post.message.user_id.as_uuid().to_string()),
+    );
+}
+
+/// Freezes polls whose close time has passed and pushes their final
+/// results.
+pub async fn close_polls_continuously(
+    state: crate::State,
+    interval: std::time::Duration,
+) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        match state.chat.close_due_polls().await {
+            Ok(closed) => {
+                for results in &closed {
+                    push_poll(&state, results);
+                }
+            }
+            Err(error) => tracing::warn!(?error, "chat poll close failed"),
+        }
+    }
+}
+
+/// Sends due webhook deliveries on a fixed interval; failed attempts are
+/// retried with backoff by later ticks.
+pub async fn deliver_webhooks_continuously(
+    state: crate::State,
+    interval: std::time::Duration,
+) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        match state
+            .chat
+            .deliver_webhooks(app::chat::DeliverWebhooks::builder().build())
+            .await
+        {
+            Ok(report) if report != app::chat::DeliveryReport::default() => tracing::info!(
+                delivered = report.delivered,
+                retrying = report.retrying,
+                failed = report.failed,
+                "chat webhook deliveries sent"
+            ),
+            Ok(_) => {}
+            Err(error) => tracing::warn!(?error, "chat webhook delivery failed"),
+        }
+    }
+}
+
+/// Deletes the files of messages that were purged or redacted.
+pub async fn sweep_attachments_continuously(
+    state: crate::State,
+    interval: std::time::Duration,
+) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        match state
+            .chat
+            .sweep_attachments(app::chat::SweepAttachments::builder().build())
+            .await
+        {
+            Ok(0) => {}
+            Ok(swept) => tracing::info!(swept, "chat attachments swept"),
+            Err(error) => tracing::warn!(?error, "chat attachment sweep failed"),
+        }
+    }
+}
+
+pub fn broadcast_presence(
+    state: &crate::State,
+    change: &crate::presence::Change,
+) {
+    let (room_id, user_id) = match change {
+        crate::presence::Change::Joined { room_id, member } => (room_id, &member.user_id),
+        crate::presence::Change::Left { room_id, user_id } => (room_id, user_id),
+    };
+    let room_text = crate::types::Text::from(room_id.as_uuid().to_string());
+    let user_text = crate::types::Text::from(user_id.as_uuid().to_string());
+    let remove = datastar::prelude::PatchElements::new_remove(
+        crate::views::partials::ChatPresenceMember::selector(&room_text, &user_text),
+    )
+    .into_datastar_event();
+    send_to_viewers(state, room_id, crate::sse::Event::from_event(remove));
+
+    if let crate::presence::Change::Joined { member, .. } = change {
+        let html = crate::views::partials::ChatPresenceMember::builder()
+            .room_id(room_text.clone())
+            .user_id(user_text)
+            .username(crate::types::Text::from(member.username.to_string()))
+            .build()
+            .render()
+            .into_string();
+        let append = datastar::prelude::PatchElements::new(html)
+            .selector(crate::views::partials::ChatPresence::list_selector(&room_text))
+            .mode(datastar::prelude::ElementPatchMode::Append)
+            .into_datastar_event();
+        send_to_viewers(state, room_id, crate::sse::Event::from_event(append));
+    }
+}
+
+pub async fn sweep_presence_continuously(
+    state: crate::State,
+    interval: std::time::Duration,
+) {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        for change in state.presence.sweep(std::time::Instant::now()) {
+            broadcast_presence(&state, &change);
+        }
+    }
+}
+
+pub fn online_members(
+    state: &crate::State,
+    room_id: &chat::RoomId,
+) -> Vec<crate::views::partials::ChatPresenceMember> {
+    let room_text = crate::types::Text::from(room_id.as_uuid().to_string());
+    state
+        .presence
+        .online(room_id)
+        .into_iter()
+        .map(|member| {
+            crate::views::partials::ChatPresenceMember::builder()
+                .room_id(room_text.clone())
+                .user_id(crate::types::Text::from(member.user_id.as_uuid().to_string()))
+                .username(crate::types::Text::from(member.username.to_string()))
+                .build()
+        })
+        .collect()
+}
+
+async fn room_unread_views(
+    state: &crate::State,
+    user_id: chat::UserId,
+) -> Result<Vec<crate::views::partials::ChatRoomUnread>, crate::error::Error> {
+    Ok(state
+        .chat
+        .list_unread(&user_id)
+        .await?
+        .into_iter()
+        .map(|room| {
+            crate::views::partials::ChatRoomUnread::builder()
+                .room_name(crate::types::Text::from(room.room_name.to_string()))
+                .unread(room.unread)
+                .build()
+        })
+        .collect())
+}
+
+async fn seen_by_names(
+    state: &crate::State,
+    room_id: chat::RoomId,
+    viewer_id: chat::UserId,
+    message_id: chat::MessageId,
+) -> Result<Vec<crate::types::Text>, crate::error::Error> {
+    let readers = state
+        .chat
+        .seen_by(
+            app::chat::SeenBy::builder()
+                .room_id(room_id)
+                .viewer_id(viewer_id)
+                .message_id(message_id)
+                .build(),
+        )
+        .await?;
+    let mut names = Vec::with_capacity(readers.len());
+    for reader in readers {
+        let user_id = domain::user::Id::from_uuid(*reader.as_uuid());
+        if let Ok(Some(user)) = state.auth.get_user(&user_id).await {
+            names.push(crate::types::Text::from(user.username.to_string()));
+        }
+    }
+    Ok(names)
+}
+
+pub(crate) async fn schedule_views(
+    state: &crate::State,
+    room_id: chat::RoomId,
+    user_id: chat::UserId,
+) -> Result<Vec<crate::views::partials::ChatScheduleItem>, crate::error::Error> {
+    Ok(state
+        .chat
+        .list_schedules(
+            app::chat::ListSchedules::builder()
+                .room_id(room_id)
+                .user_id(user_id)
+                .build(),
+        )
+        .await?
+        .into_iter()
+        .map(|schedule| {
+            crate::views::partials::ChatScheduleItem::builder()
+                .schedule_id(crate::types::Text::from(schedule.id.as_uuid().to_string()))
+                .send_at(crate::types::Text::from(format_message_time(schedule.send_at)))
+                .body(crate::types::Text::from(schedule.body.to_string()))
+                .cancel_action(crate::paths::Route::ChatScheduleCancel)
+                .build()
+        })
+        .collect())
+}
+
+/// Re-renders the author's pending schedules on every stream they have
+/// open.
+pub(crate) async fn push_schedules(
+    state: &crate::State,
+    room_id: chat::RoomId,
+    user_id: chat::UserId,
+) -> Result<(), crate::error::Error> {
+    let html = crate::views::partials::ChatScheduleList::builder()
+        .schedules(schedule_views(state, room_id, user_id).await?)
+        .build()
+        .render()
+        .into_string();
+    let owner = crate::auth::UserId::from(domain::user::Id::from_uuid(*user_id.as_uuid()));
+    let _ = state
+        .sse
+        .send_to_user(&owner, crate::sse::Event::patch_elements(html));
+    Ok(())
+}
+
+pub(crate) async fn pin_views(
+    state: &crate::State,
+    pins: &[app::chat::PinnedMessage],
+) -> Vec<crate::views::partials::ChatPinnedItem> {
+    let mut views = Vec::with_capacity(pins.len());
+    for pin in pins {
+        views.push(
+            crate::views::partials::ChatPinnedItem::builder()
+                .message_id(crate::types::Text::from(pin.message.id.as_uuid().to_string()))
+                .author(username_or_unknown(state, pin.message.user_id).await)
+                .body(crate::types::Text::from(pin.message.body.to_string()))
+                .pinned_by(username_or_unknown(state, pin.pinned_by).await)
+                .build(),
+        );
+    }
+    views
+}
+
+async fn username_or_unknown(
+    state: &crate::State,
+    user_id: chat::UserId,
+) -> crate::types::Text {
+    match state.chat.author_name(&user_id).await {
+        Ok(Some(username)) => crate::types::Text::from(username.to_string()),
+        _ => crate::types::Text::from("unknown"),
+    }
+}
+
+/// The strip shows the open room's pins, so it goes only to that room's
+/// viewers.
+pub(crate) async fn push_pins(
+    state: &crate::State,
+    pins: &app::chat::RoomPins,
+) {
+    let html = crate::views::partials::ChatPinnedStrip::builder()
+        .pins(pin_views(state, &pins.pins).await)
+        .build()
+        .render()
+        .into_string();
+    send_to_viewers(
+        state,
+        &pins.room_id,
+        crate::sse::Event::patch_elements(html),
+    );
+}
+
+/// Shows a slash-command reply on the stream that sent the command only.
+pub(crate) fn send_command_reply(
+    state: &crate::State,
+    reply: &app::chat::CommandReply,
+) {
+    let Some(target) = crate::request::current_sse_target() else {
+        return;
+    };
+    let html = crate::views::partials::ChatCommandReply::builder()
+        .text(crate::types::Text::from(reply.to_string()))
+        .build()
+        .render()
+        .into_string();
+    let event = datastar::prelude::PatchElements::new(html)
+        .selector(crate::views::partials::ChatCommandReply::SELECTOR)
+        .mode(datastar::prelude::ElementPatchMode::Append)
+        .into_datastar_event();
+    let _ = state
+        .sse
+        .send_to(&target, crate::sse::Event::from_event(event));
+}
+
+/// Tells a member about a moderation decision on their message, on every
+/// stream they have open, with a link to their appeals.
+pub(crate) fn send_moderation_notice(
+    state: &crate::State,
+    user_id: &chat::UserId,
+    text: crate::types::Text,
+) {
+    let html = crate::views::partials::ChatModerationNotice::builder()
+        .text(text)
+        .appeals(crate::paths::Route::ChatAppeals)
+        .build()
+        .render()
+        .into_string();
+    let event = datastar::prelude::PatchElements::new(html)
+        .selector(crate::views::partials::ChatCommandReply::SELECTOR)
+        .mode(datastar::prelude::ElementPatchMode::Append)
+        .into_datastar_event();
+    let owner = crate::auth::UserId::from(domain::user::Id::from_uuid(*user_id.as_uuid()));
+    let _ = state
+        .sse
+        .send_to_user(&owner, crate::sse::Event::from_event(event));
+}
+
+/// The header shows the open room's topic, so only that room's viewers get
+/// the new one.
+pub(crate) fn push_topic(
+    state: &crate::State,
+    room_id: &chat::RoomId,
+    topic: Option<&domain::chat::RoomTopic>,
+) {
+    let html = crate::views::partials::ChatRoomTopic::builder()
+        .maybe_topic(topic.map(|topic| crate::types::Text::from(topic.to_string())))
+        .build()
+        .render()
+        .into_string();
+    send_to_viewers(state, room_id, crate::sse::Event::patch_elements(html));
+}
+
+/// Re-renders the room list with unread badges for one user, on every
+/// stream they have open.
+pub(crate) async fn push_unread(
+    state: &crate::State,
+    user_id: chat::UserId,
+) -> Result<(), crate::error::Error> {
+    let html = crate::views::partials::ChatRoomList::builder()
+        .rooms(room_unread_views(state, user_id).await?)
+        .build()
+        .render()
+        .into_string();
+    let owner = crate::auth::UserId::from(domain::user::Id::from_uuid(*user_id.as_uuid()));
+    let _ = state
+        .sse
+        .send_to_user(&owner, crate::sse::Event::patch_elements(html));
+    Ok(())
+}
+
+pub(crate) async fn push_seen_by(
+    state: &crate::State,
+    room_id: chat::RoomId,
+    viewer_id: chat::UserId,
+    message_id: chat::MessageId,
+) -> Result<(), crate::error::Error> {
+    let html = crate::views::partials::ChatSeenBy::builder()
+        .room_id(crate::types::Text::from(room_id.as_uuid().to_string()))
+        .readers(seen_by_names(state, room_id, viewer_id, message_id).await?)
+        .build()
+        .render()
+        .into_string();
+    send_to_members(state, room_id, crate::sse::Event::patch_elements(html)).await?;
+    Ok(())
+}
+
+/// A new message has no readers yet and bumps unread counts for every other
+/// member of the room, online in it or not. For a quarantined message that
+/// is only the members in its audience.
+pub(crate) async fn publish_read_state(
+    state: &crate::State,
+    message: &domain::chat::Message,
+) {
+    let empty = crate::views::partials::ChatSeenBy::builder()
+        .room_id(crate::types::Text::from(message.room_id.as_uuid().to_string()))
+        .readers(Vec::new())
+        .build()
+        .render()
+        .into_string();
+    let members = match state.chat.room_members(&message.room_id).await {
+        Ok(members) => members,
+        Err(error) => {
+            tracing::warn!(?error, "chat member lookup failed");
+            return;
+        }
+    };
+    let audience = audience(state, message).await;
+
+    let event = crate::sse::Event::patch_elements(empty);
+    for member in members {
+        if let app::chat::Audience::Only(users) = &audience
+            && !users.contains(&member)
+        {
+            continue;
+        }
+        let owner =
+            crate::auth::UserId::from(domain::user::Id::from_uuid(*member.as_uuid()));
+        let _ = state.sse.send_to_user(&owner, event.clone());
+        if member == message.user_id {
+            continue;
+        }
+        if let Err(error) = push_unread(state, member).await {
+            tracing::warn!(?error, "chat unread push failed");
+        }
+    }
+}
+
+/// Sends `event` to every member of the room. Read state outlives a visit,
+/// so it goes to members who are not looking at the room right now too.
+async fn send_to_members(
+    state: &crate::State,
+    room_id: chat::RoomId,
+    event: crate::sse::Event,
+) -> Result<(), crate::error::Error> {
+    for member in state.chat.room_members(&room_id).await? {
+        let owner =
+            crate::auth::UserId::from(domain::user::Id::from_uuid(*member.as_uuid()));
+        let _ = state.sse.send_to_user(&owner, event.clone());
+    }
+    Ok(())
+}
+
 pub fn format_message_time(value: std::time::SystemTime) -> String {
     let time = time::OffsetDateTime::from(value);
     let format = time::format_description::parse(
@@ -109,3 +990,4 @@ pub fn format_message_time(value: std::time::SystemTime) -> String {
     time.format(&format).unwrap_or_else(|_| "--:--".to_string())
 }
 use domain::chat;
+use maud::Render;
@@ -10,6 +10,8 @@ pub enum Error {
     Auth(app::auth::Error),
     Chat(app::chat::Error),
     Json(axum::extract::rejection::JsonRejection),
+    /// No signed-in user where one is required.
+    Unauthenticated,
     Internal,
 }
 
@@ -82,11 +84,21 @@ impl Error {
                 "Unauthorized",
                 "Unable to authenticate.",
             ),
+            Error::Unauthenticated => (
+                axum::http::StatusCode::UNAUTHORIZED,
+                "Unauthorized",
+                "Sign in to continue.",
+            ),
             Error::Chat(app::chat::Error::RateLimited) => (
                 axum::http::StatusCode::TOO_MANY_REQUESTS,
                 "Too many messages",
                 "Slow down and try again.",
             ),
+            Error::Chat(app::chat::Error::Flooded) => (
+                axum::http::StatusCode::TOO_MANY_REQUESTS,
+                "Repeated message",
+                "That repeats your recent posts or floods several rooms. Keep going and posts are held for review, then you are muted.",
+            ),
             Error::Chat(app::chat::Error::RoomNotFound)
             | Error::Chat(app::chat::Error::MessageNotFound) => (
                 axum::http::StatusCode::NOT_FOUND,
@@ -98,6 +110,91 @@ impl Error {
                 "Access denied",
                 "You are not a member of this room.",
             ),
+            Error::Chat(app::chat::Error::Forbidden) => (
+                axum::http::StatusCode::FORBIDDEN,
+                "Access denied",
+                "Your role in this room doesn't allow that.",
+            ),
+            Error::Chat(app::chat::Error::PinLimitReached) => (
+                axum::http::StatusCode::CONFLICT,
+                "Pin limit reached",
+                "Unpin a message before pinning another.",
+            ),
+            Error::Chat(app::chat::Error::InvalidPoll) => (
+                axum::http::StatusCode::BAD_REQUEST,
+                "Invalid poll",
+                "Polls need 2 to 10 distinct options, a future close time, and single-choice ballots take one option.",
+            ),
+            Error::Chat(app::chat::Error::PollNotFound) => (
+                axum::http::StatusCode::NOT_FOUND,
+                "Not found",
+                "The poll was not found.",
+            ),
+            Error::Chat(app::chat::Error::PollClosed) => (
+                axum::http::StatusCode::CONFLICT,
+                "Poll closed",
+                "This poll is closed; its results are final.",
+            ),
+            Error::Chat(app::chat::Error::AlreadyVoted) => (
+                axum::http::StatusCode::CONFLICT,
+                "Already voted",
+                "You already voted in this poll.",
+            ),
+            Error::Chat(app::chat::Error::Muted) => (
+                axum::http::StatusCode::FORBIDDEN,
+                "Muted",
+                "A moderator muted you in this room for now.",
+            ),
+            Error::Chat(app::chat::Error::InvalidWebhook) => (
+                axum::http::StatusCode::BAD_REQUEST,
+                "Invalid webhook",
+                "Webhooks need a public http(s) URL, at least one event, a secret of 16+ characters, and rooms hold at most 10.",
+            ),
+            Error::Chat(app::chat::Error::BotNameTaken) => (
+                axum::http::StatusCode::CONFLICT,
+                "Name taken",
+                "A person already uses that name; pick another bot name.",
+            ),
+            Error::Chat(app::chat::Error::WebhookNotFound) => (
+                axum::http::StatusCode::NOT_FOUND,
+                "Not found",
+                "The webhook was not found.",
+            ),
+            Error::Chat(app::chat::Error::InvalidAttachment) => (
+                axum::http::StatusCode::BAD_REQUEST,
+                "Invalid attachment",
+                "Files must be PNG, JPEG, GIF, WebP, PDF or text, at most 10 MB, up to 4 per message.",
+            ),
+            Error::Chat(app::chat::Error::AttachmentNotFound) => (
+                axum::http::StatusCode::NOT_FOUND,
+                "Not found",
+                "The attachment was not found.",
+            ),
+            Error::Chat(app::chat::Error::InvalidReport) => (
+                axum::http::StatusCode::BAD_REQUEST,
+                "Invalid report",
+                "Pick a report category; you can't report your own message.",
+            ),
+            Error::Chat(app::chat::Error::ModerationConflict) => (
+                axum::http::StatusCode::CONFLICT,
+                "Already claimed",
+                "Someone else is reviewing this message or it was already reviewed.",
+            ),
+            Error::Chat(app::chat::Error::InvalidAppeal) => (
+                axum::http::StatusCode::BAD_REQUEST,
+                "Invalid appeal",
+                "You can appeal each of your removed messages once; explain why it should be restored.",
+            ),
+            Error::Chat(app::chat::Error::InvalidSchedule) => (
+                axum::http::StatusCode::BAD_REQUEST,
+                "Invalid schedule",
+                "Pick a pending schedule and a time in the future, at most 30 days ahead.",
+            ),
+            Error::Chat(app::chat::Error::ScheduleNotFound) => (
+                axum::http::StatusCode::NOT_FOUND,
+                "Not found",
+                "The scheduled message was not found.",
+            ),
             Error::Chat(app::chat::Error::InvalidId(_))
             | Error::Chat(app::chat::Error::Domain(_)) => (
                 axum::http::StatusCode::BAD_REQUEST,
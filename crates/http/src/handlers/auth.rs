@@ -7,6 +7,7 @@ use bon::Builder;
 use serde::Deserialize;
 
 use crate::paths::Route;
+use crate::request;
 use crate::types::Text;
 use crate::views::{self, pages};
 use secrecy::SecretString;
@@ -138,9 +139,17 @@ pub async fn register(
 }
 
 pub async fn logout(
+    Extension(state): Extension<crate::State>,
     mut auth_session: crate::auth::Session,
 ) -> crate::Result<axum::response::Response> {
-    auth_session.logout().await?;
+    if auth_session.logout().await?.is_some()
+        && let Some(session_id) = request::current_context()
+            .and_then(|context| context.session_id)
+    {
+        // Only this session's streams; the user's other devices stay
+        // signed in.
+        state.sse.close_stale_streams(&session_id, None);
+    }
     Ok(Redirect::to(Route::Home.as_str()).into_response())
 }
 
@@ -12,21 +12,256 @@ use crate::{paths::Route, request, views};
 use crate::trace_log::{LogMessageKnown, LogTargetKnown};
 use crate::types::{LogFieldKey, Text};
 
-const DEMO_USER_EMAIL: &str = "demo.bot@example.com";
-const DEMO_USER_NAME: &str = "Demo Bot";
-
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ChatSignals {
     pub room_id: Text,
     pub body: Text,
+    pub tab_id: Option<crate::types::TabId>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TypingSignals {
+    pub room_id: Text,
+    pub tab_id: Option<crate::types::TabId>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadSignalsPayload {
+    pub room_id: Text,
+    pub last_message_id: Text,
+    pub tab_id: Option<crate::types::TabId>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchSignals {
+    pub search_query: Option<Text>,
+}
+
+/// `schedule_at_ms` is the local `datetime-local` value converted to epoch
+/// millis in the browser.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleSignals {
+    pub room_id: Text,
+    pub schedule_body: Text,
+    pub schedule_at_ms: Option<i64>,
+    pub tab_id: Option<crate::types::TabId>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelScheduleSignals {
+    pub room_id: Text,
+    pub schedule_id: Text,
+    pub tab_id: Option<crate::types::TabId>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinSignals {
+    pub pin_message_id: Text,
+    pub tab_id: Option<crate::types::TabId>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportSignals {
+    pub report_message_id: Text,
+    pub report_category: Text,
+    pub tab_id: Option<crate::types::TabId>,
+}
+
+/// `poll_options` holds one option per line; `poll_closes_at_ms` of 0 means
+/// the poll stays open until closed by hand.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PollSignals {
+    pub room_id: Text,
+    pub poll_question: Text,
+    pub poll_options: Text,
+    #[serde(default)]
+    pub poll_multiple: bool,
+    pub poll_closes_at_ms: Option<i64>,
+    pub tab_id: Option<crate::types::TabId>,
 }
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
-pub struct DemoChatSignals {
+pub struct VoteSignals {
+    pub poll_message_id: Text,
+    #[serde(default)]
+    pub poll_option_ids: Vec<Text>,
+    pub tab_id: Option<crate::types::TabId>,
+}
+
+#[derive(Deserialize)]
+pub struct ExportParams {
+    pub room_id: Text,
+    pub format: Option<Text>,
+}
+
+#[derive(Deserialize)]
+pub struct ImportParams {
+    pub room_id: Text,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ImportResponse {
+    pub imported: usize,
+    pub pending: usize,
+    pub placeholders: usize,
+    pub errors: Vec<ImportLineResponse>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct ImportLineResponse {
+    pub line: usize,
+    pub reason: Text,
+}
+
+impl From<app::chat::ImportReport> for ImportResponse {
+    fn from(report: app::chat::ImportReport) -> Self {
+        Self {
+            imported: report.imported,
+            pending: report.pending,
+            placeholders: report.placeholders,
+            errors: report
+                .errors
+                .into_iter()
+                .map(|error| ImportLineResponse {
+                    line: error.line,
+                    reason: Text::from(error.reason.to_string()),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RetentionParams {
+    pub room_id: Text,
+}
+
+/// `max_age_days` and `max_messages` both absent keeps messages forever.
+#[derive(Deserialize)]
+pub struct RetentionRequest {
+    pub room_id: Text,
+    pub max_age_days: Option<u32>,
+    pub max_messages: Option<u32>,
+    pub mode: Option<Text>,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct RetentionResponse {
     pub room_id: Text,
-    pub bot_body: Text,
+    pub max_age_days: Option<u32>,
+    pub max_messages: Option<u32>,
+    pub mode: Option<Text>,
+    /// Messages the next purge would remove (dry run).
+    pub expired: u64,
+}
+
+#[derive(Deserialize)]
+pub struct WebhookListParams {
+    pub room_id: Text,
+}
+
+/// `events` uses the payload names, e.g. `message.posted`.
+#[derive(Deserialize)]
+pub struct WebhookRequest {
+    pub room_id: Text,
+    pub url: Text,
+    pub events: Vec<Text>,
+    pub secret: Text,
+}
+
+#[derive(Deserialize)]
+pub struct WebhookIdRequest {
+    pub webhook_id: Text,
+}
+
+/// The secret is write-only and never echoed back.
+#[derive(Debug, serde::Serialize)]
+pub struct WebhookResponse {
+    pub webhook_id: Text,
+    pub room_id: Text,
+    pub url: Text,
+    pub events: Vec<Text>,
+    pub created_at_ms: u64,
+}
+
+impl From<domain::chat::Webhook> for WebhookResponse {
+    fn from(webhook: domain::chat::Webhook) -> Self {
+        Self {
+            webhook_id: Text::from(webhook.id.as_uuid().to_string()),
+            room_id: Text::from(webhook.room_id.as_uuid().to_string()),
+            url: Text::from(webhook.url.to_string()),
+            events: webhook
+                .events
+                .iter()
+                .map(|event| Text::from(event.to_string()))
+                .collect(),
+            created_at_ms: epoch_ms(webhook.created_at),
+        }
+    }
+}
+
+/// `bot_name` renames the posting account; omit it to keep the current one.
+#[derive(Deserialize)]
+pub struct IncomingWebhookRequest {
+    pub room_id: Text,
+    pub bot_name: Option<Text>,
+}
+
+/// `url` is a path on this server; anyone holding it can post.
+#[derive(Debug, serde::Serialize)]
+pub struct IncomingWebhookResponse {
+    pub room_id: Text,
+    pub url: Text,
+    pub bot: Text,
+    pub created_at_ms: u64,
+}
+
+#[derive(Deserialize)]
+pub struct IncomingPostRequest {
+    pub text: Text,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct IncomingPostResponse {
+    pub message_id: Text,
+    pub status: Text,
+}
+
+#[derive(Debug, serde::Serialize)]
+pub struct WebhookDeliveryResponse {
+    pub delivery_id: Text,
+    pub event: Text,
+    pub status: Text,
+    pub attempts: u32,
+    pub response_status: Option<u16>,
+    pub last_error: Option<Text>,
+    pub next_attempt_at_ms: u64,
+    pub created_at_ms: u64,
+}
+
+impl From<domain::chat::WebhookDelivery> for WebhookDeliveryResponse {
+    fn from(delivery: domain::chat::WebhookDelivery) -> Self {
+        Self {
+            delivery_id: Text::from(delivery.id.as_uuid().to_string()),
+            event: Text::from(delivery.event.to_string()),
+            status: Text::from(delivery.status.to_string()),
+            attempts: delivery.attempts,
+            response_status: delivery.response_status,
+            last_error: delivery.last_error.map(|error| Text::from(error.to_string())),
+            next_attempt_at_ms: epoch_ms(delivery.next_attempt_at),
+            created_at_ms: epoch_ms(delivery.created_at),
+        }
+    }
 }
 
 pub async fn chat_page(
@@ -50,18 +285,89 @@ pub struct ModerationForm {
     pub message_id: Text,
     pub decision: Text,
     pub reason: Option<Text>,
+    pub revision: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct ModerationClaimForm {
+    pub message_id: Text,
+}
+
+/// Queue filters; forms post back with the same query so the reviewer
+/// lands on the view they came from.
+#[derive(Deserialize, Default)]
+pub struct ModerationParams {
+    pub room_id: Option<Text>,
+    pub reason: Option<Text>,
+}
+
+impl ModerationParams {
+    fn room_id(&self) -> Result<Option<domain::chat::RoomId>, crate::error::Error> {
+        non_empty(&self.room_id)
+            .map(|value| parse_room_id(&value))
+            .transpose()
+    }
+
+    fn reason(&self) -> Result<Option<app::chat::ModerationReason>, crate::error::Error> {
+        parse_reason(non_empty(&self.reason).map(Text::from))
+    }
+
+    fn query(&self) -> Text {
+        let pairs = [("room_id", &self.room_id), ("reason", &self.reason)];
+        Text::from(
+            pairs
+                .iter()
+                .filter_map(|(key, value)| {
+                    non_empty(value).map(|value| {
+                        format!("{}={}", key, urlencoding::encode(&value))
+                    })
+                })
+                .collect::<Vec<_>>()
+                .join("&"),
+        )
+    }
+
+    fn redirect(&self) -> axum::response::Response {
+        let query = self.query().to_string();
+        let target = if query.is_empty() {
+            Route::ChatModeration.as_str().to_string()
+        } else {
+            Route::ChatModeration.with_query(&query)
+        };
+        axum::response::Redirect::to(target.as_str()).into_response()
+    }
+}
+
+fn non_empty(
+    value: &Option<Text>,
+) -> Option<String> {
+    value
+        .as_ref()
+        .map(|value| value.to_string().trim().to_string())
+        .filter(|value| !value.is_empty())
 }
 
 pub async fn moderation_page(
     Extension(state): Extension<crate::State>,
     auth_session: crate::auth::Session,
+    axum::extract::Query(params): axum::extract::Query<ModerationParams>,
 ) -> crate::Result<axum::response::Html<String>> {
     let user = auth_session
         .user
         .as_ref()
         .ok_or(crate::error::Error::Internal)?;
+    let viewer_id = chat_user_id_from_user_id(user.id.to_domain()?);
 
-    let entries = state.chat.list_moderation_queue(50).await?;
+    let entries = state
+        .chat
+        .list_moderation_queue(
+            app::chat::ListModerationQueue::builder()
+                .reviewer_id(viewer_id)
+                .maybe_room_id(params.room_id()?)
+                .maybe_reason(params.reason()?)
+                .build(),
+        )
+        .await?;
     let user_nav = crate::views::page::UserNav::builder()
         .username(Text::from(user.username.to_string()))
         .email(Text::from(user.email.to_string()))
@@ -70,6 +376,11 @@ pub async fn moderation_page(
     Ok(views::render(
         views::pages::ChatModeration::builder()
             .entries(entries)
+            .now(std::time::SystemTime::now())
+            .viewer_id(chat_user_id_from_user_id(user.id.to_domain()?))
+            .maybe_room_id(non_empty(&params.room_id).map(Text::from))
+            .maybe_reason(non_empty(&params.reason).map(Text::from))
+            .filter_query(params.query())
             .maybe_with_user(Some(user_nav))
             .build(),
     ))
@@ -78,8 +389,9 @@ pub async fn moderation_page(
 pub async fn moderate_message(
     Extension(state): Extension<crate::State>,
     auth_session: crate::auth::Session,
+    axum::extract::Query(params): axum::extract::Query<ModerationParams>,
     axum::extract::Form(form): axum::extract::Form<ModerationForm>,
-) -> crate::Result<axum::response::Html<String>> {
+) -> crate::Result<axum::response::Response> {
     let user = auth_session
         .user
         .as_ref()
@@ -95,19 +407,218 @@ pub async fn moderate_message(
         None => return Err(crate::error::Error::Internal),
     };
 
-    state
+    let reason = parse_reason(form.reason)?;
+    let outcome = state
         .chat
         .moderate_message(
             app::chat::ModerateMessage::builder()
                 .message_id(parse_message_id(&form.message_id.to_string())?)
                 .reviewer_id(chat_user_id_from_user_id(user.id.to_domain()?))
                 .decision(decision)
-                .maybe_reason(parse_reason(form.reason)?)
+                .maybe_reason(reason.clone())
+                .maybe_revision(form.revision)
+                .build(),
+        )
+        .await?;
+    let message = outcome.message;
+    crate::chat_demo::push_message_status(
+        &state,
+        &outcome.audience,
+        &message.id,
+        message.status,
+    );
+    if message.status == domain::chat::MessageStatus::Removed {
+        crate::chat_demo::send_moderation_notice(
+            &state,
+            &message.user_id,
+            Text::from(format!(
+                "A moderator removed your message \"{}\" (reason: {}). You can appeal once.",
+                excerpt(&message.body.to_string()),
+                reason
+                    .map(|reason| reason.to_string())
+                    .filter(|reason| !reason.is_empty())
+                    .unwrap_or_else(|| "not given".to_string()),
+            )),
+        );
+    }
+
+    Ok(params.redirect())
+}
+
+/// First 60 characters of a message for notices.
+fn excerpt(
+    body: &str,
+) -> String {
+    const MAX_CHARS: usize = 60;
+    match body.char_indices().nth(MAX_CHARS) {
+        Some((end, _)) => format!("{}\u{2026}", &body[..end]),
+        None => body.to_string(),
+    }
+}
+
+pub async fn claim_moderation(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    axum::extract::Query(params): axum::extract::Query<ModerationParams>,
+    axum::extract::Form(form): axum::extract::Form<ModerationClaimForm>,
+) -> crate::Result<axum::response::Response> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+
+    state
+        .chat
+        .claim_moderation(
+            app::chat::ClaimModeration::builder()
+                .message_id(parse_message_id(&form.message_id.to_string())?)
+                .reviewer_id(chat_user_id_from_user_id(user.id.to_domain()?))
+                .build(),
+        )
+        .await?;
+
+    Ok(params.redirect())
+}
+
+pub async fn release_moderation(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    axum::extract::Query(params): axum::extract::Query<ModerationParams>,
+    axum::extract::Form(form): axum::extract::Form<ModerationClaimForm>,
+) -> crate::Result<axum::response::Response> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+
+    state
+        .chat
+        .release_moderation(
+            app::chat::ReleaseModeration::builder()
+                .message_id(parse_message_id(&form.message_id.to_string())?)
+                .reviewer_id(chat_user_id_from_user_id(user.id.to_domain()?))
+                .build(),
+        )
+        .await?;
+
+    Ok(params.redirect())
+}
+
+#[derive(Deserialize)]
+pub struct AppealForm {
+    pub message_id: Text,
+    pub statement: Text,
+}
+
+#[derive(Deserialize)]
+pub struct AppealDecisionForm {
+    pub message_id: Text,
+    pub decision: Text,
+}
+
+pub async fn appeals_page(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+) -> crate::Result<axum::response::Html<String>> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+    let viewer_id = chat_user_id_from_user_id(user.id.to_domain()?);
+
+    let removals = state
+        .chat
+        .list_removals(app::chat::ListRemovals::builder().author_id(viewer_id).build())
+        .await?;
+    let appeals = state
+        .chat
+        .list_appeals(app::chat::ListAppeals::builder().reviewer_id(viewer_id).build())
+        .await?;
+    let user_nav = crate::views::page::UserNav::builder()
+        .username(Text::from(user.username.to_string()))
+        .email(Text::from(user.email.to_string()))
+        .build();
+
+    Ok(views::render(
+        views::pages::ChatAppeals::builder()
+            .removals(removals)
+            .appeals(appeals)
+            .viewer_id(viewer_id)
+            .maybe_with_user(Some(user_nav))
+            .build(),
+    ))
+}
+
+pub async fn file_appeal(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    axum::extract::Form(form): axum::extract::Form<AppealForm>,
+) -> crate::Result<axum::response::Response> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+    let statement = app::chat::AppealStatement::try_new(form.statement.to_string())
+        .map_err(|_| app::chat::Error::InvalidAppeal)?;
+
+    state
+        .chat
+        .file_appeal(
+            app::chat::FileAppeal::builder()
+                .message_id(parse_message_id(&form.message_id.to_string())?)
+                .appellant_id(chat_user_id_from_user_id(user.id.to_domain()?))
+                .statement(statement)
+                .build(),
+        )
+        .await?;
+
+    Ok(axum::response::Redirect::to(Route::ChatAppeals.as_str()).into_response())
+}
+
+pub async fn decide_appeal(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    axum::extract::Form(form): axum::extract::Form<AppealDecisionForm>,
+) -> crate::Result<axum::response::Response> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+    let decision = form
+        .decision
+        .to_string()
+        .parse::<app::chat::AppealDecision>()
+        .map_err(|_| crate::error::Error::Internal)?;
+
+    let appeal = state
+        .chat
+        .decide_appeal(
+            app::chat::DecideAppeal::builder()
+                .message_id(parse_message_id(&form.message_id.to_string())?)
+                .reviewer_id(chat_user_id_from_user_id(user.id.to_domain()?))
+                .decision(decision)
                 .build(),
         )
         .await?;
+    if decision == app::chat::AppealDecision::Overturn {
+        crate::chat_demo::push_message_status(
+            &state,
+            &app::chat::Audience::Everyone,
+            &appeal.message_id,
+            domain::chat::MessageStatus::Visible,
+        );
+    }
+    crate::chat_demo::send_moderation_notice(
+        &state,
+        &appeal.appellant_id,
+        Text::from(format!(
+            "Your appeal for \"{}\": {}.",
+            excerpt(&appeal.body.to_string()),
+            appeal.status.label(),
+        )),
+    );
 
-    moderation_page(Extension(state), auth_session).await
+    Ok(axum::response::Redirect::to(Route::ChatAppeals.as_str()).into_response())
 }
 
 pub async fn post_chat_message(
@@ -115,14 +626,15 @@ pub async fn post_chat_message(
     auth_session: crate::auth::Session,
     ReadSignals(signals): ReadSignals<ChatSignals>,
 ) -> Result<axum::response::Response, crate::error::Error> {
+    request::set_tab_id(signals.tab_id.clone());
     let user = auth_session
         .user
         .as_ref()
         .ok_or(crate::error::Error::Internal)?;
 
-    let message = state
+    let outcome = state
         .chat
-        .post_message(
+        .handle_input(
             app::chat::PostMessage::builder()
                 .room_id(parse_room_id(&signals.room_id.to_string())?)
                 .user_id(chat_user_id_from_user_id(user.id.to_domain()?))
@@ -130,11 +642,25 @@ pub async fn post_chat_message(
                 .build(),
         )
         .await?;
+    let message = match outcome {
+        app::chat::CommandOutcome::Posted(message) => message,
+        app::chat::CommandOutcome::Reply(reply) => {
+            crate::chat_demo::send_command_reply(&state, &reply);
+            return Ok(command_response());
+        }
+        app::chat::CommandOutcome::TopicChanged {
+            room_id,
+            topic,
+            reply,
+        } => {
+            crate::chat_demo::push_topic(&state, &room_id, topic.as_ref());
+            crate::chat_demo::send_command_reply(&state, &reply);
+            return Ok(command_response());
+        }
+    };
 
     state.trace_log.record_sse_event(
-        request::current_context()
-            .and_then(|value| value.session_id)
-            .as_ref(),
+        request::current_sse_target().as_ref(),
         crate::trace_log::TraceEntry::builder()
             .timestamp(crate::trace_log::now_timestamp_short())
             .level(crate::types::LogLevelText::new("INFO"))
@@ -167,22 +693,19 @@ pub async fn post_chat_message(
             .build(),
     );
 
-    let message_html = views::partials::ChatMessage::builder()
-        .message_id(crate::types::Text::from(message.id.as_uuid().to_string()))
-        .author(crate::types::Text::from(user.username.to_string()))
-        .timestamp(crate::types::Text::from(crate::chat_demo::format_message_time(message.created_at)))
-        .body(crate::types::Text::from(message.body.to_string()))
-        .status(crate::types::Text::from(format!("{:?}", message.status)))
-        .build()
-        .render()
-        .into_string();
+    crate::chat_demo::broadcast_typing_stopped(&state, &message.room_id, &message.user_id);
+    crate::chat_demo::publish_read_state(&state, &message).await;
+    let message_html = render_message(&message, &user.username);
     broadcast_message(
         &state,
+        &crate::chat_demo::audience(&state, &message).await,
         &message_html,
         Text::from(message.body.to_string()),
         ChatSender::You,
         crate::types::UserIdText::new(user.id.to_string()),
     );
+    crate::chat_demo::spawn_unfurl(&state, message.clone());
+    crate::chat_demo::spawn_bot_replies(&state, message);
 
     let response = match crate::request::current_kind() {
         crate::request::Kind::Datastar => (
@@ -190,105 +713,968 @@ pub async fn post_chat_message(
             axum::response::Html(message_html),
         )
             .into_response(),
-        crate::request::Kind::Page => {
-            let target = format!(
-                "{}#{}",
-                Route::Home.as_str(),
-                crate::views::partials::ChatDemoSection::ANCHOR_ID
-            );
-            axum::response::Redirect::to(target.as_str()).into_response()
-        }
+        crate::request::Kind::Page => chat_redirect(),
     };
 
     Ok(response)
 }
 
-pub async fn post_demo_chat_message(
-    Extension(state): Extension<crate::State>,
-    auth_session: crate::auth::Session,
-    ReadSignals(signals): ReadSignals<DemoChatSignals>,
-) -> Result<axum::response::Response, crate::error::Error> {
-    let _ = auth_session
-        .user
+/// Command output already went out over SSE; Datastar needs no body.
+fn command_response() -> axum::response::Response {
+    match crate::request::current_kind() {
+        crate::request::Kind::Datastar => StatusCode::NO_CONTENT.into_response(),
+        crate::request::Kind::Page => chat_redirect(),
+    }
+}
+
+fn chat_redirect() -> axum::response::Response {
+    let target = format!(
+        "{}#{}",
+        Route::Home.as_str(),
+        crate::views::partials::ChatDemoSection::ANCHOR_ID
+    );
+    axum::response::Redirect::to(target.as_str()).into_response()
+}
+
+pub async fn post_typing(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    ReadSignals(signals): ReadSignals<TypingSignals>,
+) -> Result<axum::response::Response, crate::error::Error> {
+    request::set_tab_id(signals.tab_id.clone());
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+
+    crate::chat_demo::start_typing(
+        &state,
+        parse_room_id(&signals.room_id.to_string())?,
+        user,
+    )
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+pub async fn mark_read(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    ReadSignals(signals): ReadSignals<ReadSignalsPayload>,
+) -> Result<axum::response::Response, crate::error::Error> {
+    request::set_tab_id(signals.tab_id.clone());
+    let user = auth_session
+        .user
         .as_ref()
         .ok_or(crate::error::Error::Internal)?;
 
-    let demo_user = ensure_demo_user(&state).await?;
-    let _ = state
+    let room_id = parse_room_id(&signals.room_id.to_string())?;
+    let user_id = chat_user_id_from_user_id(user.id.to_domain()?);
+    let message_id = parse_message_id(&signals.last_message_id.to_string())?;
+    let advanced = state
         .chat
-        .join_room(
-            app::chat::JoinRoom::builder()
-                .room_id(parse_room_id(&signals.room_id.to_string())?)
-                .user_id(chat_user_id_from_user_id(demo_user.id))
+        .mark_read(
+            app::chat::MarkRead::builder()
+                .room_id(room_id)
+                .user_id(user_id)
+                .message_id(message_id)
                 .build(),
         )
-        .await;
+        .await?;
+
+    if advanced {
+        crate::chat_demo::push_unread(&state, user_id).await?;
+        crate::chat_demo::push_seen_by(&state, room_id, user_id, message_id).await?;
+    }
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+pub async fn search_messages(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    ReadSignals(signals): ReadSignals<SearchSignals>,
+) -> crate::Result<axum::response::Response> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+
+    let query = signals
+        .search_query
+        .and_then(|value| app::chat::SearchQuery::try_new(value.to_string()).ok());
+    let hits = match &query {
+        Some(query) => {
+            state
+                .chat
+                .search_messages(
+                    app::chat::SearchMessages::builder()
+                        .user_id(chat_user_id_from_user_id(user.id.to_domain()?))
+                        .query(query.clone())
+                        .build(),
+                )
+                .await?
+        }
+        None => Vec::new(),
+    };
+
+    let partial = views::partials::ChatSearchResults::builder()
+        .maybe_query(query.map(|query| Text::from(query.to_string())))
+        .hits(hits.iter().map(search_hit_view).collect())
+        .build();
+
+    Ok((
+        StatusCode::OK,
+        axum::response::Html(partial.render().into_string()),
+    )
+        .into_response())
+}
 
-    let message = state
+pub async fn schedule_message(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    ReadSignals(signals): ReadSignals<ScheduleSignals>,
+) -> crate::Result<axum::response::Response> {
+    request::set_tab_id(signals.tab_id.clone());
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+
+    let send_at = signals
+        .schedule_at_ms
+        .and_then(|value| u64::try_from(value).ok())
+        .map(|value| std::time::UNIX_EPOCH + std::time::Duration::from_millis(value))
+        .ok_or(app::chat::Error::InvalidSchedule)?;
+    let room_id = parse_room_id(&signals.room_id.to_string())?;
+    let user_id = chat_user_id_from_user_id(user.id.to_domain()?);
+    state
         .chat
-        .post_message(
-            app::chat::PostMessage::builder()
+        .schedule_message(
+            app::chat::ScheduleMessage::builder()
+                .room_id(room_id)
+                .user_id(user_id)
+                .body(parse_message_body(&signals.schedule_body.to_string())?)
+                .send_at(send_at)
+                .build(),
+        )
+        .await?;
+
+    schedule_list_response(&state, room_id, user_id).await
+}
+
+pub async fn cancel_schedule(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    ReadSignals(signals): ReadSignals<CancelScheduleSignals>,
+) -> crate::Result<axum::response::Response> {
+    request::set_tab_id(signals.tab_id.clone());
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+
+    let schedule_id = signals
+        .schedule_id
+        .to_string()
+        .parse::<uuid::Uuid>()
+        .map(domain::chat::ScheduleId::from_uuid)
+        .map_err(|_| app::chat::Error::ScheduleNotFound)?;
+    let room_id = parse_room_id(&signals.room_id.to_string())?;
+    let user_id = chat_user_id_from_user_id(user.id.to_domain()?);
+    state
+        .chat
+        .cancel_schedule(
+            app::chat::CancelSchedule::builder()
+                .schedule_id(schedule_id)
+                .user_id(user_id)
+                .build(),
+        )
+        .await?;
+
+    schedule_list_response(&state, room_id, user_id).await
+}
+
+async fn schedule_list_response(
+    state: &crate::State,
+    room_id: domain::chat::RoomId,
+    user_id: domain::chat::UserId,
+) -> crate::Result<axum::response::Response> {
+    let partial = views::partials::ChatScheduleList::builder()
+        .schedules(crate::chat_demo::schedule_views(state, room_id, user_id).await?)
+        .build();
+
+    Ok((
+        StatusCode::OK,
+        axum::response::Html(partial.render().into_string()),
+    )
+        .into_response())
+}
+
+pub async fn pin_message(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    ReadSignals(signals): ReadSignals<PinSignals>,
+) -> crate::Result<axum::response::Response> {
+    request::set_tab_id(signals.tab_id.clone());
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+
+    let pins = state
+        .chat
+        .pin_message(
+            app::chat::PinMessage::builder()
+                .message_id(parse_message_id(&signals.pin_message_id.to_string())?)
+                .user_id(chat_user_id_from_user_id(user.id.to_domain()?))
+                .build(),
+        )
+        .await?;
+    crate::chat_demo::push_pins(&state, &pins).await;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+pub async fn unpin_message(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    ReadSignals(signals): ReadSignals<PinSignals>,
+) -> crate::Result<axum::response::Response> {
+    request::set_tab_id(signals.tab_id.clone());
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+
+    let pins = state
+        .chat
+        .unpin_message(
+            app::chat::UnpinMessage::builder()
+                .message_id(parse_message_id(&signals.pin_message_id.to_string())?)
+                .user_id(chat_user_id_from_user_id(user.id.to_domain()?))
+                .build(),
+        )
+        .await?;
+    crate::chat_demo::push_pins(&state, &pins).await;
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+pub async fn report_message(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    ReadSignals(signals): ReadSignals<ReportSignals>,
+) -> crate::Result<axum::response::Response> {
+    request::set_tab_id(signals.tab_id.clone());
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+    let category = signals
+        .report_category
+        .to_string()
+        .parse::<app::chat::ReportCategory>()
+        .map_err(|_| app::chat::Error::InvalidReport)?;
+
+    let outcome = state
+        .chat
+        .report_message(
+            app::chat::ReportMessage::builder()
+                .message_id(parse_message_id(&signals.report_message_id.to_string())?)
+                .reporter_id(chat_user_id_from_user_id(user.id.to_domain()?))
+                .category(category)
+                .build(),
+        )
+        .await?;
+    if outcome.hidden {
+        crate::chat_demo::push_message_status(
+            &state,
+            &crate::chat_demo::audience(&state, &outcome.message).await,
+            &outcome.message.id,
+            outcome.message.status,
+        );
+    }
+    crate::chat_demo::send_command_reply(
+        &state,
+        &app::chat::CommandReply::from("Thanks, a moderator will review this message."),
+    );
+
+    Ok(command_response())
+}
+
+pub async fn create_poll(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    ReadSignals(signals): ReadSignals<PollSignals>,
+) -> crate::Result<axum::response::Response> {
+    request::set_tab_id(signals.tab_id.clone());
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+
+    let options = signals
+        .poll_options
+        .to_string()
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(domain::chat::PollOptionLabel::try_new)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| app::chat::Error::InvalidPoll)?;
+    let closes_at = signals
+        .poll_closes_at_ms
+        .filter(|value| *value > 0)
+        .map(|value| {
+            u64::try_from(value)
+                .map(|value| std::time::UNIX_EPOCH + std::time::Duration::from_millis(value))
+                .map_err(|_| app::chat::Error::InvalidPoll)
+        })
+        .transpose()?;
+    let kind = if signals.poll_multiple {
+        domain::chat::PollKind::Multiple
+    } else {
+        domain::chat::PollKind::Single
+    };
+
+    let (message, results) = state
+        .chat
+        .create_poll(
+            app::chat::CreatePoll::builder()
                 .room_id(parse_room_id(&signals.room_id.to_string())?)
-                .user_id(chat_user_id_from_user_id(demo_user.id))
-                .body(parse_message_body(&signals.bot_body.to_string())?)
+                .user_id(chat_user_id_from_user_id(user.id.to_domain()?))
+                .question(parse_message_body(&signals.poll_question.to_string())?)
+                .options(options)
+                .kind(kind)
+                .maybe_closes_at(closes_at)
                 .build(),
         )
         .await?;
 
-    state.trace_log.record_sse_event(
-        request::current_context()
-            .and_then(|value| value.session_id)
-            .as_ref(),
-        crate::trace_log::TraceEntry::builder()
-            .timestamp(crate::trace_log::now_timestamp_short())
-            .level(crate::types::LogLevelText::new("INFO"))
-            .target(crate::types::LogTargetText::from(LogTargetKnown::DemoChat))
-            .message(crate::types::LogMessageText::from(
-                LogMessageKnown::ChatMessageIncoming,
-            ))
-            .fields(vec![
-                (
-                    crate::types::LogFieldName::from(LogFieldKey::Direction),
-                    crate::types::LogFieldValue::new("incoming"),
-                ),
-                (
-                    crate::types::LogFieldName::from(LogFieldKey::Sender),
-                    crate::types::LogFieldValue::new("demo"),
-                ),
-                (
-                    crate::types::LogFieldName::from(LogFieldKey::Receiver),
-                    crate::types::LogFieldValue::new("server"),
-                ),
-                (
-                    crate::types::LogFieldName::from(LogFieldKey::UserId),
-                    crate::types::LogFieldValue::new(
-                        demo_user.id.as_uuid().to_string(),
-                    ),
-                ),
-                (
-                    crate::types::LogFieldName::from(LogFieldKey::Body),
-                    crate::types::LogFieldValue::new(signals.bot_body.to_string()),
-                ),
-            ])
-            .build(),
+    crate::chat_demo::publish_read_state(&state, &message).await;
+    let mut view = message_view(&message, &user.username);
+    view.poll = Some(crate::chat_demo::poll_view(&results));
+    broadcast_message(
+        &state,
+        &crate::chat_demo::audience(&state, &message).await,
+        &view.render().into_string(),
+        Text::from(message.body.to_string()),
+        ChatSender::You,
+        crate::types::UserIdText::new(user.id.to_string()),
     );
 
-    let message_html = views::partials::ChatMessage::builder()
-        .message_id(crate::types::Text::from(message.id.as_uuid().to_string()))
-        .author(crate::types::Text::from(demo_user.username.to_string()))
-        .timestamp(crate::types::Text::from(crate::chat_demo::format_message_time(message.created_at)))
-        .body(crate::types::Text::from(message.body.to_string()))
-        .status(crate::types::Text::from(format!("{:?}", message.status)))
-        .build()
-        .render()
-        .into_string();
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+pub async fn vote_poll(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    ReadSignals(signals): ReadSignals<VoteSignals>,
+) -> crate::Result<axum::response::Response> {
+    request::set_tab_id(signals.tab_id.clone());
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+
+    let option_ids = signals
+        .poll_option_ids
+        .iter()
+        .map(|value| {
+            value
+                .to_string()
+                .parse::<uuid::Uuid>()
+                .map(domain::chat::PollOptionId::from_uuid)
+                .map_err(|_| app::chat::Error::InvalidPoll)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let results = state
+        .chat
+        .cast_vote(
+            app::chat::CastVote::builder()
+                .message_id(parse_message_id(&signals.poll_message_id.to_string())?)
+                .user_id(chat_user_id_from_user_id(user.id.to_domain()?))
+                .option_ids(option_ids)
+                .build(),
+        )
+        .await?;
+    crate::chat_demo::push_poll(&state, &results);
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+pub async fn close_poll(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    ReadSignals(signals): ReadSignals<VoteSignals>,
+) -> crate::Result<axum::response::Response> {
+    request::set_tab_id(signals.tab_id.clone());
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+
+    let results = state
+        .chat
+        .close_poll(
+            app::chat::ClosePoll::builder()
+                .message_id(parse_message_id(&signals.poll_message_id.to_string())?)
+                .user_id(chat_user_id_from_user_id(user.id.to_domain()?))
+                .build(),
+        )
+        .await?;
+    crate::chat_demo::push_poll(&state, &results);
+
+    Ok(StatusCode::NO_CONTENT.into_response())
+}
+
+/// Streams a room transcript as a download; rows are fetched page by page.
+pub async fn export_room(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    axum::extract::Query(params): axum::extract::Query<ExportParams>,
+) -> crate::Result<axum::response::Response> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+
+    let format = match &params.format {
+        Some(value) => value
+            .to_string()
+            .parse::<app::chat::ExportFormat>()
+            .map_err(|_| app::chat::Error::InvalidId(format!("unknown export format: {}", value).into()))?,
+        None => app::chat::ExportFormat::Json,
+    };
+    let mut export = state
+        .chat
+        .export_room(
+            app::chat::ExportRoom::builder()
+                .room_id(parse_room_id(&params.room_id.to_string())?)
+                .user_id(chat_user_id_from_user_id(user.id.to_domain()?))
+                .format(format)
+                .build(),
+        )
+        .await?;
+
+    let filename = format!(
+        "room-{}.{}",
+        export.room_id().as_uuid(),
+        format.extension()
+    );
+    let body = axum::body::Body::from_stream(async_stream::stream! {
+        loop {
+            match export.next_chunk().await {
+                Ok(Some(chunk)) => yield Ok(chunk),
+                Ok(None) => break,
+                Err(error) => {
+                    tracing::warn!(?error, "chat export aborted");
+                    yield Err(error);
+                    break;
+                }
+            }
+        }
+    });
+
+    Ok((
+        StatusCode::OK,
+        [
+            (axum::http::header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                axum::http::header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// Admin import of an NDJSON transcript posted as the raw request body.
+pub async fn import_room(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    axum::extract::Query(params): axum::extract::Query<ImportParams>,
+    transcript: String,
+) -> crate::Result<axum::Json<ImportResponse>> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+
+    let report = state
+        .chat
+        .import_transcript(
+            app::chat::ImportTranscript::builder()
+                .room_id(parse_room_id(&params.room_id.to_string())?)
+                .actor_id(chat_user_id_from_user_id(user.id.to_domain()?))
+                .transcript(app::chat::ImportSource::new(transcript))
+                .build(),
+        )
+        .await?;
+
+    Ok(axum::Json(ImportResponse::from(report)))
+}
+
+pub async fn retention(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    axum::extract::Query(params): axum::extract::Query<RetentionParams>,
+) -> crate::Result<axum::Json<RetentionResponse>> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+    let room_id = parse_room_id(&params.room_id.to_string())?;
+
+    retention_response(
+        &state,
+        room_id,
+        chat_user_id_from_user_id(user.id.to_domain()?),
+    )
+    .await
+    .map(axum::Json)
+}
+
+pub async fn set_retention(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    axum::Json(request): axum::Json<RetentionRequest>,
+) -> crate::Result<axum::Json<RetentionResponse>> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+    let room_id = parse_room_id(&request.room_id.to_string())?;
+    let user_id = chat_user_id_from_user_id(user.id.to_domain()?);
+
+    let invalid = |reason: String| app::chat::Error::InvalidId(reason.into());
+    let max_age = request
+        .max_age_days
+        .map(|value| {
+            app::chat::RetentionDays::try_new(value)
+                .map_err(|error| invalid(format!("max_age_days: {error}")))
+        })
+        .transpose()?;
+    let max_messages = request
+        .max_messages
+        .map(|value| {
+            app::chat::RetentionCount::try_new(value)
+                .map_err(|error| invalid(format!("max_messages: {error}")))
+        })
+        .transpose()?;
+    let mode = match &request.mode {
+        Some(value) => value
+            .to_string()
+            .parse::<app::chat::PurgeMode>()
+            .map_err(|_| invalid(format!("unknown purge mode: {value}")))?,
+        None => app::chat::PurgeMode::Delete,
+    };
+    let policy = (max_age.is_some() || max_messages.is_some()).then(|| {
+        app::chat::RetentionPolicy::builder()
+            .maybe_max_age(max_age)
+            .maybe_max_messages(max_messages)
+            .mode(mode)
+            .build()
+    });
+
+    state
+        .chat
+        .set_retention(
+            app::chat::SetRetention::builder()
+                .room_id(room_id)
+                .user_id(user_id)
+                .maybe_policy(policy)
+                .build(),
+        )
+        .await?;
+
+    retention_response(&state, room_id, user_id)
+        .await
+        .map(axum::Json)
+}
+
+async fn retention_response(
+    state: &crate::State,
+    room_id: domain::chat::RoomId,
+    user_id: domain::chat::UserId,
+) -> crate::Result<RetentionResponse> {
+    let preview = state
+        .chat
+        .preview_purge(
+            app::chat::PreviewPurge::builder()
+                .room_id(room_id)
+                .user_id(user_id)
+                .build(),
+        )
+        .await?;
+    let policy = state
+        .chat
+        .find_retention(&room_id)
+        .await?
+        .map(|retention| retention.policy);
+
+    Ok(RetentionResponse {
+        room_id: Text::from(room_id.as_uuid().to_string()),
+        max_age_days: policy
+            .as_ref()
+            .and_then(|policy| policy.max_age)
+            .map(|value| value.into_inner()),
+        max_messages: policy
+            .as_ref()
+            .and_then(|policy| policy.max_messages)
+            .map(|value| value.into_inner()),
+        mode: policy.map(|policy| Text::from(policy.mode.to_string())),
+        expired: preview.expired(),
+    })
+}
+
+pub async fn list_webhooks(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    axum::extract::Query(params): axum::extract::Query<WebhookListParams>,
+) -> crate::Result<axum::Json<Vec<WebhookResponse>>> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+
+    let webhooks = state
+        .chat
+        .list_webhooks(
+            app::chat::ListWebhooks::builder()
+                .room_id(parse_room_id(&params.room_id.to_string())?)
+                .user_id(chat_user_id_from_user_id(user.id.to_domain()?))
+                .build(),
+        )
+        .await?;
+
+    Ok(axum::Json(webhooks.into_iter().map(WebhookResponse::from).collect()))
+}
+
+pub async fn create_webhook(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    axum::Json(request): axum::Json<WebhookRequest>,
+) -> crate::Result<axum::Json<WebhookResponse>> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+    let url = domain::chat::WebhookUrl::try_new(request.url.to_string())
+        .map_err(|_| app::chat::Error::InvalidWebhook)?;
+    let secret = domain::chat::WebhookSecret::try_new(request.secret.to_string())
+        .map_err(|_| app::chat::Error::InvalidWebhook)?;
+    let events = request
+        .events
+        .iter()
+        .map(|event| {
+            event
+                .to_string()
+                .parse::<domain::chat::WebhookEvent>()
+                .map_err(|_| app::chat::Error::InvalidWebhook)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let webhook = state
+        .chat
+        .create_webhook(
+            app::chat::CreateWebhook::builder()
+                .room_id(parse_room_id(&request.room_id.to_string())?)
+                .user_id(chat_user_id_from_user_id(user.id.to_domain()?))
+                .url(url)
+                .events(events)
+                .secret(secret)
+                .build(),
+        )
+        .await?;
+
+    Ok(axum::Json(WebhookResponse::from(webhook)))
+}
+
+pub async fn delete_webhook(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    axum::Json(request): axum::Json<WebhookIdRequest>,
+) -> crate::Result<StatusCode> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+
+    state
+        .chat
+        .delete_webhook(
+            app::chat::DeleteWebhook::builder()
+                .webhook_id(parse_webhook_id(&request.webhook_id.to_string())?)
+                .user_id(chat_user_id_from_user_id(user.id.to_domain()?))
+                .build(),
+        )
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn webhook_deliveries(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    axum::extract::Query(params): axum::extract::Query<WebhookIdRequest>,
+) -> crate::Result<axum::Json<Vec<WebhookDeliveryResponse>>> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+
+    let deliveries = state
+        .chat
+        .list_webhook_deliveries(
+            app::chat::ListDeliveries::builder()
+                .webhook_id(parse_webhook_id(&params.webhook_id.to_string())?)
+                .user_id(chat_user_id_from_user_id(user.id.to_domain()?))
+                .build(),
+        )
+        .await?;
+
+    Ok(axum::Json(
+        deliveries
+            .into_iter()
+            .map(WebhookDeliveryResponse::from)
+            .collect(),
+    ))
+}
+
+pub async fn incoming_webhook(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    axum::extract::Query(params): axum::extract::Query<WebhookListParams>,
+) -> crate::Result<axum::Json<Option<IncomingWebhookResponse>>> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+
+    let webhook = state
+        .chat
+        .find_incoming_webhook(
+            app::chat::FindIncomingWebhook::builder()
+                .room_id(parse_room_id(&params.room_id.to_string())?)
+                .user_id(chat_user_id_from_user_id(user.id.to_domain()?))
+                .build(),
+        )
+        .await?;
+    let response = match webhook {
+        Some(webhook) => Some(incoming_webhook_response(&state, webhook).await?),
+        None => None,
+    };
+
+    Ok(axum::Json(response))
+}
+
+pub async fn regenerate_incoming_webhook(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    axum::Json(request): axum::Json<IncomingWebhookRequest>,
+) -> crate::Result<axum::Json<IncomingWebhookResponse>> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+    let bot_name = request
+        .bot_name
+        .map(|name| {
+            domain::user::Username::try_new(name.to_string())
+                .map_err(|_| app::chat::Error::InvalidWebhook)
+        })
+        .transpose()?;
+
+    let webhook = state
+        .chat
+        .regenerate_incoming_webhook(
+            app::chat::RegenerateIncomingWebhook::builder()
+                .room_id(parse_room_id(&request.room_id.to_string())?)
+                .user_id(chat_user_id_from_user_id(user.id.to_domain()?))
+                .maybe_bot_name(bot_name)
+                .build(),
+        )
+        .await?;
+
+    Ok(axum::Json(incoming_webhook_response(&state, webhook).await?))
+}
+
+pub async fn revoke_incoming_webhook(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    axum::Json(request): axum::Json<WebhookListParams>,
+) -> crate::Result<StatusCode> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+
+    state
+        .chat
+        .revoke_incoming_webhook(
+            app::chat::RevokeIncomingWebhook::builder()
+                .room_id(parse_room_id(&request.room_id.to_string())?)
+                .user_id(chat_user_id_from_user_id(user.id.to_domain()?))
+                .build(),
+        )
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// The public endpoint behind an incoming webhook URL. No session: the
+/// token in the path is the credential.
+pub async fn post_incoming(
+    Extension(state): Extension<crate::State>,
+    axum::extract::Path(token): axum::extract::Path<Text>,
+    axum::Json(request): axum::Json<IncomingPostRequest>,
+) -> crate::Result<(StatusCode, axum::Json<IncomingPostResponse>)> {
+    let token = domain::chat::IncomingWebhookToken::try_new(token.to_string())
+        .map_err(|_| app::chat::Error::WebhookNotFound)?;
+    let body = domain::chat::MessageBody::try_new(request.text.to_string())
+        .map_err(|error| app::chat::Error::Domain(error.into()))?;
+
+    let post = state
+        .chat
+        .post_incoming(
+            app::chat::PostIncoming::builder()
+                .token(token)
+                .body(body)
+                .build(),
+        )
+        .await?;
+    crate::chat_demo::publish_bot_post(&state, &post).await;
+    let response = IncomingPostResponse {
+        message_id: Text::from(post.message.id.as_uuid().to_string()),
+        status: Text::from(format!("{:?}", post.message.status).to_lowercase()),
+    };
+    crate::chat_demo::spawn_unfurl(&state, post.message.clone());
+    crate::chat_demo::spawn_bot_replies(&state, post.message);
+
+    Ok((StatusCode::CREATED, axum::Json(response)))
+}
+
+async fn incoming_webhook_response(
+    state: &crate::State,
+    webhook: domain::chat::IncomingWebhook,
+) -> crate::Result<IncomingWebhookResponse> {
+    let bot = state
+        .chat
+        .author_name(&webhook.bot_id)
+        .await?
+        .map(|name| Text::from(name.to_string()))
+        .unwrap_or_else(|| Text::from("unknown"));
+
+    Ok(IncomingWebhookResponse {
+        room_id: Text::from(webhook.room_id.as_uuid().to_string()),
+        url: Text::from(
+            crate::paths::Route::IncomingHook
+                .as_str()
+                .replace("{token}", webhook.token.as_ref()),
+        ),
+        bot,
+        created_at_ms: epoch_ms(webhook.created_at),
+    })
+}
+
+/// Multipart upload with `room_id`, an optional `body` caption and up to
+/// four `file` parts.
+pub async fn post_attachments(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    mut multipart: axum::extract::Multipart,
+) -> Result<axum::response::Response, crate::error::Error> {
+    let user = auth_session
+        .user
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+
+    let mut room_id = None;
+    let mut body = None;
+    let mut files = Vec::new();
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|_| app::chat::Error::InvalidAttachment)?
+    {
+        match field.name() {
+            Some("room_id") => {
+                let value = field
+                    .text()
+                    .await
+                    .map_err(|_| app::chat::Error::InvalidAttachment)?;
+                room_id = Some(parse_room_id(&value)?);
+            }
+            Some("body") => {
+                let value = field
+                    .text()
+                    .await
+                    .map_err(|_| app::chat::Error::InvalidAttachment)?;
+                if !value.trim().is_empty() {
+                    body = Some(parse_message_body(&value)?);
+                }
+            }
+            Some("file") => {
+                let name = field.file_name().map(ToString::to_string).unwrap_or_default();
+                let bytes = field
+                    .bytes()
+                    .await
+                    .map_err(|_| app::chat::Error::InvalidAttachment)?;
+                // Browsers send an empty part when no file was picked.
+                if name.is_empty() && bytes.is_empty() {
+                    continue;
+                }
+                if files.len() == app::chat::MAX_ATTACHMENTS_PER_MESSAGE {
+                    return Err(app::chat::Error::InvalidAttachment.into());
+                }
+                files.push(
+                    app::chat::AttachmentUpload::builder()
+                        .name(
+                            domain::chat::AttachmentName::try_new(name)
+                                .map_err(|_| app::chat::Error::InvalidAttachment)?,
+                        )
+                        .bytes(bytes.to_vec())
+                        .build(),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    let posted = state
+        .chat
+        .post_attachments(
+            app::chat::PostAttachments::builder()
+                .room_id(room_id.ok_or(app::chat::Error::InvalidAttachment)?)
+                .user_id(chat_user_id_from_user_id(user.id.to_domain()?))
+                .maybe_body(body)
+                .files(files)
+                .build(),
+        )
+        .await?;
+    let message = posted.message;
+
+    crate::chat_demo::broadcast_typing_stopped(&state, &message.room_id, &message.user_id);
+    crate::chat_demo::publish_read_state(&state, &message).await;
+    let mut view = message_view(&message, &user.username);
+    view.attachments = posted
+        .attachments
+        .iter()
+        .map(crate::chat_demo::attachment_view)
+        .collect();
+    let message_html = view.render().into_string();
     broadcast_message(
         &state,
+        &crate::chat_demo::audience(&state, &message).await,
         &message_html,
         Text::from(message.body.to_string()),
-        ChatSender::Demo,
-        crate::types::UserIdText::new(demo_user.id.as_uuid().to_string()),
+        ChatSender::You,
+        crate::types::UserIdText::new(user.id.to_string()),
     );
+    crate::chat_demo::spawn_unfurl(&state, message.clone());
+    crate::chat_demo::spawn_bot_replies(&state, message);
 
     let response = match crate::request::current_kind() {
         crate::request::Kind::Datastar => (
@@ -296,61 +1682,172 @@ pub async fn post_demo_chat_message(
             axum::response::Html(message_html),
         )
             .into_response(),
-        crate::request::Kind::Page => {
-            let target = format!(
-                "{}#{}",
-                Route::Home.as_str(),
-                crate::views::partials::ChatDemoSection::ANCHOR_ID
-            );
-            axum::response::Redirect::to(target.as_str()).into_response()
-        }
+        crate::request::Kind::Page => chat_redirect(),
     };
 
     Ok(response)
 }
 
-async fn ensure_demo_user(
+pub async fn get_attachment(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    axum::extract::Path(attachment_id): axum::extract::Path<Text>,
+) -> crate::Result<axum::response::Response> {
+    open_attachment(
+        &state,
+        &auth_session,
+        &attachment_id,
+        app::chat::AttachmentVariant::Original,
+    )
+    .await
+}
+
+pub async fn get_attachment_thumbnail(
+    Extension(state): Extension<crate::State>,
+    auth_session: crate::auth::Session,
+    axum::extract::Path(attachment_id): axum::extract::Path<Text>,
+) -> crate::Result<axum::response::Response> {
+    open_attachment(
+        &state,
+        &auth_session,
+        &attachment_id,
+        app::chat::AttachmentVariant::Thumbnail,
+    )
+    .await
+}
+
+/// Images are shown inline; everything else downloads. The type is the
+/// sniffed one and `nosniff` keeps browsers from second-guessing it.
+async fn open_attachment(
     state: &crate::State,
-) -> Result<domain::user::User, crate::error::Error> {
-    let demo_email = domain::user::Email::try_new(DEMO_USER_EMAIL)
-        .map_err(|_| crate::error::Error::Internal)?;
-    let demo_username = domain::user::Username::try_new(DEMO_USER_NAME)
-        .map_err(|_| crate::error::Error::Internal)?;
-    if let Some(user) = state
+    auth_session: &crate::auth::Session,
+    attachment_id: &Text,
+    variant: app::chat::AttachmentVariant,
+) -> crate::Result<axum::response::Response> {
+    let user = auth_session
         .user
-        .find_by_email(demo_email.clone())
-        .await?
-    {
-        return Ok(user);
-    }
+        .as_ref()
+        .ok_or(crate::error::Error::Internal)?;
+    let attachment_id = attachment_id
+        .to_string()
+        .parse::<uuid::Uuid>()
+        .map_err(|_| app::chat::Error::AttachmentNotFound)?;
 
-    let password = secrecy::SecretString::new(
-        uuid::Uuid::new_v4().to_string().into(),
-    );
-    match state
-        .user
-        .register_user(
-            app::user::RegisterUser::builder()
-                .username(demo_username)
-                .email(demo_email.clone())
-                .password(password)
+    let download = state
+        .chat
+        .open_attachment(
+            app::chat::OpenAttachment::builder()
+                .attachment_id(domain::chat::AttachmentId::from_uuid(attachment_id))
+                .user_id(chat_user_id_from_user_id(user.id.to_domain()?))
+                .variant(variant)
                 .build(),
         )
-        .await
-    {
-        Ok(_) | Err(app::user::Error::EmailTaken) => {}
-        Err(error) => return Err(error.into()),
-    }
+        .await?;
 
-    state
-        .user
-        .find_by_email(demo_email)
-        .await?
-        .ok_or(crate::error::Error::Internal)
+    let content_type = match download.content_type {
+        domain::chat::AttachmentType::Text => "text/plain; charset=utf-8".to_string(),
+        content_type => content_type.to_string(),
+    };
+    let disposition = if download.content_type.is_image() {
+        "inline"
+    } else {
+        "attachment"
+    };
+    let filename = download
+        .attachment
+        .name
+        .to_string()
+        .chars()
+        .map(|c| match c {
+            '"' | '\\' => '_',
+            c if c.is_ascii() => c,
+            _ => '_',
+        })
+        .collect::<String>();
+
+    Ok((
+        StatusCode::OK,
+        [
+            (axum::http::header::CONTENT_TYPE, content_type),
+            (
+                axum::http::header::CONTENT_DISPOSITION,
+                format!("{}; filename=\"{}\"", disposition, filename),
+            ),
+            (
+                axum::http::header::X_CONTENT_TYPE_OPTIONS,
+                "nosniff".to_string(),
+            ),
+            (
+                axum::http::header::CACHE_CONTROL,
+                "private, max-age=3600".to_string(),
+            ),
+        ],
+        download.bytes,
+    )
+        .into_response())
+}
+
+fn parse_webhook_id(
+    value: &str,
+) -> Result<domain::chat::WebhookId, crate::error::Error> {
+    let id = value
+        .parse::<uuid::Uuid>()
+        .map_err(|_| app::chat::Error::WebhookNotFound)?;
+    Ok(domain::chat::WebhookId::from_uuid(id))
+}
+
+fn epoch_ms(value: std::time::SystemTime) -> u64 {
+    value
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|value| u64::try_from(value.as_millis()).unwrap_or(u64::MAX))
+        .unwrap_or_default()
+}
+
+fn search_hit_view(hit: &app::chat::SearchHit) -> views::partials::ChatSearchHit {
+    views::partials::ChatSearchHit::builder()
+        .message_id(Text::from(hit.message.id.as_uuid().to_string()))
+        .room_name(Text::from(hit.room_name.to_string()))
+        .timestamp(Text::from(crate::chat_demo::format_message_time(hit.message.created_at)))
+        .excerpt(
+            hit.highlight
+                .segments()
+                .into_iter()
+                .map(|(text, matched)| {
+                    views::partials::ChatSearchSegment::builder()
+                        .text(Text::from(text))
+                        .matched(matched)
+                        .build()
+                })
+                .collect(),
+        )
+        .build()
+}
+
+pub(crate) fn render_message(
+    message: &domain::chat::Message,
+    author: &domain::user::Username,
+) -> String {
+    message_view(message, author).render().into_string()
+}
+
+fn message_view(
+    message: &domain::chat::Message,
+    author: &domain::user::Username,
+) -> views::partials::ChatMessage {
+    views::partials::ChatMessage::builder()
+        .message_id(crate::types::Text::from(message.id.as_uuid().to_string()))
+        .author(crate::types::Text::from(author.to_string()))
+        .timestamp(crate::types::Text::from(crate::chat_demo::format_message_time(message.created_at)))
+        .body(crate::types::Text::from(message.body.to_string()))
+        .status(crate::types::Text::from(format!("{:?}", message.status)))
+        .build()
 }
 
-fn broadcast_message(
+/// Appends the message on every stream, or only on the audience's streams
+/// when it is quarantined.
+pub(crate) fn broadcast_message(
     state: &crate::State,
+    audience: &app::chat::Audience,
     message_html: &str,
     body: Text,
     sender: ChatSender,
@@ -367,14 +1864,10 @@ fn broadcast_message(
         mode = "append",
         payload_bytes = message_html.len() as u64
     );
-    let _ = state
-        .sse
-        .broadcast(crate::sse::Event::from_event(event));
+    crate::chat_demo::send_to_audience(state, audience, crate::sse::Event::from_event(event));
 
-    let session_id = request::current_context()
-        .and_then(|value| value.session_id);
     state.trace_log.record_sse_event(
-        session_id.as_ref(),
+        request::current_sse_target().as_ref(),
         crate::trace_log::TraceEntry::builder()
             .timestamp(crate::trace_log::now_timestamp_short())
             .level(crate::types::LogLevelText::new("INFO"))
@@ -421,21 +1914,25 @@ fn broadcast_message(
 }
 
 #[derive(Clone, Copy, Debug)]
-enum ChatSender {
+pub(crate) enum ChatSender {
     You,
-    Demo,
+    Bot,
+    Socket,
+    Schedule,
 }
 
 impl ChatSender {
     fn as_str(self) -> &'static str {
         match self {
             ChatSender::You => "you",
-            ChatSender::Demo => "demo",
+            ChatSender::Bot => "bot",
+            ChatSender::Socket => "socket",
+            ChatSender::Schedule => "schedule",
         }
     }
 }
 
-fn parse_room_id(
+pub(crate) fn parse_room_id(
     value: &str,
 ) -> Result<domain::chat::RoomId, crate::error::Error> {
     let id = value
@@ -453,7 +1950,7 @@ fn parse_message_id(
     Ok(domain::chat::MessageId::from_uuid(id))
 }
 
-fn parse_message_body(
+pub(crate) fn parse_message_body(
     value: &str,
 ) -> Result<domain::chat::MessageBody, crate::error::Error> {
     domain::chat::MessageBody::try_new(value)
@@ -471,7 +1968,7 @@ fn parse_reason(
         .transpose()
 }
 
-fn chat_user_id_from_user_id(
+pub(crate) fn chat_user_id_from_user_id(
     value: domain::user::Id,
 ) -> domain::chat::UserId {
     domain::chat::UserId::from_uuid(*value.as_uuid())
@@ -6,6 +6,16 @@ pub use partials::{
     ping_partial, request_meta_partial, session_status_partial,
 };
 pub use chat::{
-    chat_page, post_chat_message, post_demo_chat_message, moderation_page,
-    moderate_message,
+    chat_page, post_chat_message, moderation_page,
+    moderate_message, claim_moderation, release_moderation, appeals_page, file_appeal,
+    decide_appeal, post_typing, mark_read, search_messages, export_room, import_room, retention, set_retention,
+    schedule_message, cancel_schedule, pin_message, unpin_message, report_message,
+    create_poll, vote_poll, close_poll,
+    list_webhooks, create_webhook, delete_webhook, webhook_deliveries, incoming_webhook,
+    regenerate_incoming_webhook, revoke_incoming_webhook, post_incoming,
+    post_attachments, get_attachment, get_attachment_thumbnail,
+};
+pub(crate) use chat::{
+    ChatSender, broadcast_message, chat_user_id_from_user_id, parse_message_body,
+    parse_room_id, render_message,
 };
@@ -2,12 +2,22 @@ mod auth;
 mod demo;
 mod pages;
 mod sse;
+mod ws;
 
 pub use auth::{login, login_form, logout, protected, register, register_form};
 pub use pages::{error_test, health, home};
 pub use demo::{
     auth_status_partial, boundary_check_partial, db_check_partial, ping_partial,
     request_meta_partial, session_status_partial, chat_page, post_chat_message,
-    post_demo_chat_message, moderation_page, moderate_message,
+    moderation_page, moderate_message, claim_moderation, release_moderation, appeals_page, file_appeal,
+    decide_appeal, post_typing,
+    mark_read, search_messages, export_room, import_room, retention, set_retention,
+    schedule_message, cancel_schedule, pin_message, unpin_message, report_message,
+    create_poll, vote_poll, close_poll,
+    list_webhooks, create_webhook, delete_webhook, webhook_deliveries, incoming_webhook,
+    regenerate_incoming_webhook, revoke_incoming_webhook, post_incoming,
+    post_attachments, get_attachment, get_attachment_thumbnail,
 };
+pub(crate) use demo::{ChatSender, broadcast_message, render_message};
 pub use sse::{events, surreal_message_cancel, surreal_message_guarded};
+pub use ws::socket;
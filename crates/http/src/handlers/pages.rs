@@ -30,6 +30,18 @@ pub async fn home(
                     context.room.name.to_string(),
                 ))
                 .messages(context.messages)
+                .online(crate::chat_demo::online_members(&state, &context.room.id))
+                .rooms(context.rooms)
+                .seen_by(context.seen_by)
+                .schedules(context.schedules)
+                .pins(context.pins)
+                .can_pin(context.can_pin)
+                .user_id(crate::types::Text::from(user.id.to_string()))
+                .maybe_topic(
+                    context
+                        .topic
+                        .map(|topic| crate::types::Text::from(topic.to_string())),
+                )
                 .build(),
         )
     } else {
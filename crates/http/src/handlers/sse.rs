@@ -1,7 +1,7 @@
 use async_stream::stream;
 use axum::{
     extract::Extension,
-    response::Sse,
+    response::{IntoResponse, Sse, sse::KeepAlive},
 };
 use core::convert::Infallible;
 use datastar::axum::ReadSignals;
@@ -10,7 +10,7 @@ use tokio::sync::broadcast::error::RecvError;
 use tokio::time::{Duration, sleep};
 use tower_cookies::Cookies;
 
-use crate::types::{SessionId, Text};
+use crate::types::{TabId, Text};
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -18,6 +18,14 @@ pub(crate) struct SurrealSignals {
     surreal_message: Option<Text>,
     original_surreal_message: Option<Text>,
     _surreal_status: Option<Text>,
+    tab_id: Option<TabId>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TabSignals {
+    tab_id: Option<TabId>,
+    room_id: Option<Text>,
 }
 
 fn surreal_payload(
@@ -57,8 +65,8 @@ pub async fn surreal_message_guarded(
     Extension(cookies): Extension<Cookies>,
     ReadSignals(signals): ReadSignals<SurrealSignals>,
 ) -> impl axum::response::IntoResponse {
-    let session =
-        crate::sse::Handle::from_cookies(&cookies, &state.cookie_key);
+    let session = crate::sse::Handle::from_cookies(&cookies, &state.cookie_key)
+        .with_tab(signals.tab_id.clone());
     let session_id = session.id();
     let sequence = state
         .demo
@@ -129,8 +137,8 @@ pub async fn surreal_message_cancel(
     Extension(cookies): Extension<Cookies>,
     ReadSignals(signals): ReadSignals<SurrealSignals>,
 ) -> impl axum::response::IntoResponse {
-    let session =
-        crate::sse::Handle::from_cookies(&cookies, &state.cookie_key);
+    let session = crate::sse::Handle::from_cookies(&cookies, &state.cookie_key)
+        .with_tab(signals.tab_id.clone());
     let session_id = session.id();
     let sequence = state
         .demo
@@ -186,24 +194,47 @@ pub async fn surreal_message_cancel(
 pub async fn events(
     Extension(state): Extension<crate::State>,
     Extension(cookies): Extension<Cookies>,
-) -> impl axum::response::IntoResponse {
-    // TODO: Support per-tab SSE streams by mixing a tab id into the session key.
-    let session =
-        crate::sse::Handle::from_cookies(&cookies, &state.cookie_key);
+    auth_session: crate::auth::Session,
+    signals: Option<ReadSignals<TabSignals>>,
+) -> axum::response::Response {
+    let (tab_id, room_id) = signals
+        .map(|ReadSignals(signals)| (signals.tab_id, signals.room_id))
+        .unwrap_or_default();
+    let user = auth_session.user;
+    let session = crate::sse::Handle::from_cookies(&cookies, &state.cookie_key)
+        .with_tab(tab_id)
+        .with_user(user.as_ref().map(|user| user.id.clone()));
     let session_id = session.id();
-    let (mut receiver, guard) = state.sse.subscribe(&session);
-    let trace_guard = TraceLogGuard::new(state.trace_log.clone(), session_id.clone());
+    let (mut receiver, guard) = match state.sse.subscribe(&session) {
+        Ok(subscription) => subscription,
+        Err(error) => {
+            tracing::warn!(session_id = %session_id, ?error, "sse connection rejected");
+            return stream_limit_response(error);
+        }
+    };
+    let stream_target = crate::sse::Target::from(guard.key().clone());
+    let trace_guard = TraceLogGuard::new(state.trace_log.clone(), stream_target.clone());
 
-    tracing::info!(session_id = %session_id, "sse connected");
+    tracing::info!(
+        session_id = %session_id,
+        tab_id = %guard.key().tab_id,
+        "sse connected"
+    );
     let _ = state
         .sse
-        .send(&session, crate::sse::Event::patch_signals(serde_json::json!({
+        .send_to(&stream_target, crate::sse::Event::patch_signals(serde_json::json!({
             "sseConnected": true
         })));
 
+    let presence_guard = match (user.as_ref(), room_id) {
+        (Some(user), Some(room_id)) => join_presence(&state, user, &room_id).await,
+        _ => None,
+    };
+
     let stream = stream! {
         let _guard = guard;
         let _trace_guard = trace_guard;
+        let _presence_guard = presence_guard;
         loop {
             match receiver.recv().await {
                 Ok(event) => {
@@ -220,27 +251,83 @@ pub async fn events(
     };
 
     Sse::new(stream)
+        .keep_alive(KeepAlive::new().interval(state.sse.limits().heartbeat))
+        .into_response()
+}
+
+fn stream_limit_response(error: crate::sse::SubscribeError) -> axum::response::Response {
+    let message = match error {
+        crate::sse::SubscribeError::SessionLimit => {
+            "Too many open tabs for this session. Close one and reload."
+        }
+        crate::sse::SubscribeError::GlobalLimit => {
+            "The server is at its live connection limit. Try again shortly."
+        }
+    };
+    (
+        axum::http::StatusCode::TOO_MANY_REQUESTS,
+        [(axum::http::header::RETRY_AFTER, "30")],
+        message,
+    )
+        .into_response()
 }
 
+async fn join_presence(
+    state: &crate::State,
+    user: &crate::auth::User,
+    room_id: &Text,
+) -> Option<PresenceGuard> {
+    let room_id = domain::chat::RoomId::from_uuid(room_id.to_string().parse().ok()?);
+    let user_id = domain::chat::UserId::from_uuid(*user.id.to_domain().ok()?.as_uuid());
+    if let Err(error) = state.chat.check_membership(&room_id, &user_id).await {
+        tracing::debug!(?error, "sse presence skipped");
+        return None;
+    }
+
+    if let Some(change) = state
+        .presence
+        .connect(room_id, user_id, user.username.clone())
+    {
+        crate::chat_demo::broadcast_presence(state, &change);
+    }
+    Some(PresenceGuard {
+        presence: state.presence.clone(),
+        room_id,
+        user_id,
+    })
+}
+
+struct PresenceGuard {
+    presence: crate::presence::Presence,
+    room_id: domain::chat::RoomId,
+    user_id: domain::chat::UserId,
+}
+
+impl Drop for PresenceGuard {
+    fn drop(&mut self) {
+        self.presence
+            .disconnect(self.room_id, self.user_id, std::time::Instant::now());
+    }
+}
+
+/// Clears the closing tab's trace buffer. The session-wide buffer stays,
+/// since the session's other tabs still read it.
 struct TraceLogGuard {
     store: crate::trace_log::TraceLogStore,
-    session_id: SessionId,
+    target: crate::sse::Target,
 }
 
 impl TraceLogGuard {
     fn new(
         store: crate::trace_log::TraceLogStore,
-        session_id: SessionId,
+        target: crate::sse::Target,
     ) -> Self {
-        Self {
-            store,
-            session_id,
-        }
+        Self { store, target }
     }
 }
 
 impl Drop for TraceLogGuard {
     fn drop(&mut self) {
-        self.store.clear_session(&self.session_id);
+        self.store.clear_session(&self.target);
     }
 }
@@ -0,0 +1,302 @@
+use axum::{
+    extract::{
+        Extension,
+        ws::{Message, WebSocket, WebSocketUpgrade},
+    },
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tower_cookies::Cookies;
+
+use super::demo::{
+    ChatSender, broadcast_message, chat_user_id_from_user_id, parse_message_body,
+    parse_room_id, render_message,
+};
+use crate::types::{TabId, Text};
+
+/// Frames sent by socket clients. `type` selects the variant.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientFrame {
+    Post { room_id: Text, body: Text },
+    Typing { room_id: Text },
+}
+
+/// Frames sent to socket clients. Registry events are forwarded as
+/// `event` frames carrying the same name and data lines as the SSE stream.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerFrame {
+    Connected { tab_id: TabId },
+    Event { event: Text, data: Vec<Text> },
+    Posted { message_id: Text },
+    /// Slash-command output for this connection only.
+    Reply { text: Text },
+    Error { code: FrameError },
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameError {
+    InvalidFrame,
+    InvalidInput,
+    RateLimited,
+    NotMember,
+    Forbidden,
+    NotFound,
+    Internal,
+}
+
+impl From<&crate::error::Error> for FrameError {
+    fn from(value: &crate::error::Error) -> Self {
+        match value {
+            crate::error::Error::Chat(app::chat::Error::RateLimited)
+            | crate::error::Error::Chat(app::chat::Error::Flooded) => {
+                FrameError::RateLimited
+            }
+            crate::error::Error::Chat(app::chat::Error::NotMember) => {
+                FrameError::NotMember
+            }
+            crate::error::Error::Chat(app::chat::Error::Forbidden)
+            | crate::error::Error::Chat(app::chat::Error::Muted) => {
+                FrameError::Forbidden
+            }
+            crate::error::Error::Chat(app::chat::Error::RoomNotFound)
+            | crate::error::Error::Chat(app::chat::Error::MessageNotFound)
+            | crate::error::Error::Chat(app::chat::Error::ScheduleNotFound)
+            | crate::error::Error::Chat(app::chat::Error::PollNotFound)
+            | crate::error::Error::Chat(app::chat::Error::WebhookNotFound)
+            | crate::error::Error::Chat(app::chat::Error::AttachmentNotFound) => {
+                FrameError::NotFound
+            }
+            crate::error::Error::Chat(app::chat::Error::InvalidId(_))
+            | crate::error::Error::Chat(app::chat::Error::InvalidSchedule)
+            | crate::error::Error::Chat(app::chat::Error::PinLimitReached)
+            | crate::error::Error::Chat(app::chat::Error::InvalidPoll)
+            | crate::error::Error::Chat(app::chat::Error::PollClosed)
+            | crate::error::Error::Chat(app::chat::Error::AlreadyVoted)
+            | crate::error::Error::Chat(app::chat::Error::InvalidWebhook)
+            | crate::error::Error::Chat(app::chat::Error::BotNameTaken)
+            | crate::error::Error::Chat(app::chat::Error::InvalidAttachment)
+            | crate::error::Error::Chat(app::chat::Error::InvalidReport)
+            | crate::error::Error::Chat(app::chat::Error::ModerationConflict)
+            | crate::error::Error::Chat(app::chat::Error::InvalidAppeal)
+            | crate::error::Error::Chat(app::chat::Error::Domain(_)) => {
+                FrameError::InvalidInput
+            }
+            _ => FrameError::Internal,
+        }
+    }
+}
+
+pub async fn socket(
+    Extension(state): Extension<crate::State>,
+    Extension(cookies): Extension<Cookies>,
+    auth_session: crate::auth::Session,
+    upgrade: WebSocketUpgrade,
+) -> crate::Result<axum::response::Response> {
+    let user = auth_session
+        .user
+        .ok_or(crate::error::Error::Unauthenticated)?;
+    let handle = crate::sse::Handle::from_cookies(&cookies, &state.cookie_key)
+        .with_tab(Some(TabId::generate()))
+        .with_user(Some(user.id.clone()));
+    let (receiver, guard) = match state.sse.subscribe(&handle) {
+        Ok(subscription) => subscription,
+        Err(error) => {
+            tracing::warn!(session_id = %handle.id(), ?error, "ws connection rejected");
+            return Ok(axum::http::StatusCode::TOO_MANY_REQUESTS.into_response());
+        }
+    };
+
+    Ok(upgrade
+        .on_upgrade(move |socket| async move {
+            let _guard = guard;
+            run_socket(state, user, handle, socket, receiver).await;
+        })
+        .into_response())
+}
+
+async fn run_socket(
+    state: crate::State,
+    user: crate::auth::User,
+    handle: crate::sse::Handle,
+    mut socket: WebSocket,
+    mut receiver: broadcast::Receiver<crate::sse::Event>,
+) {
+    tracing::info!(session_id = %handle.id(), user_id = %user.id, "ws connected");
+    if let Some(tab_id) = handle.tab()
+        && send_frame(&mut socket, &ServerFrame::Connected { tab_id })
+            .await
+            .is_err()
+    {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            event = receiver.recv() => match event {
+                Ok(event) => {
+                    let frame = ServerFrame::Event {
+                        event: Text::from(event.name()),
+                        data: event.data().iter().map(|line| Text::from(line.as_str())).collect(),
+                    };
+                    if send_frame(&mut socket, &frame).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let reply = handle_frame(&state, &user, text.as_str()).await;
+                    if let Some(reply) = reply
+                        && send_frame(&mut socket, &reply).await.is_err()
+                    {
+                        break;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+        }
+    }
+
+    tracing::info!(session_id = %handle.id(), user_id = %user.id, "ws disconnected");
+}
+
+async fn handle_frame(
+    state: &crate::State,
+    user: &crate::auth::User,
+    text: &str,
+) -> Option<ServerFrame> {
+    let frame = match serde_json::from_str::<ClientFrame>(text) {
+        Ok(frame) => frame,
+        Err(_) => {
+            return Some(ServerFrame::Error {
+                code: FrameError::InvalidFrame,
+            });
+        }
+    };
+
+    let result = match frame {
+        ClientFrame::Post { room_id, body } => {
+            post_message(state, user, &room_id, &body).await.map(Some)
+        }
+        ClientFrame::Typing { room_id } => {
+            start_typing(state, user, &room_id).await.map(|()| None)
+        }
+    };
+
+    match result {
+        Ok(reply) => reply,
+        Err(error) => {
+            tracing::warn!(?error, user_id = %user.id, "ws frame failed");
+            Some(ServerFrame::Error {
+                code: FrameError::from(&error),
+            })
+        }
+    }
+}
+
+async fn post_message(
+    state: &crate::State,
+    user: &crate::auth::User,
+    room_id: &Text,
+    body: &Text,
+) -> crate::Result<ServerFrame> {
+    let outcome = state
+        .chat
+        .handle_input(
+            app::chat::PostMessage::builder()
+                .room_id(parse_room_id(&room_id.to_string())?)
+                .user_id(chat_user_id_from_user_id(user.id.to_domain()?))
+                .body(parse_message_body(&body.to_string())?)
+                .build(),
+        )
+        .await?;
+    let message = match outcome {
+        app::chat::CommandOutcome::Posted(message) => message,
+        app::chat::CommandOutcome::Reply(reply) => {
+            return Ok(ServerFrame::Reply {
+                text: Text::from(reply.to_string()),
+            });
+        }
+        app::chat::CommandOutcome::TopicChanged {
+            room_id,
+            topic,
+            reply,
+        } => {
+            crate::chat_demo::push_topic(state, &room_id, topic.as_ref());
+            return Ok(ServerFrame::Reply {
+                text: Text::from(reply.to_string()),
+            });
+        }
+    };
+
+    crate::chat_demo::broadcast_typing_stopped(state, &message.room_id, &message.user_id);
+    crate::chat_demo::publish_read_state(state, &message).await;
+    broadcast_message(
+        state,
+        &crate::chat_demo::audience(state, &message).await,
+        &render_message(&message, &user.username),
+        Text::from(message.body.to_string()),
+        ChatSender::Socket,
+        crate::types::UserIdText::new(user.id.to_string()),
+    );
+    let message_id = Text::from(message.id.as_uuid().to_string());
+    crate::chat_demo::spawn_unfurl(state, message.clone());
+    crate::chat_demo::spawn_bot_replies(state, message);
+
+    Ok(ServerFrame::Posted { message_id })
+}
+
+async fn start_typing(
+    state: &crate::State,
+    user: &crate::auth::User,
+    room_id: &Text,
+) -> crate::Result<()> {
+    crate::chat_demo::start_typing(state, parse_room_id(&room_id.to_string())?, user).await
+}
+
+async fn send_frame(
+    socket: &mut WebSocket,
+    frame: &ServerFrame,
+) -> Result<(), axum::Error> {
+    let payload = serde_json::to_string(frame).map_err(axum::Error::new)?;
+    socket.send(Message::Text(payload.into())).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tagged_client_frames() {
+        let frame = serde_json::from_str::<ClientFrame>(
+            r#"{"type":"post","room_id":"r1","body":"hello"}"#,
+        )
+        .expect("frame");
+
+        assert!(matches!(
+            frame,
+            ClientFrame::Post { ref body, .. } if body == &Text::from("hello")
+        ));
+    }
+
+    #[test]
+    fn serializes_error_frames_with_snake_case_codes() {
+        let frame = ServerFrame::Error {
+            code: FrameError::RateLimited,
+        };
+
+        let json = serde_json::to_value(&frame).expect("json");
+
+        assert_eq!(
+            json,
+            serde_json::json!({ "type": "error", "code": "rate_limited" })
+        );
+    }
+}
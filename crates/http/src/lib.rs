@@ -2,6 +2,7 @@ mod auth;
 pub mod chat_demo;
 mod error;
 mod handlers;
+pub mod presence;
 pub mod request;
 pub mod sse;
 mod trace;
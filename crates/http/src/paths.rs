@@ -18,12 +18,66 @@ pub enum Route {
     Chat,
     #[strum(serialize = "/demo/chat/messages")]
     ChatMessages,
-    #[strum(serialize = "/demo/chat/messages/demo")]
-    ChatMessagesDemo,
     #[strum(serialize = "/demo/chat/moderation")]
     ChatModeration,
+    #[strum(serialize = "/demo/chat/moderation/claim")]
+    ChatModerationClaim,
+    #[strum(serialize = "/demo/chat/moderation/release")]
+    ChatModerationRelease,
+    #[strum(serialize = "/demo/chat/appeals")]
+    ChatAppeals,
+    #[strum(serialize = "/demo/chat/appeals/decide")]
+    ChatAppealDecide,
+    #[strum(serialize = "/demo/chat/typing")]
+    ChatTyping,
+    #[strum(serialize = "/demo/chat/read")]
+    ChatRead,
+    #[strum(serialize = "/demo/chat/search")]
+    ChatSearch,
+    #[strum(serialize = "/demo/chat/export")]
+    ChatExport,
+    #[strum(serialize = "/demo/chat/import")]
+    ChatImport,
+    #[strum(serialize = "/demo/chat/retention")]
+    ChatRetention,
+    #[strum(serialize = "/demo/chat/schedule")]
+    ChatSchedule,
+    #[strum(serialize = "/demo/chat/schedule/cancel")]
+    ChatScheduleCancel,
+    #[strum(serialize = "/demo/chat/pin")]
+    ChatPin,
+    #[strum(serialize = "/demo/chat/unpin")]
+    ChatUnpin,
+    #[strum(serialize = "/demo/chat/report")]
+    ChatReport,
+    #[strum(serialize = "/demo/chat/poll")]
+    ChatPoll,
+    #[strum(serialize = "/demo/chat/poll/vote")]
+    ChatPollVote,
+    #[strum(serialize = "/demo/chat/poll/close")]
+    ChatPollClose,
+    #[strum(serialize = "/demo/chat/webhooks")]
+    ChatWebhooks,
+    #[strum(serialize = "/demo/chat/webhooks/delete")]
+    ChatWebhookDelete,
+    #[strum(serialize = "/demo/chat/webhooks/deliveries")]
+    ChatWebhookDeliveries,
+    #[strum(serialize = "/demo/chat/incoming-webhook")]
+    ChatIncomingWebhook,
+    #[strum(serialize = "/demo/chat/incoming-webhook/revoke")]
+    ChatIncomingWebhookRevoke,
+    #[strum(serialize = "/demo/chat/attachments")]
+    ChatAttachments,
+    #[strum(serialize = "/demo/chat/attachments/{{attachment_id}}")]
+    ChatAttachment,
+    #[strum(serialize = "/demo/chat/attachments/{{attachment_id}}/thumbnail")]
+    ChatAttachmentThumbnail,
+    #[strum(serialize = "/hooks/chat/{{token}}")]
+    IncomingHook,
     #[strum(serialize = "/events")]
     Events,
+    #[strum(serialize = "/ws")]
+    Socket,
     #[strum(serialize = "/error-test")]
     ErrorTest,
     #[strum(serialize = "/health")]
@@ -56,9 +110,36 @@ impl Route {
             Route::Protected => "/protected",
             Route::Chat => "/demo/chat",
             Route::ChatMessages => "/demo/chat/messages",
-            Route::ChatMessagesDemo => "/demo/chat/messages/demo",
             Route::ChatModeration => "/demo/chat/moderation",
+            Route::ChatModerationClaim => "/demo/chat/moderation/claim",
+            Route::ChatModerationRelease => "/demo/chat/moderation/release",
+            Route::ChatAppeals => "/demo/chat/appeals",
+            Route::ChatAppealDecide => "/demo/chat/appeals/decide",
+            Route::ChatTyping => "/demo/chat/typing",
+            Route::ChatRead => "/demo/chat/read",
+            Route::ChatSearch => "/demo/chat/search",
+            Route::ChatExport => "/demo/chat/export",
+            Route::ChatImport => "/demo/chat/import",
+            Route::ChatRetention => "/demo/chat/retention",
+            Route::ChatSchedule => "/demo/chat/schedule",
+            Route::ChatScheduleCancel => "/demo/chat/schedule/cancel",
+            Route::ChatPin => "/demo/chat/pin",
+            Route::ChatUnpin => "/demo/chat/unpin",
+            Route::ChatReport => "/demo/chat/report",
+            Route::ChatPoll => "/demo/chat/poll",
+            Route::ChatPollVote => "/demo/chat/poll/vote",
+            Route::ChatPollClose => "/demo/chat/poll/close",
+            Route::ChatWebhooks => "/demo/chat/webhooks",
+            Route::ChatWebhookDelete => "/demo/chat/webhooks/delete",
+            Route::ChatWebhookDeliveries => "/demo/chat/webhooks/deliveries",
+            Route::ChatIncomingWebhook => "/demo/chat/incoming-webhook",
+            Route::ChatIncomingWebhookRevoke => "/demo/chat/incoming-webhook/revoke",
+            Route::ChatAttachments => "/demo/chat/attachments",
+            Route::ChatAttachment => "/demo/chat/attachments/{attachment_id}",
+            Route::ChatAttachmentThumbnail => "/demo/chat/attachments/{attachment_id}/thumbnail",
+            Route::IncomingHook => "/hooks/chat/{token}",
             Route::Events => "/events",
+            Route::Socket => "/ws",
             Route::ErrorTest => "/error-test",
             Route::Health => "/health",
             Route::PartialAuthStatus => "/partials/auth-status",
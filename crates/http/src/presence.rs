@@ -0,0 +1,195 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use dashmap::DashMap;
+use domain::chat::{RoomId, UserId};
+use domain::user::Username;
+
+const DEFAULT_GRACE: Duration = Duration::from_secs(10);
+
+/// Per-room presence derived from live SSE streams. A user stays online
+/// while at least one of their streams is subscribed to the room; the last
+/// stream closing starts a grace period so reconnects don't flap.
+#[derive(Clone)]
+pub struct Presence {
+    entries: Arc<DashMap<(RoomId, UserId), Entry>>,
+    grace: Duration,
+}
+
+struct Entry {
+    username: Username,
+    streams: usize,
+    idle_since: Option<Instant>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Member {
+    pub user_id: UserId,
+    pub username: Username,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Change {
+    Joined {
+        room_id: RoomId,
+        member: Member,
+    },
+    Left {
+        room_id: RoomId,
+        user_id: UserId,
+    },
+}
+
+impl Default for Presence {
+    fn default() -> Self {
+        Self::new(DEFAULT_GRACE)
+    }
+}
+
+impl Presence {
+    pub fn new(grace: Duration) -> Self {
+        Self {
+            entries: Arc::new(DashMap::new()),
+            grace,
+        }
+    }
+
+    /// Records a new stream; returns `Joined` only when the user was not
+    /// already online (including during a reconnect grace period).
+    pub fn connect(
+        &self,
+        room_id: RoomId,
+        user_id: UserId,
+        username: Username,
+    ) -> Option<Change> {
+        let mut entry = self
+            .entries
+            .entry((room_id, user_id))
+            .or_insert_with(|| Entry {
+                username: username.clone(),
+                streams: 0,
+                idle_since: None,
+            });
+        let joined = entry.streams == 0 && entry.idle_since.is_none();
+        entry.streams += 1;
+        entry.idle_since = None;
+        entry.username = username.clone();
+
+        joined.then_some(Change::Joined {
+            room_id,
+            member: Member { user_id, username },
+        })
+    }
+
+    pub fn disconnect(
+        &self,
+        room_id: RoomId,
+        user_id: UserId,
+        now: Instant,
+    ) {
+        if let Some(mut entry) = self.entries.get_mut(&(room_id, user_id)) {
+            entry.streams = entry.streams.saturating_sub(1);
+            if entry.streams == 0 {
+                entry.idle_since = Some(now);
+            }
+        }
+    }
+
+    /// Drops users whose grace period has elapsed and reports them as `Left`.
+    pub fn sweep(
+        &self,
+        now: Instant,
+    ) -> Vec<Change> {
+        let expired = self
+            .entries
+            .iter()
+            .filter(|entry| {
+                entry
+                    .idle_since
+                    .is_some_and(|since| now.duration_since(since) >= self.grace)
+            })
+            .map(|entry| *entry.key())
+            .collect::<Vec<_>>();
+
+        expired
+            .into_iter()
+            .filter_map(|key| {
+                self.entries
+                    .remove_if(&key, |_, entry| entry.streams == 0)
+                    .map(|((room_id, user_id), _)| Change::Left { room_id, user_id })
+            })
+            .collect()
+    }
+
+    pub fn online(
+        &self,
+        room_id: &RoomId,
+    ) -> Vec<Member> {
+        let mut members = self
+            .entries
+            .iter()
+            .filter(|entry| &entry.key().0 == room_id)
+            .map(|entry| Member {
+                user_id: entry.key().1,
+                username: entry.username.clone(),
+            })
+            .collect::<Vec<_>>();
+        members.sort_by_key(|member| member.username.to_string());
+        members
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn username(value: &str) -> Username {
+        Username::try_new(value).expect("username")
+    }
+
+    #[test]
+    fn joins_once_across_multiple_streams() {
+        let presence = Presence::default();
+        let room_id = RoomId::new_v4();
+        let user_id = UserId::new_v4();
+
+        assert!(matches!(
+            presence.connect(room_id, user_id, username("ada")),
+            Some(Change::Joined { .. })
+        ));
+        assert_eq!(presence.connect(room_id, user_id, username("ada")), None);
+        assert_eq!(presence.online(&room_id).len(), 1);
+    }
+
+    #[test]
+    fn reconnect_within_grace_does_not_leave() {
+        let presence = Presence::new(Duration::from_secs(10));
+        let room_id = RoomId::new_v4();
+        let user_id = UserId::new_v4();
+        let start = Instant::now();
+
+        presence.connect(room_id, user_id, username("ada"));
+        presence.disconnect(room_id, user_id, start);
+        assert_eq!(presence.connect(room_id, user_id, username("ada")), None);
+
+        assert!(presence.sweep(start + Duration::from_secs(30)).is_empty());
+    }
+
+    #[test]
+    fn leaves_after_grace_elapses() {
+        let presence = Presence::new(Duration::from_secs(10));
+        let room_id = RoomId::new_v4();
+        let user_id = UserId::new_v4();
+        let start = Instant::now();
+
+        presence.connect(room_id, user_id, username("ada"));
+        presence.disconnect(room_id, user_id, start);
+
+        assert!(presence.sweep(start + Duration::from_secs(5)).is_empty());
+        assert_eq!(
+            presence.sweep(start + Duration::from_secs(10)),
+            vec![Change::Left { room_id, user_id }]
+        );
+        assert!(presence.online(&room_id).is_empty());
+    }
+}
@@ -7,7 +7,10 @@ use axum::{
 };
 
 use crate::sse::SESSION_COOKIE;
-use crate::types::{ClientIp, RequestId, SessionId, UserAgent, UserIdText};
+use crate::types::{
+    ClientIp, RequestId, SessionId, TabId, UserAgent, UserIdText,
+};
+use serde::Deserialize;
 use std::cell::RefCell;
 use tracing::Span;
 use tower_cookies::{Cookies, Key};
@@ -60,21 +63,47 @@ pub async fn set_context_middleware(
 pub struct Context {
     pub request_id: Option<RequestId>,
     pub session_id: Option<SessionId>,
+    pub tab_id: Option<TabId>,
     pub user_id: Option<UserIdText>,
     pub client_ip: Option<ClientIp>,
     pub user_agent: Option<UserAgent>,
     pub kind: Kind,
 }
 
+impl Context {
+    pub fn sse_target(&self) -> Option<crate::sse::Target> {
+        let session_id = self.session_id.clone()?;
+        Some(match self.tab_id.clone() {
+            Some(tab_id) => crate::sse::Target::Tab(
+                crate::sse::StreamKey::new(session_id, tab_id),
+            ),
+            None => crate::sse::Target::Session(session_id),
+        })
+    }
+}
+
 pub fn set_user_id(user_id: impl Into<UserIdText>) {
     let user_id = user_id.into();
     if let Ok(()) = REQUEST_CONTEXT.try_with(|context| {
         context.borrow_mut().user_id = Some(user_id.clone());
     }) {
-        Span::current().record("user_id", &user_id.to_string().as_str());
+        Span::current().record("user_id", user_id.to_string().as_str());
     }
 }
 
+pub fn set_tab_id(tab_id: Option<TabId>) {
+    let Some(tab_id) = tab_id else {
+        return;
+    };
+    let _ = REQUEST_CONTEXT.try_with(|context| {
+        context.borrow_mut().tab_id = Some(tab_id);
+    });
+}
+
+pub fn current_sse_target() -> Option<crate::sse::Target> {
+    current_context().and_then(|context| context.sse_target())
+}
+
 fn context_from_request(
     req: &Request<Body>,
     key: &Key,
@@ -83,15 +112,16 @@ fn context_from_request(
     let cookies = req.extensions().get::<Cookies>();
     Context {
         request_id: header_value(headers, header::HeaderName::from_static("x-request-id"))
-            .map(|value| RequestId::new(value)),
+            .map(RequestId::new),
         session_id: cookies
             .and_then(|cookies| session_id_from_cookies(cookies, key))
-            .map(|value| SessionId::new(value)),
+            .map(SessionId::new),
+        tab_id: tab_id_from_query(req),
         user_id: None,
         client_ip: client_ip_from_headers(headers)
-            .map(|value| ClientIp::new(value)),
+            .map(ClientIp::new),
         user_agent: header_value(headers, header::USER_AGENT)
-            .map(|value| UserAgent::new(value)),
+            .map(UserAgent::new),
         kind: kind_from_headers(headers),
     }
 }
@@ -104,6 +134,26 @@ fn kind_from_headers(headers: &HeaderMap) -> Kind {
     }
 }
 
+#[derive(Deserialize)]
+struct DatastarQuery {
+    datastar: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TabSignals {
+    tab_id: Option<TabId>,
+}
+
+/// Datastar GETs carry their signals in the `datastar` query parameter;
+/// POST bodies are read by handlers, which call `set_tab_id` themselves.
+fn tab_id_from_query(req: &Request<Body>) -> Option<TabId> {
+    let axum::extract::Query(query) =
+        axum::extract::Query::<DatastarQuery>::try_from_uri(req.uri()).ok()?;
+    let signals: TabSignals = serde_json::from_str(&query.datastar?).ok()?;
+    signals.tab_id
+}
+
 fn session_id_from_cookies(
     cookies: &Cookies,
     key: &Key,
@@ -210,7 +260,19 @@ mod tests {
 
         let context = context_from_request(&req, &key);
 
-        assert_eq!(context.session_id.as_deref(), None);
+        assert_eq!(context.session_id, None);
+    }
+
+    #[test]
+    fn reads_tab_id_from_datastar_query() {
+        let req = Request::builder()
+            .uri("/events?datastar=%7B%22tabId%22%3A%22tab-1%22%7D")
+            .body(Body::empty())
+            .unwrap();
+
+        let tab_id = tab_id_from_query(&req);
+
+        assert_eq!(tab_id, Some(TabId::from("tab-1")));
     }
 
     #[tokio::test]
@@ -218,6 +280,7 @@ mod tests {
         let context = Context {
             request_id: None,
             session_id: None,
+            tab_id: None,
             user_id: None,
             client_ip: None,
             user_agent: None,
@@ -228,7 +291,7 @@ mod tests {
             .scope(RefCell::new(context), async move {
                 set_user_id("user-123");
                 let updated = current_context().expect("context");
-                assert_eq!(updated.user_id.as_deref(), Some("user-123"));
+                assert_eq!(updated.user_id.map(|value| value.to_string()).as_deref(), Some("user-123"));
             })
             .await;
     }
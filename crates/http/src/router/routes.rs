@@ -1,4 +1,5 @@
 use axum::Router;
+use axum::extract::DefaultBodyLimit;
 use axum::middleware::from_fn;
 use axum::routing::{get, post};
 use tower_http::services::ServeDir;
@@ -84,15 +85,81 @@ fn pages_routes() -> Router {
     let chat = Router::new()
         .route(Route::Chat.as_str(), get(crate::handlers::chat_page))
         .route(Route::ChatMessages.as_str(), post(crate::handlers::post_chat_message))
-        .route(
-            Route::ChatMessagesDemo.as_str(),
-            post(crate::handlers::post_demo_chat_message),
-        )
         .route(
             Route::ChatModeration.as_str(),
             get(crate::handlers::moderation_page)
                 .post(crate::handlers::moderate_message),
         )
+        .route(
+            Route::ChatModerationClaim.as_str(),
+            post(crate::handlers::claim_moderation),
+        )
+        .route(
+            Route::ChatModerationRelease.as_str(),
+            post(crate::handlers::release_moderation),
+        )
+        .route(
+            Route::ChatAppeals.as_str(),
+            get(crate::handlers::appeals_page).post(crate::handlers::file_appeal),
+        )
+        .route(
+            Route::ChatAppealDecide.as_str(),
+            post(crate::handlers::decide_appeal),
+        )
+        .route(Route::ChatTyping.as_str(), post(crate::handlers::post_typing))
+        .route(Route::ChatRead.as_str(), post(crate::handlers::mark_read))
+        .route(Route::ChatSearch.as_str(), get(crate::handlers::search_messages))
+        .route(Route::ChatExport.as_str(), get(crate::handlers::export_room))
+        .route(Route::ChatImport.as_str(), post(crate::handlers::import_room))
+        .route(
+            Route::ChatRetention.as_str(),
+            get(crate::handlers::retention).post(crate::handlers::set_retention),
+        )
+        .route(Route::ChatSchedule.as_str(), post(crate::handlers::schedule_message))
+        .route(
+            Route::ChatScheduleCancel.as_str(),
+            post(crate::handlers::cancel_schedule),
+        )
+        .route(Route::ChatPin.as_str(), post(crate::handlers::pin_message))
+        .route(Route::ChatUnpin.as_str(), post(crate::handlers::unpin_message))
+        .route(Route::ChatReport.as_str(), post(crate::handlers::report_message))
+        .route(Route::ChatPoll.as_str(), post(crate::handlers::create_poll))
+        .route(Route::ChatPollVote.as_str(), post(crate::handlers::vote_poll))
+        .route(Route::ChatPollClose.as_str(), post(crate::handlers::close_poll))
+        .route(
+            Route::ChatWebhooks.as_str(),
+            get(crate::handlers::list_webhooks).post(crate::handlers::create_webhook),
+        )
+        .route(
+            Route::ChatWebhookDelete.as_str(),
+            post(crate::handlers::delete_webhook),
+        )
+        .route(
+            Route::ChatWebhookDeliveries.as_str(),
+            get(crate::handlers::webhook_deliveries),
+        )
+        .route(
+            Route::ChatIncomingWebhook.as_str(),
+            get(crate::handlers::incoming_webhook)
+                .post(crate::handlers::regenerate_incoming_webhook),
+        )
+        .route(
+            Route::ChatIncomingWebhookRevoke.as_str(),
+            post(crate::handlers::revoke_incoming_webhook),
+        )
+        .route(
+            Route::ChatAttachments.as_str(),
+            post(crate::handlers::post_attachments).layer(DefaultBodyLimit::max(
+                app::chat::MAX_ATTACHMENTS_PER_MESSAGE * app::chat::MAX_ATTACHMENT_BYTES
+                    + 64 * 1024,
+            )),
+        )
+        .route(Route::ChatAttachment.as_str(), get(crate::handlers::get_attachment))
+        .route(
+            Route::ChatAttachmentThumbnail.as_str(),
+            get(crate::handlers::get_attachment_thumbnail),
+        )
+        .route(Route::Socket.as_str(), get(crate::handlers::socket))
         .route_layer(from_fn(crate::auth::require_auth_middleware));
 
     Router::new()
@@ -106,6 +173,7 @@ fn pages_routes() -> Router {
             get(crate::handlers::register_form).post(crate::handlers::register),
         )
         .route(Route::Logout.as_str(), axum::routing::post(crate::handlers::logout))
+        .route(Route::IncomingHook.as_str(), post(crate::handlers::post_incoming))
         .merge(protected)
         .merge(chat)
 }
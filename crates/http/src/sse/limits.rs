@@ -0,0 +1,22 @@
+use std::time::Duration;
+
+const DEFAULT_MAX_STREAMS_PER_SESSION: usize = 8;
+const DEFAULT_MAX_STREAMS: usize = 1024;
+const DEFAULT_HEARTBEAT: Duration = Duration::from_secs(15);
+
+#[derive(Clone, Copy, Debug)]
+pub struct Limits {
+    pub max_streams_per_session: usize,
+    pub max_streams: usize,
+    pub heartbeat: Duration,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_streams_per_session: DEFAULT_MAX_STREAMS_PER_SESSION,
+            max_streams: DEFAULT_MAX_STREAMS,
+            heartbeat: DEFAULT_HEARTBEAT,
+        }
+    }
+}
@@ -1,15 +1,22 @@
 use dashmap::DashMap;
+use datastar::consts::EventType;
 use datastar::prelude::{DatastarEvent, ExecuteScript, PatchElements, PatchSignals};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::broadcast;
 
-use crate::types::SessionId;
+use crate::auth::UserId;
+use crate::types::{SessionId, TabId};
 
 pub const SESSION_COOKIE: &str = "session_id";
 
+mod limits;
 mod session;
+mod target;
 
+pub use limits::Limits;
 pub use session::{Handle, Session};
+pub use target::{StreamKey, Target};
 
 #[derive(Clone, Debug)]
 pub struct Event {
@@ -46,6 +53,18 @@ impl Event {
     pub fn as_datastar_event(&self) -> &DatastarEvent {
         &self.inner
     }
+
+    /// SSE event name, also used to tag WebSocket frames.
+    pub fn name(&self) -> &'static str {
+        match self.inner.event {
+            EventType::PatchElements => "datastar-patch-elements",
+            EventType::PatchSignals => "datastar-patch-signals",
+        }
+    }
+
+    pub fn data(&self) -> &[String] {
+        &self.inner.data
+    }
 }
 
 #[derive(Debug)]
@@ -56,9 +75,19 @@ pub enum SendError {
 
 pub type SendResult<T> = Result<T, SendError>;
 
+#[derive(Debug)]
+pub enum SubscribeError {
+    SessionLimit,
+    GlobalLimit,
+}
+
+pub type SubscribeResult<T> = Result<T, SubscribeError>;
+
 #[derive(Clone, Default)]
 pub struct Registry {
-    sessions: Arc<DashMap<SessionId, Session>>,
+    streams: Arc<DashMap<StreamKey, Session>>,
+    owners: Arc<DashMap<StreamKey, UserId>>,
+    limits: Limits,
 }
 
 impl Registry {
@@ -66,74 +95,193 @@ impl Registry {
         Self::default()
     }
 
+    pub fn with_limits(limits: Limits) -> Self {
+        Self {
+            limits,
+            ..Self::default()
+        }
+    }
+
+    pub fn limits(&self) -> Limits {
+        self.limits
+    }
+
     pub fn subscribe(
         &self,
         handle: &Handle,
-    ) -> (broadcast::Receiver<Event>, SessionGuard) {
-        let session_id = handle.id();
+    ) -> SubscribeResult<(broadcast::Receiver<Event>, SessionGuard)> {
+        let key = StreamKey::new(
+            handle.id(),
+            handle.tab().unwrap_or_else(TabId::generate),
+        );
+        self.check_limits(&key.session_id)?;
         let receiver = self
-            .sessions
-            .entry(session_id.clone())
-            .or_insert_with(Session::new)
+            .streams
+            .entry(key.clone())
+            .or_default()
             .subscribe();
-        let guard = SessionGuard::new(self.clone(), session_id);
+        match handle.user() {
+            Some(user_id) => {
+                self.owners.insert(key.clone(), user_id);
+            }
+            None => {
+                self.owners.remove(&key);
+            }
+        }
+        let guard = SessionGuard::new(self.clone(), key);
+
+        Ok((receiver, guard))
+    }
+
+    /// Drops streams that no longer have a live receiver, e.g. when a
+    /// client vanished without its guard running. Returns the count evicted.
+    pub fn sweep_idle(&self) -> usize {
+        let idle = self
+            .streams
+            .iter()
+            .filter(|entry| entry.value().receivers() == 0)
+            .map(|entry| entry.key().clone())
+            .collect::<Vec<_>>();
+        for key in &idle {
+            self.drop_stream(key);
+        }
+        if !idle.is_empty() {
+            tracing::info!(streams = idle.len(), "sse idle streams evicted");
+        }
+        idle.len()
+    }
 
-        (receiver, guard)
+    pub async fn sweep_idle_continuously(
+        self,
+        interval: Duration,
+    ) {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            self.sweep_idle();
+        }
     }
 
     pub fn send(
         &self,
         handle: &Handle,
         event: Event,
+    ) -> SendResult<()> {
+        self.send_to(&handle.target(), event)
+    }
+
+    pub fn send_by_id(
+        &self,
+        session_id: &SessionId,
+        event: Event,
+    ) -> SendResult<()> {
+        self.send_to(&Target::Session(session_id.clone()), event)
+    }
+
+    pub fn send_to(
+        &self,
+        target: &Target,
+        event: Event,
     ) -> SendResult<()> {
         let event_type = format!("{:?}", event.as_datastar_event().event);
-        let session_id = handle.id();
         tracing::debug!(
             target: "demo.sse",
             message = "sse send",
-            session_id = %session_id,
+            session_id = %target.session_id(),
+            tab_id = ?tab_of(target),
             event_type = event_type
         );
-        let session = self
-            .sessions
-            .get(&session_id)
-            .ok_or(SendError::SessionMissing)?;
-
-        let result = session.send(event).map(|_| ());
-        if result.is_err() {
-            drop(session);
-            self.sessions.remove(&session_id);
+
+        let keys = self.keys_for(target);
+        if keys.is_empty() {
+            return Err(SendError::SessionMissing);
+        }
+
+        let mut sent = 0;
+        for key in keys {
+            let Some(stream) = self.streams.get(&key) else {
+                continue;
+            };
+            if stream.send(event.clone()).is_ok() {
+                sent += 1;
+            } else {
+                drop(stream);
+                self.drop_stream(&key);
+            }
+        }
+
+        if sent == 0 {
             return Err(SendError::SendFailed);
         }
         Ok(())
     }
 
-    pub fn send_by_id(
+    pub fn send_to_user(
         &self,
-        session_id: &SessionId,
+        user_id: &UserId,
         event: Event,
     ) -> SendResult<()> {
-        let event_type = format!("{:?}", event.as_datastar_event().event);
+        let keys = self.keys_for_user(user_id);
         tracing::debug!(
             target: "demo.sse",
-            message = "sse send",
-            session_id = %session_id,
-            event_type = event_type
+            message = "sse send to user",
+            user_id = %user_id,
+            streams = keys.len()
         );
-        let session = self
-            .sessions
-            .get(session_id)
-            .ok_or(SendError::SessionMissing)?;
-
-        let result = session.send(event).map(|_| ());
-        if result.is_err() {
-            drop(session);
-            self.sessions.remove(session_id);
+        if keys.is_empty() {
+            return Err(SendError::SessionMissing);
+        }
+
+        let mut sent = 0;
+        for key in keys {
+            match self.send_to(&Target::Tab(key), event.clone()) {
+                Ok(()) => sent += 1,
+                Err(err) => {
+                    tracing::debug!(?err, "sse user stream dropped");
+                }
+            }
+        }
+
+        if sent == 0 {
             return Err(SendError::SendFailed);
         }
         Ok(())
     }
 
+    pub fn session_user(
+        &self,
+        session_id: &SessionId,
+    ) -> Option<UserId> {
+        self.owners
+            .iter()
+            .find(|entry| &entry.key().session_id == session_id)
+            .map(|entry| entry.value().clone())
+    }
+
+    /// Closes streams in `session_id` that were opened by a user other than
+    /// `current`, e.g. after logout or when the auth session was invalidated.
+    pub fn close_stale_streams(
+        &self,
+        session_id: &SessionId,
+        current: Option<&UserId>,
+    ) -> usize {
+        let stale = self
+            .owners
+            .iter()
+            .filter(|entry| &entry.key().session_id == session_id)
+            .filter(|entry| Some(entry.value()) != current)
+            .map(|entry| entry.key().clone())
+            .collect::<Vec<_>>();
+        for key in &stale {
+            self.drop_stream(key);
+        }
+        if !stale.is_empty() {
+            tracing::info!(session_id = %session_id, streams = stale.len(), "sse stale streams closed");
+        }
+        stale.len()
+    }
+
     pub fn broadcast(
         &self,
         event: Event,
@@ -141,14 +289,14 @@ impl Registry {
         let event_type = format!("{:?}", event.as_datastar_event().event);
         let mut sent = 0;
         let mut failed = Vec::new();
-        let total = self.sessions.len();
+        let total = self.streams.len();
         tracing::debug!(
             target: "demo.sse",
             message = "sse broadcast",
             sessions = total,
             event_type = event_type
         );
-        for entry in self.sessions.iter() {
+        for entry in self.streams.iter() {
             let result = entry.value().send(event.clone());
             if result.is_err() {
                 failed.push(entry.key().clone());
@@ -157,58 +305,144 @@ impl Registry {
             }
         }
 
-        for session_id in failed {
-            self.sessions.remove(&session_id);
+        for key in failed {
+            self.drop_stream(&key);
         }
 
-        if sent == 0 && !self.sessions.is_empty() {
+        if sent == 0 && !self.streams.is_empty() {
             return Err(SendError::SendFailed);
         }
 
         Ok(sent)
     }
 
+    pub fn tabs(
+        &self,
+        session_id: &SessionId,
+    ) -> Vec<TabId> {
+        self.keys_for(&Target::Session(session_id.clone()))
+            .into_iter()
+            .map(|key| key.tab_id)
+            .collect()
+    }
+
     pub fn remove(
         &self,
         session_id: &SessionId,
     ) {
-        self.sessions.remove(session_id);
+        self.streams
+            .retain(|key, _| &key.session_id != session_id);
+        self.owners
+            .retain(|key, _| &key.session_id != session_id);
     }
 
     pub fn release(
         &self,
-        session_id: &SessionId,
+        key: &StreamKey,
     ) {
-        if let Some(entry) = self.sessions.get(session_id) {
+        if let Some(entry) = self.streams.get(key) {
             let remaining = entry.release();
             if remaining == 0 {
                 drop(entry);
-                self.sessions.remove(session_id);
+                self.drop_stream(key);
             }
         }
     }
+
+    fn check_limits(
+        &self,
+        session_id: &SessionId,
+    ) -> SubscribeResult<()> {
+        let (session, total) = self.streams.iter().fold(
+            (0, 0),
+            |(session, total), entry| {
+                let active = entry.value().active();
+                if &entry.key().session_id == session_id {
+                    (session + active, total + active)
+                } else {
+                    (session, total + active)
+                }
+            },
+        );
+        if total >= self.limits.max_streams {
+            tracing::warn!(streams = total, "sse global stream limit reached");
+            return Err(SubscribeError::GlobalLimit);
+        }
+        if session >= self.limits.max_streams_per_session {
+            tracing::warn!(session_id = %session_id, streams = session, "sse session stream limit reached");
+            return Err(SubscribeError::SessionLimit);
+        }
+        Ok(())
+    }
+
+    fn drop_stream(
+        &self,
+        key: &StreamKey,
+    ) {
+        self.streams.remove(key);
+        self.owners.remove(key);
+    }
+
+    fn keys_for_user(
+        &self,
+        user_id: &UserId,
+    ) -> Vec<StreamKey> {
+        self.owners
+            .iter()
+            .filter(|entry| entry.value() == user_id)
+            .map(|entry| entry.key().clone())
+            .collect()
+    }
+
+    fn keys_for(
+        &self,
+        target: &Target,
+    ) -> Vec<StreamKey> {
+        match target {
+            Target::Tab(key) => self
+                .streams
+                .contains_key(key)
+                .then(|| key.clone())
+                .into_iter()
+                .collect(),
+            Target::Session(_) => self
+                .streams
+                .iter()
+                .filter(|entry| target.matches(entry.key()))
+                .map(|entry| entry.key().clone())
+                .collect(),
+        }
+    }
+}
+
+fn tab_of(target: &Target) -> Option<&TabId> {
+    match target {
+        Target::Session(_) => None,
+        Target::Tab(key) => Some(&key.tab_id),
+    }
 }
 
 pub struct SessionGuard {
     registry: Registry,
-    session_id: SessionId,
+    key: StreamKey,
 }
 
 impl SessionGuard {
     pub fn new(
         registry: Registry,
-        session_id: SessionId,
+        key: StreamKey,
     ) -> Self {
-        Self {
-            registry,
-            session_id,
-        }
+        Self { registry, key }
+    }
+
+    pub fn key(&self) -> &StreamKey {
+        &self.key
     }
 }
 
 impl Drop for SessionGuard {
     fn drop(&mut self) {
-        self.registry.release(&self.session_id);
+        self.registry.release(&self.key);
     }
 }
 
@@ -217,15 +451,23 @@ mod tests {
     use super::*;
     use tower_cookies::{Cookies, Key};
 
+    fn handle(
+        cookies: &Cookies,
+        key: &Key,
+        tab: &str,
+    ) -> Handle {
+        Handle::from_cookies(cookies, key).with_tab(Some(TabId::from(tab)))
+    }
+
     #[test]
     fn keeps_session_until_last_guard_drops() {
         let registry = Registry::new();
         let key = Key::generate();
         let cookies = Cookies::default();
-        let handle = Handle::from_cookies(&cookies, &key);
+        let handle = handle(&cookies, &key, "tab-a");
 
-        let (_rx1, guard1) = registry.subscribe(&handle);
-        let (_rx2, guard2) = registry.subscribe(&handle);
+        let (_rx1, guard1) = registry.subscribe(&handle).expect("subscribe");
+        let (_rx2, guard2) = registry.subscribe(&handle).expect("subscribe");
 
         drop(guard1);
         let send_result = registry.send(&handle, Event::patch_elements("ok"));
@@ -235,4 +477,132 @@ mod tests {
         let send_result = registry.send(&handle, Event::patch_elements("ok"));
         assert!(matches!(send_result, Err(SendError::SessionMissing)));
     }
+
+    #[test]
+    fn tab_target_only_reaches_that_tab() {
+        let registry = Registry::new();
+        let key = Key::generate();
+        let cookies = Cookies::default();
+        let tab_a = handle(&cookies, &key, "tab-a");
+        let tab_b = handle(&cookies, &key, "tab-b");
+
+        let (mut rx_a, _guard_a) = registry.subscribe(&tab_a).expect("subscribe");
+        let (mut rx_b, _guard_b) = registry.subscribe(&tab_b).expect("subscribe");
+
+        registry
+            .send(&tab_a, Event::patch_elements("only-a"))
+            .expect("send to tab");
+
+        assert!(rx_a.try_recv().is_ok());
+        assert!(rx_b.try_recv().is_err());
+    }
+
+    #[test]
+    fn session_target_reaches_every_tab() {
+        let registry = Registry::new();
+        let key = Key::generate();
+        let cookies = Cookies::default();
+        let tab_a = handle(&cookies, &key, "tab-a");
+        let tab_b = handle(&cookies, &key, "tab-b");
+
+        let (mut rx_a, _guard_a) = registry.subscribe(&tab_a).expect("subscribe");
+        let (mut rx_b, _guard_b) = registry.subscribe(&tab_b).expect("subscribe");
+
+        registry
+            .send_by_id(&tab_a.id(), Event::patch_elements("all"))
+            .expect("send to session");
+
+        assert!(rx_a.try_recv().is_ok());
+        assert!(rx_b.try_recv().is_ok());
+        assert_eq!(registry.tabs(&tab_a.id()).len(), 2);
+    }
+
+    #[test]
+    fn user_target_reaches_only_owned_streams() {
+        let registry = Registry::new();
+        let key = Key::generate();
+        let owner = UserId::new("user-1");
+        let owned = handle(&Cookies::default(), &key, "tab-a")
+            .with_user(Some(owner.clone()));
+        let anonymous = handle(&Cookies::default(), &key, "tab-b");
+
+        let (mut rx_owned, _guard_owned) = registry.subscribe(&owned).expect("subscribe");
+        let (mut rx_anonymous, _guard_anonymous) = registry.subscribe(&anonymous).expect("subscribe");
+
+        registry
+            .send_to_user(&owner, Event::patch_elements("hi"))
+            .expect("send to user");
+
+        assert!(rx_owned.try_recv().is_ok());
+        assert!(rx_anonymous.try_recv().is_err());
+        assert_eq!(registry.session_user(&owned.id()), Some(owner));
+    }
+
+    #[test]
+    fn stale_streams_close_when_user_signs_out() {
+        let registry = Registry::new();
+        let key = Key::generate();
+        let cookies = Cookies::default();
+        let owned = handle(&cookies, &key, "tab-a")
+            .with_user(Some(UserId::new("user-1")));
+
+        let (_rx, _guard) = registry.subscribe(&owned).expect("subscribe");
+
+        assert_eq!(registry.close_stale_streams(&owned.id(), None), 1);
+        assert!(registry.tabs(&owned.id()).is_empty());
+    }
+
+    #[test]
+    fn rejects_streams_over_session_limit() {
+        let registry = Registry::with_limits(Limits {
+            max_streams_per_session: 1,
+            ..Limits::default()
+        });
+        let key = Key::generate();
+        let cookies = Cookies::default();
+        let tab_a = handle(&cookies, &key, "tab-a");
+        let tab_b = handle(&cookies, &key, "tab-b");
+
+        let (_rx, guard) = registry.subscribe(&tab_a).expect("subscribe");
+
+        assert!(matches!(
+            registry.subscribe(&tab_b),
+            Err(SubscribeError::SessionLimit)
+        ));
+        drop(guard);
+        assert!(registry.subscribe(&tab_b).is_ok());
+    }
+
+    #[test]
+    fn rejects_streams_over_global_limit() {
+        let registry = Registry::with_limits(Limits {
+            max_streams: 1,
+            ..Limits::default()
+        });
+        let key = Key::generate();
+        let first = handle(&Cookies::default(), &key, "tab-a");
+        let second = handle(&Cookies::default(), &key, "tab-b");
+
+        let (_rx, _guard) = registry.subscribe(&first).expect("subscribe");
+
+        assert!(matches!(
+            registry.subscribe(&second),
+            Err(SubscribeError::GlobalLimit)
+        ));
+    }
+
+    #[test]
+    fn sweeps_streams_without_receivers() {
+        let registry = Registry::new();
+        let key = Key::generate();
+        let tab = handle(&Cookies::default(), &key, "tab-a");
+
+        let (rx, _guard) = registry.subscribe(&tab).expect("subscribe");
+        assert_eq!(registry.sweep_idle(), 0);
+
+        drop(rx);
+
+        assert_eq!(registry.sweep_idle(), 1);
+        assert!(registry.tabs(&tab.id()).is_empty());
+    }
 }
@@ -3,13 +3,16 @@ use tower_cookies::cookie::SameSite;
 use tower_cookies::{Cookie, Cookies, Key};
 use uuid::Uuid;
 
-use super::{Event, SESSION_COOKIE};
-use crate::types::SessionId;
+use super::{Event, SESSION_COOKIE, StreamKey, Target};
+use crate::auth::UserId;
+use crate::types::{SessionId, TabId};
 
 const SESSION_CHANNEL_SIZE: usize = 32;
 
 pub struct Handle {
     id: SessionId,
+    tab: Option<TabId>,
+    user: Option<UserId>,
 }
 
 impl Handle {
@@ -18,12 +21,48 @@ impl Handle {
         key: &Key,
     ) -> Self {
         let id = ensure_session(cookies, key);
-        Self { id }
+        Self {
+            id,
+            tab: None,
+            user: None,
+        }
+    }
+
+    pub fn with_tab(
+        mut self,
+        tab: Option<TabId>,
+    ) -> Self {
+        self.tab = tab;
+        self
+    }
+
+    pub fn with_user(
+        mut self,
+        user: Option<UserId>,
+    ) -> Self {
+        self.user = user;
+        self
     }
 
     pub fn id(&self) -> SessionId {
         self.id.clone()
     }
+
+    pub fn tab(&self) -> Option<TabId> {
+        self.tab.clone()
+    }
+
+    pub fn user(&self) -> Option<UserId> {
+        self.user.clone()
+    }
+
+    /// Targets the handle's tab when known, otherwise every tab of the session.
+    pub fn target(&self) -> Target {
+        match &self.tab {
+            Some(tab) => Target::Tab(StreamKey::new(self.id(), tab.clone())),
+            None => Target::Session(self.id()),
+        }
+    }
 }
 
 pub struct Session {
@@ -31,6 +70,12 @@ pub struct Session {
     active: AtomicUsize,
 }
 
+impl Default for Session {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Session {
     pub fn new() -> Self {
         let (sender, _receiver) = tokio::sync::broadcast::channel(SESSION_CHANNEL_SIZE);
@@ -52,6 +97,14 @@ impl Session {
         self.sender.send(event)
     }
 
+    pub fn active(&self) -> usize {
+        self.active.load(Ordering::Relaxed)
+    }
+
+    pub fn receivers(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
     pub fn release(&self) -> usize {
         let prev = self.active.fetch_sub(1, Ordering::Relaxed);
         prev.saturating_sub(1)
@@ -0,0 +1,50 @@
+use crate::types::{SessionId, TabId};
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StreamKey {
+    pub session_id: SessionId,
+    pub tab_id: TabId,
+}
+
+impl StreamKey {
+    pub fn new(
+        session_id: SessionId,
+        tab_id: TabId,
+    ) -> Self {
+        Self {
+            session_id,
+            tab_id,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Target {
+    Session(SessionId),
+    Tab(StreamKey),
+}
+
+impl Target {
+    pub fn session_id(&self) -> &SessionId {
+        match self {
+            Target::Session(session_id) => session_id,
+            Target::Tab(key) => &key.session_id,
+        }
+    }
+
+    pub fn matches(
+        &self,
+        key: &StreamKey,
+    ) -> bool {
+        match self {
+            Target::Session(session_id) => &key.session_id == session_id,
+            Target::Tab(target) => target == key,
+        }
+    }
+}
+
+impl From<StreamKey> for Target {
+    fn from(value: StreamKey) -> Self {
+        Target::Tab(value)
+    }
+}
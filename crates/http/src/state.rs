@@ -11,6 +11,7 @@ pub struct State {
     pub sse: crate::sse::Registry,
     pub cookie_key: Key,
     pub trace_log: crate::trace_log::TraceLogStore,
+    pub presence: crate::presence::Presence,
     pub demo: DemoState,
 }
 
@@ -19,6 +20,12 @@ pub struct DemoState {
     pub surreal: SurrealState,
 }
 
+impl Default for DemoState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DemoState {
     pub fn new() -> Self {
         Self {
@@ -64,6 +71,7 @@ impl State {
             sse,
             cookie_key,
             trace_log,
+            presence: crate::presence::Presence::default(),
             demo: DemoState::new(),
         }
     }
@@ -19,7 +19,7 @@ use tracing_subscriber::{Layer, registry::LookupSpan};
 use crate::{request, sse, views};
 use crate::types::{
     LogFieldKey, LogFieldName, LogFieldValue, LogLevelText, LogMessageText,
-    LogTargetText, RequestId, SessionId, TimestampText,
+    LogTargetText, RequestId, TimestampText,
 };
 use crate::paths::Route;
 use bon::{bon, Builder};
@@ -38,7 +38,7 @@ pub struct TraceEntry {
 #[derive(Clone)]
 pub struct TraceLogStore {
     requests: Arc<DashMap<RequestId, VecDeque<TraceEntry>>>,
-    sessions: Arc<DashMap<SessionId, VecDeque<TraceEntry>>>,
+    sessions: Arc<DashMap<sse::Target, VecDeque<TraceEntry>>>,
     global: Arc<Mutex<VecDeque<TraceEntry>>>,
     max_entries: usize,
     sse: sse::Registry,
@@ -64,13 +64,13 @@ impl TraceLogStore {
     pub fn record_with_session(
         &self,
         request_id: &RequestId,
-        session_id: Option<&SessionId>,
+        target: Option<&sse::Target>,
         entry: TraceEntry,
     ) {
         let mut queue = self
             .requests
             .entry(request_id.clone())
-            .or_insert_with(VecDeque::new);
+            .or_default();
         if queue.len() >= self.max_entries {
             queue.pop_front();
         }
@@ -78,11 +78,11 @@ impl TraceLogStore {
 
         let entry = queue.back().cloned().expect("entry");
 
-        if let Some(session_id) = session_id {
+        if let Some(target) = target {
             let mut session_queue = self
                 .sessions
-                .entry(session_id.clone())
-                .or_insert_with(VecDeque::new);
+                .entry(target.clone())
+                .or_default();
             if session_queue.len() >= self.max_entries {
                 session_queue.pop_front();
             }
@@ -96,39 +96,39 @@ impl TraceLogStore {
             global.push_back(entry);
         }
 
-        if self.emit_sse {
-            if let Some(session_id) = session_id {
-                let entries = self.snapshot_session(session_id);
-                let live_log = views::partials::LiveLog::builder()
-                    .entries(&entries)
-                    .build()
-                    .render()
-                    .into_string();
-                let network_log = views::partials::NetworkLog::builder()
-                    .entries(&entries)
-                    .build()
-                    .render()
-                    .into_string();
-                let _ = self
-                    .sse
-                    .send_by_id(session_id, sse::Event::patch_elements(live_log));
-                let _ = self
-                    .sse
-                    .send_by_id(session_id, sse::Event::patch_elements(network_log));
-            }
+        if self.emit_sse
+            && let Some(target) = target
+        {
+            let entries = self.snapshot_session(target);
+            let live_log = views::partials::LiveLog::builder()
+                .entries(&entries)
+                .build()
+                .render()
+                .into_string();
+            let network_log = views::partials::NetworkLog::builder()
+                .entries(&entries)
+                .build()
+                .render()
+                .into_string();
+            let _ = self
+                .sse
+                .send_to(target, sse::Event::patch_elements(live_log));
+            let _ = self
+                .sse
+                .send_to(target, sse::Event::patch_elements(network_log));
         }
     }
 
     pub fn record_sse_event(
         &self,
-        session_id: Option<&SessionId>,
+        target: Option<&sse::Target>,
         entry: TraceEntry,
     ) {
-        if let Some(session_id) = session_id {
+        if let Some(target) = target {
             let mut session_queue = self
                 .sessions
-                .entry(session_id.clone())
-                .or_insert_with(VecDeque::new);
+                .entry(target.clone())
+                .or_default();
             if session_queue.len() >= self.max_entries {
                 session_queue.pop_front();
             }
@@ -142,26 +142,26 @@ impl TraceLogStore {
             global.push_back(entry);
         }
 
-        if self.emit_sse {
-            if let Some(session_id) = session_id {
-                let entries = self.snapshot_session(session_id);
-                let live_log = views::partials::LiveLog::builder()
-                    .entries(&entries)
-                    .build()
-                    .render()
-                    .into_string();
-                let network_log = views::partials::NetworkLog::builder()
-                    .entries(&entries)
-                    .build()
-                    .render()
-                    .into_string();
-                let _ = self
-                    .sse
-                    .send_by_id(session_id, sse::Event::patch_elements(live_log));
-                let _ = self
-                    .sse
-                    .send_by_id(session_id, sse::Event::patch_elements(network_log));
-            }
+        if self.emit_sse
+            && let Some(target) = target
+        {
+            let entries = self.snapshot_session(target);
+            let live_log = views::partials::LiveLog::builder()
+                .entries(&entries)
+                .build()
+                .render()
+                .into_string();
+            let network_log = views::partials::NetworkLog::builder()
+                .entries(&entries)
+                .build()
+                .render()
+                .into_string();
+            let _ = self
+                .sse
+                .send_to(target, sse::Event::patch_elements(live_log));
+            let _ = self
+                .sse
+                .send_to(target, sse::Event::patch_elements(network_log));
         }
     }
 
@@ -177,19 +177,19 @@ impl TraceLogStore {
 
     pub fn snapshot_session(
         &self,
-        session_id: &SessionId,
+        target: &sse::Target,
     ) -> Vec<TraceEntry> {
         self.sessions
-            .get(session_id)
+            .get(target)
             .map(|queue| queue.iter().cloned().collect())
             .unwrap_or_default()
     }
 
     pub fn clear_session(
         &self,
-        session_id: &SessionId,
+        target: &sse::Target,
     ) {
-        self.sessions.remove(session_id);
+        self.sessions.remove(target);
     }
 
     pub fn snapshot_global(&self) -> Vec<TraceEntry> {
@@ -288,11 +288,11 @@ where
             .fields(visitor.fields)
             .build();
 
-        let session_id = context
+        let target = context
             .as_ref()
-            .and_then(|value| value.session_id.as_ref());
+            .and_then(|value| value.sse_target());
         self.store
-            .record_with_session(&request_id, session_id, entry);
+            .record_with_session(&request_id, target.as_ref(), entry);
     }
 }
 
@@ -349,11 +349,11 @@ where
             .fields(visitor.fields)
             .build();
 
-        let session_id = context
+        let target = context
             .as_ref()
-            .and_then(|value| value.session_id.as_ref());
+            .and_then(|value| value.sse_target());
         self.store
-            .record_with_session(&request_id, session_id, entry);
+            .record_with_session(&request_id, target.as_ref(), entry);
     }
 }
 
@@ -378,6 +378,7 @@ pub enum LogTargetKind {
 }
 
 impl LogTargetKind {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(value: &str) -> Self {
         LogTargetKnown::from_str(value)
             .map(Self::Known)
@@ -423,6 +424,7 @@ pub enum LogMessageKind {
 }
 
 impl LogMessageKind {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(value: &str) -> Self {
         LogMessageKnown::from_str(value)
             .map(Self::Known)
@@ -493,17 +495,19 @@ pub async fn audit_middleware(
     );
 
     let response = next.run(req).await;
+    let target = request::current_sse_target();
+    let tab_id = request::current_context()
+        .and_then(|value| value.tab_id);
     let latency_ms = started_at.elapsed().as_millis().to_string();
     let sender = match Route::from_path(path.as_str()) {
         Some(Route::ChatMessages) => ChatSender::You,
-        Some(Route::ChatMessagesDemo) => ChatSender::Demo,
         _ => ChatSender::Unknown,
     };
     let sent_at = now_timestamp_short();
 
     state.trace_log.record_with_session(
         &request_id,
-        session_id.as_ref(),
+        target.as_ref(),
         TraceEntry::builder()
             .timestamp(now_timestamp_short())
             .level(LogLevelText::new("INFO"))
@@ -534,6 +538,12 @@ pub async fn audit_middleware(
                         .map(|value| LogFieldValue::new(value.to_string()))
                         .unwrap_or_else(LogFieldValue::missing),
                 ),
+                (
+                    LogFieldName::from(LogFieldKey::TabId),
+                    tab_id
+                        .map(|value| LogFieldValue::new(value.to_string()))
+                        .unwrap_or_else(LogFieldValue::missing),
+                ),
                 (
                     LogFieldName::from(LogFieldKey::UserId),
                     user_id
@@ -563,7 +573,6 @@ pub async fn audit_middleware(
 #[derive(Clone, Copy, Debug)]
 enum ChatSender {
     You,
-    Demo,
     Unknown,
 }
 
@@ -571,7 +580,6 @@ impl ChatSender {
     fn as_str(self) -> &'static str {
         match self {
             ChatSender::You => "you",
-            ChatSender::Demo => "demo",
             ChatSender::Unknown => "-",
         }
     }
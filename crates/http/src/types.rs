@@ -52,6 +52,24 @@ impl From<&str> for SessionId {
     }
 }
 
+#[nutype(
+    sanitize(trim),
+    derive(Clone, Debug, PartialEq, Eq, Hash, Display, Serialize, Deserialize)
+)]
+pub struct TabId(String);
+
+impl TabId {
+    pub fn generate() -> Self {
+        TabId::new(uuid::Uuid::new_v4().to_string())
+    }
+}
+
+impl From<&str> for TabId {
+    fn from(value: &str) -> Self {
+        TabId::new(value)
+    }
+}
+
 #[nutype(
     sanitize(trim),
     derive(Clone, Debug, PartialEq, Eq, Hash, Display, Serialize, Deserialize)
@@ -184,6 +202,8 @@ pub enum LogFieldKey {
     RequestId,
     #[strum(serialize = "session_id")]
     SessionId,
+    #[strum(serialize = "tab_id")]
+    TabId,
     #[strum(serialize = "user_id")]
     UserId,
     #[strum(serialize = "sender")]
@@ -1,7 +1,7 @@
 use bon::Builder;
 use maud::{Markup, Render};
 use crate::paths::Route;
-use crate::types::Text;
+use crate::types::{TabId, Text};
 
 #[derive(Clone, Debug, Builder)]
 pub struct UserNav {
@@ -15,6 +15,23 @@ pub struct Layout<'a> {
     pub content: Markup,
     #[builder(setters(name = with_user))]
     pub user: Option<UserNav>,
+    #[builder(setters(name = with_room_id))]
+    pub room_id: Option<Text>,
+}
+
+impl Layout<'_> {
+    /// Page-level signals are sent with `GET /events`, so the room is seeded
+    /// here rather than on the chat section to be present at connect time.
+    fn signals(&self) -> String {
+        match &self.room_id {
+            Some(room_id) => format!(
+                "{{tabId: '{}', roomId: '{}'}}",
+                TabId::generate(),
+                room_id
+            ),
+            None => format!("{{tabId: '{}'}}", TabId::generate()),
+        }
+    }
 }
 
 impl Render for Layout<'_> {
@@ -33,7 +50,9 @@ impl Render for Layout<'_> {
                     script type="module" src="https://cdn.jsdelivr.net/gh/starfederation/datastar@1.0.0-RC.7/bundles/datastar.js" {}
                     script src="/static/css-scope-inline.js" {}
                 }
-                body data-init=(format!("@get('{}')", Route::Events)) {
+                body
+                    data-signals=(self.signals())
+                    data-init=(format!("@get('{}')", Route::Events)) {
                     header class="container" {
                         nav {
                             ul {
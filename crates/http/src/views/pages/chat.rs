@@ -21,7 +21,7 @@ impl Render for Chat {
                 class="container"
                 data-signals=({
                     format!(
-                        "{{roomId: '{}', body: '', botBody: '', sseConnected: false}}",
+                        "{{roomId: '{}', body: '', sseConnected: false}}",
                         self.room_id,
                     )
                 })
@@ -44,18 +44,12 @@ impl Render for Chat {
                     div class="chat-columns" {
                         ({
                             crate::views::partials::ChatPanel::builder()
+                                .room_id(self.room_id.clone())
                                 .role(crate::views::partials::ChatPanelRole::You)
                                 .messages(self.messages.clone())
                                 .build()
                                 .render()
                         })
-                        ({
-                            crate::views::partials::ChatPanel::builder()
-                                .role(crate::views::partials::ChatPanelRole::Demo)
-                                .messages(self.messages.clone())
-                                .build()
-                                .render()
-                        })
                     }
                 }
             }
@@ -0,0 +1,123 @@
+use bon::Builder;
+use maud::Render;
+
+use crate::views::partials::CtaRow;
+use crate::views::page::{Layout, UserNav};
+use crate::paths::Route;
+
+#[derive(Builder)]
+pub struct ChatAppeals {
+    /// The viewer's removed messages.
+    pub removals: Vec<app::chat::Removal>,
+    /// Pending appeals from rooms the viewer moderates.
+    pub appeals: Vec<app::chat::Appeal>,
+    pub viewer_id: domain::chat::UserId,
+    #[builder(setters(name = with_user))]
+    pub user: Option<UserNav>,
+}
+
+impl Render for ChatAppeals {
+    fn render(&self) -> maud::Markup {
+        let content = maud::html! {
+            main class="container" {
+                header class="hero" {
+                    div {
+                        h1 { "Moderation appeals" }
+                        p {
+                            "Appeal a removal of your own message once, or review other members' appeals. "
+                            a href=(Route::ChatModeration) { "Moderation queue" }
+                        }
+                    }
+                }
+
+                section class="flow-card" {
+                    h2 { "Your removed messages" }
+                    @if self.removals.is_empty() {
+                        p class="muted" { "None of your messages were removed." }
+                    } @else {
+                        div class="stack" {
+                            @for removal in &self.removals {
+                                article class="card" {
+                                    header {
+                                        h3 { (&removal.room_name) }
+                                        p class="muted" {
+                                            "Removed " (crate::chat_demo::format_message_time(removal.removed_at))
+                                            " · Reason: " (&removal.reason)
+                                        }
+                                    }
+                                    p { (&removal.body) }
+                                    @match removal.appeal {
+                                        Some(status) => {
+                                            p class="muted" { (status.label()) }
+                                        }
+                                        None => {
+                                            form method="post" action=(Route::ChatAppeals) {
+                                                input type="hidden" name="message_id" value=(removal.message_id.as_uuid());
+                                                label {
+                                                    "Why should it be restored?"
+                                                    textarea name="statement" maxlength="1000" required {}
+                                                }
+                                                button type="submit" class="button secondary" { "Appeal" }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+
+                section class="flow-card" {
+                    h2 { "Appeals to review" }
+                    @if self.appeals.is_empty() {
+                        p class="muted" { "No pending appeals." }
+                    } @else {
+                        div class="stack" {
+                            @for appeal in &self.appeals {
+                                article class="card" {
+                                    header {
+                                        h3 { (&appeal.room_name) }
+                                        p class="muted" {
+                                            (&appeal.appellant_name)
+                                            " · Filed " (crate::chat_demo::format_message_time(appeal.filed_at))
+                                            " · Reason: " (&appeal.reason)
+                                        }
+                                    }
+                                    p { (&appeal.body) }
+                                    blockquote { (&appeal.statement) }
+                                    @if appeal.appellant_id == self.viewer_id {
+                                        p class="muted" { "This is your appeal; another moderator will decide it." }
+                                    } @else if appeal.original_reviewer_id.as_ref() == Some(&self.viewer_id) {
+                                        p class="muted" { "You removed this message; another moderator must decide." }
+                                    } @else {
+                                        form method="post" action=(Route::ChatAppealDecide) {
+                                            input type="hidden" name="message_id" value=(appeal.message_id.as_uuid());
+                                            (CtaRow::builder()
+                                                .items(vec![
+                                                    maud::html! {
+                                                        button type="submit" name="decision" value=(app::chat::AppealDecision::Overturn) class="button secondary" { "Restore message" }
+                                                    },
+                                                    maud::html! {
+                                                        button type="submit" name="decision" value=(app::chat::AppealDecision::Uphold) class="button" { "Keep removed" }
+                                                    },
+                                                ])
+                                                .build()
+                                                .render())
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        };
+
+        Layout::builder()
+            .title("Moderation appeals")
+            .content(content)
+            .maybe_with_user(self.user.clone())
+            .build()
+            .render()
+    }
+}
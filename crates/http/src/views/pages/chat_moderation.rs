@@ -2,12 +2,23 @@ use bon::Builder;
 use maud::Render;
 
 use crate::views::partials::{CtaRow, ModerationAction};
+use crate::views::partials::components::{Pill, PillColor};
 use crate::views::page::{Layout, UserNav};
 use crate::paths::Route;
+use crate::types::Text;
+
+/// Reasons the queue can be filtered by.
+const REASONS: [&str; 5] = ["auto", "report", "flood", "quarantine", "import"];
 
 #[derive(Builder)]
 pub struct ChatModeration {
     pub entries: Vec<app::chat::ModerationItem>,
+    pub now: std::time::SystemTime,
+    pub viewer_id: domain::chat::UserId,
+    pub room_id: Option<Text>,
+    pub reason: Option<Text>,
+    /// Active filters as a query string, empty when unfiltered.
+    pub filter_query: Text,
     #[builder(setters(name = with_user))]
     pub user: Option<UserNav>,
 }
@@ -19,7 +30,27 @@ impl Render for ChatModeration {
                 header class="hero" {
                     div {
                         h1 { "Chat moderation queue" }
-                        p { "Review pending messages and apply moderation decisions." }
+                        p {
+                            "Review pending messages and apply moderation decisions. "
+                            a href=(Route::ChatAppeals) { "Appeals" }
+                        }
+                    }
+                }
+
+                nav class="muted" {
+                    "Reason: "
+                    a href=(self.filter_href(self.room_id.as_ref(), None)) { "all" }
+                    @for reason in REASONS {
+                        " · "
+                        @if self.reason.as_ref().is_some_and(|value| value.to_string() == reason) {
+                            strong { (reason) }
+                        } @else {
+                            a href=(self.filter_href(self.room_id.as_ref(), Some(reason))) { (reason) }
+                        }
+                    }
+                    @if self.room_id.is_some() || self.reason.is_some() {
+                        " · "
+                        a href=(Route::ChatModeration) { "Clear filters" }
                     }
                 }
 
@@ -30,19 +61,73 @@ impl Render for ChatModeration {
                         div class="stack" {
                             @for entry in &self.entries {
                                 article class="card" {
+                                    @let room_id = Text::from(entry.room_id.as_uuid().to_string());
+                                    @let reason = self.reason.as_ref().map(|value| value.to_string());
                                     header {
-                                        h3 { (&entry.room_name) }
+                                        h3 {
+                                            a href=(self.filter_href(Some(&room_id), reason.as_deref()))
+                                                title="Only this room" { (&entry.room_name) }
+                                        }
                                         p class="muted" {
                                             "Message " (&entry.message_id.as_uuid().to_string()[..8])
                                             " · User " (&entry.user_id.as_uuid().to_string()[..8])
                                             " · " (&entry.created_at)
                                         }
+                                        p {
+                                            (sla_pill(entry.sla(self.now)))
+                                            " "
+                                            span class="muted" { "Queued " (format_age(entry.age(self.now))) " ago" }
+                                        }
                                     }
                                     p { (&entry.body) }
                                     p class="muted" { "Reason: " (&entry.reason) }
-                                    form method="post" action=(Route::ChatModeration) {
+                                    @if !entry.reports.is_empty() {
+                                        div class="chat-reports" {
+                                            p {
+                                                strong { (entry.reports.len()) " open report(s): " }
+                                                (report_summary(&entry.reports))
+                                            }
+                                            ul {
+                                                @for report in &entry.reports {
+                                                    li class="muted" {
+                                                        (&report.reporter_name)
+                                                        " (" (&report.reporter_id.as_uuid().to_string()[..8]) ")"
+                                                        " · " (report.category.label())
+                                                        " · " (crate::chat_demo::format_message_time(report.reported_at))
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                    @match &entry.claim {
+                                        Some(claim) if claim.reviewer_id == self.viewer_id => {
+                                            form method="post" action=(self.action(Route::ChatModerationRelease)) {
+                                                input type="hidden" name="message_id" value=(entry.message_id.as_uuid());
+                                                p class="muted" {
+                                                    "Claimed by you until "
+                                                    (crate::chat_demo::format_message_time(claim.expires_at))
+                                                    " "
+                                                    button type="submit" class="button secondary" { "Release" }
+                                                }
+                                            }
+                                        }
+                                        Some(claim) => {
+                                            p class="muted" {
+                                                "Claimed by " strong { (&claim.reviewer_name) }
+                                                " until " (crate::chat_demo::format_message_time(claim.expires_at))
+                                            }
+                                        }
+                                        None => {
+                                            form method="post" action=(self.action(Route::ChatModerationClaim)) {
+                                                input type="hidden" name="message_id" value=(entry.message_id.as_uuid());
+                                                button type="submit" class="button secondary" { "Claim" }
+                                            }
+                                        }
+                                    }
+                                    form method="post" action=(self.action(Route::ChatModeration)) {
                                         input type="hidden" name="message_id" value=(entry.message_id.as_uuid());
                                         input type="hidden" name="reason" value=(&entry.reason);
+                                        input type="hidden" name="revision" value=(entry.revision);
                                         (CtaRow::builder()
                                             .items(vec![
                                                 maud::html! {
@@ -71,3 +156,76 @@ impl Render for ChatModeration {
             .render()
     }
 }
+
+impl ChatModeration {
+    /// Posts keep the active filters so the redirect lands on the same view.
+    fn action(
+        &self,
+        route: Route,
+    ) -> String {
+        if self.filter_query.to_string().is_empty() {
+            route.as_str().to_string()
+        } else {
+            route.with_query(&self.filter_query.to_string())
+        }
+    }
+
+    fn filter_href(
+        &self,
+        room_id: Option<&Text>,
+        reason: Option<&str>,
+    ) -> String {
+        let room_id = room_id.map(|value| value.to_string());
+        let query = [("room_id", room_id.as_deref()), ("reason", reason)]
+            .iter()
+            .filter_map(|(key, value)| {
+                value.map(|value| format!("{}={}", key, urlencoding::encode(value)))
+            })
+            .collect::<Vec<_>>()
+            .join("&");
+        if query.is_empty() {
+            Route::ChatModeration.as_str().to_string()
+        } else {
+            Route::ChatModeration.with_query(&query)
+        }
+    }
+}
+
+fn sla_pill(
+    status: app::chat::SlaStatus,
+) -> Pill {
+    let color = match status {
+        app::chat::SlaStatus::OnTrack => PillColor::Emerald,
+        app::chat::SlaStatus::DueSoon => PillColor::Amber,
+        app::chat::SlaStatus::Overdue => PillColor::Rose,
+    };
+    Pill::builder().text(Text::from(status.label())).color(color).build()
+}
+
+fn format_age(
+    age: std::time::Duration,
+) -> String {
+    let minutes = age.as_secs() / 60;
+    match minutes {
+        0 => "under a minute".to_string(),
+        1..=59 => format!("{minutes}m"),
+        _ => format!("{}h {}m", minutes / 60, minutes % 60),
+    }
+}
+
+/// Report counts per category, most reported first.
+fn report_summary(reports: &[app::chat::MessageReport]) -> String {
+    let mut counts: Vec<(app::chat::ReportCategory, usize)> = Vec::new();
+    for report in reports {
+        match counts.iter_mut().find(|(category, _)| *category == report.category) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((report.category, 1)),
+        }
+    }
+    counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+    counts
+        .iter()
+        .map(|(category, count)| format!("{} \u{d7}{}", category.label(), count))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -157,7 +157,7 @@ impl maud::Render for Home {
                                         vec![
                                             Text::from("Postgres-backed message history"),
                                             Text::from("Per-room moderation queues"),
-                                            Text::from("Single SSE stream per session"),
+                                            Text::from("One SSE stream per tab"),
                                         ],
                                     )
                                     .accent(FeatureAccent::Indigo)
@@ -274,7 +274,7 @@ impl maud::Render for Home {
                                                 .build(),
                                             DiagramRow::builder()
                                                 .label(Text::from("sse stream"))
-                                                .value(Text::from("one per visitor"))
+                                                .value(Text::from("one per tab"))
                                                 .status(DiagramStatus::Passive)
                                                 .build(),
                                         ],
@@ -397,6 +397,11 @@ impl maud::Render for Home {
             .title("Home")
             .content(content)
             .maybe_with_user(self.user.clone())
+            .maybe_with_room_id(
+                self.chat_demo
+                    .as_ref()
+                    .map(|chat_demo| chat_demo.room_id.clone()),
+            )
             .build()
             .render()
     }
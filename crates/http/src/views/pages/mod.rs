@@ -1,5 +1,6 @@
-moddef::moddef!(mod { chat_moderation, home, login, protected, register });
+moddef::moddef!(mod { chat_appeals, chat_moderation, home, login, protected, register });
 
+pub use chat_appeals::ChatAppeals;
 pub use chat_moderation::ChatModeration;
 pub use home::Home;
 pub use login::Login;
@@ -0,0 +1,70 @@
+use bon::Builder;
+use maud::Render;
+
+use crate::paths::Route;
+use crate::types::Text;
+
+/// A file on a message: the thumbnail for images, a download link otherwise.
+#[derive(Clone, Debug, Builder)]
+pub struct ChatAttachment {
+    pub url: Text,
+    pub name: Text,
+    pub size: Text,
+    pub thumbnail_url: Option<Text>,
+}
+
+impl Render for ChatAttachment {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            @if let Some(thumbnail_url) = &self.thumbnail_url {
+                a class="chat-attachment chat-attachment-image"
+                    href=(&self.url)
+                    target="_blank"
+                    rel="noopener"
+                    title=(&self.name) {
+                    img src=(thumbnail_url) alt=(&self.name) loading="lazy";
+                }
+            } @else {
+                a class="chat-attachment" href=(&self.url) download=(&self.name) {
+                    (&self.name)
+                    " "
+                    span class="muted" { "(" (&self.size) ")" }
+                }
+            }
+        }
+    }
+}
+
+/// Plain multipart form; the handler redirects back to the chat and the
+/// new message reaches every tab over SSE.
+#[derive(Clone, Debug, Builder)]
+pub struct ChatAttachmentForm {
+    pub action: Route,
+    pub room_id: Text,
+}
+
+impl Render for ChatAttachmentForm {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            details class="chat-attachment-form" {
+                summary { "Attach files" }
+                form method="post" action=(self.action) enctype="multipart/form-data" {
+                    input type="hidden" name="room_id" value=(&self.room_id);
+                    label {
+                        "Files (up to 4, 10 MB each)"
+                        input type="file"
+                            name="file"
+                            accept="image/png,image/jpeg,image/gif,image/webp,application/pdf,text/plain"
+                            multiple
+                            required;
+                    }
+                    label {
+                        "Caption (optional)"
+                        input type="text" name="body";
+                    }
+                    button type="submit" { "Upload" }
+                }
+            }
+        }
+    }
+}
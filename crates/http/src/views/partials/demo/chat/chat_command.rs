@@ -0,0 +1,74 @@
+use bon::Builder;
+use maud::Render;
+
+use crate::paths::Route;
+use crate::types::Text;
+
+/// Slash-command output appended to the caller's history only. It has no
+/// message id, so read markers skip it.
+#[derive(Clone, Debug, Builder)]
+pub struct ChatCommandReply {
+    pub text: Text,
+}
+
+impl ChatCommandReply {
+    pub const SELECTOR: &'static str = ".chat-messages";
+}
+
+impl Render for ChatCommandReply {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            li class="chat-ephemeral" {
+                div class="meta" {
+                    span class="muted" { "Only visible to you" }
+                }
+                p { (&self.text) }
+            }
+        }
+    }
+}
+
+/// A moderation decision on the member's own message, shown like a command
+/// reply with a link to their appeals.
+#[derive(Clone, Debug, Builder)]
+pub struct ChatModerationNotice {
+    pub text: Text,
+    pub appeals: Route,
+}
+
+impl Render for ChatModerationNotice {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            li class="chat-ephemeral" {
+                div class="meta" {
+                    span class="muted" { "Only visible to you" }
+                }
+                p {
+                    (&self.text) " "
+                    a href=(self.appeals) { "Your appeals" }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct ChatRoomTopic {
+    pub topic: Option<Text>,
+}
+
+impl ChatRoomTopic {
+    pub const ID: &'static str = "chat-room-topic";
+}
+
+impl Render for ChatRoomTopic {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            p id=(Self::ID) class="muted chat-room-topic" hidden[self.topic.is_none()] {
+                @if let Some(topic) = &self.topic {
+                    "Topic: " (topic)
+                }
+            }
+        }
+    }
+}
@@ -3,13 +3,32 @@ use maud::{PreEscaped, Render};
 
 use crate::paths::Route;
 use crate::types::Text;
-use crate::views::partials::{ChatConnection, ChatPanel, ChatPanelRole, SectionHeader};
+use crate::views::partials::{
+    ChatAttachmentForm, ChatConnection, ChatPanel, ChatPanelRole, ChatPinnedStrip, ChatPollForm, ChatRoomTopic, ChatPresence, ChatRoomList, ChatSchedulePanel, ChatSearch, ChatSeenBy,
+    SectionHeader,
+};
 
 #[derive(Clone, Debug, Builder)]
 pub struct ChatDemoSection {
     pub room_id: Text,
     pub room_name: Text,
     pub messages: Vec<crate::views::partials::ChatMessage>,
+    #[builder(default)]
+    pub online: Vec<crate::views::partials::ChatPresenceMember>,
+    #[builder(default)]
+    pub rooms: Vec<crate::views::partials::ChatRoomUnread>,
+    #[builder(default)]
+    pub seen_by: Vec<Text>,
+    #[builder(default)]
+    pub schedules: Vec<crate::views::partials::ChatScheduleItem>,
+    #[builder(default)]
+    pub pins: Vec<crate::views::partials::ChatPinnedItem>,
+    /// Shows pin/unpin controls; the server still checks the room role.
+    #[builder(default)]
+    pub can_pin: bool,
+    /// Lets poll authors see their own close button.
+    pub user_id: Option<Text>,
+    pub topic: Option<Text>,
 }
 
 impl ChatDemoSection {
@@ -22,34 +41,92 @@ impl Render for ChatDemoSection {
             section id=(Self::ANCHOR_ID)
                 class="chat-panel"
                 data-signals=(format!(
-                    "{{roomId: '{}', body: '', botBody: '', sseConnected: false}}",
-                    self.room_id
+                    "{{roomId: '{}', body: '', lastMessageId: '', searchQuery: '', scheduleBody: '', scheduleAt: '', scheduleAtMs: 0, scheduleId: '', pinMessageId: '', reportMessageId: '', reportCategory: '', canPin: {}, userId: '{}', pollQuestion: '', pollOptions: '', pollMultiple: false, pollClosesAt: '', pollClosesAtMs: 0, pollMessageId: '', pollOptionIds: [], sseConnected: false}}",
+                    self.room_id,
+                    self.can_pin,
+                    self.user_id.as_ref().map(ToString::to_string).unwrap_or_default()
                 )) {
                 (SectionHeader::builder()
                     .title(Text::from("Live chat room"))
                     .subtitle(Text::from("Send messages as yourself or the demo user and watch SSE fanout."))
                     .action(maud::html! {
                         a class="button secondary" href=(Route::ChatModeration) { "Moderation queue" }
+                        details class="dropdown chat-export" {
+                            summary role="button" class="secondary" { "Export" }
+                            ul {
+                                @for format in [
+                                    app::chat::ExportFormat::Json,
+                                    app::chat::ExportFormat::Csv,
+                                    app::chat::ExportFormat::Ndjson,
+                                ] {
+                                    li {
+                                        a href=(Route::ChatExport.with_query(&format!(
+                                            "room_id={}&format={}",
+                                            self.room_id,
+                                            format
+                                        ))) download { (format.to_string().to_uppercase()) }
+                                    }
+                                }
+                            }
+                        }
+                    })
+                    .meta(maud::html! {
+                        p class="muted" { "Room: " (&self.room_name) }
+                        (ChatRoomTopic::builder()
+                            .maybe_topic(self.topic.clone())
+                            .build()
+                            .render())
                     })
-                    .meta(maud::html! { p class="muted" { "Room: " (&self.room_name) } })
                     .build()
                     .render())
                 (ChatConnection::builder()
                     .connected_signal(Text::from("$sseConnected"))
                     .build()
                     .render())
+                (ChatRoomList::builder()
+                    .rooms(self.rooms.clone())
+                    .build()
+                    .render())
+                (ChatPresence::builder()
+                    .room_id(self.room_id.clone())
+                    .members(self.online.clone())
+                    .build()
+                    .render())
+                (ChatSearch::builder()
+                    .action(Route::ChatSearch)
+                    .build()
+                    .render())
+                (ChatPinnedStrip::builder()
+                    .pins(self.pins.clone())
+                    .build()
+                    .render())
                 div class="chat-columns" {
                     (ChatPanel::builder()
+                        .room_id(self.room_id.clone())
                         .role(ChatPanelRole::You)
                         .messages(self.messages.clone())
                         .build()
                         .render())
-                    (ChatPanel::builder()
-                        .role(ChatPanelRole::Demo)
-                        .messages(self.messages.clone())
-                        .build()
-                        .render())
                 }
+                (ChatSeenBy::builder()
+                    .room_id(self.room_id.clone())
+                    .readers(self.seen_by.clone())
+                    .build()
+                    .render())
+                (ChatAttachmentForm::builder()
+                    .action(Route::ChatAttachments)
+                    .room_id(self.room_id.clone())
+                    .build()
+                    .render())
+                (ChatPollForm::builder()
+                    .action(Route::ChatPoll)
+                    .build()
+                    .render())
+                (ChatSchedulePanel::builder()
+                    .action(Route::ChatSchedule)
+                    .schedules(self.schedules.clone())
+                    .build()
+                    .render())
                 script {
                     (PreEscaped(r#"
 (() => {
@@ -0,0 +1,44 @@
+use bon::Builder;
+use maud::Render;
+
+use crate::types::Text;
+
+/// Card for the first link in a message. Rendered with the message from
+/// the cache, or appended over SSE once the link has been fetched.
+#[derive(Clone, Debug, Builder)]
+pub struct ChatLinkPreview {
+    pub url: Text,
+    pub title: Option<Text>,
+    pub description: Option<Text>,
+    pub image_url: Option<Text>,
+}
+
+impl ChatLinkPreview {
+    /// Every rendered copy of the message the card belongs to.
+    pub fn message_selector(message_id: &Text) -> String {
+        format!("li.chat-message[data-message-id='{}']", message_id)
+    }
+}
+
+impl Render for ChatLinkPreview {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            a class="chat-link-preview"
+                href=(&self.url)
+                target="_blank"
+                rel="noopener noreferrer nofollow" {
+                @if let Some(image_url) = &self.image_url {
+                    img src=(image_url) alt="" loading="lazy" referrerpolicy="no-referrer";
+                }
+                div {
+                    @if let Some(title) = &self.title {
+                        strong { (title) }
+                    }
+                    @if let Some(description) = &self.description {
+                        p class="muted" { (description) }
+                    }
+                }
+            }
+        }
+    }
+}
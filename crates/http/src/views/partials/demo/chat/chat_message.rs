@@ -1,5 +1,6 @@
 use bon::Builder;
 use maud::Render;
+use crate::paths::Route;
 use crate::types::Text;
 
 #[derive(Clone, Debug, Builder)]
@@ -9,32 +10,131 @@ pub struct ChatMessage {
     pub timestamp: Text,
     pub body: Text,
     pub status: Text,
+    pub poll: Option<super::chat_poll::ChatPoll>,
+    pub preview: Option<super::chat_link_preview::ChatLinkPreview>,
+    #[builder(default)]
+    pub attachments: Vec<super::chat_attachment::ChatAttachment>,
 }
 
 impl Render for ChatMessage {
     fn render(&self) -> maud::Markup {
         maud::html! {
-            li id=(format!("chat-message-{}", self.message_id)) class="chat-message" {
+            li id=(format!("chat-message-{}", self.message_id))
+                class="chat-message"
+                data-message-id=(&self.message_id) {
                 div class="meta" {
                     strong { (&self.author) }
                     span class="timestamp" { (&self.timestamp) }
                     span class="status" { (&self.status) }
+                    button type="button"
+                        class="chat-pin-button secondary outline"
+                        data-show="$canPin"
+                        data-on:click=(format!(
+                            "$pinMessageId = '{}'; @post('{}')",
+                            self.message_id,
+                            Route::ChatPin
+                        )) { "Pin" }
+                    details class="dropdown chat-report" {
+                        summary class="secondary outline" { "Report" }
+                        ul {
+                            @for category in app::chat::ReportCategory::ALL {
+                                li {
+                                    button type="button"
+                                        class="secondary outline"
+                                        data-on:click=(format!(
+                                            "$reportMessageId = '{}'; $reportCategory = '{}'; @post('{}')",
+                                            self.message_id,
+                                            category,
+                                            Route::ChatReport
+                                        )) { (category.label()) }
+                                }
+                            }
+                        }
+                    }
                 }
                 p { (&self.body) }
+                @if !self.attachments.is_empty() {
+                    div class="chat-attachments" {
+                        @for attachment in &self.attachments {
+                            (attachment.render())
+                        }
+                    }
+                }
+                @if let Some(poll) = &self.poll {
+                    (poll.render())
+                }
+                @if let Some(preview) = &self.preview {
+                    (preview.render())
+                }
             }
         }
     }
 }
 
+/// The status label of a rendered message, patched when reports hide it.
+#[derive(Clone, Debug, Builder)]
+pub struct ChatMessageStatus {
+    pub message_id: Text,
+    pub status: Text,
+}
+
+impl ChatMessageStatus {
+    pub fn selector(&self) -> String {
+        format!(
+            "li.chat-message[data-message-id='{}'] .status",
+            self.message_id
+        )
+    }
+}
+
+impl Render for ChatMessageStatus {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            span class="status" { (&self.status) }
+        }
+    }
+}
+
+/// Scrolls a history entry into view and flashes it. Messages older than the
+/// loaded history fall back to the `#chat-message-…` anchor link.
+pub(crate) fn jump_to_message_expression(message_id: &Text) -> String {
+    format!(
+        "const target = document.getElementById('chat-message-{}'); \
+         if (target) {{ evt.preventDefault(); \
+         target.scrollIntoView({{block: 'center', behavior: 'smooth'}}); \
+         target.classList.remove('chat-message-flash'); \
+         void target.offsetWidth; \
+         target.classList.add('chat-message-flash'); }}",
+        message_id
+    )
+}
+
 #[derive(Clone, Debug, Builder)]
 pub struct ChatMessages {
     pub messages: Vec<ChatMessage>,
+    /// When set, viewing the list or scrolling it to the bottom reports the
+    /// newest message as read.
+    pub read_action: Option<Route>,
+}
+
+impl ChatMessages {
+    fn read_expression(action: Route) -> String {
+        format!(
+            "el.scrollTop + el.clientHeight >= el.scrollHeight - 8 \
+             && el.querySelector('li.chat-message:last-of-type') \
+             && ($lastMessageId = el.querySelector('li.chat-message:last-of-type').dataset.messageId, \
+             @post('{}'))",
+            action
+        )
+    }
 }
 
 impl Render for ChatMessages {
     fn render(&self) -> maud::Markup {
         maud::html! {
-            ul class="chat-messages" {
+            ul class="chat-messages"
+                data-init=[self.read_action.map(Self::read_expression)]
+                "data-on:scroll__debounce.400ms"=[self.read_action.map(Self::read_expression)] {
                 @if self.messages.is_empty() {
                     li class="muted" { "No messages yet." }
                 } @else {
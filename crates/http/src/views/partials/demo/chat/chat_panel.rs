@@ -8,62 +8,67 @@ use crate::types::Text;
 #[derive(Clone, Copy, Debug)]
 pub enum ChatPanelRole {
     You,
-    Demo,
 }
 
 impl ChatPanelRole {
     fn title(&self) -> &'static str {
         match self {
             ChatPanelRole::You => "You",
-            ChatPanelRole::Demo => "Demo user",
         }
     }
 
     fn input_label(&self) -> &'static str {
         match self {
             ChatPanelRole::You => "Message as you",
-            ChatPanelRole::Demo => "Message as demo user",
         }
     }
 
     fn placeholder(&self) -> &'static str {
         match self {
-            ChatPanelRole::You => "Say something...",
-            ChatPanelRole::Demo => "Send as demo user...",
+            ChatPanelRole::You => "Say something, or /help...",
         }
     }
 
     fn action(&self) -> Route {
         match self {
             ChatPanelRole::You => Route::ChatMessages,
-            ChatPanelRole::Demo => Route::ChatMessagesDemo,
         }
     }
 
     fn input_signal(&self) -> &'static str {
         match self {
             ChatPanelRole::You => "body",
-            ChatPanelRole::Demo => "botBody",
+        }
+    }
+
+    fn typing_action(&self) -> Option<Route> {
+        match self {
+            ChatPanelRole::You => Some(Route::ChatTyping),
+        }
+    }
+
+    fn read_action(&self) -> Option<Route> {
+        match self {
+            ChatPanelRole::You => Some(Route::ChatRead),
         }
     }
 
     fn button_label(&self) -> &'static str {
         match self {
             ChatPanelRole::You => "Send",
-            ChatPanelRole::Demo => "Send as demo",
         }
     }
 
     fn button_class(&self) -> Option<&'static str> {
         match self {
             ChatPanelRole::You => None,
-            ChatPanelRole::Demo => Some("secondary"),
         }
     }
 }
 
 #[derive(Clone, Debug, Builder)]
 pub struct ChatPanel {
+    pub room_id: Text,
     pub role: ChatPanelRole,
     pub messages: Vec<crate::views::partials::ChatMessage>,
 }
@@ -75,8 +80,10 @@ impl Render for ChatPanel {
         maud::html! {
             div class="chat-stack" {
                 (ChatWindow::builder()
+                    .room_id(self.room_id.clone())
                     .maybe_title(Some(Text::from(self.role.title())))
                     .messages(self.messages.clone())
+                    .maybe_read_action(self.role.read_action())
                     .build()
                     .render())
                 form method="post"
@@ -91,6 +98,10 @@ impl Render for ChatPanel {
                             name="body"
                             placeholder=(self.role.placeholder())
                             data-bind=(input_signal)
+                            "data-on:input__throttle.1500ms"=[self
+                                .role
+                                .typing_action()
+                                .map(|route| format!("@post('{}')", route))]
                             required;
                     }
                     @if let Some(class) = self.role.button_class() {
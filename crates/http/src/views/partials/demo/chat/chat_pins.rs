@@ -0,0 +1,62 @@
+use bon::Builder;
+use maud::Render;
+
+use crate::paths::Route;
+use crate::types::Text;
+
+use super::chat_message::jump_to_message_expression;
+
+#[derive(Clone, Debug, Builder)]
+pub struct ChatPinnedItem {
+    pub message_id: Text,
+    pub author: Text,
+    pub body: Text,
+    pub pinned_by: Text,
+}
+
+impl Render for ChatPinnedItem {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            li class="chat-pinned-item" {
+                a href=(format!("#chat-message-{}", self.message_id))
+                    data-on:click=(jump_to_message_expression(&self.message_id)) {
+                    strong { (&self.author) }
+                    " "
+                    span { (&self.body) }
+                }
+                span class="muted" { "Pinned by " (&self.pinned_by) }
+                button type="button"
+                    class="secondary outline"
+                    data-show="$canPin"
+                    data-on:click=(format!(
+                        "$pinMessageId = '{}'; @post('{}')",
+                        self.message_id,
+                        Route::ChatUnpin
+                    )) { "Unpin" }
+            }
+        }
+    }
+}
+
+/// Pinned messages shown above the history; re-sent whole on every pin or
+/// unpin.
+#[derive(Clone, Debug, Builder)]
+pub struct ChatPinnedStrip {
+    pub pins: Vec<ChatPinnedItem>,
+}
+
+impl ChatPinnedStrip {
+    pub const ID: &'static str = "chat-pinned";
+}
+
+impl Render for ChatPinnedStrip {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            ul id=(Self::ID) class="chat-pinned" hidden[self.pins.is_empty()] {
+                @for pin in &self.pins {
+                    (pin.render())
+                }
+            }
+        }
+    }
+}
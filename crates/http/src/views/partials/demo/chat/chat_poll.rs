@@ -0,0 +1,165 @@
+use bon::Builder;
+use maud::Render;
+
+use crate::paths::Route;
+use crate::types::Text;
+
+#[derive(Clone, Debug, Builder)]
+pub struct ChatPollOption {
+    pub option_id: Text,
+    pub label: Text,
+    pub votes: u64,
+    /// Votes across all options, for the bar.
+    pub total_votes: u64,
+}
+
+impl Render for ChatPollOption {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            span class="chat-poll-label" {
+                (&self.label)
+                " "
+                span class="muted" { (self.votes) }
+            }
+            progress value=(self.votes) max=(self.total_votes.max(1)) {}
+        }
+    }
+}
+
+/// Poll block rendered inside its question message. Vote and close patches
+/// replace every copy by `data-poll-id`, so both chat panels stay in sync.
+#[derive(Clone, Debug, Builder)]
+pub struct ChatPoll {
+    pub message_id: Text,
+    pub author_id: Text,
+    pub options: Vec<ChatPollOption>,
+    pub voters: u64,
+    #[builder(default)]
+    pub multiple: bool,
+    #[builder(default)]
+    pub closed: bool,
+    pub closes_at: Option<Text>,
+}
+
+impl ChatPoll {
+    pub fn selector(message_id: &Text) -> String {
+        format!(".chat-poll[data-poll-id='{}']", message_id)
+    }
+
+    fn vote_expression(
+        &self,
+        option_ids: &str,
+    ) -> String {
+        format!(
+            "$pollMessageId = '{}'; $pollOptionIds = {}; @post('{}')",
+            self.message_id,
+            option_ids,
+            Route::ChatPollVote
+        )
+    }
+}
+
+impl Render for ChatPoll {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            div class="chat-poll" data-poll-id=(&self.message_id) {
+                ul {
+                    @for option in &self.options {
+                        li {
+                            @if !self.closed && self.multiple {
+                                input type="checkbox" value=(&option.option_id);
+                            }
+                            (option.render())
+                            @if !self.closed && !self.multiple {
+                                button type="button"
+                                    class="secondary outline"
+                                    data-on:click=(self.vote_expression(&format!(
+                                        "['{}']",
+                                        option.option_id
+                                    ))) { "Vote" }
+                            }
+                        }
+                    }
+                }
+                div class="meta" {
+                    span class="muted" {
+                        (self.voters) " voted"
+                        @if self.closed {
+                            " · final results"
+                        } @else if let Some(closes_at) = &self.closes_at {
+                            " · closes " (closes_at) " UTC"
+                        }
+                    }
+                    @if !self.closed {
+                        @if self.multiple {
+                            button type="button"
+                                class="secondary"
+                                data-on:click=(self.vote_expression(
+                                    "Array.from(el.closest('.chat-poll').querySelectorAll('input[type=checkbox]:checked')).map((input) => input.value)"
+                                )) { "Vote" }
+                        }
+                        button type="button"
+                            class="secondary outline"
+                            data-show=(format!("$canPin || $userId === '{}'", self.author_id))
+                            data-on:click=(format!(
+                                "$pollMessageId = '{}'; @post('{}')",
+                                self.message_id,
+                                Route::ChatPollClose
+                            )) { "Close poll" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Compose form for a new poll: one option per line.
+#[derive(Clone, Debug, Builder)]
+pub struct ChatPollForm {
+    pub action: Route,
+}
+
+impl Render for ChatPollForm {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            details class="chat-poll-form" {
+                summary { "New poll" }
+                form data-on:submit=(format!(
+                    "$pollClosesAtMs = $pollClosesAt ? new Date($pollClosesAt).getTime() : 0; \
+                     @post('{}'); $pollQuestion = ''; $pollOptions = ''",
+                    self.action
+                )) {
+                    label {
+                        "Question"
+                        input type="text"
+                            name="pollQuestion"
+                            data-bind="pollQuestion"
+                            required;
+                    }
+                    label {
+                        "Options (one per line)"
+                        textarea name="pollOptions"
+                            rows="3"
+                            data-bind="pollOptions"
+                            required {}
+                    }
+                    div class="grid" {
+                        label {
+                            input type="checkbox"
+                                name="pollMultiple"
+                                data-bind="pollMultiple";
+                            "Allow multiple choices"
+                        }
+                        label {
+                            "Closes at (optional)"
+                            input type="datetime-local"
+                                name="pollClosesAt"
+                                data-bind="pollClosesAt";
+                        }
+                    }
+                    button type="submit" class="secondary" { "Post poll" }
+                }
+            }
+        }
+    }
+}
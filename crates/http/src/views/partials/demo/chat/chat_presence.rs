@@ -0,0 +1,65 @@
+use bon::Builder;
+use maud::Render;
+
+use crate::types::Text;
+
+#[derive(Clone, Debug, Builder)]
+pub struct ChatPresenceMember {
+    pub room_id: Text,
+    pub user_id: Text,
+    pub username: Text,
+}
+
+impl ChatPresenceMember {
+    pub fn selector(
+        room_id: &Text,
+        user_id: &Text,
+    ) -> String {
+        format!(
+            "{} [data-presence-room='{}'][data-presence-user='{}']",
+            ChatPresence::list_selector(room_id),
+            room_id,
+            user_id
+        )
+    }
+}
+
+impl Render for ChatPresenceMember {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            li class="chat-presence-member"
+                data-presence-room=(&self.room_id)
+                data-presence-user=(&self.user_id) {
+                (&self.username)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct ChatPresence {
+    pub room_id: Text,
+    pub members: Vec<ChatPresenceMember>,
+}
+
+impl ChatPresence {
+    /// The member list of one room's presence panel.
+    pub fn list_selector(room_id: &Text) -> String {
+        format!(".chat-presence[data-presence-room='{}']", room_id)
+    }
+}
+
+impl Render for ChatPresence {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            div class="chat-presence-panel" {
+                span class="muted" { "Online now" }
+                ul class="chat-presence" data-presence-room=(&self.room_id) aria-live="polite" {
+                    @for member in &self.members {
+                        (member.render())
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use bon::Builder;
+use maud::Render;
+
+use crate::types::Text;
+
+#[derive(Clone, Debug, Builder)]
+pub struct ChatRoomUnread {
+    pub room_name: Text,
+    pub unread: u64,
+}
+
+impl Render for ChatRoomUnread {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            li {
+                span { (&self.room_name) }
+                @if self.unread > 0 {
+                    span class="pill badge-secondary" title="Unread messages" {
+                        (self.unread)
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct ChatRoomList {
+    pub rooms: Vec<ChatRoomUnread>,
+}
+
+impl ChatRoomList {
+    pub const ID: &'static str = "chat-room-list";
+}
+
+impl Render for ChatRoomList {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            ul id=(Self::ID) class="chat-room-list" {
+                @for room in &self.rooms {
+                    (room.render())
+                }
+            }
+        }
+    }
+}
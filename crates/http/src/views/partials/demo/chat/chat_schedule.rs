@@ -0,0 +1,102 @@
+use bon::Builder;
+use maud::Render;
+
+use crate::paths::Route;
+use crate::types::Text;
+
+#[derive(Clone, Debug, Builder)]
+pub struct ChatScheduleItem {
+    pub schedule_id: Text,
+    pub send_at: Text,
+    pub body: Text,
+    pub cancel_action: Route,
+}
+
+impl Render for ChatScheduleItem {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            li class="chat-schedule" {
+                div class="meta" {
+                    span class="timestamp" { "Sends " (&self.send_at) " UTC" }
+                    button type="button"
+                        class="secondary outline"
+                        data-on:click=(format!(
+                            "$scheduleId = '{}'; @post('{}')",
+                            self.schedule_id,
+                            self.cancel_action
+                        )) { "Cancel" }
+                }
+                p { (&self.body) }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct ChatScheduleList {
+    pub schedules: Vec<ChatScheduleItem>,
+}
+
+impl ChatScheduleList {
+    pub const ID: &'static str = "chat-schedule-list";
+}
+
+impl Render for ChatScheduleList {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            ul id=(Self::ID) class="chat-schedule-list" {
+                @if self.schedules.is_empty() {
+                    li class="muted" { "Nothing scheduled." }
+                }
+                @for schedule in &self.schedules {
+                    (schedule.render())
+                }
+            }
+        }
+    }
+}
+
+/// Compose-now, post-later form plus the author's pending schedules. The
+/// local `datetime-local` value is converted to epoch millis client-side.
+#[derive(Clone, Debug, Builder)]
+pub struct ChatSchedulePanel {
+    pub action: Route,
+    pub schedules: Vec<ChatScheduleItem>,
+}
+
+impl Render for ChatSchedulePanel {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            details class="chat-schedule-panel" {
+                summary { "Scheduled messages" }
+                form data-on:submit=(format!(
+                    "$scheduleAtMs = new Date($scheduleAt).getTime(); @post('{}'); $scheduleBody = ''",
+                    self.action
+                )) {
+                    div class="grid" {
+                        label {
+                            "Message"
+                            input type="text"
+                                name="scheduleBody"
+                                placeholder="Post this later..."
+                                data-bind="scheduleBody"
+                                required;
+                        }
+                        label {
+                            "Send at"
+                            input type="datetime-local"
+                                name="scheduleAt"
+                                data-bind="scheduleAt"
+                                required;
+                        }
+                    }
+                    button type="submit" class="secondary" { "Schedule" }
+                }
+                (ChatScheduleList::builder()
+                    .schedules(self.schedules.clone())
+                    .build()
+                    .render())
+            }
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use bon::Builder;
+use maud::Render;
+
+use crate::paths::Route;
+use crate::types::Text;
+
+use super::chat_message::jump_to_message_expression;
+
+#[derive(Clone, Debug, Builder)]
+pub struct ChatSearchSegment {
+    pub text: Text,
+    pub matched: bool,
+}
+
+impl Render for ChatSearchSegment {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            @if self.matched {
+                mark { (&self.text) }
+            } @else {
+                (&self.text)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct ChatSearchHit {
+    pub message_id: Text,
+    pub room_name: Text,
+    pub timestamp: Text,
+    pub excerpt: Vec<ChatSearchSegment>,
+}
+
+impl Render for ChatSearchHit {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            li class="chat-search-hit" {
+                a href=(format!("#chat-message-{}", self.message_id))
+                    data-on:click=(jump_to_message_expression(&self.message_id)) {
+                    div class="meta" {
+                        strong { (&self.room_name) }
+                        span class="timestamp" { (&self.timestamp) }
+                    }
+                    p {
+                        @for segment in &self.excerpt {
+                            (segment.render())
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct ChatSearchResults {
+    pub query: Option<Text>,
+    pub hits: Vec<ChatSearchHit>,
+}
+
+impl ChatSearchResults {
+    pub const ID: &'static str = "chat-search-results";
+}
+
+impl Render for ChatSearchResults {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            ul id=(Self::ID) class="chat-search-results" aria-live="polite" {
+                @if let Some(query) = &self.query {
+                    @if self.hits.is_empty() {
+                        li class="muted" { "No messages match \u{201c}" (query) "\u{201d}." }
+                    }
+                    @for hit in &self.hits {
+                        (hit.render())
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Builder)]
+pub struct ChatSearch {
+    pub action: Route,
+}
+
+impl Render for ChatSearch {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            div class="chat-search" {
+                label {
+                    "Search messages"
+                    input type="search"
+                        name="searchQuery"
+                        placeholder="Search rooms you belong to..."
+                        data-bind="searchQuery"
+                        "data-on:input__debounce.300ms"=(format!("@get('{}')", self.action));
+                }
+                (ChatSearchResults::builder()
+                    .hits(Vec::new())
+                    .build()
+                    .render())
+            }
+        }
+    }
+}
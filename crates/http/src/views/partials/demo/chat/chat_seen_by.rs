@@ -0,0 +1,42 @@
+use bon::Builder;
+use maud::Render;
+
+use crate::types::Text;
+
+#[derive(Clone, Debug, Builder)]
+pub struct ChatSeenBy {
+    pub room_id: Text,
+    pub readers: Vec<Text>,
+}
+
+impl ChatSeenBy {
+    /// Keyed by room so a receipt never lands in another room's strip.
+    pub fn id(room_id: &Text) -> String {
+        format!("chat-seen-by-{}", room_id)
+    }
+}
+
+impl Render for ChatSeenBy {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            div id=(Self::id(&self.room_id)) class="chat-seen-by" {
+                @if !self.readers.is_empty() {
+                    span class="muted" { "Seen by" }
+                    @for reader in &self.readers {
+                        span class="chat-avatar" title=(reader) {
+                            (initial(reader))
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn initial(name: &Text) -> String {
+    name.to_string()
+        .chars()
+        .next()
+        .map(|value| value.to_uppercase().to_string())
+        .unwrap_or_default()
+}
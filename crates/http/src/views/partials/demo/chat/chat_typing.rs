@@ -0,0 +1,44 @@
+use bon::Builder;
+use maud::Render;
+
+use crate::types::Text;
+
+#[derive(Clone, Debug, Builder)]
+pub struct ChatTyping {
+    pub room_id: Text,
+    pub user_id: Text,
+    pub username: Text,
+}
+
+impl ChatTyping {
+    /// The indicator lists of one room's chat windows.
+    pub fn list_selector(room_id: &Text) -> String {
+        format!(".chat-typing[data-typing-room='{}']", room_id)
+    }
+
+    /// Matches every rendered indicator for this user in this room; both chat
+    /// panels carry a copy, so removal targets an attribute rather than an id.
+    pub fn selector(
+        room_id: &Text,
+        user_id: &Text,
+    ) -> String {
+        format!(
+            "{} [data-typing-room='{}'][data-typing-user='{}']",
+            Self::list_selector(room_id),
+            room_id,
+            user_id
+        )
+    }
+}
+
+impl Render for ChatTyping {
+    fn render(&self) -> maud::Markup {
+        maud::html! {
+            li class="chat-typing-entry muted"
+                data-typing-room=(&self.room_id)
+                data-typing-user=(&self.user_id) {
+                (&self.username) " is typing…"
+            }
+        }
+    }
+}
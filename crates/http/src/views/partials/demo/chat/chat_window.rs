@@ -6,8 +6,10 @@ use crate::types::Text;
 
 #[derive(Clone, Debug, Builder)]
 pub struct ChatWindow {
+    pub room_id: Text,
     pub title: Option<Text>,
     pub messages: Vec<crate::views::partials::ChatMessage>,
+    pub read_action: Option<crate::paths::Route>,
 }
 
 impl Render for ChatWindow {
@@ -21,8 +23,10 @@ impl Render for ChatWindow {
                 }
                 (ChatMessages::builder()
                     .messages(self.messages.clone())
+                    .maybe_read_action(self.read_action)
                     .build()
                     .render())
+                ul class="chat-typing" data-typing-room=(&self.room_id) aria-live="polite" {}
             }
         }
     }
@@ -1,7 +1,18 @@
-moddef::moddef!(mod { chat_connection, chat_demo_section, chat_message, chat_panel, chat_window });
+moddef::moddef!(mod { chat_attachment, chat_command, chat_connection, chat_demo_section, chat_link_preview, chat_message, chat_panel, chat_pins, chat_poll, chat_presence, chat_room_list, chat_schedule, chat_search, chat_seen_by, chat_typing, chat_window });
 
+pub use chat_attachment::{ChatAttachment, ChatAttachmentForm};
 pub use chat_connection::ChatConnection;
 pub use chat_demo_section::ChatDemoSection;
-pub use chat_message::{ChatMessage, ChatMessages};
+pub use chat_link_preview::ChatLinkPreview;
+pub use chat_message::{ChatMessage, ChatMessageStatus, ChatMessages};
 pub use chat_panel::{ChatPanel, ChatPanelRole};
+pub use chat_presence::{ChatPresence, ChatPresenceMember};
+pub use chat_room_list::{ChatRoomList, ChatRoomUnread};
+pub use chat_command::{ChatCommandReply, ChatModerationNotice, ChatRoomTopic};
+pub use chat_pins::{ChatPinnedItem, ChatPinnedStrip};
+pub use chat_poll::{ChatPoll, ChatPollForm, ChatPollOption};
+pub use chat_schedule::{ChatScheduleItem, ChatScheduleList, ChatSchedulePanel};
+pub use chat_search::{ChatSearch, ChatSearchHit, ChatSearchResults, ChatSearchSegment};
+pub use chat_seen_by::ChatSeenBy;
+pub use chat_typing::ChatTyping;
 pub use chat_window::ChatWindow;
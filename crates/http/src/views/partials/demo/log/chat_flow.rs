@@ -74,7 +74,7 @@ fn sender_pill(entry: &TraceEntry) -> Pill {
     let sender = ChatSender::from_entry(entry);
     let (label, kind) = match sender {
         ChatSender::You => (Text::from("You"), BadgeKind::You),
-        ChatSender::Demo => (Text::from("Demo"), BadgeKind::Demo),
+        ChatSender::Bot => (Text::from("Bot"), BadgeKind::Demo),
         ChatSender::Unknown => (Text::from("User"), BadgeKind::Secondary),
     };
     Pill::badge(label, kind)
@@ -100,7 +100,7 @@ fn user_pill(entry: &TraceEntry) -> Pill {
     let sender = ChatSender::from_entry(entry);
     let (label, kind) = match sender {
         ChatSender::You => (format!("You ({short_id})"), BadgeKind::You),
-        ChatSender::Demo => (format!("Demo ({short_id})"), BadgeKind::Demo),
+        ChatSender::Bot => (format!("Bot ({short_id})"), BadgeKind::Demo),
         ChatSender::Unknown => (format!("User ({short_id})"), BadgeKind::Secondary),
     };
     Pill::badge(label, kind)
@@ -109,7 +109,7 @@ fn user_pill(entry: &TraceEntry) -> Pill {
 #[derive(Clone, Copy, Debug)]
 enum ChatSender {
     You,
-    Demo,
+    Bot,
     Unknown,
 }
 
@@ -129,15 +129,15 @@ impl ChatSender {
 enum ChatSenderKnown {
     #[strum(serialize = "you")]
     You,
-    #[strum(serialize = "demo")]
-    Demo,
+    #[strum(serialize = "bot")]
+    Bot,
 }
 
 impl From<ChatSenderKnown> for ChatSender {
     fn from(kind: ChatSenderKnown) -> Self {
         match kind {
             ChatSenderKnown::You => Self::You,
-            ChatSenderKnown::Demo => Self::Demo,
+            ChatSenderKnown::Bot => Self::Bot,
         }
     }
 }
@@ -2,8 +2,9 @@ use bon::Builder;
 use maud::Render;
 use crate::types::Text;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Default)]
 pub enum TableVariant {
+    #[default]
     Default,
     ChatFlow,
 }
@@ -17,12 +18,6 @@ impl TableVariant {
     }
 }
 
-impl Default for TableVariant {
-    fn default() -> Self {
-        TableVariant::Default
-    }
-}
-
 #[derive(Clone, Debug, Builder)]
 pub struct DataTable {
     pub headers: Vec<Text>,
@@ -145,7 +145,7 @@ fn compact_fields(entry: &TraceEntry) -> Vec<Pill> {
         ) {
             continue;
         }
-        extras.push(format!("{}={}", name.to_string(), value.to_string()));
+        extras.push(format!("{}={}", name, value));
     }
     if !extras.is_empty() {
         let extra = extras.into_iter().take(2).collect::<Vec<_>>().join(" · ");
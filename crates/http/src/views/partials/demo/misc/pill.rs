@@ -147,8 +147,9 @@ impl PillColor {
     }
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Default)]
 pub enum PillVariant {
+    #[default]
     Plain,
     Method(MethodKind),
     Status(StatusKind),
@@ -174,12 +175,6 @@ impl PillVariant {
     }
 }
 
-impl Default for PillVariant {
-    fn default() -> Self {
-        PillVariant::Plain
-    }
-}
-
 #[derive(Clone, Debug, Builder)]
 pub struct Pill {
     pub text: Text,
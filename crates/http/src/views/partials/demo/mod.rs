@@ -4,7 +4,7 @@ mod support;
 mod layout;
 pub(super) mod misc;
 
-pub use chat::{ChatConnection, ChatDemoSection, ChatMessage, ChatMessages, ChatPanel, ChatPanelRole, ChatWindow};
+pub use chat::{ChatAttachment, ChatAttachmentForm, ChatConnection, ChatDemoSection, ChatLinkPreview, ChatMessage, ChatMessageStatus, ChatMessages, ChatModerationNotice, ChatPanel, ChatPanelRole, ChatPresence, ChatPresenceMember, ChatRoomList, ChatCommandReply, ChatPinnedItem, ChatPinnedStrip, ChatPoll, ChatPollForm, ChatPollOption, ChatRoomTopic, ChatRoomUnread, ChatScheduleItem, ChatScheduleList, ChatSchedulePanel, ChatSearch, ChatSearchHit, ChatSearchResults, ChatSearchSegment, ChatSeenBy, ChatTyping, ChatWindow};
 pub use log::{ChatFlow, LiveLog, NetworkLog, TraceLog};
 pub use support::{AuthStatus, BoundaryCheck, DbCheck, KeyValueList, RequestMeta, SessionStatus, StatusCard};
 pub use layout::{CtaRow, DemoResultPlaceholder, DemoSection, DiagramPanel, DiagramRow, DiagramStatus, FeatureAccent, FeatureCard, FeatureGallery, HomeHero, SectionHeader};
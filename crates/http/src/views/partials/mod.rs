@@ -3,8 +3,9 @@ mod error;
 pub mod components;
 
 pub use demo::{
-    AuthStatus, BoundaryCheck, ChatConnection, ChatDemoSection, ChatFlow, ChatMessage,
-    ChatMessages, ChatPanel, ChatPanelRole, ChatWindow, CtaRow, DbCheck,
+    AuthStatus, BoundaryCheck, ChatAttachment, ChatAttachmentForm, ChatConnection, ChatDemoSection, ChatFlow, ChatLinkPreview, ChatMessage, ChatMessageStatus,
+    ChatMessages, ChatModerationNotice, ChatPanel, ChatPanelRole, ChatPresence, ChatPresenceMember, ChatRoomList, ChatCommandReply, ChatPinnedItem, ChatPinnedStrip, ChatPoll, ChatPollForm, ChatPollOption, ChatRoomTopic, ChatRoomUnread, ChatScheduleItem, ChatScheduleList, ChatSchedulePanel, ChatSearch, ChatSearchHit, ChatSearchResults, ChatSearchSegment,
+    ChatSeenBy, ChatTyping, ChatWindow, CtaRow, DbCheck,
     DemoResultPlaceholder, DemoSection, DiagramPanel, DiagramRow, DiagramStatus,
     FeatureAccent, FeatureCard, FeatureGallery, HomeHero, KeyValueList, LiveLog,
     ModerationAction, NetworkLog, Ping, RequestMeta, SectionHeader, SessionStatus,
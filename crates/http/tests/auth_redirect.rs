@@ -178,7 +178,7 @@ impl app::chat::Repository for ChatRepo {
         &self,
         _room_id: &domain_chat::RoomId,
         _user_id: &domain_chat::UserId,
-        _role: &str,
+        _role: app::chat::RoomRole,
     ) -> app::chat::Result<()> {
         Ok(())
     }
@@ -191,6 +191,13 @@ impl app::chat::Repository for ChatRepo {
         Ok(true)
     }
 
+    async fn room_members(
+        &self,
+        _room_id: &domain_chat::RoomId,
+    ) -> app::chat::Result<Vec<domain_chat::UserId>> {
+        Ok(Vec::new())
+    }
+
     async fn update_message_status(
         &self,
         _message_id: &domain_chat::MessageId,
@@ -198,6 +205,443 @@ impl app::chat::Repository for ChatRepo {
     ) -> app::chat::Result<()> {
         Ok(())
     }
+
+    async fn mark_read(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _user_id: &domain_chat::UserId,
+        _message_id: &domain_chat::MessageId,
+    ) -> app::chat::Result<bool> {
+        Ok(false)
+    }
+
+    async fn list_unread(
+        &self,
+        _user_id: &domain_chat::UserId,
+    ) -> app::chat::Result<Vec<app::chat::RoomUnread>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_readers(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _message_id: &domain_chat::MessageId,
+    ) -> app::chat::Result<Vec<domain_chat::UserId>> {
+        Ok(Vec::new())
+    }
+
+    async fn search_messages(
+        &self,
+        _user_id: &domain_chat::UserId,
+        _query: &app::chat::SearchQuery,
+        _limit: usize,
+    ) -> app::chat::Result<Vec<app::chat::SearchHit>> {
+        Ok(Vec::new())
+    }
+
+    async fn member_role(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _user_id: &domain_chat::UserId,
+    ) -> app::chat::Result<Option<app::chat::RoomRole>> {
+        Ok(None)
+    }
+
+    async fn export_messages(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _after: Option<&app::chat::ExportCursor>,
+        _limit: usize,
+    ) -> app::chat::Result<Vec<app::chat::ExportRecord>> {
+        Ok(Vec::new())
+    }
+
+    async fn save_retention(
+        &self,
+        _retention: &app::chat::RoomRetention,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn clear_retention(
+        &self,
+        _room_id: &domain_chat::RoomId,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn find_retention(
+        &self,
+        _room_id: &domain_chat::RoomId,
+    ) -> app::chat::Result<Option<app::chat::RoomRetention>> {
+        Ok(None)
+    }
+
+    async fn list_retention(&self) -> app::chat::Result<Vec<app::chat::RoomRetention>> {
+        Ok(Vec::new())
+    }
+
+    async fn count_expired(
+        &self,
+        _retention: &app::chat::RoomRetention,
+        _now: std::time::SystemTime,
+    ) -> app::chat::Result<u64> {
+        Ok(0)
+    }
+
+    async fn purge_expired(
+        &self,
+        _retention: &app::chat::RoomRetention,
+        _now: std::time::SystemTime,
+        _limit: usize,
+    ) -> app::chat::Result<u64> {
+        Ok(0)
+    }
+
+    async fn insert_schedule(
+        &self,
+        _schedule: &domain_chat::ScheduledMessage,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn find_schedule(
+        &self,
+        _schedule_id: &domain_chat::ScheduleId,
+    ) -> app::chat::Result<Option<domain_chat::ScheduledMessage>> {
+        Ok(None)
+    }
+
+    async fn list_schedules(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _user_id: &domain_chat::UserId,
+    ) -> app::chat::Result<Vec<domain_chat::ScheduledMessage>> {
+        Ok(Vec::new())
+    }
+
+    async fn cancel_schedule(
+        &self,
+        _schedule_id: &domain_chat::ScheduleId,
+    ) -> app::chat::Result<bool> {
+        Ok(false)
+    }
+
+    async fn claim_due_schedules(
+        &self,
+        _now: std::time::SystemTime,
+        _limit: usize,
+    ) -> app::chat::Result<Vec<domain_chat::ScheduledMessage>> {
+        Ok(Vec::new())
+    }
+
+    async fn finish_schedule(
+        &self,
+        _schedule_id: &domain_chat::ScheduleId,
+        _status: domain_chat::ScheduleStatus,
+        _message_id: Option<&domain_chat::MessageId>,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn insert_pin(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _message_id: &domain_chat::MessageId,
+        _pinned_by: &domain_chat::UserId,
+        _pinned_at: std::time::SystemTime,
+    ) -> app::chat::Result<bool> {
+        Ok(false)
+    }
+
+    async fn delete_pin(
+        &self,
+        _message_id: &domain_chat::MessageId,
+    ) -> app::chat::Result<bool> {
+        Ok(false)
+    }
+
+    async fn list_pins(
+        &self,
+        _room_id: &domain_chat::RoomId,
+    ) -> app::chat::Result<Vec<app::chat::PinnedMessage>> {
+        Ok(Vec::new())
+    }
+
+    async fn count_pins(
+        &self,
+        _room_id: &domain_chat::RoomId,
+    ) -> app::chat::Result<usize> {
+        Ok(0)
+    }
+
+    async fn insert_poll(
+        &self,
+        _poll: &domain_chat::Poll,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn find_poll(
+        &self,
+        _message_id: &domain_chat::MessageId,
+    ) -> app::chat::Result<Option<domain_chat::Poll>> {
+        Ok(None)
+    }
+
+    async fn poll_results(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _message_ids: &[domain_chat::MessageId],
+    ) -> app::chat::Result<Vec<app::chat::PollResults>> {
+        Ok(Vec::new())
+    }
+
+    async fn cast_vote(
+        &self,
+        _message_id: &domain_chat::MessageId,
+        _user_id: &domain_chat::UserId,
+        _option_ids: &[domain_chat::PollOptionId],
+        _voted_at: std::time::SystemTime,
+    ) -> app::chat::Result<bool> {
+        Ok(false)
+    }
+
+    async fn close_poll(
+        &self,
+        _message_id: &domain_chat::MessageId,
+        _closed_at: std::time::SystemTime,
+    ) -> app::chat::Result<bool> {
+        Ok(false)
+    }
+
+    async fn close_due_polls(
+        &self,
+        _now: std::time::SystemTime,
+    ) -> app::chat::Result<Vec<domain_chat::Poll>> {
+        Ok(Vec::new())
+    }
+
+    async fn set_room_topic(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _topic: Option<&domain_chat::RoomTopic>,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn find_room_topic(
+        &self,
+        _room_id: &domain_chat::RoomId,
+    ) -> app::chat::Result<Option<domain_chat::RoomTopic>> {
+        Ok(None)
+    }
+
+    async fn save_mute(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _user_id: &domain_chat::UserId,
+        _until: std::time::SystemTime,
+        _muted_by: Option<&domain_chat::UserId>,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn muted_until(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _user_id: &domain_chat::UserId,
+    ) -> app::chat::Result<Option<std::time::SystemTime>> {
+        Ok(None)
+    }
+
+    async fn trust_level(
+        &self,
+        _user_id: &domain_chat::UserId,
+    ) -> app::chat::Result<app::chat::TrustLevel> {
+        Ok(app::chat::TrustLevel::Standard)
+    }
+
+    async fn save_trust_level(
+        &self,
+        _user_id: &domain_chat::UserId,
+        _level: app::chat::TrustLevel,
+        _set_by: &domain_chat::UserId,
+        _set_at: std::time::SystemTime,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn room_moderators(
+        &self,
+        _room_id: &domain_chat::RoomId,
+    ) -> app::chat::Result<Vec<domain_chat::UserId>> {
+        Ok(Vec::new())
+    }
+
+    async fn moderated_rooms(
+        &self,
+        _user_id: &domain_chat::UserId,
+    ) -> app::chat::Result<Vec<domain_chat::RoomId>> {
+        Ok(Vec::new())
+    }
+
+    async fn recent_posts(
+        &self,
+        _user_id: &domain_chat::UserId,
+        _since: std::time::SystemTime,
+        _limit: usize,
+    ) -> app::chat::Result<Vec<app::chat::RecentPost>> {
+        Ok(Vec::new())
+    }
+
+    async fn record_flood_strike(
+        &self,
+        _user_id: &domain_chat::UserId,
+        _room_id: &domain_chat::RoomId,
+        _signal: app::chat::FloodSignal,
+        _at: std::time::SystemTime,
+        _since: std::time::SystemTime,
+    ) -> app::chat::Result<usize> {
+        Ok(1)
+    }
+
+    async fn insert_webhook(
+        &self,
+        _webhook: &domain_chat::Webhook,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn find_webhook(
+        &self,
+        _webhook_id: &domain_chat::WebhookId,
+    ) -> app::chat::Result<Option<domain_chat::Webhook>> {
+        Ok(None)
+    }
+
+    async fn list_webhooks(
+        &self,
+        _room_id: &domain_chat::RoomId,
+    ) -> app::chat::Result<Vec<domain_chat::Webhook>> {
+        Ok(Vec::new())
+    }
+
+    async fn delete_webhook(
+        &self,
+        _webhook_id: &domain_chat::WebhookId,
+    ) -> app::chat::Result<bool> {
+        Ok(false)
+    }
+
+    async fn enqueue_webhook_deliveries(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _event: domain_chat::WebhookEvent,
+        _payload: &domain_chat::WebhookPayload,
+        _now: std::time::SystemTime,
+    ) -> app::chat::Result<usize> {
+        Ok(0)
+    }
+
+    async fn claim_due_deliveries(
+        &self,
+        _now: std::time::SystemTime,
+        _limit: usize,
+    ) -> app::chat::Result<Vec<(domain_chat::Webhook, domain_chat::WebhookDelivery)>> {
+        Ok(Vec::new())
+    }
+
+    async fn save_delivery_attempt(
+        &self,
+        _delivery: &domain_chat::WebhookDelivery,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn list_deliveries(
+        &self,
+        _webhook_id: &domain_chat::WebhookId,
+        _limit: usize,
+    ) -> app::chat::Result<Vec<domain_chat::WebhookDelivery>> {
+        Ok(Vec::new())
+    }
+
+    async fn save_incoming_webhook(
+        &self,
+        _webhook: &domain_chat::IncomingWebhook,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn find_incoming_webhook(
+        &self,
+        _room_id: &domain_chat::RoomId,
+    ) -> app::chat::Result<Option<domain_chat::IncomingWebhook>> {
+        Ok(None)
+    }
+
+    async fn find_incoming_webhook_by_token(
+        &self,
+        _token: &domain_chat::IncomingWebhookToken,
+    ) -> app::chat::Result<Option<domain_chat::IncomingWebhook>> {
+        Ok(None)
+    }
+
+    async fn delete_incoming_webhook(
+        &self,
+        _room_id: &domain_chat::RoomId,
+    ) -> app::chat::Result<bool> {
+        Ok(false)
+    }
+
+    async fn find_link_previews(
+        &self,
+        _urls: &[domain_chat::PreviewUrl],
+    ) -> app::chat::Result<Vec<app::chat::CachedPreview>> {
+        Ok(Vec::new())
+    }
+
+    async fn save_link_preview(
+        &self,
+        _preview: &app::chat::CachedPreview,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+    async fn insert_attachment(
+        &self,
+        _attachment: &domain_chat::Attachment,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn find_attachment(
+        &self,
+        _attachment_id: &domain_chat::AttachmentId,
+    ) -> app::chat::Result<Option<domain_chat::Attachment>> {
+        Ok(None)
+    }
+
+    async fn list_attachments(
+        &self,
+        _message_ids: &[domain_chat::MessageId],
+    ) -> app::chat::Result<Vec<domain_chat::Attachment>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_orphaned_attachments(
+        &self,
+        _limit: usize,
+    ) -> app::chat::Result<Vec<app::chat::OrphanedAttachment>> {
+        Ok(Vec::new())
+    }
+
+    async fn delete_attachment(
+        &self,
+        _attachment_id: &domain_chat::AttachmentId,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
 }
 
 struct ModerationQueue;
@@ -207,27 +651,107 @@ impl app::chat::ModerationQueue for ModerationQueue {
     async fn enqueue(
         &self,
         _message_id: &domain_chat::MessageId,
-        _reason: &str,
+        _reason: &app::chat::ModerationReason,
     ) -> app::chat::Result<()> {
         Ok(())
     }
 
     async fn list_pending(
         &self,
+        _rooms: &[domain_chat::RoomId],
+        _room_id: Option<&domain_chat::RoomId>,
+        _reason: Option<&app::chat::ModerationReason>,
+        _now: std::time::SystemTime,
         _limit: usize,
     ) -> app::chat::Result<Vec<app::chat::ModerationItem>> {
         Ok(Vec::new())
     }
 
-    async fn complete(
+    async fn claim(
+        &self,
+        _message_id: &domain_chat::MessageId,
+        _reviewer_id: &domain_chat::UserId,
+        _now: std::time::SystemTime,
+        _until: std::time::SystemTime,
+    ) -> app::chat::Result<bool> {
+        Ok(true)
+    }
+
+    async fn release(
         &self,
         _message_id: &domain_chat::MessageId,
         _reviewer_id: &domain_chat::UserId,
-        _decision: app::chat::ModerationDecision,
-        _reason: Option<String>,
     ) -> app::chat::Result<()> {
         Ok(())
     }
+
+    async fn complete(
+        &self,
+        _message_id: &domain_chat::MessageId,
+        _reviewer_id: &domain_chat::UserId,
+        _decision: app::chat::ModerationDecision,
+        _reason: Option<app::chat::ModerationReason>,
+        _revision: Option<u64>,
+        _now: std::time::SystemTime,
+    ) -> app::chat::Result<bool> {
+        Ok(true)
+    }
+
+    async fn report(
+        &self,
+        _message_id: &domain_chat::MessageId,
+        _reporter_id: &domain_chat::UserId,
+        _category: app::chat::ReportCategory,
+        _reported_at: std::time::SystemTime,
+    ) -> app::chat::Result<app::chat::ReportTally> {
+        Ok(app::chat::ReportTally {
+            open_reports: 1,
+            counted: true,
+        })
+    }
+
+    async fn file_appeal(
+        &self,
+        _message_id: &domain_chat::MessageId,
+        _appellant_id: &domain_chat::UserId,
+        _statement: &app::chat::AppealStatement,
+        _filed_at: std::time::SystemTime,
+    ) -> app::chat::Result<bool> {
+        Ok(true)
+    }
+
+    async fn find_appeal(
+        &self,
+        _message_id: &domain_chat::MessageId,
+    ) -> app::chat::Result<Option<app::chat::Appeal>> {
+        Ok(None)
+    }
+
+    async fn list_appeals(
+        &self,
+        _rooms: &[domain_chat::RoomId],
+        _limit: usize,
+    ) -> app::chat::Result<Vec<app::chat::Appeal>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_removals(
+        &self,
+        _author_id: &domain_chat::UserId,
+        _limit: usize,
+    ) -> app::chat::Result<Vec<app::chat::Removal>> {
+        Ok(Vec::new())
+    }
+
+    async fn decide_appeal(
+        &self,
+        _message_id: &domain_chat::MessageId,
+        _reviewer_id: &domain_chat::UserId,
+        _decision: app::chat::AppealDecision,
+        _decided_at: std::time::SystemTime,
+    ) -> app::chat::Result<bool> {
+        Ok(true)
+    }
 }
 
 struct RateLimiter;
@@ -255,6 +779,74 @@ impl app::chat::AuditLog for AuditLog {
     }
 }
 
+struct Typing;
+
+impl app::chat::TypingStore for Typing {
+    fn start(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _user_id: &domain_chat::UserId,
+        _now: std::time::SystemTime,
+    ) -> app::chat::TypingChange {
+        app::chat::TypingChange::Started
+    }
+
+    fn stop(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _user_id: &domain_chat::UserId,
+    ) -> bool {
+        false
+    }
+
+    fn expire(
+        &self,
+        _now: std::time::SystemTime,
+    ) -> Vec<app::chat::TypingExpired> {
+        Vec::new()
+    }
+}
+
+struct Authors;
+
+#[async_trait]
+impl app::chat::AuthorDirectory for Authors {
+    async fn user_exists(
+        &self,
+        _user_id: &domain_chat::UserId,
+    ) -> app::chat::Result<bool> {
+        Ok(false)
+    }
+
+    async fn find_by_username(
+        &self,
+        _username: &domain::user::Username,
+    ) -> app::chat::Result<Option<domain_chat::UserId>> {
+        Ok(None)
+    }
+
+    async fn create_placeholder(
+        &self,
+        _username: &domain::user::Username,
+    ) -> app::chat::Result<domain_chat::UserId> {
+        Ok(domain_chat::UserId::new_v4())
+    }
+
+    async fn ensure_bot(
+        &self,
+        _username: &domain::user::Username,
+    ) -> app::chat::Result<domain_chat::UserId> {
+        Ok(domain_chat::UserId::new_v4())
+    }
+
+    async fn find_username(
+        &self,
+        _user_id: &domain_chat::UserId,
+    ) -> app::chat::Result<Option<domain::user::Username>> {
+        Ok(None)
+    }
+}
+
 struct Clock;
 
 impl app::chat::Clock for Clock {
@@ -273,6 +865,29 @@ impl app::chat::IdGenerator for Ids {
     fn new_message_id(&self) -> domain_chat::MessageId {
         domain_chat::MessageId::new_v4()
     }
+
+    fn new_schedule_id(&self) -> domain_chat::ScheduleId {
+        domain_chat::ScheduleId::new_v4()
+    }
+
+    fn new_poll_option_id(&self) -> domain_chat::PollOptionId {
+        domain_chat::PollOptionId::new_v4()
+    }
+
+    fn new_webhook_id(&self) -> domain_chat::WebhookId {
+        domain_chat::WebhookId::new_v4()
+    }
+
+    fn new_incoming_webhook_token(&self) -> domain_chat::IncomingWebhookToken {
+        domain_chat::IncomingWebhookToken::try_new(
+            uuid::Uuid::new_v4().simple().to_string(),
+        )
+        .expect("incoming webhook token")
+    }
+
+    fn new_attachment_id(&self) -> domain_chat::AttachmentId {
+        domain_chat::AttachmentId::new_v4()
+    }
 }
 
 fn test_app() -> axum::Router {
@@ -291,6 +906,8 @@ fn test_app() -> axum::Router {
         .with_moderation_queue(Arc::new(ModerationQueue))
         .with_rate_limiter(Arc::new(RateLimiter))
         .with_audit_log(Arc::new(AuditLog))
+        .with_typing_store(Arc::new(Typing))
+        .with_author_directory(Arc::new(Authors))
         .with_clock(Arc::new(Clock))
         .with_id_generator(Arc::new(Ids))
         .build();
@@ -324,6 +941,22 @@ async fn unauthenticated_chat_redirects_to_login() {
     assert_eq!(location, "/login?next=%2Fdemo%2Fchat");
 }
 
+#[tokio::test]
+async fn incoming_hook_skips_login_and_rejects_unknown_tokens() {
+    let app = test_app();
+    let response = app
+        .oneshot(
+            Request::post(format!("/hooks/chat/{}", "a".repeat(32)))
+                .header(axum::http::header::CONTENT_TYPE, "application/json")
+                .body(Body::from(r#"{"text":"build passed"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
 #[tokio::test]
 async fn login_redirects_to_next() {
     let app = test_app();
@@ -395,7 +1028,14 @@ async fn login_sets_session_cookie_and_allows_chat() {
         .await
         .unwrap();
 
-    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.status().is_redirection());
+    let location = response
+        .headers()
+        .get(axum::http::header::LOCATION)
+        .unwrap()
+        .to_str()
+        .unwrap();
+    assert_eq!(location, "/#chat-demo");
 }
 
 #[derive(Clone, Copy, Debug)]
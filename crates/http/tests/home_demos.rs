@@ -59,14 +59,7 @@ fn test_app() -> axum::Router {
     let trace_log = app_http::trace_log::TraceLogStore::builder()
         .with_sse(sse_registry.clone())
         .build();
-    let chat = app::chat::Service::builder()
-        .with_repo(Arc::new(ChatRepo))
-        .with_moderation_queue(Arc::new(ModerationQueue))
-        .with_rate_limiter(Arc::new(RateLimiter))
-        .with_audit_log(Arc::new(AuditLog))
-        .with_clock(Arc::new(Clock))
-        .with_id_generator(Arc::new(Ids))
-        .build();
+    let chat = chat_service();
     let state = app_http::State::builder()
         .with_user(user_service)
         .with_auth(auth_service)
@@ -79,6 +72,20 @@ fn test_app() -> axum::Router {
     app_http::router(state, session_store)
 }
 
+fn chat_service() -> app::chat::Service {
+    app::chat::Service::builder()
+        .with_repo(Arc::new(ChatRepo))
+        .with_moderation_queue(Arc::new(ModerationQueue))
+        .with_rate_limiter(Arc::new(RateLimiter))
+        .with_audit_log(Arc::new(AuditLog))
+        .with_typing_store(Arc::new(Typing))
+        .with_author_directory(Arc::new(Authors))
+        .with_clock(Arc::new(Clock))
+        .with_id_generator(Arc::new(Ids))
+        .with_link_preview_fetcher(Arc::new(Previews))
+        .build()
+}
+
 struct ChatRepo;
 
 #[async_trait]
@@ -130,7 +137,7 @@ impl app::chat::Repository for ChatRepo {
         &self,
         _room_id: &domain_chat::RoomId,
         _user_id: &domain_chat::UserId,
-        _role: &str,
+        _role: app::chat::RoomRole,
     ) -> app::chat::Result<()> {
         Ok(())
     }
@@ -143,6 +150,13 @@ impl app::chat::Repository for ChatRepo {
         Ok(true)
     }
 
+    async fn room_members(
+        &self,
+        _room_id: &domain_chat::RoomId,
+    ) -> app::chat::Result<Vec<domain_chat::UserId>> {
+        Ok(Vec::new())
+    }
+
     async fn update_message_status(
         &self,
         _message_id: &domain_chat::MessageId,
@@ -150,6 +164,443 @@ impl app::chat::Repository for ChatRepo {
     ) -> app::chat::Result<()> {
         Ok(())
     }
+
+    async fn mark_read(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _user_id: &domain_chat::UserId,
+        _message_id: &domain_chat::MessageId,
+    ) -> app::chat::Result<bool> {
+        Ok(false)
+    }
+
+    async fn list_unread(
+        &self,
+        _user_id: &domain_chat::UserId,
+    ) -> app::chat::Result<Vec<app::chat::RoomUnread>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_readers(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _message_id: &domain_chat::MessageId,
+    ) -> app::chat::Result<Vec<domain_chat::UserId>> {
+        Ok(Vec::new())
+    }
+
+    async fn search_messages(
+        &self,
+        _user_id: &domain_chat::UserId,
+        _query: &app::chat::SearchQuery,
+        _limit: usize,
+    ) -> app::chat::Result<Vec<app::chat::SearchHit>> {
+        Ok(Vec::new())
+    }
+
+    async fn member_role(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _user_id: &domain_chat::UserId,
+    ) -> app::chat::Result<Option<app::chat::RoomRole>> {
+        Ok(None)
+    }
+
+    async fn export_messages(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _after: Option<&app::chat::ExportCursor>,
+        _limit: usize,
+    ) -> app::chat::Result<Vec<app::chat::ExportRecord>> {
+        Ok(Vec::new())
+    }
+
+    async fn save_retention(
+        &self,
+        _retention: &app::chat::RoomRetention,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn clear_retention(
+        &self,
+        _room_id: &domain_chat::RoomId,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn find_retention(
+        &self,
+        _room_id: &domain_chat::RoomId,
+    ) -> app::chat::Result<Option<app::chat::RoomRetention>> {
+        Ok(None)
+    }
+
+    async fn list_retention(&self) -> app::chat::Result<Vec<app::chat::RoomRetention>> {
+        Ok(Vec::new())
+    }
+
+    async fn count_expired(
+        &self,
+        _retention: &app::chat::RoomRetention,
+        _now: std::time::SystemTime,
+    ) -> app::chat::Result<u64> {
+        Ok(0)
+    }
+
+    async fn purge_expired(
+        &self,
+        _retention: &app::chat::RoomRetention,
+        _now: std::time::SystemTime,
+        _limit: usize,
+    ) -> app::chat::Result<u64> {
+        Ok(0)
+    }
+
+    async fn insert_schedule(
+        &self,
+        _schedule: &domain_chat::ScheduledMessage,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn find_schedule(
+        &self,
+        _schedule_id: &domain_chat::ScheduleId,
+    ) -> app::chat::Result<Option<domain_chat::ScheduledMessage>> {
+        Ok(None)
+    }
+
+    async fn list_schedules(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _user_id: &domain_chat::UserId,
+    ) -> app::chat::Result<Vec<domain_chat::ScheduledMessage>> {
+        Ok(Vec::new())
+    }
+
+    async fn cancel_schedule(
+        &self,
+        _schedule_id: &domain_chat::ScheduleId,
+    ) -> app::chat::Result<bool> {
+        Ok(false)
+    }
+
+    async fn claim_due_schedules(
+        &self,
+        _now: std::time::SystemTime,
+        _limit: usize,
+    ) -> app::chat::Result<Vec<domain_chat::ScheduledMessage>> {
+        Ok(Vec::new())
+    }
+
+    async fn finish_schedule(
+        &self,
+        _schedule_id: &domain_chat::ScheduleId,
+        _status: domain_chat::ScheduleStatus,
+        _message_id: Option<&domain_chat::MessageId>,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn insert_pin(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _message_id: &domain_chat::MessageId,
+        _pinned_by: &domain_chat::UserId,
+        _pinned_at: std::time::SystemTime,
+    ) -> app::chat::Result<bool> {
+        Ok(false)
+    }
+
+    async fn delete_pin(
+        &self,
+        _message_id: &domain_chat::MessageId,
+    ) -> app::chat::Result<bool> {
+        Ok(false)
+    }
+
+    async fn list_pins(
+        &self,
+        _room_id: &domain_chat::RoomId,
+    ) -> app::chat::Result<Vec<app::chat::PinnedMessage>> {
+        Ok(Vec::new())
+    }
+
+    async fn count_pins(
+        &self,
+        _room_id: &domain_chat::RoomId,
+    ) -> app::chat::Result<usize> {
+        Ok(0)
+    }
+
+    async fn insert_poll(
+        &self,
+        _poll: &domain_chat::Poll,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn find_poll(
+        &self,
+        _message_id: &domain_chat::MessageId,
+    ) -> app::chat::Result<Option<domain_chat::Poll>> {
+        Ok(None)
+    }
+
+    async fn poll_results(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _message_ids: &[domain_chat::MessageId],
+    ) -> app::chat::Result<Vec<app::chat::PollResults>> {
+        Ok(Vec::new())
+    }
+
+    async fn cast_vote(
+        &self,
+        _message_id: &domain_chat::MessageId,
+        _user_id: &domain_chat::UserId,
+        _option_ids: &[domain_chat::PollOptionId],
+        _voted_at: std::time::SystemTime,
+    ) -> app::chat::Result<bool> {
+        Ok(false)
+    }
+
+    async fn close_poll(
+        &self,
+        _message_id: &domain_chat::MessageId,
+        _closed_at: std::time::SystemTime,
+    ) -> app::chat::Result<bool> {
+        Ok(false)
+    }
+
+    async fn close_due_polls(
+        &self,
+        _now: std::time::SystemTime,
+    ) -> app::chat::Result<Vec<domain_chat::Poll>> {
+        Ok(Vec::new())
+    }
+
+    async fn set_room_topic(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _topic: Option<&domain_chat::RoomTopic>,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn find_room_topic(
+        &self,
+        _room_id: &domain_chat::RoomId,
+    ) -> app::chat::Result<Option<domain_chat::RoomTopic>> {
+        Ok(None)
+    }
+
+    async fn save_mute(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _user_id: &domain_chat::UserId,
+        _until: std::time::SystemTime,
+        _muted_by: Option<&domain_chat::UserId>,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn muted_until(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _user_id: &domain_chat::UserId,
+    ) -> app::chat::Result<Option<std::time::SystemTime>> {
+        Ok(None)
+    }
+
+    async fn trust_level(
+        &self,
+        _user_id: &domain_chat::UserId,
+    ) -> app::chat::Result<app::chat::TrustLevel> {
+        Ok(app::chat::TrustLevel::Standard)
+    }
+
+    async fn save_trust_level(
+        &self,
+        _user_id: &domain_chat::UserId,
+        _level: app::chat::TrustLevel,
+        _set_by: &domain_chat::UserId,
+        _set_at: std::time::SystemTime,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn room_moderators(
+        &self,
+        _room_id: &domain_chat::RoomId,
+    ) -> app::chat::Result<Vec<domain_chat::UserId>> {
+        Ok(Vec::new())
+    }
+
+    async fn moderated_rooms(
+        &self,
+        _user_id: &domain_chat::UserId,
+    ) -> app::chat::Result<Vec<domain_chat::RoomId>> {
+        Ok(Vec::new())
+    }
+
+    async fn recent_posts(
+        &self,
+        _user_id: &domain_chat::UserId,
+        _since: std::time::SystemTime,
+        _limit: usize,
+    ) -> app::chat::Result<Vec<app::chat::RecentPost>> {
+        Ok(Vec::new())
+    }
+
+    async fn record_flood_strike(
+        &self,
+        _user_id: &domain_chat::UserId,
+        _room_id: &domain_chat::RoomId,
+        _signal: app::chat::FloodSignal,
+        _at: std::time::SystemTime,
+        _since: std::time::SystemTime,
+    ) -> app::chat::Result<usize> {
+        Ok(1)
+    }
+
+    async fn insert_webhook(
+        &self,
+        _webhook: &domain_chat::Webhook,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn find_webhook(
+        &self,
+        _webhook_id: &domain_chat::WebhookId,
+    ) -> app::chat::Result<Option<domain_chat::Webhook>> {
+        Ok(None)
+    }
+
+    async fn list_webhooks(
+        &self,
+        _room_id: &domain_chat::RoomId,
+    ) -> app::chat::Result<Vec<domain_chat::Webhook>> {
+        Ok(Vec::new())
+    }
+
+    async fn delete_webhook(
+        &self,
+        _webhook_id: &domain_chat::WebhookId,
+    ) -> app::chat::Result<bool> {
+        Ok(false)
+    }
+
+    async fn enqueue_webhook_deliveries(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _event: domain_chat::WebhookEvent,
+        _payload: &domain_chat::WebhookPayload,
+        _now: std::time::SystemTime,
+    ) -> app::chat::Result<usize> {
+        Ok(0)
+    }
+
+    async fn claim_due_deliveries(
+        &self,
+        _now: std::time::SystemTime,
+        _limit: usize,
+    ) -> app::chat::Result<Vec<(domain_chat::Webhook, domain_chat::WebhookDelivery)>> {
+        Ok(Vec::new())
+    }
+
+    async fn save_delivery_attempt(
+        &self,
+        _delivery: &domain_chat::WebhookDelivery,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn list_deliveries(
+        &self,
+        _webhook_id: &domain_chat::WebhookId,
+        _limit: usize,
+    ) -> app::chat::Result<Vec<domain_chat::WebhookDelivery>> {
+        Ok(Vec::new())
+    }
+
+    async fn save_incoming_webhook(
+        &self,
+        _webhook: &domain_chat::IncomingWebhook,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn find_incoming_webhook(
+        &self,
+        _room_id: &domain_chat::RoomId,
+    ) -> app::chat::Result<Option<domain_chat::IncomingWebhook>> {
+        Ok(None)
+    }
+
+    async fn find_incoming_webhook_by_token(
+        &self,
+        _token: &domain_chat::IncomingWebhookToken,
+    ) -> app::chat::Result<Option<domain_chat::IncomingWebhook>> {
+        Ok(None)
+    }
+
+    async fn delete_incoming_webhook(
+        &self,
+        _room_id: &domain_chat::RoomId,
+    ) -> app::chat::Result<bool> {
+        Ok(false)
+    }
+
+    async fn find_link_previews(
+        &self,
+        _urls: &[domain_chat::PreviewUrl],
+    ) -> app::chat::Result<Vec<app::chat::CachedPreview>> {
+        Ok(Vec::new())
+    }
+
+    async fn save_link_preview(
+        &self,
+        _preview: &app::chat::CachedPreview,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+    async fn insert_attachment(
+        &self,
+        _attachment: &domain_chat::Attachment,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
+
+    async fn find_attachment(
+        &self,
+        _attachment_id: &domain_chat::AttachmentId,
+    ) -> app::chat::Result<Option<domain_chat::Attachment>> {
+        Ok(None)
+    }
+
+    async fn list_attachments(
+        &self,
+        _message_ids: &[domain_chat::MessageId],
+    ) -> app::chat::Result<Vec<domain_chat::Attachment>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_orphaned_attachments(
+        &self,
+        _limit: usize,
+    ) -> app::chat::Result<Vec<app::chat::OrphanedAttachment>> {
+        Ok(Vec::new())
+    }
+
+    async fn delete_attachment(
+        &self,
+        _attachment_id: &domain_chat::AttachmentId,
+    ) -> app::chat::Result<()> {
+        Ok(())
+    }
 }
 
 struct ModerationQueue;
@@ -159,27 +610,107 @@ impl app::chat::ModerationQueue for ModerationQueue {
     async fn enqueue(
         &self,
         _message_id: &domain_chat::MessageId,
-        _reason: &str,
+        _reason: &app::chat::ModerationReason,
     ) -> app::chat::Result<()> {
         Ok(())
     }
 
     async fn list_pending(
         &self,
+        _rooms: &[domain_chat::RoomId],
+        _room_id: Option<&domain_chat::RoomId>,
+        _reason: Option<&app::chat::ModerationReason>,
+        _now: std::time::SystemTime,
         _limit: usize,
     ) -> app::chat::Result<Vec<app::chat::ModerationItem>> {
         Ok(Vec::new())
     }
 
-    async fn complete(
+    async fn claim(
+        &self,
+        _message_id: &domain_chat::MessageId,
+        _reviewer_id: &domain_chat::UserId,
+        _now: std::time::SystemTime,
+        _until: std::time::SystemTime,
+    ) -> app::chat::Result<bool> {
+        Ok(true)
+    }
+
+    async fn release(
         &self,
         _message_id: &domain_chat::MessageId,
         _reviewer_id: &domain_chat::UserId,
-        _decision: app::chat::ModerationDecision,
-        _reason: Option<String>,
     ) -> app::chat::Result<()> {
         Ok(())
     }
+
+    async fn complete(
+        &self,
+        _message_id: &domain_chat::MessageId,
+        _reviewer_id: &domain_chat::UserId,
+        _decision: app::chat::ModerationDecision,
+        _reason: Option<app::chat::ModerationReason>,
+        _revision: Option<u64>,
+        _now: std::time::SystemTime,
+    ) -> app::chat::Result<bool> {
+        Ok(true)
+    }
+
+    async fn report(
+        &self,
+        _message_id: &domain_chat::MessageId,
+        _reporter_id: &domain_chat::UserId,
+        _category: app::chat::ReportCategory,
+        _reported_at: std::time::SystemTime,
+    ) -> app::chat::Result<app::chat::ReportTally> {
+        Ok(app::chat::ReportTally {
+            open_reports: 1,
+            counted: true,
+        })
+    }
+
+    async fn file_appeal(
+        &self,
+        _message_id: &domain_chat::MessageId,
+        _appellant_id: &domain_chat::UserId,
+        _statement: &app::chat::AppealStatement,
+        _filed_at: std::time::SystemTime,
+    ) -> app::chat::Result<bool> {
+        Ok(true)
+    }
+
+    async fn find_appeal(
+        &self,
+        _message_id: &domain_chat::MessageId,
+    ) -> app::chat::Result<Option<app::chat::Appeal>> {
+        Ok(None)
+    }
+
+    async fn list_appeals(
+        &self,
+        _rooms: &[domain_chat::RoomId],
+        _limit: usize,
+    ) -> app::chat::Result<Vec<app::chat::Appeal>> {
+        Ok(Vec::new())
+    }
+
+    async fn list_removals(
+        &self,
+        _author_id: &domain_chat::UserId,
+        _limit: usize,
+    ) -> app::chat::Result<Vec<app::chat::Removal>> {
+        Ok(Vec::new())
+    }
+
+    async fn decide_appeal(
+        &self,
+        _message_id: &domain_chat::MessageId,
+        _reviewer_id: &domain_chat::UserId,
+        _decision: app::chat::AppealDecision,
+        _decided_at: std::time::SystemTime,
+    ) -> app::chat::Result<bool> {
+        Ok(true)
+    }
 }
 
 struct RateLimiter;
@@ -207,6 +738,74 @@ impl app::chat::AuditLog for AuditLog {
     }
 }
 
+struct Typing;
+
+impl app::chat::TypingStore for Typing {
+    fn start(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _user_id: &domain_chat::UserId,
+        _now: std::time::SystemTime,
+    ) -> app::chat::TypingChange {
+        app::chat::TypingChange::Started
+    }
+
+    fn stop(
+        &self,
+        _room_id: &domain_chat::RoomId,
+        _user_id: &domain_chat::UserId,
+    ) -> bool {
+        false
+    }
+
+    fn expire(
+        &self,
+        _now: std::time::SystemTime,
+    ) -> Vec<app::chat::TypingExpired> {
+        Vec::new()
+    }
+}
+
+struct Authors;
+
+#[async_trait]
+impl app::chat::AuthorDirectory for Authors {
+    async fn user_exists(
+        &self,
+        _user_id: &domain_chat::UserId,
+    ) -> app::chat::Result<bool> {
+        Ok(false)
+    }
+
+    async fn find_by_username(
+        &self,
+        _username: &domain::user::Username,
+    ) -> app::chat::Result<Option<domain_chat::UserId>> {
+        Ok(None)
+    }
+
+    async fn create_placeholder(
+        &self,
+        _username: &domain::user::Username,
+    ) -> app::chat::Result<domain_chat::UserId> {
+        Ok(domain_chat::UserId::new_v4())
+    }
+
+    async fn ensure_bot(
+        &self,
+        _username: &domain::user::Username,
+    ) -> app::chat::Result<domain_chat::UserId> {
+        Ok(domain_chat::UserId::new_v4())
+    }
+
+    async fn find_username(
+        &self,
+        _user_id: &domain_chat::UserId,
+    ) -> app::chat::Result<Option<domain::user::Username>> {
+        Ok(None)
+    }
+}
+
 struct Clock;
 
 impl app::chat::Clock for Clock {
@@ -225,6 +824,81 @@ impl app::chat::IdGenerator for Ids {
     fn new_message_id(&self) -> domain_chat::MessageId {
         domain_chat::MessageId::new_v4()
     }
+
+    fn new_schedule_id(&self) -> domain_chat::ScheduleId {
+        domain_chat::ScheduleId::new_v4()
+    }
+
+    fn new_poll_option_id(&self) -> domain_chat::PollOptionId {
+        domain_chat::PollOptionId::new_v4()
+    }
+
+    fn new_webhook_id(&self) -> domain_chat::WebhookId {
+        domain_chat::WebhookId::new_v4()
+    }
+
+    fn new_incoming_webhook_token(&self) -> domain_chat::IncomingWebhookToken {
+        domain_chat::IncomingWebhookToken::try_new(
+            uuid::Uuid::new_v4().simple().to_string(),
+        )
+        .expect("incoming webhook token")
+    }
+
+    fn new_attachment_id(&self) -> domain_chat::AttachmentId {
+        domain_chat::AttachmentId::new_v4()
+    }
+}
+
+/// Answers every link with a fixed card, without touching the network.
+struct Previews;
+
+#[async_trait]
+impl app::chat::LinkPreviewFetcher for Previews {
+    async fn fetch(
+        &self,
+        url: &domain_chat::PreviewUrl,
+    ) -> Option<domain_chat::LinkPreview> {
+        Some(
+            domain_chat::LinkPreview::builder()
+                .url(url.clone())
+                .title(domain_chat::PreviewTitle::try_new("Example page").expect("title"))
+                .build(),
+        )
+    }
+}
+
+#[tokio::test]
+async fn unfurl_reads_cards_for_visible_links_only() {
+    let chat = chat_service();
+    let message = |body: &str, status| domain_chat::Message {
+        id: domain_chat::MessageId::new_v4(),
+        room_id: domain_chat::RoomId::new_v4(),
+        user_id: domain_chat::UserId::new_v4(),
+        body: domain_chat::MessageBody::try_new(body).expect("body"),
+        status,
+        client_id: None,
+        created_at: std::time::SystemTime::UNIX_EPOCH,
+    };
+
+    let preview = chat
+        .unfurl_message(&message(
+            "docs at https://example.com/docs.",
+            domain_chat::MessageStatus::Visible,
+        ))
+        .await
+        .unwrap()
+        .expect("preview");
+    let held = chat
+        .unfurl_message(&message(
+            "https://example.com",
+            domain_chat::MessageStatus::Pending,
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(preview.url.to_string(), "https://example.com/docs");
+    assert_eq!(preview.title.map(|title| title.to_string()).as_deref(), Some("Example page"));
+    assert!(held.is_none());
 }
 
 #[tokio::test]
@@ -249,18 +923,16 @@ async fn home_page_includes_demo_sections() {
 
 #[derive(Clone, Copy, Debug)]
 enum HomeCopy {
-    Demo1,
-    Demo2,
-    Demo3,
-    Demo4,
-    Demo5,
-    Demo6,
-    CheckAuth,
-    ShowSession,
-    CheckDemoEmail,
+    SystemsIntro,
+    ArchitectureAudit,
+    FeatureGallery,
+    RealtimeChatFanout,
     LiveBackendLog,
-    StartDemo,
+    LiveNetworkLog,
+    ChatCapstone,
+    LiveChatRoom,
     SignIn,
+    CreateAccount,
     RegisterPath,
     LoginPath,
 }
@@ -268,18 +940,16 @@ enum HomeCopy {
 impl HomeCopy {
     fn all() -> &'static [HomeCopy] {
         &[
-            HomeCopy::Demo1,
-            HomeCopy::Demo2,
-            HomeCopy::Demo3,
-            HomeCopy::Demo4,
-            HomeCopy::Demo5,
-            HomeCopy::Demo6,
-            HomeCopy::CheckAuth,
-            HomeCopy::ShowSession,
-            HomeCopy::CheckDemoEmail,
+            HomeCopy::SystemsIntro,
+            HomeCopy::ArchitectureAudit,
+            HomeCopy::FeatureGallery,
+            HomeCopy::RealtimeChatFanout,
             HomeCopy::LiveBackendLog,
-            HomeCopy::StartDemo,
+            HomeCopy::LiveNetworkLog,
+            HomeCopy::ChatCapstone,
+            HomeCopy::LiveChatRoom,
             HomeCopy::SignIn,
+            HomeCopy::CreateAccount,
             HomeCopy::RegisterPath,
             HomeCopy::LoginPath,
         ]
@@ -287,18 +957,20 @@ impl HomeCopy {
 
     fn as_str(self) -> &'static str {
         match self {
-            HomeCopy::Demo1 => "Demo 1: Auth flow walkthrough",
-            HomeCopy::Demo2 => "Demo 2: Persistent session resilience",
-            HomeCopy::Demo3 => "Demo 3: Architecture boundary map",
-            HomeCopy::Demo4 => "Demo 4: Error handling showcase",
-            HomeCopy::Demo5 => "Demo 5: Tracing and observability",
-            HomeCopy::Demo6 => "Demo 6: SSE and Datastar patches",
-            HomeCopy::CheckAuth => "Check auth status",
-            HomeCopy::ShowSession => "Show session details",
-            HomeCopy::CheckDemoEmail => "Check demo@example.com",
-            HomeCopy::LiveBackendLog => "Live backend log",
-            HomeCopy::StartDemo => "Start demo",
+            HomeCopy::SystemsIntro => "How I Think About Systems",
+            HomeCopy::ArchitectureAudit => {
+                "Architecture Audit (What This Site Demonstrates)"
+            }
+            HomeCopy::FeatureGallery => {
+                "Feature gallery: realtime delivery, grounded in systems"
+            }
+            HomeCopy::RealtimeChatFanout => "Realtime chat fanout",
+            HomeCopy::LiveBackendLog => "Live backend log (SSE)",
+            HomeCopy::LiveNetworkLog => "Live network log (SSE)",
+            HomeCopy::ChatCapstone => "Demo D: Live Chat System (Capstone)",
+            HomeCopy::LiveChatRoom => "Live chat room",
             HomeCopy::SignIn => "Sign in",
+            HomeCopy::CreateAccount => "Create account",
             HomeCopy::RegisterPath => "/register",
             HomeCopy::LoginPath => "/login",
         }
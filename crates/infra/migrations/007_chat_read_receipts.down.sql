@@ -0,0 +1,3 @@
+ALTER TABLE chat_room_memberships
+    DROP COLUMN IF EXISTS last_read_at,
+    DROP COLUMN IF EXISTS last_read_message_id;
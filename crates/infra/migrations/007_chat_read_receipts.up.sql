@@ -0,0 +1,3 @@
+ALTER TABLE chat_room_memberships
+    ADD COLUMN last_read_message_id UUID NULL REFERENCES chat_messages(id) ON DELETE SET NULL,
+    ADD COLUMN last_read_at TIMESTAMPTZ NULL;
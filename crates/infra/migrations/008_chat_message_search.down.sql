@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS chat_messages_body_tsv_idx;
+
+ALTER TABLE chat_messages
+    DROP COLUMN IF EXISTS body_tsv;
@@ -0,0 +1,5 @@
+ALTER TABLE chat_messages
+    ADD COLUMN body_tsv tsvector
+        GENERATED ALWAYS AS (to_tsvector('english', body)) STORED;
+
+CREATE INDEX chat_messages_body_tsv_idx ON chat_messages USING GIN (body_tsv);
@@ -0,0 +1,4 @@
+ALTER TABLE chat_messages
+    DROP COLUMN IF EXISTS redacted_at;
+
+DROP TABLE IF EXISTS chat_room_retention;
@@ -0,0 +1,11 @@
+CREATE TABLE chat_room_retention (
+    room_id UUID PRIMARY KEY REFERENCES chat_rooms(id) ON DELETE CASCADE,
+    max_age_days INTEGER NULL CHECK (max_age_days > 0),
+    max_messages INTEGER NULL CHECK (max_messages > 0),
+    mode TEXT NOT NULL DEFAULT 'delete' CHECK (mode IN ('delete', 'redact')),
+    set_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+ALTER TABLE chat_messages
+    ADD COLUMN redacted_at TIMESTAMPTZ NULL;
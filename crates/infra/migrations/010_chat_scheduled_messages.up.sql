@@ -0,0 +1,19 @@
+CREATE TABLE chat_scheduled_messages (
+    id UUID PRIMARY KEY,
+    room_id UUID NOT NULL REFERENCES chat_rooms(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    send_at TIMESTAMPTZ NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'sending', 'sent', 'cancelled', 'failed')),
+    message_id UUID NULL REFERENCES chat_messages(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX chat_scheduled_messages_due_idx
+    ON chat_scheduled_messages (send_at)
+    WHERE status = 'pending';
+
+CREATE INDEX chat_scheduled_messages_author_idx
+    ON chat_scheduled_messages (room_id, user_id, status);
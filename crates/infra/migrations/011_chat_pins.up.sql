@@ -0,0 +1,9 @@
+CREATE TABLE chat_pins (
+    message_id UUID PRIMARY KEY REFERENCES chat_messages(id) ON DELETE CASCADE,
+    room_id UUID NOT NULL REFERENCES chat_rooms(id) ON DELETE CASCADE,
+    pinned_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    pinned_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX chat_pins_room_idx
+    ON chat_pins (room_id, pinned_at DESC);
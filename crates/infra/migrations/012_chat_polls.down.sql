@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS chat_poll_votes;
+DROP TABLE IF EXISTS chat_poll_ballots;
+DROP TABLE IF EXISTS chat_poll_options;
+DROP TABLE IF EXISTS chat_polls;
@@ -0,0 +1,37 @@
+CREATE TABLE chat_polls (
+    message_id UUID PRIMARY KEY REFERENCES chat_messages(id) ON DELETE CASCADE,
+    room_id UUID NOT NULL REFERENCES chat_rooms(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL CHECK (kind IN ('single', 'multiple')),
+    closes_at TIMESTAMPTZ NULL,
+    closed_at TIMESTAMPTZ NULL
+);
+
+CREATE INDEX chat_polls_due_idx
+    ON chat_polls (closes_at)
+    WHERE closed_at IS NULL AND closes_at IS NOT NULL;
+
+CREATE TABLE chat_poll_options (
+    id UUID PRIMARY KEY,
+    message_id UUID NOT NULL REFERENCES chat_polls(message_id) ON DELETE CASCADE,
+    position INT NOT NULL,
+    label TEXT NOT NULL,
+    UNIQUE (message_id, position)
+);
+
+-- One ballot per member and poll; the ballot's choices live in
+-- chat_poll_votes.
+CREATE TABLE chat_poll_ballots (
+    message_id UUID NOT NULL REFERENCES chat_polls(message_id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    voted_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (message_id, user_id)
+);
+
+CREATE TABLE chat_poll_votes (
+    message_id UUID NOT NULL,
+    user_id UUID NOT NULL,
+    option_id UUID NOT NULL REFERENCES chat_poll_options(id) ON DELETE CASCADE,
+    PRIMARY KEY (message_id, user_id, option_id),
+    FOREIGN KEY (message_id, user_id)
+        REFERENCES chat_poll_ballots(message_id, user_id) ON DELETE CASCADE
+);
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS chat_room_mutes;
+
+ALTER TABLE chat_rooms DROP COLUMN IF EXISTS topic;
@@ -0,0 +1,10 @@
+ALTER TABLE chat_rooms ADD COLUMN topic TEXT NULL;
+
+CREATE TABLE chat_room_mutes (
+    room_id UUID NOT NULL REFERENCES chat_rooms(id) ON DELETE CASCADE,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    muted_until TIMESTAMPTZ NOT NULL,
+    muted_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (room_id, user_id)
+);
@@ -0,0 +1,11 @@
+ALTER TABLE users
+    ADD COLUMN kind TEXT NOT NULL DEFAULT 'human' CHECK (kind IN ('human', 'bot'));
+
+-- The old relay account becomes the built-in demo bot.
+UPDATE users
+SET kind = 'bot', username = 'demobot'
+WHERE email = 'demo.bot@example.com'
+  AND NOT EXISTS (SELECT 1 FROM users WHERE username = 'demobot');
+
+DELETE FROM credentials
+WHERE user_id IN (SELECT id FROM users WHERE kind = 'bot');
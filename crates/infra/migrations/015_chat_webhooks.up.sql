@@ -0,0 +1,34 @@
+CREATE TABLE chat_webhooks (
+    id UUID PRIMARY KEY,
+    room_id UUID NOT NULL REFERENCES chat_rooms(id) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    events TEXT[] NOT NULL,
+    secret TEXT NOT NULL,
+    created_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX chat_webhooks_room_idx ON chat_webhooks (room_id);
+
+-- Payloads are stored as sent so signatures can be recomputed byte for byte.
+CREATE TABLE chat_webhook_deliveries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    webhook_id UUID NOT NULL REFERENCES chat_webhooks(id) ON DELETE CASCADE,
+    event TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'sending', 'delivered', 'failed')),
+    attempts INT NOT NULL DEFAULT 0,
+    next_attempt_at TIMESTAMPTZ NOT NULL,
+    response_status INT NULL,
+    last_error TEXT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX chat_webhook_deliveries_due_idx
+    ON chat_webhook_deliveries (next_attempt_at)
+    WHERE status IN ('pending', 'sending');
+
+CREATE INDEX chat_webhook_deliveries_webhook_idx
+    ON chat_webhook_deliveries (webhook_id, created_at DESC);
@@ -0,0 +1,8 @@
+-- One incoming webhook per room; regenerating replaces the token in place.
+CREATE TABLE chat_incoming_webhooks (
+    room_id UUID PRIMARY KEY REFERENCES chat_rooms(id) ON DELETE CASCADE,
+    token TEXT NOT NULL UNIQUE,
+    bot_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -0,0 +1,9 @@
+-- Link cards keyed by the URL as posted. Rows without a title or
+-- description record failed fetches and are retried sooner.
+CREATE TABLE chat_link_previews (
+    url TEXT PRIMARY KEY,
+    title TEXT NULL,
+    description TEXT NULL,
+    image_url TEXT NULL,
+    fetched_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
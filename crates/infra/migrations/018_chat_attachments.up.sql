@@ -0,0 +1,16 @@
+-- No cascades: a row must outlive its message until the attachment sweeper
+-- has deleted its blobs. Deleting the message clears message_id instead.
+CREATE TABLE chat_attachments (
+    id UUID PRIMARY KEY,
+    message_id UUID NULL REFERENCES chat_messages(id) ON DELETE SET NULL,
+    room_id UUID NOT NULL,
+    uploaded_by UUID NOT NULL,
+    name TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL CHECK (size_bytes > 0),
+    blob_key TEXT NOT NULL UNIQUE,
+    thumbnail_key TEXT NULL UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX chat_attachments_message_idx ON chat_attachments (message_id);
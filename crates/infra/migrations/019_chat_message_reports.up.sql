@@ -0,0 +1,7 @@
+CREATE TABLE chat_message_reports (
+    message_id UUID NOT NULL REFERENCES chat_messages(id) ON DELETE CASCADE,
+    reporter_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    category TEXT NOT NULL CHECK (category IN ('spam', 'harassment', 'hate', 'violence', 'sexual', 'other')),
+    reported_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (message_id, reporter_id)
+);
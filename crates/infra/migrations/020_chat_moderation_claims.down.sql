@@ -0,0 +1,7 @@
+DROP INDEX IF EXISTS chat_moderation_queue_pending_idx;
+
+ALTER TABLE chat_moderation_queue
+    DROP COLUMN IF EXISTS claimed_until,
+    DROP COLUMN IF EXISTS claimed_by,
+    DROP COLUMN IF EXISTS revision,
+    DROP COLUMN IF EXISTS queued_at;
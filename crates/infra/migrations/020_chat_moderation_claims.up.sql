@@ -0,0 +1,14 @@
+ALTER TABLE chat_moderation_queue
+    ADD COLUMN queued_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    ADD COLUMN revision BIGINT NOT NULL DEFAULT 0,
+    ADD COLUMN claimed_by UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+    ADD COLUMN claimed_until TIMESTAMPTZ NULL;
+
+UPDATE chat_moderation_queue q
+SET queued_at = m.created_at
+FROM chat_messages m
+WHERE m.id = q.message_id;
+
+CREATE INDEX chat_moderation_queue_pending_idx
+    ON chat_moderation_queue (queued_at)
+    WHERE status = 'pending';
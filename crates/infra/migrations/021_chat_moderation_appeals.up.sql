@@ -0,0 +1,14 @@
+CREATE TABLE chat_moderation_appeals (
+    message_id UUID PRIMARY KEY REFERENCES chat_messages(id) ON DELETE CASCADE,
+    appellant_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    statement TEXT NOT NULL,
+    original_reviewer_id UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'upheld', 'overturned')),
+    reviewer_id UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+    filed_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    decided_at TIMESTAMPTZ NULL
+);
+
+CREATE INDEX chat_moderation_appeals_pending_idx
+    ON chat_moderation_appeals (filed_at)
+    WHERE status = 'pending';
@@ -0,0 +1,7 @@
+DROP TABLE IF EXISTS chat_user_trust;
+
+UPDATE chat_messages SET status = 'pending' WHERE status = 'quarantined';
+ALTER TABLE chat_messages DROP CONSTRAINT chat_messages_status_check;
+ALTER TABLE chat_messages
+    ADD CONSTRAINT chat_messages_status_check
+    CHECK (status IN ('visible', 'pending', 'removed'));
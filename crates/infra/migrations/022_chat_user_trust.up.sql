@@ -0,0 +1,11 @@
+ALTER TABLE chat_messages DROP CONSTRAINT chat_messages_status_check;
+ALTER TABLE chat_messages
+    ADD CONSTRAINT chat_messages_status_check
+    CHECK (status IN ('visible', 'pending', 'removed', 'quarantined'));
+
+CREATE TABLE chat_user_trust (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    level TEXT NOT NULL CHECK (level IN ('standard', 'quarantined')),
+    set_by UUID NULL REFERENCES users(id) ON DELETE SET NULL,
+    set_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
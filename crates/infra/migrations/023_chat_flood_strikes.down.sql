@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS chat_messages_user_created_at_idx;
+DROP TABLE IF EXISTS chat_flood_strikes;
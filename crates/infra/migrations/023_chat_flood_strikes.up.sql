@@ -0,0 +1,10 @@
+CREATE TABLE chat_flood_strikes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    room_id UUID NOT NULL REFERENCES chat_rooms(id) ON DELETE CASCADE,
+    signal TEXT NOT NULL CHECK (signal IN ('duplicate', 'near_duplicate', 'room_burst')),
+    struck_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX chat_flood_strikes_user_idx ON chat_flood_strikes (user_id, struck_at);
+CREATE INDEX chat_messages_user_created_at_idx ON chat_messages (user_id, created_at);
@@ -0,0 +1,2 @@
+DELETE FROM chat_room_mutes WHERE muted_by IS NULL;
+ALTER TABLE chat_room_mutes ALTER COLUMN muted_by SET NOT NULL;
@@ -0,0 +1,2 @@
+-- Flood screening mutes members without a moderator behind the decision.
+ALTER TABLE chat_room_mutes ALTER COLUMN muted_by DROP NOT NULL;
@@ -0,0 +1,138 @@
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use app::chat::{BlobStore, Error, Result, THUMBNAIL_SIZE, Thumbnailer};
+use async_trait::async_trait;
+use domain::chat;
+
+/// Larger images are not thumbnailed; decoding them could exhaust memory.
+const MAX_IMAGE_DIMENSION: u32 = 8_000;
+const MAX_DECODE_BYTES: u64 = 128 * 1024 * 1024;
+
+/// Stores blobs as files under `root`, one path segment per key segment.
+/// `BlobKey` only allows `[a-z0-9_-]` segments, so keys cannot escape it.
+pub struct LocalBlobStore {
+    root: PathBuf,
+}
+
+impl LocalBlobStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(
+        &self,
+        key: &chat::BlobKey,
+    ) -> PathBuf {
+        key.as_ref()
+            .split('/')
+            .fold(self.root.clone(), |path, segment| path.join(segment))
+    }
+}
+
+fn blob_error(error: std::io::Error) -> Error {
+    Error::Repo(format!("blob store: {error}").into())
+}
+
+#[async_trait]
+impl BlobStore for LocalBlobStore {
+    /// Writes to a temporary sibling first, so readers never see half a
+    /// file.
+    async fn put(
+        &self,
+        key: &chat::BlobKey,
+        bytes: &[u8],
+    ) -> Result<()> {
+        let path = self.path(key);
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(blob_error)?;
+        }
+        let partial = path.with_extension("partial");
+        tokio::fs::write(&partial, bytes).await.map_err(blob_error)?;
+        tokio::fs::rename(&partial, &path).await.map_err(blob_error)
+    }
+
+    async fn get(
+        &self,
+        key: &chat::BlobKey,
+    ) -> Result<Option<Vec<u8>>> {
+        match tokio::fs::read(self.path(key)).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(blob_error(error)),
+        }
+    }
+
+    /// Also drops the key's directory once it is empty.
+    async fn delete(
+        &self,
+        key: &chat::BlobKey,
+    ) -> Result<()> {
+        let path = self.path(key);
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => {}
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => return Err(blob_error(error)),
+        }
+        if let Some(parent) = path.parent()
+            && parent != self.root
+        {
+            let _ = tokio::fs::remove_dir(parent).await;
+        }
+        Ok(())
+    }
+}
+
+/// PNG thumbnails via the `image` crate, decoded on the blocking pool with
+/// dimension and allocation limits.
+#[derive(Default)]
+pub struct ImageThumbnailer;
+
+impl ImageThumbnailer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Thumbnailer for ImageThumbnailer {
+    async fn thumbnail(
+        &self,
+        content_type: chat::AttachmentType,
+        bytes: &[u8],
+    ) -> Option<Vec<u8>> {
+        let bytes = bytes.to_vec();
+        tokio::task::spawn_blocking(move || render_thumbnail(content_type, &bytes))
+            .await
+            .ok()
+            .flatten()
+    }
+}
+
+/// Fits the image into `THUMBNAIL_SIZE` square and encodes it as PNG.
+pub fn render_thumbnail(
+    content_type: chat::AttachmentType,
+    bytes: &[u8],
+) -> Option<Vec<u8>> {
+    let format = match content_type {
+        chat::AttachmentType::Png => image::ImageFormat::Png,
+        chat::AttachmentType::Jpeg => image::ImageFormat::Jpeg,
+        chat::AttachmentType::Gif => image::ImageFormat::Gif,
+        chat::AttachmentType::Webp => image::ImageFormat::WebP,
+        chat::AttachmentType::Pdf | chat::AttachmentType::Text => return None,
+    };
+    let mut limits = image::Limits::default();
+    limits.max_image_width = Some(MAX_IMAGE_DIMENSION);
+    limits.max_image_height = Some(MAX_IMAGE_DIMENSION);
+    limits.max_alloc = Some(MAX_DECODE_BYTES);
+    let mut reader = image::ImageReader::with_format(Cursor::new(bytes), format);
+    reader.limits(limits);
+
+    let image = reader.decode().ok()?;
+    let mut thumbnail = Cursor::new(Vec::new());
+    image
+        .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        .write_to(&mut thumbnail, image::ImageFormat::Png)
+        .ok()?;
+    Some(thumbnail.into_inner())
+}
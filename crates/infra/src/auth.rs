@@ -37,17 +37,18 @@ impl Repository for AuthRepository {
         tracing::info!(
             target: "demo.db",
             message = "db query",
-            db_statement = "SELECT u.id, u.username, u.email, c.password_hash FROM users u JOIN credentials c ON c.user_id = u.id WHERE u.email = $1"
+            db_statement = "SELECT u.id, u.username, u.email, c.password_hash FROM users u JOIN credentials c ON c.user_id = u.id WHERE u.email = $1 AND u.kind = $2"
         );
         let record = sqlx::query(
             r#"
             SELECT u.id, u.username, u.email, c.password_hash
             FROM users u
             JOIN credentials c ON c.user_id = u.id
-            WHERE u.email = $1
+            WHERE u.email = $1 AND u.kind = $2
             "#,
         )
         .bind(email.to_string())
+        .bind(user::Kind::Human.to_string())
         .fetch_optional(&self.pg)
         .await
         .map_err(Self::map_error)?;
@@ -102,17 +103,18 @@ impl Repository for AuthRepository {
         tracing::info!(
             target: "demo.db",
             message = "db query",
-            db_statement = "SELECT u.id, u.username, u.email, c.password_hash FROM users u JOIN credentials c ON c.user_id = u.id WHERE u.id = $1"
+            db_statement = "SELECT u.id, u.username, u.email, c.password_hash FROM users u JOIN credentials c ON c.user_id = u.id WHERE u.id = $1 AND u.kind = $2"
         );
         let record = sqlx::query(
             r#"
             SELECT u.id, u.username, u.email, c.password_hash
             FROM users u
             JOIN credentials c ON c.user_id = u.id
-            WHERE u.id = $1
+            WHERE u.id = $1 AND u.kind = $2
             "#,
         )
         .bind(user_id.as_uuid())
+        .bind(user::Kind::Human.to_string())
         .fetch_optional(&self.pg)
         .await
         .map_err(Self::map_error)?;
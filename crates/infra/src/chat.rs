@@ -1,16 +1,30 @@
 pub use crate::repo::chat::{
-    AuditLog, ModerationQueue, RateLimiter, Repository,
+    AuditLog, AuthorDirectory, ModerationQueue, RateLimiter, Repository,
 };
+pub use crate::attachment::{ImageThumbnailer, LocalBlobStore, render_thumbnail};
+pub use crate::net::is_public_address;
+pub use crate::preview::{HttpLinkPreviewFetcher, parse_link_preview};
+pub use crate::webhook::{
+    DELIVERY_HEADER, EVENT_HEADER, HttpWebhookSender, SIGNATURE_HEADER, TIMESTAMP_HEADER,
+    sign_webhook,
+};
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
 
-use app::chat::{Clock, IdGenerator};
+use app::chat::{Clock, IdGenerator, TypingChange, TypingExpired, TypingStore};
 use domain::chat;
 
+const TYPING_TTL: Duration = Duration::from_secs(5);
+const TYPING_MIN_INTERVAL: Duration = Duration::from_millis(750);
+
 #[derive(Default)]
 pub struct SystemClock;
 
 impl SystemClock {
     pub fn new() -> Self {
-        Self::default()
+        Self
     }
 }
 
@@ -25,7 +39,7 @@ pub struct UuidGenerator;
 
 impl UuidGenerator {
     pub fn new() -> Self {
-        Self::default()
+        Self
     }
 }
 
@@ -37,4 +51,156 @@ impl IdGenerator for UuidGenerator {
     fn new_message_id(&self) -> chat::MessageId {
         chat::MessageId::new_v4()
     }
+
+    fn new_schedule_id(&self) -> chat::ScheduleId {
+        chat::ScheduleId::new_v4()
+    }
+
+    fn new_poll_option_id(&self) -> chat::PollOptionId {
+        chat::PollOptionId::new_v4()
+    }
+
+    fn new_webhook_id(&self) -> chat::WebhookId {
+        chat::WebhookId::new_v4()
+    }
+
+    /// Two v4 UUIDs in hex: 244 random bits.
+    fn new_incoming_webhook_token(&self) -> chat::IncomingWebhookToken {
+        chat::IncomingWebhookToken::try_new(format!(
+            "{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        ))
+        .expect("incoming webhook token")
+    }
+
+    fn new_attachment_id(&self) -> chat::AttachmentId {
+        chat::AttachmentId::new_v4()
+    }
+}
+
+struct TypingEntry {
+    last_ping: SystemTime,
+}
+
+#[derive(Default)]
+pub struct MemoryTypingStore {
+    entries: Mutex<HashMap<(chat::RoomId, chat::UserId), TypingEntry>>,
+}
+
+impl MemoryTypingStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl TypingStore for MemoryTypingStore {
+    fn start(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+        now: SystemTime,
+    ) -> TypingChange {
+        let mut entries = self.entries.lock().expect("typing lock");
+        let key = (*room_id, *user_id);
+        let change = match entries.get(&key) {
+            Some(entry) => {
+                let elapsed = now
+                    .duration_since(entry.last_ping)
+                    .unwrap_or_default();
+                if elapsed < TYPING_MIN_INTERVAL {
+                    return TypingChange::Throttled;
+                }
+                if elapsed < TYPING_TTL {
+                    TypingChange::Refreshed
+                } else {
+                    TypingChange::Started
+                }
+            }
+            None => TypingChange::Started,
+        };
+        entries.insert(key, TypingEntry { last_ping: now });
+        change
+    }
+
+    fn stop(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+    ) -> bool {
+        self.entries
+            .lock()
+            .expect("typing lock")
+            .remove(&(*room_id, *user_id))
+            .is_some()
+    }
+
+    fn expire(
+        &self,
+        now: SystemTime,
+    ) -> Vec<TypingExpired> {
+        let mut entries = self.entries.lock().expect("typing lock");
+        let expired = entries
+            .iter()
+            .filter(|(_, entry)| {
+                now.duration_since(entry.last_ping).unwrap_or_default() >= TYPING_TTL
+            })
+            .map(|((room_id, user_id), _)| TypingExpired {
+                room_id: *room_id,
+                user_id: *user_id,
+            })
+            .collect::<Vec<_>>();
+        for item in &expired {
+            entries.remove(&(item.room_id, item.user_id));
+        }
+        expired
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key() -> (chat::RoomId, chat::UserId) {
+        (
+            chat::RoomId::from_uuid(uuid::Uuid::from_u128(1)),
+            chat::UserId::from_uuid(uuid::Uuid::from_u128(2)),
+        )
+    }
+
+    #[test]
+    fn typing_pings_are_debounced_then_refresh() {
+        let store = MemoryTypingStore::new();
+        let (room_id, user_id) = key();
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+
+        assert_eq!(store.start(&room_id, &user_id, start), TypingChange::Started);
+        assert_eq!(
+            store.start(&room_id, &user_id, start + Duration::from_millis(200)),
+            TypingChange::Throttled
+        );
+        assert_eq!(
+            store.start(&room_id, &user_id, start + TYPING_MIN_INTERVAL),
+            TypingChange::Refreshed
+        );
+    }
+
+    #[test]
+    fn typing_entries_expire_after_the_ttl() {
+        let store = MemoryTypingStore::new();
+        let (room_id, user_id) = key();
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(100);
+        store.start(&room_id, &user_id, start);
+
+        assert!(store.expire(start + TYPING_TTL - Duration::from_millis(1)).is_empty());
+        assert_eq!(
+            store.expire(start + TYPING_TTL),
+            vec![TypingExpired { room_id, user_id }]
+        );
+        assert!(store.expire(start + TYPING_TTL * 2).is_empty());
+        assert_eq!(
+            store.start(&room_id, &user_id, start + TYPING_TTL * 2),
+            TypingChange::Started
+        );
+    }
 }
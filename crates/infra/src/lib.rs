@@ -1,9 +1,13 @@
 pub mod config;
+mod attachment;
 mod error;
+mod net;
 pub mod auth;
 pub mod chat;
 pub use error::{Error, Result};
+mod preview;
 mod repo;
+mod webhook;
 pub use repo::user;
 use sqlx::PgPool;
 
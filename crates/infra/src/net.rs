@@ -0,0 +1,65 @@
+//! Outbound request guards shared by every fetcher that takes a URL from
+//! users: link previews and webhooks.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+/// The address to connect to, if every address the host resolves to is
+/// public.
+pub(crate) async fn public_addr(url: &reqwest::Url) -> Option<SocketAddr> {
+    let addrs = resolve(url).await?;
+    if !addrs.iter().all(|addr| is_public_address(addr.ip())) {
+        tracing::debug!(host = url.host_str(), "host is not public");
+        return None;
+    }
+    addrs.first().copied()
+}
+
+/// Every address the URL's host resolves to; `None` if there are none.
+pub(crate) async fn resolve(url: &reqwest::Url) -> Option<Vec<SocketAddr>> {
+    let host = url.host_str()?.trim_start_matches('[').trim_end_matches(']');
+    let port = url.port_or_known_default()?;
+    let addrs = tokio::net::lookup_host((host, port))
+        .await
+        .ok()?
+        .collect::<Vec<_>>();
+    (!addrs.is_empty()).then_some(addrs)
+}
+
+/// False for loopback, private, link-local, shared, documentation and
+/// other special-purpose ranges, including IPv4 addresses embedded in IPv6.
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_v4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(mapped) => is_public_v4(mapped),
+            None => is_public_v6(ip),
+        },
+    }
+}
+
+fn is_public_v4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        || a == 0
+        || (a == 100 && (64..128).contains(&b))
+        || (a == 192 && b == 0 && c == 0)
+        || (a == 198 && (18..20).contains(&b))
+        || a >= 240)
+}
+
+fn is_public_v6(ip: Ipv6Addr) -> bool {
+    let [first, second, ..] = ip.segments();
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80
+        || (first == 0x2001 && second == 0x0db8)
+        || (first == 0x0064 && second == 0xff9b))
+}
@@ -0,0 +1,276 @@
+use std::time::Duration;
+
+use app::chat::LinkPreviewFetcher;
+use async_trait::async_trait;
+use domain::chat;
+
+use crate::net::public_addr;
+
+/// Whole fetch, redirects included.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_REDIRECTS: usize = 3;
+/// Cards live in `<head>`; anything past this is never read.
+const MAX_BODY_BYTES: usize = 256 * 1024;
+const USER_AGENT: &str = "chat-link-preview/0.1";
+
+const MAX_TITLE_CHARS: usize = 300;
+const MAX_DESCRIPTION_CHARS: usize = 1000;
+
+/// Reads OpenGraph cards over HTTP. Every hop resolves the host once,
+/// refuses non-public addresses and pins the connection to the checked
+/// address, so DNS rebinding cannot reach the private network either.
+/// Redirects are followed by hand for the same reason.
+#[derive(Default)]
+pub struct HttpLinkPreviewFetcher;
+
+impl HttpLinkPreviewFetcher {
+    pub fn new() -> Self {
+        Self
+    }
+
+    async fn fetch_page(
+        &self,
+        url: &chat::PreviewUrl,
+    ) -> Option<chat::LinkPreview> {
+        let mut target = reqwest::Url::parse(url.as_ref()).ok()?;
+        for _ in 0..=MAX_REDIRECTS {
+            if !matches!(target.scheme(), "http" | "https") {
+                return None;
+            }
+            let host = target.host_str()?.to_string();
+            let addr = public_addr(&target).await?;
+            let client = reqwest::Client::builder()
+                .redirect(reqwest::redirect::Policy::none())
+                .resolve(&host, addr)
+                .connect_timeout(CONNECT_TIMEOUT)
+                .user_agent(USER_AGENT)
+                .build()
+                .ok()?;
+            let mut response = client
+                .get(target.clone())
+                .header(reqwest::header::ACCEPT, "text/html")
+                .send()
+                .await
+                .ok()?;
+
+            if response.status().is_redirection() {
+                let location = response
+                    .headers()
+                    .get(reqwest::header::LOCATION)?
+                    .to_str()
+                    .ok()?;
+                target = target.join(location).ok()?;
+                continue;
+            }
+            if !response.status().is_success() || !is_html(&response) {
+                return None;
+            }
+
+            let mut body = Vec::new();
+            while body.len() < MAX_BODY_BYTES {
+                match response.chunk().await.ok()? {
+                    Some(chunk) => body.extend_from_slice(&chunk),
+                    None => break,
+                }
+            }
+            body.truncate(MAX_BODY_BYTES);
+            return Some(parse_link_preview(url, &String::from_utf8_lossy(&body)));
+        }
+        None
+    }
+}
+
+#[async_trait]
+impl LinkPreviewFetcher for HttpLinkPreviewFetcher {
+    async fn fetch(
+        &self,
+        url: &chat::PreviewUrl,
+    ) -> Option<chat::LinkPreview> {
+        let preview = tokio::time::timeout(FETCH_TIMEOUT, self.fetch_page(url))
+            .await
+            .ok()
+            .flatten();
+        if preview.is_none() {
+            tracing::debug!(url = %url, "link preview unavailable");
+        }
+        preview
+    }
+}
+
+fn is_html(response: &reqwest::Response) -> bool {
+    response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            let value = value.to_ascii_lowercase();
+            value.starts_with("text/html") || value.starts_with("application/xhtml+xml")
+        })
+}
+
+/// Reads `og:title`, `og:description` and `og:image`, falling back to
+/// Twitter cards, `<title>` and `<meta name="description">`. Relative image
+/// URLs are resolved against `url`.
+pub fn parse_link_preview(
+    url: &chat::PreviewUrl,
+    html: &str,
+) -> chat::LinkPreview {
+    let metas = meta_tags(html);
+    let meta = |keys: &[&str]| {
+        keys.iter().find_map(|key| {
+            metas
+                .iter()
+                .find(|(name, content)| name == key && !content.trim().is_empty())
+                .map(|(_, content)| content.clone())
+        })
+    };
+
+    let title = meta(&["og:title", "twitter:title"])
+        .or_else(|| title_tag(html))
+        .and_then(|value| chat::PreviewTitle::try_new(clean_text(&value, MAX_TITLE_CHARS)).ok());
+    let description = meta(&["og:description", "twitter:description", "description"])
+        .and_then(|value| {
+            chat::PreviewDescription::try_new(clean_text(&value, MAX_DESCRIPTION_CHARS)).ok()
+        });
+    let image_url = meta(&["og:image", "og:image:url", "twitter:image"]).and_then(|value| {
+        let base = reqwest::Url::parse(url.as_ref()).ok()?;
+        let image = base.join(decode_entities(value.trim()).as_str()).ok()?;
+        chat::PreviewUrl::try_new(image.to_string()).ok()
+    });
+
+    chat::LinkPreview::builder()
+        .url(url.clone())
+        .maybe_title(title)
+        .maybe_description(description)
+        .maybe_image_url(image_url)
+        .build()
+}
+
+/// `(property or name, content)` for every `<meta>` tag, names lowercased.
+fn meta_tags(html: &str) -> Vec<(String, String)> {
+    let lower = html.to_ascii_lowercase();
+    let mut tags = Vec::new();
+    let mut offset = 0;
+    while let Some(start) = lower[offset..].find("<meta") {
+        let start = offset + start + "<meta".len();
+        let Some(end) = lower[start..].find('>') else {
+            break;
+        };
+        let end = start + end;
+        let attributes = attributes(&html[start..end]);
+        let name = attributes
+            .iter()
+            .find(|(key, _)| key == "property" || key == "name")
+            .map(|(_, value)| value.to_ascii_lowercase());
+        let content = attributes
+            .iter()
+            .find(|(key, _)| key == "content")
+            .map(|(_, value)| value.clone());
+        if let (Some(name), Some(content)) = (name, content) {
+            tags.push((name, content));
+        }
+        offset = end;
+    }
+    tags
+}
+
+fn attributes(tag: &str) -> Vec<(String, String)> {
+    let mut attributes = Vec::new();
+    let mut rest = tag.trim_start();
+    while !rest.is_empty() {
+        let name_end = rest
+            .find(|c: char| c == '=' || c == '/' || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let name = rest[..name_end].to_ascii_lowercase();
+        rest = rest[name_end..].trim_start();
+        let Some(value_start) = rest.strip_prefix('=') else {
+            rest = rest.trim_start_matches('/').trim_start();
+            continue;
+        };
+        let value_start = value_start.trim_start();
+        let (value, remaining) = match value_start.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let inner = &value_start[1..];
+                let close = inner.find(quote).unwrap_or(inner.len());
+                (&inner[..close], inner.get(close + 1..).unwrap_or(""))
+            }
+            _ => {
+                let close = value_start
+                    .find(char::is_whitespace)
+                    .unwrap_or(value_start.len());
+                (&value_start[..close], &value_start[close..])
+            }
+        };
+        if !name.is_empty() {
+            attributes.push((name, value.to_string()));
+        }
+        rest = remaining.trim_start();
+    }
+    attributes
+}
+
+fn title_tag(html: &str) -> Option<String> {
+    let lower = html.to_ascii_lowercase();
+    let open = lower.find("<title")?;
+    let start = open + lower[open..].find('>')? + 1;
+    let end = start + lower[start..].find("</title")?;
+    Some(html[start..end].to_string())
+}
+
+/// Decodes entities, collapses whitespace and cuts to `max_chars`.
+fn clean_text(
+    value: &str,
+    max_chars: usize,
+) -> String {
+    decode_entities(value)
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(max_chars)
+        .collect()
+}
+
+fn decode_entities(value: &str) -> String {
+    let mut decoded = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest
+            .find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| decode_entity(&rest[1..end]).map(|c| (c, end)));
+        match entity {
+            Some((c, end)) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn decode_entity(entity: &str) -> Option<char> {
+    match entity {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some(' '),
+        _ => {
+            let code = match entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => entity.strip_prefix('#')?.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
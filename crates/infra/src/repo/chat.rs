@@ -1,11 +1,14 @@
 pub use SqlxChatAuditLog as AuditLog;
+pub use SqlxChatAuthorDirectory as AuthorDirectory;
 pub use SqlxChatModerationQueue as ModerationQueue;
 pub use SqlxChatRateLimiter as RateLimiter;
 pub use SqlxChatRepository as Repository;
 
 use app::chat::{
-    AuditEntry, Error, ModerationQueueStatus, ModerationReason, Result,
-    RoomRole,
+    AuditEntry, Error, ModerationQueueStatus, ModerationReason, PinnedMessage, PollResults, PollTally, Result,
+    ExportCursor, ExportRecord, PurgeMode, RetentionCount, RetentionDays,
+    RetentionPolicy, RoomRetention, RoomRole, RoomUnread, SearchHighlight, SearchHit,
+    SearchQuery,
 };
 use async_trait::async_trait;
 use domain::chat;
@@ -14,6 +17,44 @@ use sqlx::types::time;
 
 const RATE_LIMIT_WINDOW_SECS: i64 = 10;
 const RATE_LIMIT_MAX: i64 = 5;
+const REDACTED_BODY: &str = "[redacted]";
+
+/// Messages in room `$1` past the retention policy as of `$2`: older than
+/// `$3` days or beyond the newest `$4`. Already-redacted rows don't count.
+const EXPIRED_MESSAGES: &str = r#"
+    ranked AS (
+        SELECT id, created_at,
+               row_number() OVER (ORDER BY created_at DESC, id DESC) AS position
+        FROM chat_messages
+        WHERE room_id = $1 AND redacted_at IS NULL
+    ),
+    expired AS (
+        SELECT id, created_at
+        FROM ranked
+        WHERE ($3::int IS NOT NULL AND created_at < $2 - make_interval(days => $3::int))
+           OR ($4::int IS NOT NULL AND position > $4::int)
+    )
+"#;
+
+/// Appeals with their message, room, queue reason and appellant name.
+const APPEAL_SELECT: &str = r#"
+    SELECT a.message_id,
+           m.room_id,
+           r.name AS room_name,
+           a.appellant_id,
+           u.username AS appellant_name,
+           m.body,
+           q.reason,
+           a.statement,
+           a.original_reviewer_id,
+           a.status,
+           a.filed_at
+    FROM chat_moderation_appeals a
+    JOIN chat_messages m ON m.id = a.message_id
+    JOIN chat_rooms r ON r.id = m.room_id
+    JOIN chat_moderation_queue q ON q.message_id = a.message_id
+    JOIN users u ON u.id = a.appellant_id
+"#;
 
 pub struct SqlxChatRepository {
     pg: PgPool,
@@ -38,6 +79,311 @@ impl SqlxChatRepository {
         })
     }
 
+    fn retention_from_row(row: &sqlx::postgres::PgRow) -> Result<RoomRetention> {
+        let mode = row.get::<String, _>("mode");
+        let mode = mode.parse::<PurgeMode>().map_err(|_| {
+            Error::Repo(format!("unknown purge mode: {}", mode).into())
+        })?;
+        let max_age = row
+            .get::<Option<i32>, _>("max_age_days")
+            .map(|value| {
+                RetentionDays::try_new(value.max(0) as u32)
+                    .map_err(|error| Error::Repo(error.to_string().into()))
+            })
+            .transpose()?;
+        let max_messages = row
+            .get::<Option<i32>, _>("max_messages")
+            .map(|value| {
+                RetentionCount::try_new(value.max(0) as u32)
+                    .map_err(|error| Error::Repo(error.to_string().into()))
+            })
+            .transpose()?;
+
+        Ok(RoomRetention::builder()
+            .room_id(chat::RoomId::from_uuid(row.get::<uuid::Uuid, _>("room_id")))
+            .policy(
+                RetentionPolicy::builder()
+                    .maybe_max_age(max_age)
+                    .maybe_max_messages(max_messages)
+                    .mode(mode)
+                    .build(),
+            )
+            .set_by(chat::UserId::from_uuid(row.get::<uuid::Uuid, _>("set_by")))
+            .build())
+    }
+
+    fn retention_limits(retention: &RoomRetention) -> (Option<i32>, Option<i32>) {
+        (
+            retention
+                .policy
+                .max_age
+                .map(|value| i32::try_from(value.into_inner()).unwrap_or(i32::MAX)),
+            retention
+                .policy
+                .max_messages
+                .map(|value| i32::try_from(value.into_inner()).unwrap_or(i32::MAX)),
+        )
+    }
+
+    fn schedule_from_row(row: &sqlx::postgres::PgRow) -> Result<chat::ScheduledMessage> {
+        let body = chat::MessageBody::try_new(row.get::<String, _>("body"))
+            .map_err(|error| Error::Repo(error.to_string().into()))?;
+        let status = row.get::<String, _>("status");
+        let status = status.parse::<chat::ScheduleStatus>().map_err(|_| {
+            Error::Repo(format!("unknown schedule status: {}", status).into())
+        })?;
+
+        Ok(chat::ScheduledMessage::builder()
+            .id(chat::ScheduleId::from_uuid(row.get::<uuid::Uuid, _>("id")))
+            .room_id(chat::RoomId::from_uuid(row.get::<uuid::Uuid, _>("room_id")))
+            .user_id(chat::UserId::from_uuid(row.get::<uuid::Uuid, _>("user_id")))
+            .body(body)
+            .send_at(offset_to_system_time(
+                row.get::<time::OffsetDateTime, _>("send_at"),
+            ))
+            .status(status)
+            .maybe_message_id(
+                row.get::<Option<uuid::Uuid>, _>("message_id")
+                    .map(chat::MessageId::from_uuid),
+            )
+            .build())
+    }
+
+    fn recent_post_from_row(row: &sqlx::postgres::PgRow) -> Result<app::chat::RecentPost> {
+        let body = chat::MessageBody::try_new(row.get::<String, _>("body"))
+            .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(app::chat::RecentPost::builder()
+            .room_id(chat::RoomId::from_uuid(row.get::<uuid::Uuid, _>("room_id")))
+            .body(body)
+            .created_at(offset_to_system_time(
+                row.get::<time::OffsetDateTime, _>("created_at"),
+            ))
+            .build())
+    }
+
+    fn webhook_from_row(row: &sqlx::postgres::PgRow) -> Result<chat::Webhook> {
+        let url = chat::WebhookUrl::try_new(row.get::<String, _>("url"))
+            .map_err(|error| Error::Repo(error.to_string().into()))?;
+        let secret = chat::WebhookSecret::try_new(row.get::<String, _>("secret"))
+            .map_err(|error| Error::Repo(error.to_string().into()))?;
+        let events = row
+            .get::<Vec<String>, _>("events")
+            .into_iter()
+            .map(|event| {
+                event.parse::<chat::WebhookEvent>().map_err(|_| {
+                    Error::Repo(format!("unknown webhook event: {}", event).into())
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(chat::Webhook::builder()
+            .id(chat::WebhookId::from_uuid(row.get::<uuid::Uuid, _>("id")))
+            .room_id(chat::RoomId::from_uuid(row.get::<uuid::Uuid, _>("room_id")))
+            .url(url)
+            .events(events)
+            .secret(secret)
+            .created_by(chat::UserId::from_uuid(row.get::<uuid::Uuid, _>("created_by")))
+            .created_at(offset_to_system_time(
+                row.get::<time::OffsetDateTime, _>("created_at"),
+            ))
+            .build())
+    }
+
+    fn incoming_webhook_from_row(
+        row: &sqlx::postgres::PgRow,
+    ) -> Result<chat::IncomingWebhook> {
+        let token = chat::IncomingWebhookToken::try_new(row.get::<String, _>("token"))
+            .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(chat::IncomingWebhook::builder()
+            .room_id(chat::RoomId::from_uuid(row.get::<uuid::Uuid, _>("room_id")))
+            .token(token)
+            .bot_id(chat::UserId::from_uuid(row.get::<uuid::Uuid, _>("bot_id")))
+            .created_by(chat::UserId::from_uuid(row.get::<uuid::Uuid, _>("created_by")))
+            .created_at(offset_to_system_time(
+                row.get::<time::OffsetDateTime, _>("created_at"),
+            ))
+            .build())
+    }
+
+    fn link_preview_from_row(row: &sqlx::postgres::PgRow) -> Result<app::chat::CachedPreview> {
+        let url = chat::PreviewUrl::try_new(row.get::<String, _>("url"))
+            .map_err(|error| Error::Repo(error.to_string().into()))?;
+        let title = row
+            .get::<Option<String>, _>("title")
+            .and_then(|value| chat::PreviewTitle::try_new(value).ok());
+        let description = row
+            .get::<Option<String>, _>("description")
+            .and_then(|value| chat::PreviewDescription::try_new(value).ok());
+        let image_url = row
+            .get::<Option<String>, _>("image_url")
+            .and_then(|value| chat::PreviewUrl::try_new(value).ok());
+
+        Ok(app::chat::CachedPreview::builder()
+            .preview(
+                chat::LinkPreview::builder()
+                    .url(url)
+                    .maybe_title(title)
+                    .maybe_description(description)
+                    .maybe_image_url(image_url)
+                    .build(),
+            )
+            .fetched_at(offset_to_system_time(
+                row.get::<time::OffsetDateTime, _>("fetched_at"),
+            ))
+            .build())
+    }
+
+    fn attachment_from_row(row: &sqlx::postgres::PgRow) -> Result<chat::Attachment> {
+        let name = chat::AttachmentName::try_new(row.get::<String, _>("name"))
+            .map_err(|error| Error::Repo(error.to_string().into()))?;
+        let content_type = row.get::<String, _>("content_type");
+        let content_type = content_type.parse::<chat::AttachmentType>().map_err(|_| {
+            Error::Repo(format!("unknown attachment type: {}", content_type).into())
+        })?;
+        let blob_key = chat::BlobKey::try_new(row.get::<String, _>("blob_key"))
+            .map_err(|error| Error::Repo(error.to_string().into()))?;
+        let thumbnail_key = row
+            .get::<Option<String>, _>("thumbnail_key")
+            .map(|value| {
+                chat::BlobKey::try_new(value)
+                    .map_err(|error| Error::Repo(error.to_string().into()))
+            })
+            .transpose()?;
+
+        Ok(chat::Attachment::builder()
+            .id(chat::AttachmentId::from_uuid(row.get::<uuid::Uuid, _>("id")))
+            .message_id(chat::MessageId::from_uuid(row.get::<uuid::Uuid, _>("message_id")))
+            .room_id(chat::RoomId::from_uuid(row.get::<uuid::Uuid, _>("room_id")))
+            .uploaded_by(chat::UserId::from_uuid(row.get::<uuid::Uuid, _>("uploaded_by")))
+            .name(name)
+            .content_type(content_type)
+            .size_bytes(row.get::<i64, _>("size_bytes").max(0) as u64)
+            .blob_key(blob_key)
+            .maybe_thumbnail_key(thumbnail_key)
+            .created_at(offset_to_system_time(
+                row.get::<time::OffsetDateTime, _>("created_at"),
+            ))
+            .build())
+    }
+
+    fn delivery_from_row(row: &sqlx::postgres::PgRow) -> Result<chat::WebhookDelivery> {
+        let event = row.get::<String, _>("event");
+        let event = event.parse::<chat::WebhookEvent>().map_err(|_| {
+            Error::Repo(format!("unknown webhook event: {}", event).into())
+        })?;
+        let status = row.get::<String, _>("status");
+        let status = status.parse::<chat::WebhookDeliveryStatus>().map_err(|_| {
+            Error::Repo(format!("unknown delivery status: {}", status).into())
+        })?;
+        let last_error = row
+            .get::<Option<String>, _>("last_error")
+            .map(|value| {
+                chat::WebhookDeliveryError::try_new(value)
+                    .map_err(|error| Error::Repo(error.to_string().into()))
+            })
+            .transpose()?;
+
+        Ok(chat::WebhookDelivery::builder()
+            .id(chat::WebhookDeliveryId::from_uuid(row.get::<uuid::Uuid, _>("id")))
+            .webhook_id(chat::WebhookId::from_uuid(row.get::<uuid::Uuid, _>("webhook_id")))
+            .event(event)
+            .payload(chat::WebhookPayload::new(row.get::<String, _>("payload")))
+            .status(status)
+            .attempts(row.get::<i32, _>("attempts").max(0) as u32)
+            .next_attempt_at(offset_to_system_time(
+                row.get::<time::OffsetDateTime, _>("next_attempt_at"),
+            ))
+            .maybe_response_status(
+                row.get::<Option<i32>, _>("response_status")
+                    .and_then(|value| u16::try_from(value).ok()),
+            )
+            .maybe_last_error(last_error)
+            .created_at(offset_to_system_time(
+                row.get::<time::OffsetDateTime, _>("created_at"),
+            ))
+            .build())
+    }
+
+    /// Builds a poll from a `chat_polls` row, taking its options (in
+    /// position order) out of `options`.
+    fn poll_from_row(
+        row: &sqlx::postgres::PgRow,
+        options: &mut std::collections::HashMap<uuid::Uuid, Vec<(chat::PollOption, u64)>>,
+    ) -> Result<chat::Poll> {
+        let message_id = row.get::<uuid::Uuid, _>("message_id");
+        let kind = row.get::<String, _>("kind");
+        let kind = kind.parse::<chat::PollKind>().map_err(|_| {
+            Error::Repo(format!("unknown poll kind: {}", kind).into())
+        })?;
+
+        Ok(chat::Poll::builder()
+            .message_id(chat::MessageId::from_uuid(message_id))
+            .room_id(chat::RoomId::from_uuid(row.get::<uuid::Uuid, _>("room_id")))
+            .kind(kind)
+            .options(
+                options
+                    .remove(&message_id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(option, _)| option)
+                    .collect(),
+            )
+            .maybe_closes_at(
+                row.get::<Option<time::OffsetDateTime>, _>("closes_at")
+                    .map(offset_to_system_time),
+            )
+            .maybe_closed_at(
+                row.get::<Option<time::OffsetDateTime>, _>("closed_at")
+                    .map(offset_to_system_time),
+            )
+            .build())
+    }
+
+    /// Options with vote counts for each poll, keyed by message id.
+    async fn poll_options(
+        &self,
+        message_ids: &[uuid::Uuid],
+    ) -> Result<std::collections::HashMap<uuid::Uuid, Vec<(chat::PollOption, u64)>>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT o.id, o.message_id, o.label, COUNT(v.user_id) AS votes FROM chat_poll_options o LEFT JOIN chat_poll_votes v ON v.option_id = o.id WHERE o.message_id = ANY($1) GROUP BY o.id ORDER BY o.message_id, o.position"
+        );
+        let rows = sqlx::query(
+            r#"
+            SELECT o.id, o.message_id, o.label, COUNT(v.user_id) AS votes
+            FROM chat_poll_options o
+            LEFT JOIN chat_poll_votes v ON v.option_id = o.id
+            WHERE o.message_id = ANY($1)
+            GROUP BY o.id
+            ORDER BY o.message_id, o.position
+            "#,
+        )
+        .bind(message_ids)
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        let mut options = std::collections::HashMap::<_, Vec<_>>::new();
+        for row in rows {
+            let label = chat::PollOptionLabel::try_new(row.get::<String, _>("label"))
+                .map_err(|error| Error::Repo(error.to_string().into()))?;
+            options
+                .entry(row.get::<uuid::Uuid, _>("message_id"))
+                .or_default()
+                .push((
+                    chat::PollOption::builder()
+                        .id(chat::PollOptionId::from_uuid(row.get::<uuid::Uuid, _>("id")))
+                        .label(label)
+                        .build(),
+                    row.get::<i64, _>("votes").max(0) as u64,
+                ));
+        }
+        Ok(options)
+    }
+
     fn status_to_db(
         status: chat::MessageStatus,
     ) -> &'static str {
@@ -45,6 +391,7 @@ impl SqlxChatRepository {
             chat::MessageStatus::Visible => "visible",
             chat::MessageStatus::Pending => "pending",
             chat::MessageStatus::Removed => "removed",
+            chat::MessageStatus::Quarantined => "quarantined",
         }
     }
 }
@@ -349,6 +696,28 @@ impl app::chat::Repository for SqlxChatRepository {
         Ok(row.is_some())
     }
 
+    async fn room_members(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<Vec<chat::UserId>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT user_id FROM chat_room_memberships WHERE room_id = $1"
+        );
+        let rows =
+            sqlx::query("SELECT user_id FROM chat_room_memberships WHERE room_id = $1")
+                .bind(room_id.as_uuid())
+                .fetch_all(&self.pg)
+                .await
+                .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| chat::UserId::from_uuid(row.get::<uuid::Uuid, _>("user_id")))
+            .collect())
+    }
+
     async fn update_message_status(
         &self,
         message_id: &chat::MessageId,
@@ -374,169 +743,2678 @@ impl app::chat::Repository for SqlxChatRepository {
 
         Ok(())
     }
-}
 
-pub struct SqlxChatModerationQueue {
-    pg: PgPool,
-}
+    async fn mark_read(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+        message_id: &chat::MessageId,
+    ) -> Result<bool> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "UPDATE chat_room_memberships SET last_read_message_id = $3 WHERE room_id = $1 AND user_id = $2 AND newer"
+        );
+        let result = sqlx::query(
+            r#"
+            UPDATE chat_room_memberships AS m
+            SET last_read_message_id = target.id,
+                last_read_at = now()
+            FROM chat_messages AS target
+            WHERE m.room_id = $1
+              AND m.user_id = $2
+              AND target.id = $3
+              AND target.room_id = m.room_id
+              AND (
+                  m.last_read_message_id IS NULL
+                  OR target.created_at > (
+                      SELECT previous.created_at
+                      FROM chat_messages AS previous
+                      WHERE previous.id = m.last_read_message_id
+                  )
+              )
+            "#,
+        )
+        .bind(room_id.as_uuid())
+        .bind(user_id.as_uuid())
+        .bind(message_id.as_uuid())
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
 
-impl SqlxChatModerationQueue {
-    pub fn new(pg: PgPool) -> Self {
-        Self { pg }
+        Ok(result.rows_affected() > 0)
     }
-}
 
-#[async_trait]
-impl app::chat::ModerationQueue for SqlxChatModerationQueue {
-    async fn enqueue(
+    async fn list_unread(
+        &self,
+        user_id: &chat::UserId,
+    ) -> Result<Vec<RoomUnread>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT room unread counts FROM chat_room_memberships JOIN chat_messages WHERE user_id = $1"
+        );
+        let rows = sqlx::query(
+            r#"
+            SELECT r.id, r.name, COUNT(msg.id) AS unread
+            FROM chat_room_memberships AS m
+            JOIN chat_rooms AS r ON r.id = m.room_id
+            LEFT JOIN chat_messages AS last_read ON last_read.id = m.last_read_message_id
+            LEFT JOIN chat_messages AS msg
+                ON msg.room_id = m.room_id
+               AND msg.status = 'visible'
+               AND msg.user_id <> m.user_id
+               AND (last_read.id IS NULL OR msg.created_at > last_read.created_at)
+            WHERE m.user_id = $1
+            GROUP BY r.id, r.name
+            ORDER BY r.name
+            "#,
+        )
+        .bind(user_id.as_uuid())
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        rows.into_iter()
+            .map(|row| {
+                let room_name = chat::RoomName::try_new(row.get::<String, _>("name"))
+                    .map_err(|error| Error::Repo(error.to_string().into()))?;
+                Ok(RoomUnread::builder()
+                    .room_id(chat::RoomId::from_uuid(row.get::<uuid::Uuid, _>("id")))
+                    .room_name(room_name)
+                    .unread(row.get::<i64, _>("unread").max(0) as u64)
+                    .build())
+            })
+            .collect()
+    }
+
+    async fn list_readers(
         &self,
+        room_id: &chat::RoomId,
         message_id: &chat::MessageId,
-        reason: &ModerationReason,
-    ) -> Result<()> {
+    ) -> Result<Vec<chat::UserId>> {
         tracing::info!(
             target: "demo.db",
             message = "db query",
-            db_statement = "INSERT INTO chat_moderation_queue (message_id, reason) VALUES ($1, $2)"
+            db_statement = "SELECT user_id FROM chat_room_memberships WHERE room_id = $1 AND last read >= $2"
         );
-        sqlx::query(
+        let rows = sqlx::query(
             r#"
-            INSERT INTO chat_moderation_queue (message_id, reason)
-            VALUES ($1, $2)
+            SELECT m.user_id
+            FROM chat_room_memberships AS m
+            JOIN chat_messages AS last_read ON last_read.id = m.last_read_message_id
+            JOIN chat_messages AS target ON target.id = $2
+            WHERE m.room_id = $1
+              AND target.room_id = m.room_id
+              AND m.user_id <> target.user_id
+              AND last_read.created_at >= target.created_at
+            ORDER BY m.last_read_at
             "#,
         )
+        .bind(room_id.as_uuid())
         .bind(message_id.as_uuid())
-        .bind(reason.to_string())
-        .execute(&self.pg)
+        .fetch_all(&self.pg)
         .await
         .map_err(|error| Error::Repo(error.to_string().into()))?;
 
-        Ok(())
+        Ok(rows
+            .into_iter()
+            .map(|row| chat::UserId::from_uuid(row.get::<uuid::Uuid, _>("user_id")))
+            .collect())
     }
 
-    async fn list_pending(
+    async fn search_messages(
         &self,
+        user_id: &chat::UserId,
+        query: &SearchQuery,
         limit: usize,
-    ) -> Result<Vec<app::chat::ModerationItem>> {
+    ) -> Result<Vec<SearchHit>> {
         tracing::info!(
             target: "demo.db",
             message = "db query",
-            db_statement = "SELECT queue entries"
+            db_statement = "SELECT chat_messages ranked by ts_rank(body_tsv, websearch_to_tsquery($2)) JOIN chat_room_memberships WHERE user_id = $1 LIMIT $4"
+        );
+        let highlight_options = format!(
+            "StartSel={}, StopSel={}, MaxWords=24, MinWords=8, MaxFragments=2",
+            SearchHighlight::MATCH_START,
+            SearchHighlight::MATCH_END,
         );
         let rows = sqlx::query(
             r#"
-            SELECT q.message_id,
-                   q.reason,
-                   q.status,
-                   q.reviewed_at,
-                   m.room_id,
-                   m.user_id,
-                   m.body,
-                   r.name AS room_name,
-                   m.created_at::text AS created_at
-            FROM chat_moderation_queue q
-            JOIN chat_messages m ON m.id = q.message_id
-            JOIN chat_rooms r ON r.id = m.room_id
-            WHERE q.status = 'pending'
-            ORDER BY m.created_at ASC
-            LIMIT $1
+            SELECT msg.id, msg.room_id, msg.user_id, msg.body, msg.status,
+                   msg.client_id, msg.created_at, r.name AS room_name,
+                   ts_rank(msg.body_tsv, query) AS rank,
+                   ts_headline('english', msg.body, query, $3) AS highlight
+            FROM chat_messages AS msg
+            JOIN chat_room_memberships AS m
+                ON m.room_id = msg.room_id
+               AND m.user_id = $1
+            JOIN chat_rooms AS r ON r.id = msg.room_id
+            CROSS JOIN websearch_to_tsquery('english', $2) AS query
+            WHERE msg.status = 'visible'
+              AND msg.body_tsv @@ query
+            ORDER BY rank DESC, msg.created_at DESC
+            LIMIT $4
             "#,
         )
+        .bind(user_id.as_uuid())
+        .bind(query.to_string())
+        .bind(highlight_options)
         .bind(limit as i64)
         .fetch_all(&self.pg)
         .await
         .map_err(|error| Error::Repo(error.to_string().into()))?;
 
-        let mut items = Vec::with_capacity(rows.len());
-        for row in rows {
-            let room_name = row.get::<String, _>("room_name");
-            let room_name = chat::RoomName::try_new(room_name)
-                .map_err(|error| Error::Repo(error.to_string().into()))?;
-            let body = row.get::<String, _>("body");
-            let body = chat::MessageBody::try_new(body)
-                .map_err(|error| Error::Repo(error.to_string().into()))?;
-            let queue_status = row.get::<String, _>("status");
-            let queue_status =
-                queue_status.parse::<ModerationQueueStatus>().map_err(|_| {
-                    Error::Repo(
-                        app::chat::RepoErrorText::new(format!(
-                            "unknown moderation status: {}",
-                            queue_status
-                        ))
-                        ,
-                    )
-                })?;
-            let reason = ModerationReason::try_new(
-                row.get::<String, _>("reason"),
-            )
-            .map_err(|error| Error::Repo(error.to_string().into()))?;
-            let created_at = app::chat::TimestampText::try_new(
-                row.get::<String, _>("created_at"),
-            )
-            .map_err(|error| Error::Repo(error.to_string().into()))?;
-
-            items.push(
-                app::chat::ModerationItem::builder()
-                    .message_id(chat::MessageId::from_uuid(
-                        row.get::<uuid::Uuid, _>("message_id"),
-                    ))
-                    .room_id(chat::RoomId::from_uuid(
-                        row.get::<uuid::Uuid, _>("room_id"),
-                    ))
+        rows.into_iter()
+            .map(|row| {
+                let body = chat::MessageBody::try_new(row.get::<String, _>("body"))
+                    .map_err(|error| Error::Repo(error.to_string().into()))?;
+                let room_name = chat::RoomName::try_new(row.get::<String, _>("room_name"))
+                    .map_err(|error| Error::Repo(error.to_string().into()))?;
+                let message = chat::Message {
+                    id: chat::MessageId::from_uuid(row.get::<uuid::Uuid, _>("id")),
+                    room_id: chat::RoomId::from_uuid(row.get::<uuid::Uuid, _>("room_id")),
+                    user_id: chat::UserId::from_uuid(row.get::<uuid::Uuid, _>("user_id")),
+                    body,
+                    status: Self::status_from_db(row.get::<String, _>("status").as_str())?,
+                    client_id: row
+                        .get::<Option<String>, _>("client_id")
+                        .map(|value| chat::ClientId::try_new(value).expect("client id")),
+                    created_at: offset_to_system_time(
+                        row.get::<time::OffsetDateTime, _>("created_at"),
+                    ),
+                };
+                Ok(SearchHit::builder()
+                    .message(message)
                     .room_name(room_name)
-                    .user_id(chat::UserId::from_uuid(
-                        row.get::<uuid::Uuid, _>("user_id"),
-                    ))
-                    .body(body)
-                    .queue_status(queue_status)
-                    .reason(reason)
-                    .created_at(created_at)
-                    .build(),
-            );
-        }
-
-        Ok(items)
+                    .rank(row.get::<f32, _>("rank"))
+                    .highlight(SearchHighlight::new(row.get::<String, _>("highlight")))
+                    .build())
+            })
+            .collect()
     }
 
-    async fn complete(
+    async fn member_role(
         &self,
-        message_id: &chat::MessageId,
-        reviewer_id: &chat::UserId,
-        decision: app::chat::ModerationDecision,
-        reason: Option<ModerationReason>,
-    ) -> Result<()> {
-        let status = match decision {
-            app::chat::ModerationDecision::Approve => {
-                ModerationQueueStatus::Approved
-            }
-            app::chat::ModerationDecision::Remove => {
-                ModerationQueueStatus::Removed
-            }
-        };
-
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+    ) -> Result<Option<RoomRole>> {
         tracing::info!(
             target: "demo.db",
             message = "db query",
-            db_statement = "UPDATE chat_moderation_queue"
+            db_statement = "SELECT role FROM chat_room_memberships WHERE room_id = $1 AND user_id = $2"
         );
-        sqlx::query(
+        let row = sqlx::query(
             r#"
-            UPDATE chat_moderation_queue
-            SET status = $2,
-                reviewer_id = $3,
-                reviewed_at = now(),
-                reason = COALESCE($4, reason)
-            WHERE message_id = $1
+            SELECT role
+            FROM chat_room_memberships
+            WHERE room_id = $1 AND user_id = $2
             "#,
         )
-        .bind(message_id.as_uuid())
-        .bind(status.to_string())
-        .bind(reviewer_id.as_uuid())
-        .bind(reason.map(|value| value.to_string()))
-        .execute(&self.pg)
+        .bind(room_id.as_uuid())
+        .bind(user_id.as_uuid())
+        .fetch_optional(&self.pg)
         .await
         .map_err(|error| Error::Repo(error.to_string().into()))?;
 
-        Ok(())
+        row.map(|row| {
+            let value = row.get::<String, _>("role");
+            value.parse::<RoomRole>().map_err(|_| {
+                Error::Repo(format!("unknown room role: {}", value).into())
+            })
+        })
+        .transpose()
+    }
+
+    async fn export_messages(
+        &self,
+        room_id: &chat::RoomId,
+        after: Option<&ExportCursor>,
+        limit: usize,
+    ) -> Result<Vec<ExportRecord>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT chat_messages LEFT JOIN users WHERE room_id = $1 AND (created_at, id) > ($2, $3) ORDER BY created_at, id LIMIT $4"
+        );
+        let rows = sqlx::query(
+            r#"
+            SELECT msg.id, msg.user_id, u.username, msg.body, msg.status, msg.created_at
+            FROM chat_messages AS msg
+            LEFT JOIN users AS u ON u.id = msg.user_id
+            WHERE msg.room_id = $1
+              AND ($2::timestamptz IS NULL OR (msg.created_at, msg.id) > ($2, $3))
+            ORDER BY msg.created_at, msg.id
+            LIMIT $4
+            "#,
+        )
+        .bind(room_id.as_uuid())
+        .bind(after.map(|cursor| time::OffsetDateTime::from(cursor.created_at)))
+        .bind(after.map(|cursor| *cursor.message_id.as_uuid()))
+        .bind(limit as i64)
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        rows.into_iter()
+            .map(|row| {
+                let body = chat::MessageBody::try_new(row.get::<String, _>("body"))
+                    .map_err(|error| Error::Repo(error.to_string().into()))?;
+                let author = row
+                    .get::<Option<String>, _>("username")
+                    .and_then(|value| domain::user::Username::try_new(value).ok());
+                Ok(ExportRecord::builder()
+                    .message_id(chat::MessageId::from_uuid(row.get::<uuid::Uuid, _>("id")))
+                    .author_id(chat::UserId::from_uuid(row.get::<uuid::Uuid, _>("user_id")))
+                    .maybe_author(author)
+                    .body(body)
+                    .status(Self::status_from_db(row.get::<String, _>("status").as_str())?)
+                    .created_at(offset_to_system_time(
+                        row.get::<time::OffsetDateTime, _>("created_at"),
+                    ))
+                    .build())
+            })
+            .collect()
+    }
+
+    async fn save_retention(
+        &self,
+        retention: &RoomRetention,
+    ) -> Result<()> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "INSERT INTO chat_room_retention (room_id, max_age_days, max_messages, mode, set_by) VALUES ($1, $2, $3, $4, $5) ON CONFLICT (room_id) DO UPDATE"
+        );
+        let (max_age_days, max_messages) = Self::retention_limits(retention);
+        sqlx::query(
+            r#"
+            INSERT INTO chat_room_retention (room_id, max_age_days, max_messages, mode, set_by)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (room_id) DO UPDATE
+            SET max_age_days = EXCLUDED.max_age_days,
+                max_messages = EXCLUDED.max_messages,
+                mode = EXCLUDED.mode,
+                set_by = EXCLUDED.set_by,
+                updated_at = now()
+            "#,
+        )
+        .bind(retention.room_id.as_uuid())
+        .bind(max_age_days)
+        .bind(max_messages)
+        .bind(retention.policy.mode.to_string())
+        .bind(retention.set_by.as_uuid())
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(())
+    }
+
+    async fn clear_retention(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<()> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "DELETE FROM chat_room_retention WHERE room_id = $1"
+        );
+        sqlx::query(
+            r#"
+            DELETE FROM chat_room_retention
+            WHERE room_id = $1
+            "#,
+        )
+        .bind(room_id.as_uuid())
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(())
+    }
+
+    async fn find_retention(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<Option<RoomRetention>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT room_id, max_age_days, max_messages, mode, set_by FROM chat_room_retention WHERE room_id = $1"
+        );
+        let row = sqlx::query(
+            r#"
+            SELECT room_id, max_age_days, max_messages, mode, set_by
+            FROM chat_room_retention
+            WHERE room_id = $1
+            "#,
+        )
+        .bind(room_id.as_uuid())
+        .fetch_optional(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        row.as_ref().map(Self::retention_from_row).transpose()
+    }
+
+    async fn list_retention(&self) -> Result<Vec<RoomRetention>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT room_id, max_age_days, max_messages, mode, set_by FROM chat_room_retention"
+        );
+        let rows = sqlx::query(
+            r#"
+            SELECT room_id, max_age_days, max_messages, mode, set_by
+            FROM chat_room_retention
+            ORDER BY room_id
+            "#,
+        )
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        rows.iter().map(Self::retention_from_row).collect()
+    }
+
+    async fn count_expired(
+        &self,
+        retention: &RoomRetention,
+        now: std::time::SystemTime,
+    ) -> Result<u64> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "WITH expired AS (...) SELECT COUNT(*) FROM expired"
+        );
+        let (max_age_days, max_messages) = Self::retention_limits(retention);
+        let row = sqlx::query(&format!(
+            "WITH {EXPIRED_MESSAGES} SELECT COUNT(*) AS expired FROM expired"
+        ))
+        .bind(retention.room_id.as_uuid())
+        .bind(time::OffsetDateTime::from(now))
+        .bind(max_age_days)
+        .bind(max_messages)
+        .fetch_one(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(row.get::<i64, _>("expired").max(0) as u64)
+    }
+
+    async fn purge_expired(
+        &self,
+        retention: &RoomRetention,
+        now: std::time::SystemTime,
+        limit: usize,
+    ) -> Result<u64> {
+        let purge = match retention.policy.mode {
+            PurgeMode::Delete => {
+                "DELETE FROM chat_messages WHERE id IN (SELECT id FROM batch)"
+            }
+            PurgeMode::Redact => {
+                "UPDATE chat_messages \
+                 SET body = $6, status = 'removed', redacted_at = $2 \
+                 WHERE id IN (SELECT id FROM batch)"
+            }
+        };
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "WITH expired AS (...), batch AS (... LIMIT $5) DELETE FROM chat_moderation_queue; DELETE/UPDATE chat_messages",
+            mode = %retention.policy.mode
+        );
+        let (max_age_days, max_messages) = Self::retention_limits(retention);
+        let result = sqlx::query(&format!(
+            "WITH {EXPIRED_MESSAGES},
+                 batch AS (SELECT id FROM expired ORDER BY created_at, id LIMIT $5),
+                 dropped_queue AS (
+                     DELETE FROM chat_moderation_queue
+                     WHERE message_id IN (SELECT id FROM batch)
+                 )
+             {purge}"
+        ))
+        .bind(retention.room_id.as_uuid())
+        .bind(time::OffsetDateTime::from(now))
+        .bind(max_age_days)
+        .bind(max_messages)
+        .bind(limit as i64)
+        .bind(REDACTED_BODY)
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(result.rows_affected())
+    }
+
+    async fn insert_schedule(
+        &self,
+        schedule: &chat::ScheduledMessage,
+    ) -> Result<()> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "INSERT INTO chat_scheduled_messages (id, room_id, user_id, body, send_at, status) VALUES ($1, $2, $3, $4, $5, $6)"
+        );
+        sqlx::query(
+            r#"
+            INSERT INTO chat_scheduled_messages (id, room_id, user_id, body, send_at, status)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(schedule.id.as_uuid())
+        .bind(schedule.room_id.as_uuid())
+        .bind(schedule.user_id.as_uuid())
+        .bind(schedule.body.to_string())
+        .bind(time::OffsetDateTime::from(schedule.send_at))
+        .bind(schedule.status.to_string())
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(())
+    }
+
+    async fn find_schedule(
+        &self,
+        schedule_id: &chat::ScheduleId,
+    ) -> Result<Option<chat::ScheduledMessage>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT id, room_id, user_id, body, send_at, status, message_id FROM chat_scheduled_messages WHERE id = $1"
+        );
+        let row = sqlx::query(
+            r#"
+            SELECT id, room_id, user_id, body, send_at, status, message_id
+            FROM chat_scheduled_messages
+            WHERE id = $1
+            "#,
+        )
+        .bind(schedule_id.as_uuid())
+        .fetch_optional(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        row.as_ref().map(Self::schedule_from_row).transpose()
+    }
+
+    async fn list_schedules(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+    ) -> Result<Vec<chat::ScheduledMessage>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT id, room_id, user_id, body, send_at, status, message_id FROM chat_scheduled_messages WHERE room_id = $1 AND user_id = $2 AND status = 'pending' ORDER BY send_at"
+        );
+        let rows = sqlx::query(
+            r#"
+            SELECT id, room_id, user_id, body, send_at, status, message_id
+            FROM chat_scheduled_messages
+            WHERE room_id = $1 AND user_id = $2 AND status = 'pending'
+            ORDER BY send_at
+            "#,
+        )
+        .bind(room_id.as_uuid())
+        .bind(user_id.as_uuid())
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        rows.iter().map(Self::schedule_from_row).collect()
+    }
+
+    async fn cancel_schedule(
+        &self,
+        schedule_id: &chat::ScheduleId,
+    ) -> Result<bool> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "UPDATE chat_scheduled_messages SET status = 'cancelled' WHERE id = $1 AND status = 'pending'"
+        );
+        let result = sqlx::query(
+            r#"
+            UPDATE chat_scheduled_messages
+            SET status = 'cancelled', updated_at = now()
+            WHERE id = $1 AND status = 'pending'
+            "#,
+        )
+        .bind(schedule_id.as_uuid())
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn claim_due_schedules(
+        &self,
+        now: std::time::SystemTime,
+        limit: usize,
+    ) -> Result<Vec<chat::ScheduledMessage>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "UPDATE chat_scheduled_messages SET status = 'sending' WHERE id IN (SELECT ... WHERE status = 'pending' AND send_at <= $1 FOR UPDATE SKIP LOCKED LIMIT $2)"
+        );
+        let rows = sqlx::query(
+            r#"
+            UPDATE chat_scheduled_messages
+            SET status = 'sending', updated_at = now()
+            WHERE id IN (
+                SELECT id
+                FROM chat_scheduled_messages
+                WHERE status = 'pending' AND send_at <= $1
+                ORDER BY send_at
+                LIMIT $2
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, room_id, user_id, body, send_at, status, message_id
+            "#,
+        )
+        .bind(time::OffsetDateTime::from(now))
+        .bind(limit as i64)
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        let mut schedules = rows
+            .iter()
+            .map(Self::schedule_from_row)
+            .collect::<Result<Vec<_>>>()?;
+        schedules.sort_by_key(|schedule| schedule.send_at);
+        Ok(schedules)
+    }
+
+    async fn finish_schedule(
+        &self,
+        schedule_id: &chat::ScheduleId,
+        status: chat::ScheduleStatus,
+        message_id: Option<&chat::MessageId>,
+    ) -> Result<()> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "UPDATE chat_scheduled_messages SET status = $2, message_id = $3 WHERE id = $1"
+        );
+        sqlx::query(
+            r#"
+            UPDATE chat_scheduled_messages
+            SET status = $2, message_id = $3, updated_at = now()
+            WHERE id = $1
+            "#,
+        )
+        .bind(schedule_id.as_uuid())
+        .bind(status.to_string())
+        .bind(message_id.map(|value| *value.as_uuid()))
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(())
+    }
+
+    async fn insert_pin(
+        &self,
+        room_id: &chat::RoomId,
+        message_id: &chat::MessageId,
+        pinned_by: &chat::UserId,
+        pinned_at: std::time::SystemTime,
+    ) -> Result<bool> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "INSERT INTO chat_pins (message_id, room_id, pinned_by, pinned_at) VALUES ($1, $2, $3, $4) ON CONFLICT (message_id) DO NOTHING"
+        );
+        let result = sqlx::query(
+            r#"
+            INSERT INTO chat_pins (message_id, room_id, pinned_by, pinned_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (message_id) DO NOTHING
+            "#,
+        )
+        .bind(message_id.as_uuid())
+        .bind(room_id.as_uuid())
+        .bind(pinned_by.as_uuid())
+        .bind(time::OffsetDateTime::from(pinned_at))
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_pin(
+        &self,
+        message_id: &chat::MessageId,
+    ) -> Result<bool> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "DELETE FROM chat_pins WHERE message_id = $1"
+        );
+        let result = sqlx::query("DELETE FROM chat_pins WHERE message_id = $1")
+            .bind(message_id.as_uuid())
+            .execute(&self.pg)
+            .await
+            .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_pins(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<Vec<PinnedMessage>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT m.id, m.room_id, m.user_id, m.body, m.status, m.client_id, m.created_at, p.pinned_by, p.pinned_at FROM chat_pins p JOIN chat_messages m ON m.id = p.message_id WHERE p.room_id = $1 AND m.status = 'visible' ORDER BY p.pinned_at DESC"
+        );
+        let rows = sqlx::query(
+            r#"
+            SELECT m.id, m.room_id, m.user_id, m.body, m.status, m.client_id, m.created_at,
+                   p.pinned_by, p.pinned_at
+            FROM chat_pins p
+            JOIN chat_messages m ON m.id = p.message_id
+            WHERE p.room_id = $1 AND m.status = 'visible'
+            ORDER BY p.pinned_at DESC
+            "#,
+        )
+        .bind(room_id.as_uuid())
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        rows.iter()
+            .map(|row| {
+                let body = chat::MessageBody::try_new(row.get::<String, _>("body"))
+                    .map_err(|error| Error::Repo(error.to_string().into()))?;
+                let status =
+                    Self::status_from_db(row.get::<String, _>("status").as_str())?;
+                let client_id = row
+                    .get::<Option<String>, _>("client_id")
+                    .map(|value| {
+                        chat::ClientId::try_new(value)
+                            .map_err(|error| Error::Repo(error.to_string().into()))
+                    })
+                    .transpose()?;
+
+                Ok(PinnedMessage::builder()
+                    .message(chat::Message {
+                        id: chat::MessageId::from_uuid(row.get::<uuid::Uuid, _>("id")),
+                        room_id: chat::RoomId::from_uuid(row.get::<uuid::Uuid, _>("room_id")),
+                        user_id: chat::UserId::from_uuid(row.get::<uuid::Uuid, _>("user_id")),
+                        body,
+                        status,
+                        client_id,
+                        created_at: offset_to_system_time(
+                            row.get::<time::OffsetDateTime, _>("created_at"),
+                        ),
+                    })
+                    .pinned_by(chat::UserId::from_uuid(row.get::<uuid::Uuid, _>("pinned_by")))
+                    .pinned_at(offset_to_system_time(
+                        row.get::<time::OffsetDateTime, _>("pinned_at"),
+                    ))
+                    .build())
+            })
+            .collect()
+    }
+
+    async fn count_pins(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<usize> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT COUNT(*) AS pins FROM chat_pins p JOIN chat_messages m ON m.id = p.message_id WHERE p.room_id = $1 AND m.status = 'visible'"
+        );
+        let row = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS pins
+            FROM chat_pins p
+            JOIN chat_messages m ON m.id = p.message_id
+            WHERE p.room_id = $1 AND m.status = 'visible'
+            "#,
+        )
+        .bind(room_id.as_uuid())
+        .fetch_one(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(row.get::<i64, _>("pins").max(0) as usize)
+    }
+
+    async fn insert_poll(
+        &self,
+        poll: &chat::Poll,
+    ) -> Result<()> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "WITH poll AS (INSERT INTO chat_polls (...) RETURNING message_id) INSERT INTO chat_poll_options (id, message_id, position, label) SELECT ... FROM poll, unnest($6, $7) WITH ORDINALITY"
+        );
+        sqlx::query(
+            r#"
+            WITH poll AS (
+                INSERT INTO chat_polls (message_id, room_id, kind, closes_at, closed_at)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING message_id
+            )
+            INSERT INTO chat_poll_options (id, message_id, position, label)
+            SELECT option.id, poll.message_id, option.position, option.label
+            FROM poll,
+                 unnest($6::uuid[], $7::text[]) WITH ORDINALITY AS option(id, label, position)
+            "#,
+        )
+        .bind(poll.message_id.as_uuid())
+        .bind(poll.room_id.as_uuid())
+        .bind(poll.kind.to_string())
+        .bind(poll.closes_at.map(time::OffsetDateTime::from))
+        .bind(poll.closed_at.map(time::OffsetDateTime::from))
+        .bind(
+            poll.options
+                .iter()
+                .map(|option| *option.id.as_uuid())
+                .collect::<Vec<_>>(),
+        )
+        .bind(
+            poll.options
+                .iter()
+                .map(|option| option.label.to_string())
+                .collect::<Vec<_>>(),
+        )
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(())
+    }
+
+    async fn find_poll(
+        &self,
+        message_id: &chat::MessageId,
+    ) -> Result<Option<chat::Poll>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT message_id, room_id, kind, closes_at, closed_at FROM chat_polls WHERE message_id = $1"
+        );
+        let row = sqlx::query(
+            r#"
+            SELECT message_id, room_id, kind, closes_at, closed_at
+            FROM chat_polls
+            WHERE message_id = $1
+            "#,
+        )
+        .bind(message_id.as_uuid())
+        .fetch_optional(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        let mut options = self.poll_options(&[*message_id.as_uuid()]).await?;
+        Self::poll_from_row(&row, &mut options).map(Some)
+    }
+
+    async fn poll_results(
+        &self,
+        room_id: &chat::RoomId,
+        message_ids: &[chat::MessageId],
+    ) -> Result<Vec<PollResults>> {
+        let ids = message_ids
+            .iter()
+            .map(|id| *id.as_uuid())
+            .collect::<Vec<_>>();
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT p.message_id, p.room_id, p.kind, p.closes_at, p.closed_at, m.user_id AS author_id FROM chat_polls p JOIN chat_messages m ON m.id = p.message_id WHERE p.room_id = $1 AND p.message_id = ANY($2)"
+        );
+        let rows = sqlx::query(
+            r#"
+            SELECT p.message_id, p.room_id, p.kind, p.closes_at, p.closed_at,
+                   m.user_id AS author_id
+            FROM chat_polls p
+            JOIN chat_messages m ON m.id = p.message_id
+            WHERE p.room_id = $1 AND p.message_id = ANY($2)
+            "#,
+        )
+        .bind(room_id.as_uuid())
+        .bind(&ids)
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+        if rows.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut options = self.poll_options(&ids).await?;
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT message_id, COUNT(*) AS voters FROM chat_poll_ballots WHERE message_id = ANY($1) GROUP BY message_id"
+        );
+        let voters = sqlx::query(
+            r#"
+            SELECT message_id, COUNT(*) AS voters
+            FROM chat_poll_ballots
+            WHERE message_id = ANY($1)
+            GROUP BY message_id
+            "#,
+        )
+        .bind(&ids)
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?
+        .iter()
+        .map(|row| {
+            (
+                row.get::<uuid::Uuid, _>("message_id"),
+                row.get::<i64, _>("voters").max(0) as u64,
+            )
+        })
+        .collect::<std::collections::HashMap<_, _>>();
+
+        rows.iter()
+            .map(|row| {
+                let message_id = row.get::<uuid::Uuid, _>("message_id");
+                let votes = options
+                    .get(&message_id)
+                    .map(|options| options.iter().map(|(_, votes)| *votes).collect::<Vec<_>>())
+                    .unwrap_or_default();
+                let poll = Self::poll_from_row(row, &mut options)?;
+                Ok(PollResults::builder()
+                    .tallies(
+                        poll.options
+                            .iter()
+                            .zip(votes)
+                            .map(|(option, votes)| {
+                                PollTally::builder().option(option.clone()).votes(votes).build()
+                            })
+                            .collect(),
+                    )
+                    .poll(poll)
+                    .author_id(chat::UserId::from_uuid(row.get::<uuid::Uuid, _>("author_id")))
+                    .voters(voters.get(&message_id).copied().unwrap_or_default())
+                    .build())
+            })
+            .collect()
+    }
+
+    async fn cast_vote(
+        &self,
+        message_id: &chat::MessageId,
+        user_id: &chat::UserId,
+        option_ids: &[chat::PollOptionId],
+        voted_at: std::time::SystemTime,
+    ) -> Result<bool> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "WITH ballot AS (INSERT INTO chat_poll_ballots ... ON CONFLICT DO NOTHING RETURNING ...) INSERT INTO chat_poll_votes SELECT ... FROM ballot, unnest($3)"
+        );
+        let result = sqlx::query(
+            r#"
+            WITH ballot AS (
+                INSERT INTO chat_poll_ballots (message_id, user_id, voted_at)
+                VALUES ($1, $2, $4)
+                ON CONFLICT (message_id, user_id) DO NOTHING
+                RETURNING message_id, user_id
+            )
+            INSERT INTO chat_poll_votes (message_id, user_id, option_id)
+            SELECT ballot.message_id, ballot.user_id, option_id
+            FROM ballot, unnest($3::uuid[]) AS option_id
+            "#,
+        )
+        .bind(message_id.as_uuid())
+        .bind(user_id.as_uuid())
+        .bind(
+            option_ids
+                .iter()
+                .map(|id| *id.as_uuid())
+                .collect::<Vec<_>>(),
+        )
+        .bind(time::OffsetDateTime::from(voted_at))
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn close_poll(
+        &self,
+        message_id: &chat::MessageId,
+        closed_at: std::time::SystemTime,
+    ) -> Result<bool> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "UPDATE chat_polls SET closed_at = $2 WHERE message_id = $1 AND closed_at IS NULL"
+        );
+        let result = sqlx::query(
+            "UPDATE chat_polls SET closed_at = $2 WHERE message_id = $1 AND closed_at IS NULL",
+        )
+        .bind(message_id.as_uuid())
+        .bind(time::OffsetDateTime::from(closed_at))
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn close_due_polls(
+        &self,
+        now: std::time::SystemTime,
+    ) -> Result<Vec<chat::Poll>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "UPDATE chat_polls SET closed_at = closes_at WHERE closed_at IS NULL AND closes_at <= $1 RETURNING ..."
+        );
+        let rows = sqlx::query(
+            r#"
+            UPDATE chat_polls
+            SET closed_at = closes_at
+            WHERE closed_at IS NULL AND closes_at <= $1
+            RETURNING message_id, room_id, kind, closes_at, closed_at
+            "#,
+        )
+        .bind(time::OffsetDateTime::from(now))
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+        if rows.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids = rows
+            .iter()
+            .map(|row| row.get::<uuid::Uuid, _>("message_id"))
+            .collect::<Vec<_>>();
+        let mut options = self.poll_options(&ids).await?;
+        rows.iter()
+            .map(|row| Self::poll_from_row(row, &mut options))
+            .collect()
+    }
+
+    async fn set_room_topic(
+        &self,
+        room_id: &chat::RoomId,
+        topic: Option<&chat::RoomTopic>,
+    ) -> Result<()> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "UPDATE chat_rooms SET topic = $2 WHERE id = $1"
+        );
+        sqlx::query("UPDATE chat_rooms SET topic = $2 WHERE id = $1")
+            .bind(room_id.as_uuid())
+            .bind(topic.map(ToString::to_string))
+            .execute(&self.pg)
+            .await
+            .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(())
+    }
+
+    async fn find_room_topic(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<Option<chat::RoomTopic>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT topic FROM chat_rooms WHERE id = $1"
+        );
+        let row = sqlx::query("SELECT topic FROM chat_rooms WHERE id = $1")
+            .bind(room_id.as_uuid())
+            .fetch_optional(&self.pg)
+            .await
+            .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        row.and_then(|row| row.get::<Option<String>, _>("topic"))
+            .map(|topic| {
+                chat::RoomTopic::try_new(topic)
+                    .map_err(|error| Error::Repo(error.to_string().into()))
+            })
+            .transpose()
+    }
+
+    async fn save_mute(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+        until: std::time::SystemTime,
+        muted_by: Option<&chat::UserId>,
+    ) -> Result<()> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "INSERT INTO chat_room_mutes (room_id, user_id, muted_until, muted_by) VALUES ($1, $2, $3, $4) ON CONFLICT (room_id, user_id) DO UPDATE"
+        );
+        sqlx::query(
+            r#"
+            INSERT INTO chat_room_mutes (room_id, user_id, muted_until, muted_by)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (room_id, user_id)
+            DO UPDATE SET muted_until = EXCLUDED.muted_until,
+                          muted_by = EXCLUDED.muted_by,
+                          created_at = now()
+            "#,
+        )
+        .bind(room_id.as_uuid())
+        .bind(user_id.as_uuid())
+        .bind(time::OffsetDateTime::from(until))
+        .bind(muted_by.map(|muted_by| *muted_by.as_uuid()))
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(())
+    }
+
+    async fn muted_until(
+        &self,
+        room_id: &chat::RoomId,
+        user_id: &chat::UserId,
+    ) -> Result<Option<std::time::SystemTime>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT muted_until FROM chat_room_mutes WHERE room_id = $1 AND user_id = $2"
+        );
+        let row = sqlx::query(
+            "SELECT muted_until FROM chat_room_mutes WHERE room_id = $1 AND user_id = $2",
+        )
+        .bind(room_id.as_uuid())
+        .bind(user_id.as_uuid())
+        .fetch_optional(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(row.map(|row| {
+            offset_to_system_time(row.get::<time::OffsetDateTime, _>("muted_until"))
+        }))
+    }
+
+    async fn trust_level(
+        &self,
+        user_id: &chat::UserId,
+    ) -> Result<app::chat::TrustLevel> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT level FROM chat_user_trust WHERE user_id = $1"
+        );
+        let row = sqlx::query("SELECT level FROM chat_user_trust WHERE user_id = $1")
+            .bind(user_id.as_uuid())
+            .fetch_optional(&self.pg)
+            .await
+            .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        row.map(|row| {
+            let level = row.get::<String, _>("level");
+            level.parse::<app::chat::TrustLevel>().map_err(|_| {
+                Error::Repo(format!("unknown trust level: {}", level).into())
+            })
+        })
+        .transpose()
+        .map(Option::unwrap_or_default)
+    }
+
+    async fn save_trust_level(
+        &self,
+        user_id: &chat::UserId,
+        level: app::chat::TrustLevel,
+        set_by: &chat::UserId,
+        set_at: std::time::SystemTime,
+    ) -> Result<()> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "INSERT INTO chat_user_trust (user_id, level, set_by, set_at) VALUES ($1, $2, $3, $4) ON CONFLICT (user_id) DO UPDATE"
+        );
+        sqlx::query(
+            r#"
+            INSERT INTO chat_user_trust (user_id, level, set_by, set_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id) DO UPDATE
+            SET level = EXCLUDED.level,
+                set_by = EXCLUDED.set_by,
+                set_at = EXCLUDED.set_at
+            "#,
+        )
+        .bind(user_id.as_uuid())
+        .bind(level.to_string())
+        .bind(set_by.as_uuid())
+        .bind(time::OffsetDateTime::from(set_at))
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(())
+    }
+
+    async fn room_moderators(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<Vec<chat::UserId>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT user_id FROM chat_room_memberships WHERE room_id = $1 AND role IN ('owner', 'admin', 'moderator')"
+        );
+        let rows = sqlx::query(
+            "SELECT user_id FROM chat_room_memberships WHERE room_id = $1 AND role IN ('owner', 'admin', 'moderator')",
+        )
+        .bind(room_id.as_uuid())
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| chat::UserId::from_uuid(row.get::<uuid::Uuid, _>("user_id")))
+            .collect())
+    }
+
+    async fn moderated_rooms(
+        &self,
+        user_id: &chat::UserId,
+    ) -> Result<Vec<chat::RoomId>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT room_id FROM chat_room_memberships WHERE user_id = $1 AND role IN ('owner', 'admin', 'moderator')"
+        );
+        let rows = sqlx::query(
+            "SELECT room_id FROM chat_room_memberships WHERE user_id = $1 AND role IN ('owner', 'admin', 'moderator')",
+        )
+        .bind(user_id.as_uuid())
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| chat::RoomId::from_uuid(row.get::<uuid::Uuid, _>("room_id")))
+            .collect())
+    }
+
+    async fn recent_posts(
+        &self,
+        user_id: &chat::UserId,
+        since: std::time::SystemTime,
+        limit: usize,
+    ) -> Result<Vec<app::chat::RecentPost>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT room_id, body, created_at FROM chat_messages WHERE user_id = $1 AND created_at >= $2 ORDER BY created_at DESC LIMIT $3"
+        );
+        let rows = sqlx::query(
+            r#"
+            SELECT room_id, body, created_at
+            FROM chat_messages
+            WHERE user_id = $1 AND created_at >= $2
+            ORDER BY created_at DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(user_id.as_uuid())
+        .bind(time::OffsetDateTime::from(since))
+        .bind(i64::try_from(limit).unwrap_or(i64::MAX))
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        rows.iter().map(Self::recent_post_from_row).collect()
+    }
+
+    async fn record_flood_strike(
+        &self,
+        user_id: &chat::UserId,
+        room_id: &chat::RoomId,
+        signal: app::chat::FloodSignal,
+        at: std::time::SystemTime,
+        since: std::time::SystemTime,
+    ) -> Result<usize> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "WITH inserted AS (INSERT INTO chat_flood_strikes (...) RETURNING 1) SELECT COUNT(*) FROM chat_flood_strikes WHERE user_id = $1 AND struck_at >= $5 + COUNT(inserted)"
+        );
+        let row = sqlx::query(
+            r#"
+            WITH inserted AS (
+                INSERT INTO chat_flood_strikes (user_id, room_id, signal, struck_at)
+                VALUES ($1, $2, $3, $4)
+                RETURNING 1
+            )
+            SELECT (
+                SELECT count(*)
+                FROM chat_flood_strikes
+                WHERE user_id = $1 AND struck_at >= $5
+            ) + (SELECT count(*) FROM inserted) AS strikes
+            "#,
+        )
+        .bind(user_id.as_uuid())
+        .bind(room_id.as_uuid())
+        .bind(signal.to_string())
+        .bind(time::OffsetDateTime::from(at))
+        .bind(time::OffsetDateTime::from(since))
+        .fetch_one(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(usize::try_from(row.get::<i64, _>("strikes")).unwrap_or_default())
+    }
+
+    async fn insert_webhook(
+        &self,
+        webhook: &chat::Webhook,
+    ) -> Result<()> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "INSERT INTO chat_webhooks (id, room_id, url, events, secret, created_by, created_at) VALUES ($1, $2, $3, $4, $5, $6, $7)"
+        );
+        sqlx::query(
+            r#"
+            INSERT INTO chat_webhooks (id, room_id, url, events, secret, created_by, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+        .bind(webhook.id.as_uuid())
+        .bind(webhook.room_id.as_uuid())
+        .bind(webhook.url.to_string())
+        .bind(
+            webhook
+                .events
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+        )
+        .bind(webhook.secret.as_ref())
+        .bind(webhook.created_by.as_uuid())
+        .bind(time::OffsetDateTime::from(webhook.created_at))
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(())
+    }
+
+    async fn find_webhook(
+        &self,
+        webhook_id: &chat::WebhookId,
+    ) -> Result<Option<chat::Webhook>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT id, room_id, url, events, secret, created_by, created_at FROM chat_webhooks WHERE id = $1"
+        );
+        let row = sqlx::query(
+            r#"
+            SELECT id, room_id, url, events, secret, created_by, created_at
+            FROM chat_webhooks
+            WHERE id = $1
+            "#,
+        )
+        .bind(webhook_id.as_uuid())
+        .fetch_optional(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        row.as_ref().map(Self::webhook_from_row).transpose()
+    }
+
+    async fn list_webhooks(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<Vec<chat::Webhook>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT id, room_id, url, events, secret, created_by, created_at FROM chat_webhooks WHERE room_id = $1 ORDER BY created_at"
+        );
+        let rows = sqlx::query(
+            r#"
+            SELECT id, room_id, url, events, secret, created_by, created_at
+            FROM chat_webhooks
+            WHERE room_id = $1
+            ORDER BY created_at
+            "#,
+        )
+        .bind(room_id.as_uuid())
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        rows.iter().map(Self::webhook_from_row).collect()
+    }
+
+    async fn delete_webhook(
+        &self,
+        webhook_id: &chat::WebhookId,
+    ) -> Result<bool> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "DELETE FROM chat_webhooks WHERE id = $1"
+        );
+        let result = sqlx::query("DELETE FROM chat_webhooks WHERE id = $1")
+            .bind(webhook_id.as_uuid())
+            .execute(&self.pg)
+            .await
+            .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn enqueue_webhook_deliveries(
+        &self,
+        room_id: &chat::RoomId,
+        event: chat::WebhookEvent,
+        payload: &chat::WebhookPayload,
+        now: std::time::SystemTime,
+    ) -> Result<usize> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "INSERT INTO chat_webhook_deliveries (webhook_id, event, payload, next_attempt_at) SELECT id, $2, $3, $4 FROM chat_webhooks WHERE room_id = $1 AND $2 = ANY(events)"
+        );
+        let result = sqlx::query(
+            r#"
+            INSERT INTO chat_webhook_deliveries (webhook_id, event, payload, next_attempt_at)
+            SELECT id, $2, $3, $4
+            FROM chat_webhooks
+            WHERE room_id = $1 AND $2 = ANY(events)
+            "#,
+        )
+        .bind(room_id.as_uuid())
+        .bind(event.to_string())
+        .bind(payload.as_ref())
+        .bind(time::OffsetDateTime::from(now))
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(result.rows_affected() as usize)
+    }
+
+    async fn claim_due_deliveries(
+        &self,
+        now: std::time::SystemTime,
+        limit: usize,
+    ) -> Result<Vec<(chat::Webhook, chat::WebhookDelivery)>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "UPDATE chat_webhook_deliveries SET status = 'sending' WHERE id IN (SELECT ... WHERE status = 'pending' AND next_attempt_at <= $1 FOR UPDATE SKIP LOCKED LIMIT $2)"
+        );
+        // Rows left `sending` for five minutes belong to a worker that died
+        // mid-attempt and are picked up again.
+        let rows = sqlx::query(
+            r#"
+            UPDATE chat_webhook_deliveries
+            SET status = 'sending', updated_at = now()
+            WHERE id IN (
+                SELECT id
+                FROM chat_webhook_deliveries
+                WHERE (status = 'pending' AND next_attempt_at <= $1)
+                   OR (status = 'sending' AND updated_at < now() - interval '5 minutes')
+                ORDER BY next_attempt_at
+                LIMIT $2
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, webhook_id, event, payload, status, attempts,
+                      next_attempt_at, response_status, last_error, created_at
+            "#,
+        )
+        .bind(time::OffsetDateTime::from(now))
+        .bind(limit as i64)
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+        let mut deliveries = rows
+            .iter()
+            .map(Self::delivery_from_row)
+            .collect::<Result<Vec<_>>>()?;
+        if deliveries.is_empty() {
+            return Ok(Vec::new());
+        }
+        deliveries.sort_by_key(|delivery| delivery.next_attempt_at);
+
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT id, room_id, url, events, secret, created_by, created_at FROM chat_webhooks WHERE id = ANY($1)"
+        );
+        let webhook_ids = deliveries
+            .iter()
+            .map(|delivery| *delivery.webhook_id.as_uuid())
+            .collect::<Vec<_>>();
+        let rows = sqlx::query(
+            r#"
+            SELECT id, room_id, url, events, secret, created_by, created_at
+            FROM chat_webhooks
+            WHERE id = ANY($1)
+            "#,
+        )
+        .bind(&webhook_ids)
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+        let webhooks = rows
+            .iter()
+            .map(|row| Self::webhook_from_row(row).map(|webhook| (webhook.id, webhook)))
+            .collect::<Result<std::collections::HashMap<_, _>>>()?;
+
+        Ok(deliveries
+            .into_iter()
+            .filter_map(|delivery| {
+                webhooks
+                    .get(&delivery.webhook_id)
+                    .cloned()
+                    .map(|webhook| (webhook, delivery))
+            })
+            .collect())
+    }
+
+    async fn save_delivery_attempt(
+        &self,
+        delivery: &chat::WebhookDelivery,
+    ) -> Result<()> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "UPDATE chat_webhook_deliveries SET status = $2, attempts = $3, next_attempt_at = $4, response_status = $5, last_error = $6 WHERE id = $1"
+        );
+        sqlx::query(
+            r#"
+            UPDATE chat_webhook_deliveries
+            SET status = $2,
+                attempts = $3,
+                next_attempt_at = $4,
+                response_status = $5,
+                last_error = $6,
+                updated_at = now()
+            WHERE id = $1
+            "#,
+        )
+        .bind(delivery.id.as_uuid())
+        .bind(delivery.status.to_string())
+        .bind(i32::try_from(delivery.attempts).unwrap_or(i32::MAX))
+        .bind(time::OffsetDateTime::from(delivery.next_attempt_at))
+        .bind(delivery.response_status.map(i32::from))
+        .bind(delivery.last_error.as_ref().map(ToString::to_string))
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(())
+    }
+
+    async fn list_deliveries(
+        &self,
+        webhook_id: &chat::WebhookId,
+        limit: usize,
+    ) -> Result<Vec<chat::WebhookDelivery>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT ... FROM chat_webhook_deliveries WHERE webhook_id = $1 ORDER BY created_at DESC LIMIT $2"
+        );
+        let rows = sqlx::query(
+            r#"
+            SELECT id, webhook_id, event, payload, status, attempts,
+                   next_attempt_at, response_status, last_error, created_at
+            FROM chat_webhook_deliveries
+            WHERE webhook_id = $1
+            ORDER BY created_at DESC, id
+            LIMIT $2
+            "#,
+        )
+        .bind(webhook_id.as_uuid())
+        .bind(limit as i64)
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        rows.iter().map(Self::delivery_from_row).collect()
+    }
+
+    async fn save_incoming_webhook(
+        &self,
+        webhook: &chat::IncomingWebhook,
+    ) -> Result<()> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "INSERT INTO chat_incoming_webhooks (room_id, token, bot_id, created_by, created_at) VALUES ($1, $2, $3, $4, $5) ON CONFLICT (room_id) DO UPDATE SET ..."
+        );
+        sqlx::query(
+            r#"
+            INSERT INTO chat_incoming_webhooks (room_id, token, bot_id, created_by, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (room_id) DO UPDATE
+            SET token = EXCLUDED.token,
+                bot_id = EXCLUDED.bot_id,
+                created_by = EXCLUDED.created_by,
+                created_at = EXCLUDED.created_at
+            "#,
+        )
+        .bind(webhook.room_id.as_uuid())
+        .bind(webhook.token.as_ref())
+        .bind(webhook.bot_id.as_uuid())
+        .bind(webhook.created_by.as_uuid())
+        .bind(time::OffsetDateTime::from(webhook.created_at))
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(())
+    }
+
+    async fn find_incoming_webhook(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<Option<chat::IncomingWebhook>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT room_id, token, bot_id, created_by, created_at FROM chat_incoming_webhooks WHERE room_id = $1"
+        );
+        let row = sqlx::query(
+            r#"
+            SELECT room_id, token, bot_id, created_by, created_at
+            FROM chat_incoming_webhooks
+            WHERE room_id = $1
+            "#,
+        )
+        .bind(room_id.as_uuid())
+        .fetch_optional(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        row.as_ref().map(Self::incoming_webhook_from_row).transpose()
+    }
+
+    async fn find_incoming_webhook_by_token(
+        &self,
+        token: &chat::IncomingWebhookToken,
+    ) -> Result<Option<chat::IncomingWebhook>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT room_id, token, bot_id, created_by, created_at FROM chat_incoming_webhooks WHERE token = $1"
+        );
+        let row = sqlx::query(
+            r#"
+            SELECT room_id, token, bot_id, created_by, created_at
+            FROM chat_incoming_webhooks
+            WHERE token = $1
+            "#,
+        )
+        .bind(token.as_ref())
+        .fetch_optional(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        row.as_ref().map(Self::incoming_webhook_from_row).transpose()
+    }
+
+    async fn delete_incoming_webhook(
+        &self,
+        room_id: &chat::RoomId,
+    ) -> Result<bool> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "DELETE FROM chat_incoming_webhooks WHERE room_id = $1"
+        );
+        let result = sqlx::query("DELETE FROM chat_incoming_webhooks WHERE room_id = $1")
+            .bind(room_id.as_uuid())
+            .execute(&self.pg)
+            .await
+            .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn find_link_previews(
+        &self,
+        urls: &[chat::PreviewUrl],
+    ) -> Result<Vec<app::chat::CachedPreview>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT url, title, description, image_url, fetched_at FROM chat_link_previews WHERE url = ANY($1)"
+        );
+        let rows = sqlx::query(
+            r#"
+            SELECT url, title, description, image_url, fetched_at
+            FROM chat_link_previews
+            WHERE url = ANY($1)
+            "#,
+        )
+        .bind(urls.iter().map(ToString::to_string).collect::<Vec<_>>())
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        rows.iter().map(Self::link_preview_from_row).collect()
+    }
+
+    async fn save_link_preview(
+        &self,
+        preview: &app::chat::CachedPreview,
+    ) -> Result<()> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "INSERT INTO chat_link_previews (url, title, description, image_url, fetched_at) VALUES ($1, $2, $3, $4, $5) ON CONFLICT (url) DO UPDATE SET ..."
+        );
+        let card = &preview.preview;
+        sqlx::query(
+            r#"
+            INSERT INTO chat_link_previews (url, title, description, image_url, fetched_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (url) DO UPDATE
+            SET title = EXCLUDED.title,
+                description = EXCLUDED.description,
+                image_url = EXCLUDED.image_url,
+                fetched_at = EXCLUDED.fetched_at
+            "#,
+        )
+        .bind(card.url.to_string())
+        .bind(card.title.as_ref().map(ToString::to_string))
+        .bind(card.description.as_ref().map(ToString::to_string))
+        .bind(card.image_url.as_ref().map(ToString::to_string))
+        .bind(time::OffsetDateTime::from(preview.fetched_at))
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(())
+    }
+
+    async fn insert_attachment(
+        &self,
+        attachment: &chat::Attachment,
+    ) -> Result<()> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "INSERT INTO chat_attachments (id, message_id, room_id, uploaded_by, name, content_type, size_bytes, blob_key, thumbnail_key, created_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"
+        );
+        sqlx::query(
+            r#"
+            INSERT INTO chat_attachments
+                (id, message_id, room_id, uploaded_by, name, content_type, size_bytes,
+                 blob_key, thumbnail_key, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            "#,
+        )
+        .bind(attachment.id.as_uuid())
+        .bind(attachment.message_id.as_uuid())
+        .bind(attachment.room_id.as_uuid())
+        .bind(attachment.uploaded_by.as_uuid())
+        .bind(attachment.name.as_ref())
+        .bind(attachment.content_type.to_string())
+        .bind(i64::try_from(attachment.size_bytes).unwrap_or(i64::MAX))
+        .bind(attachment.blob_key.as_ref())
+        .bind(attachment.thumbnail_key.as_ref().map(|key| key.to_string()))
+        .bind(time::OffsetDateTime::from(attachment.created_at))
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(())
+    }
+
+    async fn find_attachment(
+        &self,
+        attachment_id: &chat::AttachmentId,
+    ) -> Result<Option<chat::Attachment>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT ... FROM chat_attachments WHERE id = $1 AND message_id IS NOT NULL"
+        );
+        let row = sqlx::query(
+            r#"
+            SELECT id, message_id, room_id, uploaded_by, name, content_type, size_bytes,
+                   blob_key, thumbnail_key, created_at
+            FROM chat_attachments
+            WHERE id = $1 AND message_id IS NOT NULL
+            "#,
+        )
+        .bind(attachment_id.as_uuid())
+        .fetch_optional(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        row.as_ref().map(Self::attachment_from_row).transpose()
+    }
+
+    async fn list_attachments(
+        &self,
+        message_ids: &[chat::MessageId],
+    ) -> Result<Vec<chat::Attachment>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT ... FROM chat_attachments WHERE message_id = ANY($1) ORDER BY created_at, id"
+        );
+        let rows = sqlx::query(
+            r#"
+            SELECT id, message_id, room_id, uploaded_by, name, content_type, size_bytes,
+                   blob_key, thumbnail_key, created_at
+            FROM chat_attachments
+            WHERE message_id = ANY($1)
+            ORDER BY created_at, id
+            "#,
+        )
+        .bind(
+            message_ids
+                .iter()
+                .map(|message_id| *message_id.as_uuid())
+                .collect::<Vec<_>>(),
+        )
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        rows.iter().map(Self::attachment_from_row).collect()
+    }
+
+    async fn list_orphaned_attachments(
+        &self,
+        limit: usize,
+    ) -> Result<Vec<app::chat::OrphanedAttachment>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT a.id, a.blob_key, a.thumbnail_key FROM chat_attachments a LEFT JOIN chat_messages m ON m.id = a.message_id WHERE m.id IS NULL OR m.redacted_at IS NOT NULL LIMIT $1"
+        );
+        let rows = sqlx::query(
+            r#"
+            SELECT a.id, a.blob_key, a.thumbnail_key
+            FROM chat_attachments a
+            LEFT JOIN chat_messages m ON m.id = a.message_id
+            WHERE m.id IS NULL OR m.redacted_at IS NOT NULL
+            ORDER BY a.created_at, a.id
+            LIMIT $1
+            "#,
+        )
+        .bind(limit as i64)
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        rows.iter()
+            .map(|row| {
+                let blob_key = chat::BlobKey::try_new(row.get::<String, _>("blob_key"))
+                    .map_err(|error| Error::Repo(error.to_string().into()))?;
+                let thumbnail_key = row
+                    .get::<Option<String>, _>("thumbnail_key")
+                    .map(|value| {
+                        chat::BlobKey::try_new(value)
+                            .map_err(|error| Error::Repo(error.to_string().into()))
+                    })
+                    .transpose()?;
+                Ok(app::chat::OrphanedAttachment::builder()
+                    .id(chat::AttachmentId::from_uuid(row.get::<uuid::Uuid, _>("id")))
+                    .blob_key(blob_key)
+                    .maybe_thumbnail_key(thumbnail_key)
+                    .build())
+            })
+            .collect()
+    }
+
+    async fn delete_attachment(
+        &self,
+        attachment_id: &chat::AttachmentId,
+    ) -> Result<()> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "DELETE FROM chat_attachments WHERE id = $1"
+        );
+        sqlx::query("DELETE FROM chat_attachments WHERE id = $1")
+            .bind(attachment_id.as_uuid())
+            .execute(&self.pg)
+            .await
+            .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(())
+    }
+}
+
+pub struct SqlxChatAuthorDirectory {
+    pg: PgPool,
+}
+
+impl SqlxChatAuthorDirectory {
+    pub fn new(pg: PgPool) -> Self {
+        Self { pg }
+    }
+}
+
+#[async_trait]
+impl app::chat::AuthorDirectory for SqlxChatAuthorDirectory {
+    async fn user_exists(
+        &self,
+        user_id: &chat::UserId,
+    ) -> Result<bool> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT 1 FROM users WHERE id = $1"
+        );
+        let row = sqlx::query(
+            r#"
+            SELECT 1
+            FROM users
+            WHERE id = $1
+            "#,
+        )
+        .bind(user_id.as_uuid())
+        .fetch_optional(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(row.is_some())
+    }
+
+    async fn find_by_username(
+        &self,
+        username: &domain::user::Username,
+    ) -> Result<Option<chat::UserId>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT id FROM users WHERE username = $1"
+        );
+        let row = sqlx::query(
+            r#"
+            SELECT id
+            FROM users
+            WHERE username = $1
+            "#,
+        )
+        .bind(username.to_string())
+        .fetch_optional(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(row.map(|row| chat::UserId::from_uuid(row.get::<uuid::Uuid, _>("id"))))
+    }
+
+    async fn create_placeholder(
+        &self,
+        username: &domain::user::Username,
+    ) -> Result<chat::UserId> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "INSERT INTO users (id, username, email) VALUES ($1, $2, $3)"
+        );
+        let id = uuid::Uuid::new_v4();
+        sqlx::query(
+            r#"
+            INSERT INTO users (id, username, email)
+            VALUES ($1, $2, $3)
+            "#,
+        )
+        .bind(id)
+        .bind(username.to_string())
+        .bind(format!("imported-{}@placeholder.invalid", id.simple()))
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(chat::UserId::from_uuid(id))
+    }
+
+    async fn ensure_bot(
+        &self,
+        username: &domain::user::Username,
+    ) -> Result<chat::UserId> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "INSERT INTO users (id, username, email, kind) VALUES ($1, $2, $3, $4) ON CONFLICT (username) DO NOTHING; SELECT id, kind FROM users WHERE username = $2"
+        );
+        let id = uuid::Uuid::new_v4();
+        let row = sqlx::query(
+            r#"
+            WITH inserted AS (
+                INSERT INTO users (id, username, email, kind)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (username) DO NOTHING
+                RETURNING id, kind
+            )
+            SELECT id, kind FROM inserted
+            UNION ALL
+            SELECT id, kind FROM users WHERE username = $2
+            LIMIT 1
+            "#,
+        )
+        .bind(id)
+        .bind(username.to_string())
+        .bind(format!("bot-{}@bots.invalid", id.simple()))
+        .bind(domain::user::Kind::Bot.to_string())
+        .fetch_one(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        let kind = row
+            .get::<String, _>("kind")
+            .parse::<domain::user::Kind>()
+            .map_err(|error| Error::Repo(error.to_string().into()))?;
+        if kind != domain::user::Kind::Bot {
+            return Err(Error::BotNameTaken);
+        }
+
+        Ok(chat::UserId::from_uuid(row.get::<uuid::Uuid, _>("id")))
+    }
+
+    async fn find_username(
+        &self,
+        user_id: &chat::UserId,
+    ) -> Result<Option<domain::user::Username>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT username FROM users WHERE id = $1"
+        );
+        let row = sqlx::query(
+            r#"
+            SELECT username
+            FROM users
+            WHERE id = $1
+            "#,
+        )
+        .bind(user_id.as_uuid())
+        .fetch_optional(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        row.map(|row| {
+            domain::user::Username::try_new(row.get::<String, _>("username"))
+                .map_err(|error| Error::Repo(error.to_string().into()))
+        })
+        .transpose()
+    }
+}
+
+pub struct SqlxChatModerationQueue {
+    pg: PgPool,
+}
+
+impl SqlxChatModerationQueue {
+    pub fn new(pg: PgPool) -> Self {
+        Self { pg }
+    }
+
+    fn appeal_from_row(row: &sqlx::postgres::PgRow) -> Result<app::chat::Appeal> {
+        let repo_error = |error: String| Error::Repo(error.into());
+        Ok(app::chat::Appeal::builder()
+            .message_id(chat::MessageId::from_uuid(row.get::<uuid::Uuid, _>("message_id")))
+            .room_id(chat::RoomId::from_uuid(row.get::<uuid::Uuid, _>("room_id")))
+            .room_name(
+                chat::RoomName::try_new(row.get::<String, _>("room_name"))
+                    .map_err(|error| repo_error(error.to_string()))?,
+            )
+            .appellant_id(chat::UserId::from_uuid(row.get::<uuid::Uuid, _>("appellant_id")))
+            .appellant_name(
+                domain::user::Username::try_new(row.get::<String, _>("appellant_name"))
+                    .map_err(|error| repo_error(error.to_string()))?,
+            )
+            .body(
+                chat::MessageBody::try_new(row.get::<String, _>("body"))
+                    .map_err(|error| repo_error(error.to_string()))?,
+            )
+            .reason(
+                ModerationReason::try_new(row.get::<String, _>("reason"))
+                    .map_err(|error| repo_error(error.to_string()))?,
+            )
+            .statement(
+                app::chat::AppealStatement::try_new(row.get::<String, _>("statement"))
+                    .map_err(|error| repo_error(error.to_string()))?,
+            )
+            .maybe_original_reviewer_id(
+                row.get::<Option<uuid::Uuid>, _>("original_reviewer_id")
+                    .map(chat::UserId::from_uuid),
+            )
+            .status(Self::parse_appeal_status(&row.get::<String, _>("status"))?)
+            .filed_at(offset_to_system_time(row.get::<time::OffsetDateTime, _>("filed_at")))
+            .build())
+    }
+
+    fn parse_appeal_status(value: &str) -> Result<app::chat::AppealStatus> {
+        value
+            .parse::<app::chat::AppealStatus>()
+            .map_err(|_| Error::Repo(format!("unknown appeal status: {}", value).into()))
+    }
+
+    /// Reports filed since each message was last reviewed, oldest first.
+    async fn open_reports(
+        &self,
+        message_ids: &[uuid::Uuid],
+    ) -> Result<Vec<(chat::MessageId, app::chat::MessageReport)>> {
+        if message_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT r.message_id, r.reporter_id, u.username, r.category, r.reported_at FROM chat_message_reports r JOIN chat_moderation_queue q ON q.message_id = r.message_id JOIN users u ON u.id = r.reporter_id WHERE r.message_id = ANY($1) AND (q.reviewed_at IS NULL OR r.reported_at > q.reviewed_at) ORDER BY r.reported_at"
+        );
+        let rows = sqlx::query(
+            r#"
+            SELECT r.message_id, r.reporter_id, u.username, r.category, r.reported_at
+            FROM chat_message_reports r
+            JOIN chat_moderation_queue q ON q.message_id = r.message_id
+            JOIN users u ON u.id = r.reporter_id
+            WHERE r.message_id = ANY($1)
+              AND (q.reviewed_at IS NULL OR r.reported_at > q.reviewed_at)
+            ORDER BY r.reported_at
+            "#,
+        )
+        .bind(message_ids)
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        rows.into_iter()
+            .map(|row| {
+                let category = row.get::<String, _>("category");
+                let category = category
+                    .parse::<app::chat::ReportCategory>()
+                    .map_err(|_| {
+                        Error::Repo(format!("unknown report category: {}", category).into())
+                    })?;
+                let reporter_name =
+                    domain::user::Username::try_new(row.get::<String, _>("username"))
+                        .map_err(|error| Error::Repo(error.to_string().into()))?;
+                Ok((
+                    chat::MessageId::from_uuid(row.get::<uuid::Uuid, _>("message_id")),
+                    app::chat::MessageReport::builder()
+                        .reporter_id(chat::UserId::from_uuid(
+                            row.get::<uuid::Uuid, _>("reporter_id"),
+                        ))
+                        .reporter_name(reporter_name)
+                        .category(category)
+                        .reported_at(offset_to_system_time(
+                            row.get::<time::OffsetDateTime, _>("reported_at"),
+                        ))
+                        .build(),
+                ))
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl app::chat::ModerationQueue for SqlxChatModerationQueue {
+    async fn enqueue(
+        &self,
+        message_id: &chat::MessageId,
+        reason: &ModerationReason,
+    ) -> Result<()> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "INSERT INTO chat_moderation_queue (message_id, reason) VALUES ($1, $2)"
+        );
+        sqlx::query(
+            r#"
+            INSERT INTO chat_moderation_queue (message_id, reason)
+            VALUES ($1, $2)
+            "#,
+        )
+        .bind(message_id.as_uuid())
+        .bind(reason.to_string())
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(())
+    }
+
+    async fn list_pending(
+        &self,
+        rooms: &[chat::RoomId],
+        room_id: Option<&chat::RoomId>,
+        reason: Option<&ModerationReason>,
+        now: std::time::SystemTime,
+        limit: usize,
+    ) -> Result<Vec<app::chat::ModerationItem>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT queue entries WHERE status = 'pending' AND room_id = ANY($5) AND ($1 IS NULL OR room_id = $1) AND ($2 IS NULL OR reason = $2) ORDER BY queued_at LIMIT $4"
+        );
+        let room_ids = rooms
+            .iter()
+            .map(|room_id| *room_id.as_uuid())
+            .collect::<Vec<_>>();
+        let rows = sqlx::query(
+            r#"
+            SELECT q.message_id,
+                   q.reason,
+                   q.status,
+                   q.reviewed_at,
+                   q.queued_at,
+                   q.revision,
+                   m.room_id,
+                   m.user_id,
+                   m.body,
+                   r.name AS room_name,
+                   m.created_at::text AS created_at,
+                   q.claimed_by,
+                   q.claimed_until,
+                   claimer.username AS claimed_by_name
+            FROM chat_moderation_queue q
+            JOIN chat_messages m ON m.id = q.message_id
+            JOIN chat_rooms r ON r.id = m.room_id
+            LEFT JOIN users claimer
+                   ON claimer.id = q.claimed_by AND q.claimed_until > $3
+            WHERE q.status = 'pending'
+              AND m.room_id = ANY($5)
+              AND ($1::uuid IS NULL OR m.room_id = $1)
+              AND ($2::text IS NULL OR q.reason = $2)
+            ORDER BY q.queued_at ASC, q.message_id
+            LIMIT $4
+            "#,
+        )
+        .bind(room_id.map(|room_id| *room_id.as_uuid()))
+        .bind(reason.map(|reason| reason.to_string()))
+        .bind(time::OffsetDateTime::from(now))
+        .bind(limit as i64)
+        .bind(&room_ids)
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        let mut items = Vec::with_capacity(rows.len());
+        for row in rows {
+            let room_name = row.get::<String, _>("room_name");
+            let room_name = chat::RoomName::try_new(room_name)
+                .map_err(|error| Error::Repo(error.to_string().into()))?;
+            let body = row.get::<String, _>("body");
+            let body = chat::MessageBody::try_new(body)
+                .map_err(|error| Error::Repo(error.to_string().into()))?;
+            let queue_status = row.get::<String, _>("status");
+            let queue_status =
+                queue_status.parse::<ModerationQueueStatus>().map_err(|_| {
+                    Error::Repo(
+                        app::chat::RepoErrorText::new(format!(
+                            "unknown moderation status: {}",
+                            queue_status
+                        ))
+                        ,
+                    )
+                })?;
+            let reason = ModerationReason::try_new(
+                row.get::<String, _>("reason"),
+            )
+            .map_err(|error| Error::Repo(error.to_string().into()))?;
+            let created_at = app::chat::TimestampText::try_new(
+                row.get::<String, _>("created_at"),
+            )
+            .map_err(|error| Error::Repo(error.to_string().into()))?;
+            let claim = match (
+                row.get::<Option<uuid::Uuid>, _>("claimed_by"),
+                row.get::<Option<String>, _>("claimed_by_name"),
+                row.get::<Option<time::OffsetDateTime>, _>("claimed_until"),
+            ) {
+                (Some(reviewer_id), Some(reviewer_name), Some(expires_at)) => Some(
+                    app::chat::ModerationClaim::builder()
+                        .reviewer_id(chat::UserId::from_uuid(reviewer_id))
+                        .reviewer_name(
+                            domain::user::Username::try_new(reviewer_name)
+                                .map_err(|error| Error::Repo(error.to_string().into()))?,
+                        )
+                        .expires_at(offset_to_system_time(expires_at))
+                        .build(),
+                ),
+                _ => None,
+            };
+
+            items.push(
+                app::chat::ModerationItem::builder()
+                    .message_id(chat::MessageId::from_uuid(
+                        row.get::<uuid::Uuid, _>("message_id"),
+                    ))
+                    .room_id(chat::RoomId::from_uuid(
+                        row.get::<uuid::Uuid, _>("room_id"),
+                    ))
+                    .room_name(room_name)
+                    .user_id(chat::UserId::from_uuid(
+                        row.get::<uuid::Uuid, _>("user_id"),
+                    ))
+                    .body(body)
+                    .queue_status(queue_status)
+                    .reason(reason)
+                    .created_at(created_at)
+                    .queued_at(offset_to_system_time(
+                        row.get::<time::OffsetDateTime, _>("queued_at"),
+                    ))
+                    .revision(row.get::<i64, _>("revision").max(0) as u64)
+                    .maybe_claim(claim)
+                    .build(),
+            );
+        }
+
+        let message_ids = items
+            .iter()
+            .map(|item| *item.message_id.as_uuid())
+            .collect::<Vec<_>>();
+        for (message_id, report) in self.open_reports(&message_ids).await? {
+            if let Some(item) = items.iter_mut().find(|item| item.message_id == message_id) {
+                item.reports.push(report);
+            }
+        }
+
+        Ok(items)
+    }
+
+    async fn claim(
+        &self,
+        message_id: &chat::MessageId,
+        reviewer_id: &chat::UserId,
+        now: std::time::SystemTime,
+        until: std::time::SystemTime,
+    ) -> Result<bool> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "UPDATE chat_moderation_queue SET claimed_by = $2, claimed_until = $4 WHERE message_id = $1 AND status = 'pending' AND (claimed_by IS NULL OR claimed_by = $2 OR claimed_until <= $3)"
+        );
+        let result = sqlx::query(
+            r#"
+            UPDATE chat_moderation_queue
+            SET claimed_by = $2,
+                claimed_until = $4
+            WHERE message_id = $1
+              AND status = 'pending'
+              AND (claimed_by IS NULL OR claimed_by = $2 OR claimed_until <= $3)
+            "#,
+        )
+        .bind(message_id.as_uuid())
+        .bind(reviewer_id.as_uuid())
+        .bind(time::OffsetDateTime::from(now))
+        .bind(time::OffsetDateTime::from(until))
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn release(
+        &self,
+        message_id: &chat::MessageId,
+        reviewer_id: &chat::UserId,
+    ) -> Result<()> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "UPDATE chat_moderation_queue SET claimed_by = NULL, claimed_until = NULL WHERE message_id = $1 AND claimed_by = $2"
+        );
+        sqlx::query(
+            r#"
+            UPDATE chat_moderation_queue
+            SET claimed_by = NULL,
+                claimed_until = NULL
+            WHERE message_id = $1 AND claimed_by = $2
+            "#,
+        )
+        .bind(message_id.as_uuid())
+        .bind(reviewer_id.as_uuid())
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(())
+    }
+
+    async fn complete(
+        &self,
+        message_id: &chat::MessageId,
+        reviewer_id: &chat::UserId,
+        decision: app::chat::ModerationDecision,
+        reason: Option<ModerationReason>,
+        revision: Option<u64>,
+        now: std::time::SystemTime,
+    ) -> Result<bool> {
+        let status = match decision {
+            app::chat::ModerationDecision::Approve => {
+                ModerationQueueStatus::Approved
+            }
+            app::chat::ModerationDecision::Remove => {
+                ModerationQueueStatus::Removed
+            }
+        };
+
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "UPDATE chat_moderation_queue SET status = $2, ... , revision = revision + 1 WHERE message_id = $1 AND status = 'pending' AND ($5 IS NULL OR revision = $5) AND (claimed_by IS NULL OR claimed_by = $3 OR claimed_until <= $6)"
+        );
+        let result = sqlx::query(
+            r#"
+            UPDATE chat_moderation_queue
+            SET status = $2,
+                reviewer_id = $3,
+                reviewed_at = $6,
+                reason = COALESCE($4, reason),
+                revision = revision + 1,
+                claimed_by = NULL,
+                claimed_until = NULL
+            WHERE message_id = $1
+              AND status = 'pending'
+              AND ($5::bigint IS NULL OR revision = $5)
+              AND (claimed_by IS NULL OR claimed_by = $3 OR claimed_until <= $6)
+            "#,
+        )
+        .bind(message_id.as_uuid())
+        .bind(status.to_string())
+        .bind(reviewer_id.as_uuid())
+        .bind(reason.map(|value| value.to_string()))
+        .bind(revision.map(|value| value as i64))
+        .bind(time::OffsetDateTime::from(now))
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn report(
+        &self,
+        message_id: &chat::MessageId,
+        reporter_id: &chat::UserId,
+        category: app::chat::ReportCategory,
+        reported_at: std::time::SystemTime,
+    ) -> Result<app::chat::ReportTally> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "WITH filed AS (INSERT INTO chat_message_reports ... ON CONFLICT DO UPDATE WHERE reported before the last review), queued AS (INSERT INTO chat_moderation_queue ... ON CONFLICT DO UPDATE SET status = 'pending') SELECT EXISTS (SELECT 1 FROM filed)"
+        );
+        // A member counts once per review cycle: their report is refreshed
+        // only if a moderator has reviewed the message since they filed it.
+        let counted = sqlx::query(
+            r#"
+            WITH filed AS (
+                INSERT INTO chat_message_reports (message_id, reporter_id, category, reported_at)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (message_id, reporter_id) DO UPDATE
+                SET category = EXCLUDED.category,
+                    reported_at = EXCLUDED.reported_at
+                WHERE chat_message_reports.reported_at <= (
+                    SELECT reviewed_at FROM chat_moderation_queue WHERE message_id = $1
+                )
+                RETURNING message_id
+            ), queued AS (
+                INSERT INTO chat_moderation_queue (message_id, reason)
+                SELECT message_id, 'report' FROM filed
+                ON CONFLICT (message_id) DO UPDATE
+                SET status = 'pending',
+                    reason = 'report',
+                    queued_at = $4,
+                    revision = chat_moderation_queue.revision + 1,
+                    claimed_by = NULL,
+                    claimed_until = NULL
+                WHERE chat_moderation_queue.status <> 'pending'
+            )
+            SELECT EXISTS (SELECT 1 FROM filed) AS counted
+            "#,
+        )
+        .bind(message_id.as_uuid())
+        .bind(reporter_id.as_uuid())
+        .bind(category.to_string())
+        .bind(time::OffsetDateTime::from(reported_at))
+        .fetch_one(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?
+        .get::<bool, _>("counted");
+
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT COUNT(*) FROM chat_message_reports r JOIN chat_moderation_queue q ON q.message_id = r.message_id WHERE r.message_id = $1 AND (q.reviewed_at IS NULL OR r.reported_at > q.reviewed_at)"
+        );
+        let open_reports = sqlx::query(
+            r#"
+            SELECT COUNT(*) AS reports
+            FROM chat_message_reports r
+            JOIN chat_moderation_queue q ON q.message_id = r.message_id
+            WHERE r.message_id = $1
+              AND (q.reviewed_at IS NULL OR r.reported_at > q.reviewed_at)
+            "#,
+        )
+        .bind(message_id.as_uuid())
+        .fetch_one(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?
+        .get::<i64, _>("reports");
+
+        Ok(app::chat::ReportTally {
+            open_reports: open_reports.max(0) as usize,
+            counted,
+        })
+    }
+
+    async fn file_appeal(
+        &self,
+        message_id: &chat::MessageId,
+        appellant_id: &chat::UserId,
+        statement: &app::chat::AppealStatement,
+        filed_at: std::time::SystemTime,
+    ) -> Result<bool> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "INSERT INTO chat_moderation_appeals (message_id, appellant_id, statement, original_reviewer_id, filed_at) SELECT q.message_id, $2, $3, q.reviewer_id, $4 FROM chat_moderation_queue q JOIN chat_messages m ON m.id = q.message_id WHERE q.message_id = $1 AND q.status = 'removed' AND m.user_id = $2 ON CONFLICT DO NOTHING"
+        );
+        let result = sqlx::query(
+            r#"
+            INSERT INTO chat_moderation_appeals
+                (message_id, appellant_id, statement, original_reviewer_id, filed_at)
+            SELECT q.message_id, $2, $3, q.reviewer_id, $4
+            FROM chat_moderation_queue q
+            JOIN chat_messages m ON m.id = q.message_id
+            WHERE q.message_id = $1
+              AND q.status = 'removed'
+              AND m.user_id = $2
+            ON CONFLICT (message_id) DO NOTHING
+            "#,
+        )
+        .bind(message_id.as_uuid())
+        .bind(appellant_id.as_uuid())
+        .bind(statement.to_string())
+        .bind(time::OffsetDateTime::from(filed_at))
+        .execute(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn find_appeal(
+        &self,
+        message_id: &chat::MessageId,
+    ) -> Result<Option<app::chat::Appeal>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT appeal WHERE message_id = $1"
+        );
+        let row = sqlx::query(&format!("{APPEAL_SELECT} WHERE a.message_id = $1"))
+            .bind(message_id.as_uuid())
+            .fetch_optional(&self.pg)
+            .await
+            .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        row.as_ref().map(Self::appeal_from_row).transpose()
+    }
+
+    async fn list_appeals(
+        &self,
+        rooms: &[chat::RoomId],
+        limit: usize,
+    ) -> Result<Vec<app::chat::Appeal>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT appeals WHERE status = 'pending' AND room_id = ANY($2) ORDER BY filed_at LIMIT $1"
+        );
+        let room_ids = rooms
+            .iter()
+            .map(|room_id| *room_id.as_uuid())
+            .collect::<Vec<_>>();
+        let rows = sqlx::query(&format!(
+            "{APPEAL_SELECT} WHERE a.status = 'pending' AND m.room_id = ANY($2) ORDER BY a.filed_at ASC LIMIT $1"
+        ))
+        .bind(limit as i64)
+        .bind(&room_ids)
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        rows.iter().map(Self::appeal_from_row).collect()
+    }
+
+    async fn list_removals(
+        &self,
+        author_id: &chat::UserId,
+        limit: usize,
+    ) -> Result<Vec<app::chat::Removal>> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "SELECT removed messages of $1 LEFT JOIN chat_moderation_appeals ORDER BY reviewed_at DESC LIMIT $2"
+        );
+        let rows = sqlx::query(
+            r#"
+            SELECT m.id AS message_id,
+                   r.name AS room_name,
+                   m.body,
+                   q.reason,
+                   q.reviewed_at,
+                   a.status AS appeal_status
+            FROM chat_moderation_queue q
+            JOIN chat_messages m ON m.id = q.message_id
+            JOIN chat_rooms r ON r.id = m.room_id
+            LEFT JOIN chat_moderation_appeals a ON a.message_id = q.message_id
+            WHERE m.user_id = $1
+              AND q.status = 'removed'
+              AND q.reviewed_at IS NOT NULL
+            ORDER BY q.reviewed_at DESC
+            LIMIT $2
+            "#,
+        )
+        .bind(author_id.as_uuid())
+        .bind(limit as i64)
+        .fetch_all(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?;
+
+        rows.iter()
+            .map(|row| {
+                let appeal = row
+                    .get::<Option<String>, _>("appeal_status")
+                    .map(|status| Self::parse_appeal_status(&status))
+                    .transpose()?;
+                Ok(app::chat::Removal::builder()
+                    .message_id(chat::MessageId::from_uuid(
+                        row.get::<uuid::Uuid, _>("message_id"),
+                    ))
+                    .room_name(
+                        chat::RoomName::try_new(row.get::<String, _>("room_name"))
+                            .map_err(|error| Error::Repo(error.to_string().into()))?,
+                    )
+                    .body(
+                        chat::MessageBody::try_new(row.get::<String, _>("body"))
+                            .map_err(|error| Error::Repo(error.to_string().into()))?,
+                    )
+                    .reason(
+                        ModerationReason::try_new(row.get::<String, _>("reason"))
+                            .map_err(|error| Error::Repo(error.to_string().into()))?,
+                    )
+                    .removed_at(offset_to_system_time(
+                        row.get::<time::OffsetDateTime, _>("reviewed_at"),
+                    ))
+                    .maybe_appeal(appeal)
+                    .build())
+            })
+            .collect()
+    }
+
+    async fn decide_appeal(
+        &self,
+        message_id: &chat::MessageId,
+        reviewer_id: &chat::UserId,
+        decision: app::chat::AppealDecision,
+        decided_at: std::time::SystemTime,
+    ) -> Result<bool> {
+        tracing::info!(
+            target: "demo.db",
+            message = "db query",
+            db_statement = "WITH decided AS (UPDATE chat_moderation_appeals SET status = $3 ... WHERE message_id = $1 AND status = 'pending' AND original_reviewer_id IS DISTINCT FROM $2 RETURNING message_id), restored AS (UPDATE chat_moderation_queue SET status = 'approved' ... WHERE $3 = 'overturned') SELECT EXISTS (SELECT 1 FROM decided)"
+        );
+        let decided = sqlx::query(
+            r#"
+            WITH decided AS (
+                UPDATE chat_moderation_appeals
+                SET status = $3,
+                    reviewer_id = $2,
+                    decided_at = $4
+                WHERE message_id = $1
+                  AND status = 'pending'
+                  AND original_reviewer_id IS DISTINCT FROM $2
+                  AND appellant_id <> $2
+                RETURNING message_id
+            ), restored AS (
+                UPDATE chat_moderation_queue q
+                SET status = 'approved',
+                    reviewer_id = $2,
+                    reviewed_at = $4,
+                    revision = q.revision + 1
+                FROM decided
+                WHERE q.message_id = decided.message_id
+                  AND $3 = 'overturned'
+            )
+            SELECT EXISTS (SELECT 1 FROM decided) AS decided
+            "#,
+        )
+        .bind(message_id.as_uuid())
+        .bind(reviewer_id.as_uuid())
+        .bind(decision.status().to_string())
+        .bind(time::OffsetDateTime::from(decided_at))
+        .fetch_one(&self.pg)
+        .await
+        .map_err(|error| Error::Repo(error.to_string().into()))?
+        .get::<bool, _>("decided");
+
+        Ok(decided)
     }
 }
 
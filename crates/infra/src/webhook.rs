@@ -0,0 +1,152 @@
+use std::net::SocketAddr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use app::chat::{WebhookAttempt, WebhookRequest, WebhookSender};
+use async_trait::async_trait;
+use domain::chat;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::net::{public_addr, resolve};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub const EVENT_HEADER: &str = "x-chat-event";
+pub const DELIVERY_HEADER: &str = "x-chat-delivery";
+pub const TIMESTAMP_HEADER: &str = "x-chat-timestamp";
+pub const SIGNATURE_HEADER: &str = "x-chat-signature";
+
+/// `sha256=` plus the hex HMAC-SHA256 of `"{timestamp}.{payload}"`, keyed
+/// with the webhook secret. Receivers recompute it to verify a delivery
+/// and reject stale timestamps to stop replays.
+pub fn sign_webhook(
+    secret: &str,
+    timestamp: u64,
+    payload: &str,
+) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("hmac accepts any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(payload.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Posts signed JSON payloads. Like link previews, every attempt resolves
+/// the subscriber's host once, refuses non-public addresses and pins the
+/// connection to the checked address. Redirects are never followed; a 3xx
+/// answer counts as a rejection.
+#[derive(Default)]
+pub struct HttpWebhookSender {
+    allow_private: bool,
+}
+
+impl HttpWebhookSender {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also delivers to loopback and private addresses, for tests and local
+    /// receivers. Never use it where room admins pick the URL.
+    pub fn allowing_private_targets() -> Self {
+        Self {
+            allow_private: true,
+        }
+    }
+
+    /// The parsed URL, its host and the address to pin it to, if the
+    /// target may be delivered to.
+    async fn target(
+        &self,
+        url: &chat::WebhookUrl,
+    ) -> Option<(reqwest::Url, String, SocketAddr)> {
+        let url = reqwest::Url::parse(url.as_ref()).ok()?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return None;
+        }
+        let host = url.host_str()?.to_string();
+        let addr = if self.allow_private {
+            resolve(&url).await?.first().copied()?
+        } else {
+            public_addr(&url).await?
+        };
+        Some((url, host, addr))
+    }
+}
+
+#[async_trait]
+impl WebhookSender for HttpWebhookSender {
+    async fn accepts(
+        &self,
+        url: &chat::WebhookUrl,
+    ) -> bool {
+        self.target(url).await.is_some()
+    }
+
+    async fn send(
+        &self,
+        request: &WebhookRequest,
+    ) -> WebhookAttempt {
+        let Some((url, host, addr)) = self.target(&request.url).await else {
+            tracing::warn!(
+                delivery_id = %request.delivery_id.as_uuid(),
+                "webhook target is not a public address"
+            );
+            return failed("webhook target is not a public address");
+        };
+        let client = match reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .resolve(&host, addr)
+            .build()
+        {
+            Ok(client) => client,
+            Err(error) => return failed(&error.to_string()),
+        };
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|value| value.as_secs())
+            .unwrap_or_default();
+        let payload = request.payload.to_string();
+        let signature = sign_webhook(request.secret.as_ref(), timestamp, &payload);
+
+        let response = client
+            .post(url)
+            .timeout(REQUEST_TIMEOUT)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, request.event.to_string())
+            .header(DELIVERY_HEADER, request.delivery_id.as_uuid().to_string())
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, signature)
+            .body(payload)
+            .send()
+            .await;
+
+        match response {
+            Ok(response) if response.status().is_success() => WebhookAttempt::Delivered {
+                status: response.status().as_u16(),
+            },
+            Ok(response) => WebhookAttempt::Rejected {
+                status: response.status().as_u16(),
+            },
+            Err(error) => {
+                tracing::warn!(
+                    %error,
+                    delivery_id = %request.delivery_id.as_uuid(),
+                    attempt = request.attempt,
+                    "webhook delivery failed"
+                );
+                failed(&error.to_string())
+            }
+        }
+    }
+}
+
+fn failed(error: &str) -> WebhookAttempt {
+    let text = error.chars().take(500).collect::<String>();
+    WebhookAttempt::Failed {
+        error: chat::WebhookDeliveryError::try_new(text).unwrap_or_else(|_| {
+            chat::WebhookDeliveryError::try_new("request failed").expect("delivery error")
+        }),
+    }
+}
@@ -0,0 +1,59 @@
+use std::io::Cursor;
+
+use app::chat::{BlobStore, THUMBNAIL_SIZE, Thumbnailer};
+use domain::chat;
+use infra::chat::{ImageThumbnailer, LocalBlobStore, render_thumbnail};
+
+fn key(value: &str) -> chat::BlobKey {
+    chat::BlobKey::try_new(value).expect("blob key")
+}
+
+fn png(
+    width: u32,
+    height: u32,
+) -> Vec<u8> {
+    let image = image::RgbImage::from_pixel(width, height, image::Rgb([200, 40, 90]));
+    let mut bytes = Cursor::new(Vec::new());
+    image
+        .write_to(&mut bytes, image::ImageFormat::Png)
+        .expect("encode png");
+    bytes.into_inner()
+}
+
+#[tokio::test]
+async fn local_store_round_trips_and_forgets() {
+    let root = std::env::temp_dir().join(format!("blobs-{}", uuid::Uuid::new_v4().simple()));
+    let store = LocalBlobStore::new(&root);
+    let key = key("attachments/abc123/original");
+
+    store.put(&key, b"hello").await.expect("put");
+    assert_eq!(store.get(&key).await.expect("get").as_deref(), Some(&b"hello"[..]));
+    assert!(root.join("attachments/abc123/original").is_file());
+
+    store.delete(&key).await.expect("delete");
+    assert_eq!(store.get(&key).await.expect("get"), None);
+    assert!(!root.join("attachments/abc123").exists());
+    store.delete(&key).await.expect("deleting twice is fine");
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[tokio::test]
+async fn thumbnails_fit_the_box_as_png() {
+    let thumbnail = ImageThumbnailer::new()
+        .thumbnail(chat::AttachmentType::Png, &png(1200, 600))
+        .await
+        .expect("thumbnail");
+
+    let decoded = image::load_from_memory_with_format(&thumbnail, image::ImageFormat::Png)
+        .expect("decode thumbnail");
+    assert_eq!(decoded.width(), THUMBNAIL_SIZE);
+    assert_eq!(decoded.height(), THUMBNAIL_SIZE / 2);
+}
+
+#[test]
+fn refuses_oversized_corrupt_and_non_images() {
+    assert!(render_thumbnail(chat::AttachmentType::Png, &png(9_000, 1)).is_none());
+    assert!(render_thumbnail(chat::AttachmentType::Jpeg, &png(10, 10)).is_none());
+    assert!(render_thumbnail(chat::AttachmentType::Pdf, b"%PDF-1.7").is_none());
+}
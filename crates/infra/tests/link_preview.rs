@@ -0,0 +1,103 @@
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use app::chat::LinkPreviewFetcher;
+use domain::chat;
+use infra::chat::{HttpLinkPreviewFetcher, is_public_address, parse_link_preview};
+
+fn url(value: &str) -> chat::PreviewUrl {
+    chat::PreviewUrl::try_new(value).expect("url")
+}
+
+#[test]
+fn parses_open_graph_tags() {
+    let html = r#"
+        <html><head>
+        <title>Fallback</title>
+        <META property="og:title" content="Release &amp; notes">
+        <meta content='What&#39;s new in 2.0' property='og:description' />
+        <meta property=og:image content=/img/card.png>
+        </head></html>
+    "#;
+
+    let preview = parse_link_preview(&url("https://example.com/blog/post"), html);
+
+    assert_eq!(preview.title.map(|value| value.to_string()).as_deref(), Some("Release & notes"));
+    assert_eq!(
+        preview.description.map(|value| value.to_string()).as_deref(),
+        Some("What's new in 2.0")
+    );
+    assert_eq!(
+        preview.image_url.map(|value| value.to_string()).as_deref(),
+        Some("https://example.com/img/card.png")
+    );
+}
+
+#[test]
+fn falls_back_to_title_and_description() {
+    let html = "<head><title>\n  Plain   page </title>\
+        <meta name=\"description\" content=\"Just HTML.\"></head>";
+
+    let preview = parse_link_preview(&url("https://example.com"), html);
+
+    assert_eq!(preview.title.map(|value| value.to_string()).as_deref(), Some("Plain page"));
+    assert_eq!(
+        preview.description.map(|value| value.to_string()).as_deref(),
+        Some("Just HTML.")
+    );
+    assert!(preview.image_url.is_none());
+    assert!(parse_link_preview(&url("https://example.com"), "<p>no head</p>").is_empty());
+}
+
+#[test]
+fn private_addresses_are_not_public() {
+    for address in [
+        "127.0.0.1",
+        "10.1.2.3",
+        "172.16.0.1",
+        "192.168.1.1",
+        "169.254.169.254",
+        "100.64.0.1",
+        "0.0.0.0",
+        "::1",
+        "fd00::1",
+        "fe80::1",
+        "::ffff:10.0.0.1",
+    ] {
+        let ip = address.parse::<IpAddr>().expect("ip");
+        assert!(!is_public_address(ip), "{address} should be blocked");
+    }
+    assert!(is_public_address("93.184.216.34".parse().expect("ip")));
+    assert!(is_public_address("2606:4700::1".parse().expect("ip")));
+}
+
+#[tokio::test]
+async fn loopback_links_are_never_requested() {
+    let hits = Arc::new(AtomicUsize::new(0));
+    let counter = hits.clone();
+    let app = axum::Router::new().route(
+        "/",
+        axum::routing::get(move || {
+            counter.fetch_add(1, Ordering::SeqCst);
+            async { axum::response::Html("<title>internal</title>") }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind stub server");
+    let addr = listener.local_addr().expect("stub addr");
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.expect("stub server");
+    });
+
+    let fetcher = HttpLinkPreviewFetcher::new();
+    let direct = fetcher.fetch(&url(&format!("http://{addr}/"))).await;
+    let by_name = fetcher
+        .fetch(&url(&format!("http://localhost:{}/", addr.port())))
+        .await;
+
+    assert!(direct.is_none());
+    assert!(by_name.is_none());
+    assert_eq!(hits.load(Ordering::SeqCst), 0);
+}
@@ -0,0 +1,169 @@
+use std::sync::{Arc, Mutex};
+
+use app::chat::{WebhookAttempt, WebhookRequest, WebhookSender};
+use axum::http::{HeaderMap, StatusCode};
+use domain::chat;
+use infra::chat::{
+    DELIVERY_HEADER, EVENT_HEADER, HttpWebhookSender, SIGNATURE_HEADER, TIMESTAMP_HEADER,
+    sign_webhook,
+};
+
+const SECRET: &str = "a-long-enough-test-secret";
+
+#[derive(Clone, Default)]
+struct Received {
+    requests: Arc<Mutex<Vec<(HeaderMap, String)>>>,
+}
+
+/// Serves `POST /hook` on an ephemeral port, answering `status` and
+/// recording every request.
+async fn stub_server(status: StatusCode) -> (String, Received) {
+    let received = Received::default();
+    let recorder = received.clone();
+    let app = axum::Router::new().route(
+        "/hook",
+        axum::routing::post(move |headers: HeaderMap, body: String| {
+            let recorder = recorder.clone();
+            async move {
+                recorder
+                    .requests
+                    .lock()
+                    .expect("requests lock")
+                    .push((headers, body));
+                status
+            }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind stub server");
+    let addr = listener.local_addr().expect("stub addr");
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.expect("stub server");
+    });
+    (format!("http://{addr}/hook"), received)
+}
+
+fn request(url: &str) -> WebhookRequest {
+    WebhookRequest::builder()
+        .delivery_id(chat::WebhookDeliveryId::new_v4())
+        .event(chat::WebhookEvent::MessagePosted)
+        .url(chat::WebhookUrl::try_new(url).expect("url"))
+        .secret(chat::WebhookSecret::try_new(SECRET).expect("secret"))
+        .payload(chat::WebhookPayload::new(
+            r#"{"event":"message.posted","data":{"body":"hi"}}"#,
+        ))
+        .attempt(1)
+        .build()
+}
+
+fn header(
+    headers: &HeaderMap,
+    name: &str,
+) -> String {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string()
+}
+
+#[tokio::test]
+async fn delivers_signed_payload() {
+    let (url, received) = stub_server(StatusCode::NO_CONTENT).await;
+    let sender = HttpWebhookSender::allowing_private_targets();
+    let request = request(&url);
+
+    let attempt = sender.send(&request).await;
+
+    assert_eq!(attempt, WebhookAttempt::Delivered { status: 204 });
+    let requests = received.requests.lock().expect("requests lock");
+    let (headers, body) = requests.first().expect("one request");
+    assert_eq!(body, &request.payload.to_string());
+    assert_eq!(header(headers, EVENT_HEADER), "message.posted");
+    assert_eq!(
+        header(headers, DELIVERY_HEADER),
+        request.delivery_id.as_uuid().to_string()
+    );
+    let timestamp = header(headers, TIMESTAMP_HEADER)
+        .parse::<u64>()
+        .expect("timestamp");
+    assert_eq!(
+        header(headers, SIGNATURE_HEADER),
+        sign_webhook(SECRET, timestamp, body)
+    );
+}
+
+#[tokio::test]
+async fn non_success_status_is_rejected() {
+    let (url, received) = stub_server(StatusCode::SERVICE_UNAVAILABLE).await;
+    let sender = HttpWebhookSender::allowing_private_targets();
+
+    let attempt = sender.send(&request(&url)).await;
+
+    assert_eq!(attempt, WebhookAttempt::Rejected { status: 503 });
+    assert_eq!(received.requests.lock().expect("requests lock").len(), 1);
+}
+
+#[tokio::test]
+async fn unreachable_subscriber_fails() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind");
+    let addr = listener.local_addr().expect("addr");
+    drop(listener);
+    let sender = HttpWebhookSender::allowing_private_targets();
+
+    let attempt = sender.send(&request(&format!("http://{addr}/hook"))).await;
+
+    assert!(matches!(attempt, WebhookAttempt::Failed { .. }));
+}
+
+#[tokio::test]
+async fn private_targets_are_refused_before_connecting() {
+    let (url, received) = stub_server(StatusCode::NO_CONTENT).await;
+    let port = url
+        .trim_start_matches("http://127.0.0.1:")
+        .trim_end_matches("/hook");
+    let by_name = format!("http://localhost:{port}/hook");
+    let sender = HttpWebhookSender::new();
+
+    for target in [&url, &by_name] {
+        let webhook_url = chat::WebhookUrl::try_new(target.as_str()).expect("url");
+        assert!(!sender.accepts(&webhook_url).await);
+        assert!(matches!(
+            sender.send(&request(target)).await,
+            WebhookAttempt::Failed { .. }
+        ));
+    }
+    for target in ["http://10.0.0.7/hook", "http://169.254.169.254/latest/meta-data"] {
+        let webhook_url = chat::WebhookUrl::try_new(target).expect("url");
+        assert!(!sender.accepts(&webhook_url).await);
+    }
+    assert!(received.requests.lock().expect("requests lock").is_empty());
+}
+
+#[tokio::test]
+async fn redirects_are_not_followed() {
+    let (target, received) = stub_server(StatusCode::NO_CONTENT).await;
+    let app = axum::Router::new().route(
+        "/hook",
+        axum::routing::post(move || {
+            let target = target.clone();
+            async move { axum::response::Redirect::temporary(&target) }
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind redirect server");
+    let addr = listener.local_addr().expect("redirect addr");
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.expect("redirect server");
+    });
+    let sender = HttpWebhookSender::allowing_private_targets();
+
+    let attempt = sender.send(&request(&format!("http://{addr}/hook"))).await;
+
+    assert_eq!(attempt, WebhookAttempt::Rejected { status: 307 });
+    assert!(received.requests.lock().expect("requests lock").is_empty());
+}
@@ -0,0 +1,88 @@
+use std::{env, process::ExitCode, sync::Arc};
+
+const USAGE: &str = "usage: cargo run --bin import_chat -- <room-id> <actor-user-id> <transcript.ndjson>";
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let [room_id, actor_id, path] = args.as_slice() else {
+        eprintln!("{USAGE}");
+        return ExitCode::from(2);
+    };
+
+    let (room_id, actor_id) = match (room_id.parse(), actor_id.parse()) {
+        (Ok(room_id), Ok(actor_id)) => (
+            domain::chat::RoomId::from_uuid(room_id),
+            domain::chat::UserId::from_uuid(actor_id),
+        ),
+        _ => {
+            eprintln!("room-id and actor-user-id must be UUIDs");
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let transcript = match tokio::fs::read_to_string(path).await {
+        Ok(transcript) => transcript,
+        Err(error) => {
+            eprintln!("failed to read {path}: {error}");
+            return ExitCode::from(1);
+        }
+    };
+
+    let infra = match infra::config::InfraConfig::from_env() {
+        Ok(cfg) => match infra::Infra::init(&cfg).await {
+            Ok(infra) => infra,
+            Err(error) => {
+                eprintln!("failed to connect: {error}");
+                return ExitCode::from(1);
+            }
+        },
+        Err(error) => {
+            eprintln!("{error}");
+            return ExitCode::from(2);
+        }
+    };
+
+    let chat = app::chat::Service::builder()
+        .with_repo(Arc::new(infra::chat::Repository::new(infra.db.clone())))
+        .with_moderation_queue(Arc::new(infra::chat::ModerationQueue::new(infra.db.clone())))
+        .with_rate_limiter(Arc::new(infra::chat::RateLimiter::new(infra.db.clone())))
+        .with_audit_log(Arc::new(infra::chat::AuditLog::new(infra.db.clone())))
+        .with_typing_store(Arc::new(infra::chat::MemoryTypingStore::new()))
+        .with_author_directory(Arc::new(infra::chat::AuthorDirectory::new(infra.db.clone())))
+        .with_clock(Arc::new(infra::chat::SystemClock::new()))
+        .with_id_generator(Arc::new(infra::chat::UuidGenerator::new()))
+        .build();
+
+    let report = match chat
+        .import_transcript(
+            app::chat::ImportTranscript::builder()
+                .room_id(room_id)
+                .actor_id(actor_id)
+                .transcript(app::chat::ImportSource::new(transcript))
+                .build(),
+        )
+        .await
+    {
+        Ok(report) => report,
+        Err(error) => {
+            eprintln!("import failed: {error}");
+            return ExitCode::from(1);
+        }
+    };
+
+    println!(
+        "imported {} messages ({} pending moderation, {} placeholder accounts)",
+        report.imported, report.pending, report.placeholders
+    );
+    for error in &report.errors {
+        eprintln!("line {}: {}", error.line, error.reason);
+    }
+
+    if report.errors.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(1)
+    }
+}
@@ -7,6 +7,45 @@ use crate::error::{EnvErrorReason, Error, Result};
 pub(crate) struct Config {
     pub infra: InfraConfig,
     pub http: HttpConfig,
+    pub chat: ChatConfig,
+}
+
+#[derive(Clone, Debug)]
+pub struct ChatConfig {
+    pub purge_interval_secs: u64,
+    pub purge_dry_run: bool,
+    pub attachments_dir: std::path::PathBuf,
+    pub report_hide_threshold: usize,
+}
+
+impl ChatConfig {
+    pub fn from_env() -> Result<Self> {
+        let purge_dry_run = match std::env::var("CHAT_PURGE_DRY_RUN") {
+            Ok(value) => value.parse().map_err(|_| Error::InvalidEnv {
+                key: "CHAT_PURGE_DRY_RUN",
+                reason: EnvErrorReason::new("must be true or false"),
+            })?,
+            Err(std::env::VarError::NotPresent) => false,
+            Err(_) => {
+                return Err(Error::InvalidEnv {
+                    key: "CHAT_PURGE_DRY_RUN",
+                    reason: EnvErrorReason::new("must be true or false"),
+                })
+            }
+        };
+
+        Ok(Self {
+            purge_interval_secs: positive_env("CHAT_PURGE_INTERVAL_SECS", 3600)?,
+            purge_dry_run,
+            report_hide_threshold: positive_env(
+                "CHAT_REPORT_HIDE_THRESHOLD",
+                app::chat::DEFAULT_REPORT_HIDE_THRESHOLD,
+            )?,
+            attachments_dir: std::env::var_os("CHAT_ATTACHMENTS_DIR")
+                .map(std::path::PathBuf::from)
+                .unwrap_or_else(|| std::path::PathBuf::from("data/attachments")),
+        })
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -15,6 +54,67 @@ pub struct HttpConfig {
     pub port: u16,
     pub session_secret: Vec<u8>,
     pub session_cleanup_interval_secs: u64,
+    pub sse: SseConfig,
+}
+
+#[derive(Clone, Debug)]
+pub struct SseConfig {
+    pub heartbeat_secs: u64,
+    pub max_streams_per_session: usize,
+    pub max_streams: usize,
+    pub sweep_interval_secs: u64,
+}
+
+impl SseConfig {
+    pub fn from_env() -> Result<Self> {
+        Ok(Self {
+            heartbeat_secs: positive_env("SSE_HEARTBEAT_SECS", 15)?,
+            max_streams_per_session: positive_env(
+                "SSE_MAX_STREAMS_PER_SESSION",
+                8,
+            )?,
+            max_streams: positive_env("SSE_MAX_STREAMS", 1024)?,
+            sweep_interval_secs: positive_env("SSE_SWEEP_INTERVAL_SECS", 60)?,
+        })
+    }
+
+    pub fn limits(&self) -> http::sse::Limits {
+        http::sse::Limits {
+            max_streams_per_session: self.max_streams_per_session,
+            max_streams: self.max_streams,
+            heartbeat: std::time::Duration::from_secs(self.heartbeat_secs),
+        }
+    }
+}
+
+fn positive_env<T>(
+    key: &'static str,
+    default: T,
+) -> Result<T>
+where
+    T: std::str::FromStr + PartialOrd + Default,
+{
+    let value = match std::env::var(key) {
+        Ok(value) => value.parse().map_err(|_| Error::InvalidEnv {
+            key,
+            reason: EnvErrorReason::new("must be a valid unsigned integer"),
+        })?,
+        Err(std::env::VarError::NotPresent) => default,
+        Err(_) => {
+            return Err(Error::InvalidEnv {
+                key,
+                reason: EnvErrorReason::new("must be a valid unsigned integer"),
+            })
+        }
+    };
+
+    if value <= T::default() {
+        return Err(Error::InvalidEnv {
+            key,
+            reason: EnvErrorReason::new("must be greater than 0"),
+        });
+    }
+    Ok(value)
 }
 
 impl HttpConfig {
@@ -83,6 +183,7 @@ impl HttpConfig {
             })?,
             session_secret,
             session_cleanup_interval_secs,
+            sse: SseConfig::from_env()?,
         })
     }
 }
@@ -97,7 +198,8 @@ impl Config {
         let infra = InfraConfig::from_env()
             .map_err(Error::Infra)?;
         let http = HttpConfig::from_env()?;
+        let chat = ChatConfig::from_env()?;
 
-        Ok(Self { infra, http })
+        Ok(Self { infra, http, chat })
     }
 }
@@ -13,7 +13,9 @@ use tracing_subscriber::{EnvFilter, layer::SubscriberExt, util::SubscriberInitEx
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let sse_registry = http::SseRegistry::new();
+    let cfg = config::Config::load()?;
+
+    let sse_registry = http::SseRegistry::with_limits(cfg.http.sse.limits());
     let trace_log = http::trace_log::TraceLogStore::builder()
         .with_sse(sse_registry.clone())
         .build();
@@ -24,8 +26,6 @@ async fn main() -> Result<()> {
         .build();
     init_tracing(trace_log.clone(), diagnostic_log.clone());
 
-    let cfg = config::Config::load()?;
-
     let addr = format!("{}:{}", &cfg.http.host, cfg.http.port);
 
     let infra = infra::Infra::init(&cfg.infra).await.map_err(Error::Infra)?;
@@ -42,15 +42,28 @@ async fn main() -> Result<()> {
     let chat_moderation = Arc::new(infra::chat::ModerationQueue::new(infra.db.clone()));
     let chat_rate_limiter = Arc::new(infra::chat::RateLimiter::new(infra.db.clone()));
     let chat_audit = Arc::new(infra::chat::AuditLog::new(infra.db.clone()));
+    let chat_typing = Arc::new(infra::chat::MemoryTypingStore::new());
+    let chat_authors = Arc::new(infra::chat::AuthorDirectory::new(infra.db.clone()));
     let chat_clock = Arc::new(infra::chat::SystemClock::new());
     let chat_ids = Arc::new(infra::chat::UuidGenerator::new());
+    let chat_webhooks = Arc::new(infra::chat::HttpWebhookSender::new());
+    let chat_previews = Arc::new(infra::chat::HttpLinkPreviewFetcher::new());
+    let chat_blobs = Arc::new(infra::chat::LocalBlobStore::new(cfg.chat.attachments_dir.clone()));
+    let chat_thumbnails = Arc::new(infra::chat::ImageThumbnailer::new());
     let chat_service = app::chat::Service::builder()
         .with_repo(chat_repo)
         .with_moderation_queue(chat_moderation)
         .with_rate_limiter(chat_rate_limiter)
         .with_audit_log(chat_audit)
+        .with_typing_store(chat_typing)
+        .with_author_directory(chat_authors)
         .with_clock(chat_clock)
         .with_id_generator(chat_ids)
+        .with_webhook_sender(chat_webhooks)
+        .with_link_preview_fetcher(chat_previews)
+        .with_blob_store(chat_blobs)
+        .with_thumbnailer(chat_thumbnails)
+        .with_report_threshold(cfg.chat.report_hide_threshold)
         .build();
 
     let session_key = Key::from(&cfg.http.session_secret);
@@ -58,7 +71,7 @@ async fn main() -> Result<()> {
         .with_user(user_service)
         .with_auth(auth_service)
         .with_chat(chat_service)
-        .with_sse(sse_registry)
+        .with_sse(sse_registry.clone())
         .with_cookie_key(session_key.clone())
         .with_trace_log(trace_log)
         .build();
@@ -76,6 +89,46 @@ async fn main() -> Result<()> {
         }
     });
 
+    let sweep_interval =
+        std::time::Duration::from_secs(cfg.http.sse.sweep_interval_secs);
+    tokio::spawn(sse_registry.clone().sweep_idle_continuously(sweep_interval));
+
+    tokio::spawn(http::chat_demo::expire_typing_continuously(
+        http_state.clone(),
+        std::time::Duration::from_secs(1),
+    ));
+
+    tokio::spawn(http::chat_demo::sweep_presence_continuously(
+        http_state.clone(),
+        std::time::Duration::from_secs(2),
+    ));
+
+    tokio::spawn(http::chat_demo::purge_expired_continuously(
+        http_state.clone(),
+        std::time::Duration::from_secs(cfg.chat.purge_interval_secs),
+        cfg.chat.purge_dry_run,
+    ));
+
+    tokio::spawn(http::chat_demo::post_scheduled_continuously(
+        http_state.clone(),
+        std::time::Duration::from_secs(5),
+    ));
+
+    tokio::spawn(http::chat_demo::close_polls_continuously(
+        http_state.clone(),
+        std::time::Duration::from_secs(5),
+    ));
+
+    tokio::spawn(http::chat_demo::deliver_webhooks_continuously(
+        http_state.clone(),
+        std::time::Duration::from_secs(5),
+    ));
+
+    tokio::spawn(http::chat_demo::sweep_attachments_continuously(
+        http_state.clone(),
+        std::time::Duration::from_secs(60),
+    ));
+
     let app = http::router(http_state, session_store);
 
     let listener = tokio::net::TcpListener::bind(&addr).await?;